@@ -5,7 +5,9 @@
 use std::time::{Duration, Instant};
 
 use base64::prelude::{Engine, BASE64_STANDARD};
-use lynx_headless_rust_test_runner::{ConnectOptions, GotoOptions, Lynx, Page, ScreenshotOptions};
+use lynx_headless_rust_test_runner::{
+  ConnectOptions, GestureOptions, GotoOptions, Lynx, Page, Point, ScreenshotOptions,
+};
 use serde::Deserialize;
 use serde_json::json;
 use thiserror::Error;
@@ -79,21 +81,54 @@ enum HeadlessPageError {
 }
 
 #[derive(Debug, Clone, Copy, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 enum PageActionKind {
   Done,
+  LongPress,
+  Scroll,
+  Swipe,
   Tap,
+  Touch,
   Unsupported,
   Wait,
 }
 
+impl PageActionKind {
+  fn as_str(self) -> &'static str {
+    match self {
+      Self::Done => "done",
+      Self::LongPress => "long_press",
+      Self::Scroll => "scroll",
+      Self::Swipe => "swipe",
+      Self::Tap => "tap",
+      Self::Touch => "touch",
+      Self::Unsupported => "unsupported",
+      Self::Wait => "wait",
+    }
+  }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct PageAction {
   action: PageActionKind,
+  delta_x: Option<f64>,
+  delta_y: Option<f64>,
   duration_ms: Option<u64>,
   reason: String,
   selector: Option<String>,
+  x: Option<f64>,
+  y: Option<f64>,
+}
+
+impl PageAction {
+  fn selector(&self) -> Option<&str> {
+    self
+      .selector
+      .as_deref()
+      .map(str::trim)
+      .filter(|selector| !selector.is_empty())
+  }
 }
 
 pub(crate) struct CapturedPage {
@@ -125,9 +160,9 @@ async fn capture_page_png(page: &Page, settle: Duration) -> Result<Vec<u8>, Head
 }
 
 /// Runs legacy natural-language UI steps through Agent SDK and the existing
-/// runner's selector-based tap API. The runner does not expose swipe, typing,
-/// scrolling, or coordinate touch, so the model must report those as
-/// unsupported rather than pretending they completed.
+/// runner's tap and pointer-gesture APIs. The runner does not expose typing,
+/// so the model must report it as unsupported rather than pretending it
+/// completed.
 async fn run_page_steps(
   client: &ModelClient,
   page: &mut Page,
@@ -514,12 +549,7 @@ async fn run_page_step(
         history.push(format!("waited {duration_ms} ms"));
       }
       PageActionKind::Tap => {
-        let Some(selector) = action
-          .selector
-          .as_deref()
-          .map(str::trim)
-          .filter(|selector| !selector.is_empty())
-        else {
+        let Some(selector) = action.selector() else {
           history.push("tap failed: selector was empty".to_string());
           continue;
         };
@@ -542,12 +572,96 @@ async fn run_page_step(
           .map_err(|_| step_timeout(step, timeout))??;
         history.push(format!("tapped {selector}"));
       }
+      PageActionKind::Touch
+      | PageActionKind::LongPress
+      | PageActionKind::Swipe
+      | PageActionKind::Scroll => {
+        let name = action.action.as_str();
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+          return Err(step_timeout(step, timeout));
+        }
+        let target = tokio::time::timeout(remaining, gesture_target(page, &action))
+          .await
+          .map_err(|_| step_timeout(step, timeout))??;
+        let (point, label) = match target {
+          Ok(target) => target,
+          Err(message) => {
+            history.push(format!("{name} failed: {message}"));
+            continue;
+          }
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match tokio::time::timeout(remaining, run_gesture(page, &action, point))
+          .await
+          .map_err(|_| step_timeout(step, timeout))?
+        {
+          Ok(description) => history.push(format!("{description} at {label}")),
+          Err(message) => history.push(format!("{name} failed: {message}")),
+        }
+      }
     }
   }
 
   Err(HeadlessPageError::TooManyActions(step.to_string()))
 }
 
+/// Resolves where a pointer gesture starts: the center of the selected node,
+/// or explicit page coordinates. The inner error is reported back to the
+/// model as history instead of failing the step.
+async fn gesture_target(
+  page: &mut Page,
+  action: &PageAction,
+) -> Result<Result<(Point, String), String>, HeadlessPageError> {
+  if let Some(selector) = action.selector() {
+    let Some(element) = page.locator(selector).await? else {
+      return Ok(Err(format!("selector did not match a node: {selector}")));
+    };
+    let center = element.bounding_box().await?.center();
+    return Ok(Ok((center, selector.to_string())));
+  }
+  match (action.x, action.y) {
+    (Some(x), Some(y)) => Ok(Ok((Point::new(x, y), format!("({x}, {y})")))),
+    _ => Ok(Err("set either selector or both x and y".to_string())),
+  }
+}
+
+async fn run_gesture(page: &Page, action: &PageAction, point: Point) -> Result<String, String> {
+  let delta_x = action.delta_x.unwrap_or(0.0);
+  let delta_y = action.delta_y.unwrap_or(0.0);
+  match action.action {
+    PageActionKind::Touch => {
+      page.touch_at(point).await;
+      Ok("touched".to_string())
+    }
+    PageActionKind::LongPress => {
+      let duration = action
+        .duration_ms
+        .map(|duration_ms| Duration::from_millis(duration_ms.min(MAX_WAIT_MS)));
+      page.long_press(point, duration).await;
+      Ok("long-pressed".to_string())
+    }
+    PageActionKind::Swipe | PageActionKind::Scroll if delta_x == 0.0 && delta_y == 0.0 => {
+      Err("deltaX and deltaY were both empty".to_string())
+    }
+    PageActionKind::Swipe => {
+      let to = Point::new(point.x + delta_x, point.y + delta_y);
+      page.swipe(point, to, GestureOptions::default()).await;
+      Ok(format!("swiped by ({delta_x}, {delta_y})"))
+    }
+    PageActionKind::Scroll => {
+      page
+        .scroll_by(point, delta_x, delta_y, GestureOptions::default())
+        .await;
+      Ok(format!("scrolled content by ({delta_x}, {delta_y})"))
+    }
+    PageActionKind::Done
+    | PageActionKind::Tap
+    | PageActionKind::Unsupported
+    | PageActionKind::Wait => Err("not a pointer gesture".to_string()),
+  }
+}
+
 fn build_step_prompt(step: &str, dom: &str, history: &[String]) -> String {
   let dom = dom.chars().take(MAX_DOM_CHARS).collect::<String>();
   let history = if history.is_empty() {
@@ -567,11 +681,15 @@ Current Lynx DOM:
 
 Choose exactly one next action:
 - tap: set selector to a CSS selector that exists verbatim in the DOM. Prefer #id, then a unique .class, then a tag.
+- touch: press and release at the center of selector, or at page coordinates x and y when no selector fits.
+- long_press: hold at selector or x/y; set durationMs to override the default long-press hold.
+- swipe: start at selector or x/y and move the finger by deltaX/deltaY page pixels, releasing with velocity (carousels, pagers, dismiss gestures).
+- scroll: start inside the scroll container at selector or x/y and scroll its content by deltaX/deltaY page pixels; positive deltaY reveals content further down.
 - wait: set durationMs between 0 and {MAX_WAIT_MS} when the UI needs time to settle.
 - done: use only when the requested step is visibly complete.
-- unsupported: use for swipe, scrolling, typing, coordinate touch, or any capability not exposed by the current runner.
+- unsupported: use for typing or any capability not exposed by the current runner.
 
-Always provide reason. Set unused selector and durationMs fields to null."#
+Always provide reason. Set unused selector, x, y, deltaX, deltaY, and durationMs fields to null."#
   )
 }
 
@@ -582,9 +700,13 @@ fn page_action_schema() -> serde_json::Value {
     "properties": {
       "action": {
         "type": "string",
-        "enum": ["tap", "wait", "done", "unsupported"]
+        "enum": ["tap", "touch", "long_press", "swipe", "scroll", "wait", "done", "unsupported"]
       },
       "selector": { "type": ["string", "null"] },
+      "x": { "type": ["number", "null"] },
+      "y": { "type": ["number", "null"] },
+      "deltaX": { "type": ["number", "null"] },
+      "deltaY": { "type": ["number", "null"] },
       "durationMs": {
         "type": ["integer", "null"],
         "minimum": 0,
@@ -592,7 +714,7 @@ fn page_action_schema() -> serde_json::Value {
       },
       "reason": { "type": "string" }
     },
-    "required": ["action", "selector", "durationMs", "reason"]
+    "required": ["action", "selector", "x", "y", "deltaX", "deltaY", "durationMs", "reason"]
   })
 }
 
@@ -680,8 +802,35 @@ mod tests {
     let prompt = build_step_prompt("Swipe left", "<view class=\"card\"></view>", &[]);
     assert!(prompt.contains("Swipe left"));
     assert!(prompt.contains(".class"));
-    assert!(prompt.contains("swipe"));
-    assert!(prompt.contains("unsupported"));
+    assert!(prompt.contains("- swipe:"));
+    assert!(prompt.contains("- scroll:"));
+    assert!(prompt.contains("unsupported: use for typing"));
+  }
+
+  #[test]
+  fn gesture_actions_parse_from_the_schema_shape() {
+    let action: PageAction = serde_json::from_str(
+      r#"{"action":"long_press","selector":null,"x":12.5,"y":40,"deltaX":null,"deltaY":null,"durationMs":900,"reason":"hold"}"#,
+    )
+    .unwrap();
+    assert!(matches!(action.action, PageActionKind::LongPress));
+    assert_eq!((action.x, action.y), (Some(12.5), Some(40.0)));
+    assert_eq!(action.selector(), None);
+
+    let schema = page_action_schema();
+    let actions = schema["properties"]["action"]["enum"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|action| action.as_str().unwrap())
+      .collect::<Vec<_>>();
+    for action in &actions {
+      let kind: PageActionKind = serde_json::from_value(json!(action)).unwrap();
+      assert_eq!(kind.as_str(), *action);
+    }
+    let properties = schema["properties"].as_object().unwrap();
+    let required = schema["required"].as_array().unwrap();
+    assert_eq!(required.len(), properties.len());
   }
 
   #[test]
@@ -9,7 +9,8 @@ use std::time::Duration;
 
 use base64::prelude::{Engine, BASE64_STANDARD, BASE64_STANDARD_NO_PAD};
 use image::imageops::{self, FilterType};
-use image::{DynamicImage, GrayImage, ImageFormat, ImageReader, Limits, Rgba, RgbaImage};
+use image::{DynamicImage, GrayImage, ImageFormat, ImageReader, Limits, RgbaImage};
+use lynx_headless_rust_test_runner::{try_diff_rgba_blocks, BlockDiffError, BlockDiffOptions};
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
 use thiserror::Error;
@@ -148,12 +149,6 @@ struct CompareImagesOutput {
   result: CompareResult,
 }
 
-#[derive(Debug, Clone)]
-struct BlockStats {
-  different_pixels: u32,
-  pixels: u32,
-}
-
 #[derive(Debug, Clone, Default)]
 struct CancellationFlag {
   cancelled: Arc<AtomicBool>,
@@ -656,72 +651,39 @@ fn compare_images(
   }
 
   let options = options.cloned().unwrap_or_default();
-  let block_size = options.block_size.unwrap_or(DEFAULT_BLOCK_SIZE).max(1);
-  let threshold = options.threshold.unwrap_or(DEFAULT_THRESHOLD);
-  let pixel_tolerance = options.pixel_tolerance.unwrap_or(DEFAULT_PIXEL_TOLERANCE);
-  let pixel_tolerance_squared = pixel_tolerance * pixel_tolerance;
   let reference = resize_to_exact_rgba(&reference, width, height);
   cancellation.check()?;
   let rendered = resize_to_exact_rgba(&rendered, width, height);
   cancellation.check()?;
-  let block_columns = width.div_ceil(block_size);
-  let block_rows = height.div_ceil(block_size);
-  let mut block_stats = vec![
-    BlockStats {
-      different_pixels: 0,
-      pixels: 0,
-    };
-    (block_columns * block_rows) as usize
-  ];
-  let mut diff = RgbaImage::new(width, height);
-
-  for y in 0..height {
-    cancellation.check()?;
-    for x in 0..width {
-      let reference_pixel = reference.get_pixel(x, y).0;
-      let rendered_pixel = rendered.get_pixel(x, y).0;
-      let distance_squared = normalized_rgba_distance_squared(&reference_pixel, &rendered_pixel);
-      let block_index = ((y / block_size) * block_columns + (x / block_size)) as usize;
-      let block = &mut block_stats[block_index];
-      block.pixels += 1;
-      if distance_squared > pixel_tolerance_squared {
-        block.different_pixels += 1;
-        diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
-      } else {
-        diff.put_pixel(x, y, Rgba(rendered_pixel));
-      }
-    }
-  }
-
-  let mut different_blocks = 0;
-  for block_y in 0..block_rows {
-    cancellation.check()?;
-    for block_x in 0..block_columns {
-      let block = &block_stats[(block_y * block_columns + block_x) as usize];
-      if block.pixels == 0 {
-        continue;
-      }
-      let diff_ratio = block.different_pixels as f64 / block.pixels as f64;
-      if diff_ratio > threshold {
-        different_blocks += 1;
-      }
-    }
-  }
+  let block_diff = try_diff_rgba_blocks(
+    reference.as_raw(),
+    rendered.as_raw(),
+    width as usize,
+    height as usize,
+    &BlockDiffOptions {
+      pixel_tolerance: options.pixel_tolerance.unwrap_or(DEFAULT_PIXEL_TOLERANCE),
+      block_size: options.block_size.unwrap_or(DEFAULT_BLOCK_SIZE) as usize,
+      block_threshold: options.threshold.unwrap_or(DEFAULT_THRESHOLD),
+    },
+    || cancellation.check(),
+  )
+  .map_err(|error| match error {
+    BlockDiffError::Check(error) => error,
+    error => image_operation_error(VisualEvaluationErrorCode::ImageCompareError, error),
+  })?;
 
-  let total_blocks = (block_columns * block_rows) as usize;
   cancellation.check()?;
+  let similarity = block_diff.similarity();
+  let diff = RgbaImage::from_raw(width, height, block_diff.diff_rgba)
+    .expect("the diff has the size of the compared images");
   let diff_png = encode_rgba_png(&diff)?;
   cancellation.check()?;
   Ok(CompareImagesOutput {
     diff_png,
     result: CompareResult {
-      different_blocks,
-      similarity: if total_blocks == 0 {
-        1.0
-      } else {
-        (1.0 - different_blocks as f64 / total_blocks as f64).clamp(0.0, 1.0)
-      },
-      total_blocks,
+      different_blocks: block_diff.different_blocks,
+      similarity,
+      total_blocks: block_diff.total_blocks,
     },
   })
 }
@@ -947,15 +909,6 @@ fn gray_pixel(image: &GrayImage, x: u32, y: u32) -> f64 {
   image.get_pixel(x, y).0[0] as f64
 }
 
-fn normalized_rgba_distance_squared(reference: &[u8; 4], rendered: &[u8; 4]) -> f64 {
-  let mut sum_squares = 0.0;
-  for channel in 0..4 {
-    let delta = reference[channel] as f64 - rendered[channel] as f64;
-    sum_squares += delta * delta;
-  }
-  sum_squares / (4.0 * 255.0 * 255.0)
-}
-
 fn round_positive(value: f64) -> u32 {
   value.round().max(1.0) as u32
 }
@@ -1039,6 +992,8 @@ mod tests {
   use std::future::Future;
   use std::task::Poll;
 
+  use image::Rgba;
+
   use super::*;
 
   #[tokio::test]
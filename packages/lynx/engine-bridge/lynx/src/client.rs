@@ -0,0 +1,576 @@
+use crate::sys;
+use crate::{c_str_to_string, Env, Error, Result};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{c_char, c_int};
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::ptr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+
+/// A lifecycle notification delivered by a Lynx view client.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ViewEvent {
+  PageStart {
+    url: String,
+  },
+  LoadSuccess,
+  FirstScreen,
+  PageUpdated,
+  DataUpdated,
+  RuntimeReady,
+  ReceivedError {
+    code: i32,
+    message: String,
+  },
+  TimingSetup {
+    timing_info: String,
+  },
+  TimingUpdate {
+    timing_info: String,
+    update_timing: String,
+    update_flag: String,
+  },
+  EnterForeground,
+  EnterBackground,
+  FrameTiming {
+    frame_start_time_in_ns: i64,
+    frame_finish_time_in_ns: i64,
+  },
+  Destroy,
+}
+
+/// Receives lifecycle callbacks for a headless view.
+///
+/// Every method defaults to a no-op, so implementations only override the
+/// callbacks they observe. Callbacks run on the Lynx thread that reports them.
+pub trait ViewClient: Send + 'static {
+  fn on_page_start(&mut self, _url: &str) {}
+  fn on_load_success(&mut self) {}
+  fn on_first_screen(&mut self) {}
+  fn on_page_updated(&mut self) {}
+  fn on_data_updated(&mut self) {}
+  fn on_runtime_ready(&mut self) {}
+  fn on_received_error(&mut self, _code: i32, _message: &str) {}
+  fn on_timing_setup(&mut self, _timing_info: &str) {}
+  fn on_timing_update(&mut self, _timing_info: &str, _update_timing: &str, _update_flag: &str) {}
+  fn on_enter_foreground(&mut self) {}
+  fn on_enter_background(&mut self) {}
+  fn on_frame_timing(&mut self, _frame_start_time_in_ns: i64, _frame_finish_time_in_ns: i64) {}
+  fn on_destroy(&mut self) {}
+}
+
+/// Creates a [`ViewClient`] that forwards every callback into [`ViewEvents`].
+///
+/// Register the returned sender with [`crate::HeadlessViewBuilder::view_client`]
+/// and await lifecycle events from the receiver. The stream ends after the
+/// sender is released together with its view.
+pub fn view_event_channel() -> (ViewEventSender, ViewEvents) {
+  let shared = Arc::new(Mutex::new(EventQueue::default()));
+  (
+    ViewEventSender {
+      shared: shared.clone(),
+    },
+    ViewEvents { shared },
+  )
+}
+
+#[derive(Default)]
+struct EventQueue {
+  events: VecDeque<ViewEvent>,
+  waker: Option<Waker>,
+  closed: bool,
+}
+
+pub struct ViewEventSender {
+  shared: Arc<Mutex<EventQueue>>,
+}
+
+impl ViewEventSender {
+  fn send(&self, event: ViewEvent) {
+    let mut queue = self.shared.lock().expect("view event queue lock poisoned");
+    queue.events.push_back(event);
+    if let Some(waker) = queue.waker.take() {
+      waker.wake();
+    }
+  }
+}
+
+impl Drop for ViewEventSender {
+  fn drop(&mut self) {
+    if let Ok(mut queue) = self.shared.lock() {
+      queue.closed = true;
+      if let Some(waker) = queue.waker.take() {
+        waker.wake();
+      }
+    }
+  }
+}
+
+impl ViewClient for ViewEventSender {
+  fn on_page_start(&mut self, url: &str) {
+    self.send(ViewEvent::PageStart { url: url.into() });
+  }
+
+  fn on_load_success(&mut self) {
+    self.send(ViewEvent::LoadSuccess);
+  }
+
+  fn on_first_screen(&mut self) {
+    self.send(ViewEvent::FirstScreen);
+  }
+
+  fn on_page_updated(&mut self) {
+    self.send(ViewEvent::PageUpdated);
+  }
+
+  fn on_data_updated(&mut self) {
+    self.send(ViewEvent::DataUpdated);
+  }
+
+  fn on_runtime_ready(&mut self) {
+    self.send(ViewEvent::RuntimeReady);
+  }
+
+  fn on_received_error(&mut self, code: i32, message: &str) {
+    self.send(ViewEvent::ReceivedError {
+      code,
+      message: message.into(),
+    });
+  }
+
+  fn on_timing_setup(&mut self, timing_info: &str) {
+    self.send(ViewEvent::TimingSetup {
+      timing_info: timing_info.into(),
+    });
+  }
+
+  fn on_timing_update(&mut self, timing_info: &str, update_timing: &str, update_flag: &str) {
+    self.send(ViewEvent::TimingUpdate {
+      timing_info: timing_info.into(),
+      update_timing: update_timing.into(),
+      update_flag: update_flag.into(),
+    });
+  }
+
+  fn on_enter_foreground(&mut self) {
+    self.send(ViewEvent::EnterForeground);
+  }
+
+  fn on_enter_background(&mut self) {
+    self.send(ViewEvent::EnterBackground);
+  }
+
+  fn on_frame_timing(&mut self, frame_start_time_in_ns: i64, frame_finish_time_in_ns: i64) {
+    self.send(ViewEvent::FrameTiming {
+      frame_start_time_in_ns,
+      frame_finish_time_in_ns,
+    });
+  }
+
+  fn on_destroy(&mut self) {
+    self.send(ViewEvent::Destroy);
+  }
+}
+
+/// The receiving half of [`view_event_channel`].
+///
+/// Events are buffered without a bound, so a receiver that is polled late
+/// still observes every callback in order.
+pub struct ViewEvents {
+  shared: Arc<Mutex<EventQueue>>,
+}
+
+impl ViewEvents {
+  /// Waits for the next lifecycle event, or `None` once the sender is gone and
+  /// every buffered event has been consumed.
+  pub fn recv(&mut self) -> RecvViewEvent<'_> {
+    RecvViewEvent { events: self }
+  }
+
+  /// Returns a buffered event without waiting.
+  pub fn try_recv(&mut self) -> Option<ViewEvent> {
+    self
+      .shared
+      .lock()
+      .expect("view event queue lock poisoned")
+      .events
+      .pop_front()
+  }
+
+  /// Removes and returns every buffered event.
+  pub fn drain(&mut self) -> Vec<ViewEvent> {
+    self
+      .shared
+      .lock()
+      .expect("view event queue lock poisoned")
+      .events
+      .drain(..)
+      .collect()
+  }
+
+  /// Polls for the next event, registering `cx` for wakeup when none is
+  /// buffered. This is the `Stream::poll_next` contract.
+  pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<ViewEvent>> {
+    let mut queue = self.shared.lock().expect("view event queue lock poisoned");
+    if let Some(event) = queue.events.pop_front() {
+      return Poll::Ready(Some(event));
+    }
+    if queue.closed {
+      return Poll::Ready(None);
+    }
+    queue.waker = Some(cx.waker().clone());
+    Poll::Pending
+  }
+}
+
+/// Future returned by [`ViewEvents::recv`].
+pub struct RecvViewEvent<'a> {
+  events: &'a mut ViewEvents,
+}
+
+impl Future for RecvViewEvent<'_> {
+  type Output = Option<ViewEvent>;
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    self.events.poll_next(cx)
+  }
+}
+
+struct ViewClientContext {
+  client: Mutex<Box<dyn ViewClient>>,
+}
+
+fn client_contexts() -> &'static Mutex<HashMap<usize, Arc<ViewClientContext>>> {
+  static CONTEXTS: OnceLock<Mutex<HashMap<usize, Arc<ViewClientContext>>>> = OnceLock::new();
+  CONTEXTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// A runtime `lynx_view_client_t` that dispatches to a Rust [`ViewClient`].
+///
+/// The view that registers this client keeps it alive until the view itself
+/// is released, so callbacks never observe a dangling client.
+pub struct LynxViewClient {
+  sys: Arc<sys::LoadedLibrary>,
+  raw: *mut sys::lynx_view_client_t,
+}
+
+impl LynxViewClient {
+  pub fn new(env: &Env, client: impl ViewClient) -> Result<Self> {
+    let sys = env.sys().clone();
+    let raw = unsafe { (sys.lynx_view_client_create)(ptr::null_mut()) };
+    if raw.is_null() {
+      return Err(Error::NullPointer {
+        operation: "create view client",
+      });
+    }
+
+    client_contexts()
+      .lock()
+      .expect("view client context lock poisoned")
+      .insert(
+        raw as usize,
+        Arc::new(ViewClientContext {
+          client: Mutex::new(Box::new(client)),
+        }),
+      );
+
+    unsafe {
+      (sys.lynx_view_client_bind_on_page_start)(raw, Some(on_page_start));
+      (sys.lynx_view_client_bind_on_load_success)(raw, Some(on_load_success));
+      (sys.lynx_view_client_bind_on_first_screen)(raw, Some(on_first_screen));
+      (sys.lynx_view_client_bind_on_page_updated)(raw, Some(on_page_updated));
+      (sys.lynx_view_client_bind_on_data_updated)(raw, Some(on_data_updated));
+      (sys.lynx_view_client_bind_on_destroy)(raw, Some(on_destroy));
+      (sys.lynx_view_client_bind_on_runtime_ready)(raw, Some(on_runtime_ready));
+      (sys.lynx_view_client_bind_on_received_error)(raw, Some(on_received_error));
+      (sys.lynx_view_client_bind_on_timing_setup)(raw, Some(on_timing_setup));
+      (sys.lynx_view_client_bind_on_timing_update)(raw, Some(on_timing_update));
+      (sys.lynx_view_client_bind_on_enter_foreground)(raw, Some(on_enter_foreground));
+      (sys.lynx_view_client_bind_on_enter_background)(raw, Some(on_enter_background));
+      (sys.lynx_view_client_bind_on_frame_timing)(raw, Some(on_frame_timing));
+    }
+
+    Ok(Self { sys, raw })
+  }
+
+  pub(crate) fn raw(&self) -> *mut sys::lynx_view_client_t {
+    self.raw
+  }
+}
+
+impl Drop for LynxViewClient {
+  fn drop(&mut self) {
+    if !self.raw.is_null() {
+      unsafe {
+        (self.sys.lynx_view_client_release)(self.raw);
+      }
+      let _ = catch_unwind(AssertUnwindSafe(|| {
+        client_contexts()
+          .lock()
+          .expect("view client context lock poisoned")
+          .remove(&(self.raw as usize));
+      }));
+      self.raw = ptr::null_mut();
+    }
+  }
+}
+
+unsafe fn with_client(client: *mut sys::lynx_view_client_t, f: impl FnOnce(&mut dyn ViewClient)) {
+  let Some(context) = client_contexts()
+    .lock()
+    .expect("view client context lock poisoned")
+    .get(&(client as usize))
+    .cloned()
+  else {
+    return;
+  };
+  let _ = catch_unwind(AssertUnwindSafe(|| {
+    if let Ok(mut client) = context.client.lock() {
+      f(client.as_mut());
+    }
+  }));
+}
+
+unsafe extern "C" fn on_page_start(client: *mut sys::lynx_view_client_t, url: *const c_char) {
+  let url = c_str_to_string(url);
+  with_client(client, |client| client.on_page_start(&url));
+}
+
+unsafe extern "C" fn on_load_success(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_load_success());
+}
+
+unsafe extern "C" fn on_first_screen(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_first_screen());
+}
+
+unsafe extern "C" fn on_page_updated(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_page_updated());
+}
+
+unsafe extern "C" fn on_data_updated(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_data_updated());
+}
+
+unsafe extern "C" fn on_destroy(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_destroy());
+}
+
+unsafe extern "C" fn on_runtime_ready(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_runtime_ready());
+}
+
+unsafe extern "C" fn on_received_error(
+  client: *mut sys::lynx_view_client_t,
+  error_code: c_int,
+  message: *const c_char,
+) {
+  let message = c_str_to_string(message);
+  with_client(client, |client| {
+    client.on_received_error(error_code, &message)
+  });
+}
+
+unsafe extern "C" fn on_timing_setup(
+  client: *mut sys::lynx_view_client_t,
+  timing_info: *const c_char,
+) {
+  let timing_info = c_str_to_string(timing_info);
+  with_client(client, |client| client.on_timing_setup(&timing_info));
+}
+
+unsafe extern "C" fn on_timing_update(
+  client: *mut sys::lynx_view_client_t,
+  timing_info: *const c_char,
+  update_timing: *const c_char,
+  update_flag: *const c_char,
+) {
+  let timing_info = c_str_to_string(timing_info);
+  let update_timing = c_str_to_string(update_timing);
+  let update_flag = c_str_to_string(update_flag);
+  with_client(client, |client| {
+    client.on_timing_update(&timing_info, &update_timing, &update_flag)
+  });
+}
+
+unsafe extern "C" fn on_enter_foreground(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_enter_foreground());
+}
+
+unsafe extern "C" fn on_enter_background(client: *mut sys::lynx_view_client_t) {
+  with_client(client, |client| client.on_enter_background());
+}
+
+unsafe extern "C" fn on_frame_timing(
+  client: *mut sys::lynx_view_client_t,
+  frame_start_time_in_ns: i64,
+  frame_finish_time_in_ns: i64,
+) {
+  with_client(client, |client| {
+    client.on_frame_timing(frame_start_time_in_ns, frame_finish_time_in_ns)
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::ffi::CString;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::task::Wake;
+
+  #[test]
+  fn client_callbacks_without_context_are_ignored() {
+    unsafe {
+      on_page_start(ptr::null_mut(), ptr::null());
+      on_load_success(ptr::null_mut());
+      on_first_screen(ptr::null_mut());
+      on_page_updated(ptr::null_mut());
+      on_data_updated(ptr::null_mut());
+      on_destroy(ptr::null_mut());
+      on_runtime_ready(ptr::null_mut());
+      on_received_error(ptr::null_mut(), 1, ptr::null());
+      on_timing_setup(ptr::null_mut(), ptr::null());
+      on_timing_update(ptr::null_mut(), ptr::null(), ptr::null(), ptr::null());
+      on_enter_foreground(ptr::null_mut());
+      on_enter_background(ptr::null_mut());
+      on_frame_timing(ptr::null_mut(), 1, 2);
+    }
+  }
+
+  #[test]
+  fn client_callbacks_dispatch_to_event_channel() {
+    let (sender, mut events) = view_event_channel();
+
+    with_client_context(sender, |raw| unsafe {
+      let url = CString::new("memory://main.lynx.bundle").unwrap();
+      let message = CString::new("boom").unwrap();
+      let timing = CString::new("{\"fcp\":1}").unwrap();
+      let flag = CString::new("__lynx_timing_actual_fmp").unwrap();
+
+      on_page_start(raw, url.as_ptr());
+      on_runtime_ready(raw);
+      on_timing_setup(raw, timing.as_ptr());
+      on_first_screen(raw);
+      on_load_success(raw);
+      on_received_error(raw, 201, message.as_ptr());
+      on_timing_update(raw, timing.as_ptr(), timing.as_ptr(), flag.as_ptr());
+      on_data_updated(raw);
+      on_page_updated(raw);
+      on_frame_timing(raw, 10, 20);
+      on_enter_background(raw);
+      on_enter_foreground(raw);
+      on_destroy(raw);
+    });
+
+    assert_eq!(
+      events.drain(),
+      vec![
+        ViewEvent::PageStart {
+          url: "memory://main.lynx.bundle".into(),
+        },
+        ViewEvent::RuntimeReady,
+        ViewEvent::TimingSetup {
+          timing_info: "{\"fcp\":1}".into(),
+        },
+        ViewEvent::FirstScreen,
+        ViewEvent::LoadSuccess,
+        ViewEvent::ReceivedError {
+          code: 201,
+          message: "boom".into(),
+        },
+        ViewEvent::TimingUpdate {
+          timing_info: "{\"fcp\":1}".into(),
+          update_timing: "{\"fcp\":1}".into(),
+          update_flag: "__lynx_timing_actual_fmp".into(),
+        },
+        ViewEvent::DataUpdated,
+        ViewEvent::PageUpdated,
+        ViewEvent::FrameTiming {
+          frame_start_time_in_ns: 10,
+          frame_finish_time_in_ns: 20,
+        },
+        ViewEvent::EnterBackground,
+        ViewEvent::EnterForeground,
+        ViewEvent::Destroy,
+      ]
+    );
+  }
+
+  #[test]
+  fn panicking_client_does_not_unwind_into_callback() {
+    struct PanickingClient;
+
+    impl ViewClient for PanickingClient {
+      fn on_first_screen(&mut self) {
+        panic!("client panicked");
+      }
+    }
+
+    with_client_context(PanickingClient, |raw| unsafe {
+      on_first_screen(raw);
+    });
+  }
+
+  #[test]
+  fn view_events_wake_pending_reader_and_close_with_sender() {
+    let (mut sender, mut events) = view_event_channel();
+    let wakes = Arc::new(CountingWaker::default());
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(events.poll_next(&mut cx), Poll::Pending);
+    sender.on_load_success();
+    assert_eq!(wakes.count.load(Ordering::SeqCst), 1);
+    assert_eq!(
+      Pin::new(&mut events.recv()).poll(&mut cx),
+      Poll::Ready(Some(ViewEvent::LoadSuccess))
+    );
+
+    assert_eq!(events.poll_next(&mut cx), Poll::Pending);
+    drop(sender);
+    assert_eq!(wakes.count.load(Ordering::SeqCst), 2);
+    assert_eq!(events.poll_next(&mut cx), Poll::Ready(None));
+    assert_eq!(events.try_recv(), None);
+  }
+
+  fn with_client_context<T>(
+    client: impl ViewClient,
+    f: impl FnOnce(*mut sys::lynx_view_client_t) -> T,
+  ) -> T {
+    let token_ptr = Box::into_raw(Box::new(0_u8));
+    let raw = token_ptr.cast::<sys::lynx_view_client_t>();
+    client_contexts()
+      .lock()
+      .expect("view client context lock poisoned")
+      .insert(
+        raw as usize,
+        Arc::new(ViewClientContext {
+          client: Mutex::new(Box::new(client)),
+        }),
+      );
+
+    let result = f(raw);
+
+    client_contexts()
+      .lock()
+      .expect("view client context lock poisoned")
+      .remove(&(raw as usize));
+    unsafe {
+      drop(Box::from_raw(token_ptr));
+    }
+    result
+  }
+
+  #[derive(Default)]
+  struct CountingWaker {
+    count: AtomicUsize,
+  }
+
+  impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+      self.count.fetch_add(1, Ordering::SeqCst);
+    }
+  }
+}
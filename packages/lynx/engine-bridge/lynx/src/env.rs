@@ -1,3 +1,4 @@
+use crate::module::{retain_process_module, NativeModule, NativeModuleRegistration};
 use crate::sys;
 use crate::{c_str_to_string, c_string, Result};
 use std::ffi::c_void;
@@ -82,6 +83,22 @@ impl Env {
     unsafe { (self.library.lynx_env_is_logbox_enabled)() != 0 }
   }
 
+  /// Registers a Rust [`NativeModule`] for every view in the process.
+  ///
+  /// The runtime has no unregister call, so the module state lives until the
+  /// process exits. Use [`crate::HeadlessViewBuilder::native_module`] for
+  /// state that should end with a view.
+  pub fn register_native_module(&self, name: &str, module: impl NativeModule) -> Result<()> {
+    let registration = NativeModuleRegistration::new(self, name, module)?;
+    let name = registration.name.clone();
+    let creator = registration.creator();
+    let opaque = retain_process_module(registration);
+    unsafe {
+      (self.library.lynx_env_register_native_module)(name.as_ptr(), Some(creator), opaque);
+    }
+    Ok(())
+  }
+
   /// Registers a process-wide native module with the loaded Lynx SDK.
   ///
   /// # Safety
@@ -102,6 +119,10 @@ impl Env {
 
   /// Registers a process-wide extension module with the loaded Lynx SDK.
   ///
+  /// There is no safe counterpart: the C ABI has no constructor for
+  /// `lynx_extension_module_t`, so a Rust type cannot build one. Use
+  /// a [`NativeModule`](crate::NativeModule) to expose Rust methods instead.
+  ///
   /// # Safety
   ///
   /// `creator` and `opaque` must obey the extension module ABI expected by
@@ -2,23 +2,32 @@
 //!
 //! The crate does not link against `libLynx_clay` at build time. Load a runtime
 //! with [`Env::load`] or [`Env::load_from_path`], then compose a
-//! [`WindowlessRenderer`], optional [`ResourceFetcher`], and [`HeadlessView`].
+//! [`WindowlessRenderer`], optional [`ResourceFetcher`], optional
+//! [`ViewClient`] lifecycle observers, optional [`NativeModule`]s, and
+//! [`HeadlessView`].
 //!
 //! Raw C ABI bindings are available in [`sys`] for integration code that needs
 //! to call a symbol not wrapped by the safe API yet.
 
 mod buffer;
+mod client;
 mod env;
 mod error;
 mod group;
+mod module;
 mod renderer;
 mod resource;
 pub mod sys;
 mod view;
 
+pub use client::{
+  view_event_channel, LynxViewClient, RecvViewEvent, ViewClient, ViewEvent, ViewEventSender,
+  ViewEvents,
+};
 pub use env::Env;
 pub use error::{Error, Result};
 pub use group::LynxGroup;
+pub use module::{NativeMethods, NativeModule};
 pub use renderer::{
   run_global_ui_task, set_global_ui_task_runner, AcceleratedPaintInfo, AcceleratedRenderer,
   GlRenderer, GlobalUiTaskRunner, NoopHost, SoftwareFrame, SoftwareRenderer, Task, WindowlessHost,
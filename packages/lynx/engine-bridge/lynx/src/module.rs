@@ -0,0 +1,399 @@
+use crate::sys;
+use crate::{c_string, Env, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::ffi::{c_char, c_void, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+const MAX_METHOD_ARGS: usize = 16;
+
+/// A native module backed by Rust methods.
+///
+/// Arguments and return values cross the JavaScript boundary as JSON, so
+/// functions, `undefined`, and other non-JSON values arrive as
+/// [`Value::Null`]. Returning `Err` throws a JavaScript `Error` with the
+/// message. Methods are synchronous and run on the JavaScript thread that
+/// calls them.
+pub trait NativeModule: Send + 'static {
+  /// Names exposed as methods on the JavaScript module object.
+  fn method_names(&self) -> Vec<String>;
+
+  fn call(&mut self, method: &str, args: Vec<Value>) -> std::result::Result<Value, String>;
+}
+
+type NativeMethod = Box<dyn FnMut(Vec<Value>) -> std::result::Result<Value, String> + Send>;
+
+/// A [`NativeModule`] assembled from closures, one per method name.
+///
+/// ```
+/// use lynx::NativeMethods;
+/// use serde_json::json;
+///
+/// let storage = NativeMethods::new()
+///   .method("getItem", |_args| Ok(json!("stored")))
+///   .method("clear", |_args| Ok(json!(null)));
+/// # let _ = storage;
+/// ```
+#[derive(Default)]
+pub struct NativeMethods {
+  methods: BTreeMap<String, NativeMethod>,
+}
+
+impl NativeMethods {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn method<F>(mut self, name: impl Into<String>, method: F) -> Self
+  where
+    F: FnMut(Vec<Value>) -> std::result::Result<Value, String> + Send + 'static,
+  {
+    self.methods.insert(name.into(), Box::new(method));
+    self
+  }
+}
+
+impl NativeModule for NativeMethods {
+  fn method_names(&self) -> Vec<String> {
+    self.methods.keys().cloned().collect()
+  }
+
+  fn call(&mut self, method: &str, args: Vec<Value>) -> std::result::Result<Value, String> {
+    match self.methods.get_mut(method) {
+      Some(method) => method(args),
+      None => Err(format!("unknown native module method: {method}")),
+    }
+  }
+}
+
+type SharedModule = Arc<Mutex<Box<dyn NativeModule>>>;
+
+struct MethodBinding {
+  module: SharedModule,
+  name: CString,
+}
+
+/// A Rust [`NativeModule`] prepared for registration with the runtime.
+///
+/// The registration owns the Rust state that JavaScript functions point at.
+/// View-scoped registrations are kept alive by their view; process-wide
+/// registrations live until the process exits.
+pub(crate) struct NativeModuleRegistration {
+  pub(crate) name: CString,
+  // Boxed so the opaque pointer handed to the runtime survives moves of the
+  // registration itself.
+  bindings: Box<ModuleBindings>,
+}
+
+struct ModuleBindings {
+  methods: Vec<MethodBinding>,
+}
+
+impl NativeModuleRegistration {
+  pub(crate) fn new(env: &Env, name: &str, module: impl NativeModule) -> Result<Self> {
+    let name = c_string(name, "native_module_name")?;
+    let sys = env.sys().clone();
+    sys.napi()?;
+    let _ = CALLBACK_RUNTIME.set(sys);
+    let method_names = module.method_names();
+    let module: SharedModule = Arc::new(Mutex::new(Box::new(module)));
+    let methods = method_names
+      .iter()
+      .map(|method| {
+        Ok(MethodBinding {
+          module: module.clone(),
+          name: c_string(method, "native_module_method")?,
+        })
+      })
+      .collect::<Result<_>>()?;
+    Ok(Self {
+      name,
+      bindings: Box::new(ModuleBindings { methods }),
+    })
+  }
+
+  pub(crate) fn creator(&self) -> sys::napi_module_creator {
+    create_native_module
+  }
+
+  pub(crate) fn opaque(&self) -> *mut c_void {
+    (self.bindings.as_ref() as *const ModuleBindings)
+      .cast_mut()
+      .cast::<c_void>()
+  }
+}
+
+/// Keeps process-wide registrations alive because the runtime has no
+/// unregister call for them.
+pub(crate) fn retain_process_module(registration: NativeModuleRegistration) -> *mut c_void {
+  static PROCESS_MODULES: OnceLock<Mutex<Vec<NativeModuleRegistration>>> = OnceLock::new();
+  let opaque = registration.opaque();
+  PROCESS_MODULES
+    .get_or_init(|| Mutex::new(Vec::new()))
+    .lock()
+    .expect("native module registry lock poisoned")
+    .push(registration);
+  opaque
+}
+
+unsafe extern "C" fn create_native_module(
+  env: sys::napi_env,
+  _exports: sys::napi_value,
+  _module_name: *const c_char,
+  opaque: *mut c_void,
+) -> sys::napi_value {
+  if opaque.is_null() {
+    return ptr::null_mut();
+  }
+  let bindings = &*(opaque.cast::<ModuleBindings>());
+  catch_unwind(AssertUnwindSafe(|| {
+    let Some(napi) = callback_napi() else {
+      return ptr::null_mut();
+    };
+    let mut object = ptr::null_mut();
+    if (napi.napi_create_object)(env, &mut object) != sys::napi_ok {
+      return ptr::null_mut();
+    }
+    for binding in &bindings.methods {
+      let mut function = ptr::null_mut();
+      let status = (napi.napi_create_function)(
+        env,
+        binding.name.as_ptr(),
+        sys::NAPI_AUTO_LENGTH,
+        Some(call_native_method),
+        (binding as *const MethodBinding)
+          .cast_mut()
+          .cast::<c_void>(),
+        &mut function,
+      );
+      if status == sys::napi_ok {
+        (napi.napi_set_named_property)(env, object, binding.name.as_ptr(), function);
+      }
+    }
+    object
+  }))
+  .unwrap_or(ptr::null_mut())
+}
+
+unsafe extern "C" fn call_native_method(
+  env: sys::napi_env,
+  info: sys::napi_callback_info,
+) -> sys::napi_value {
+  let mut argc = MAX_METHOD_ARGS;
+  let mut argv = [ptr::null_mut(); MAX_METHOD_ARGS];
+  let mut data = ptr::null_mut();
+  let Some(napi) = napi_for_callback(env, info, &mut argc, &mut argv, &mut data) else {
+    return ptr::null_mut();
+  };
+  if data.is_null() {
+    return undefined(env, napi);
+  }
+  let binding = &*(data.cast::<MethodBinding>());
+  let args = argv[..argc.min(MAX_METHOD_ARGS)]
+    .iter()
+    .map(|arg| stringify(env, napi, *arg))
+    .collect::<Vec<_>>();
+  let method = binding.name.to_string_lossy();
+  match invoke(&binding.module, &method, args) {
+    Ok(json) => parse(env, napi, &json).unwrap_or_else(|| undefined(env, napi)),
+    Err(message) => {
+      let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+      (napi.napi_throw_error)(env, ptr::null(), message.as_ptr());
+      undefined(env, napi)
+    }
+  }
+}
+
+unsafe fn napi_for_callback(
+  env: sys::napi_env,
+  info: sys::napi_callback_info,
+  argc: &mut usize,
+  argv: &mut [sys::napi_value; MAX_METHOD_ARGS],
+  data: &mut *mut c_void,
+) -> Option<&'static sys::NapiLibrary> {
+  let napi = callback_napi()?;
+  let status = (napi.napi_get_cb_info)(env, info, argc, argv.as_mut_ptr(), ptr::null_mut(), data);
+  (status == sys::napi_ok).then_some(napi)
+}
+
+/// Returns the Node-API table of the first runtime that registered a safe
+/// native module. `napi_get_cb_info` is needed before the callback data is
+/// known, and one process only ever loads one Lynx runtime.
+fn callback_napi() -> Option<&'static sys::NapiLibrary> {
+  CALLBACK_RUNTIME
+    .get()
+    .and_then(|library| library.napi().ok())
+}
+
+static CALLBACK_RUNTIME: OnceLock<Arc<sys::LoadedLibrary>> = OnceLock::new();
+
+/// Decodes JSON arguments, calls the module, and encodes the result.
+fn invoke(
+  module: &Mutex<Box<dyn NativeModule>>,
+  method: &str,
+  args: Vec<Option<String>>,
+) -> std::result::Result<String, String> {
+  let args = args
+    .into_iter()
+    .map(|arg| match arg {
+      Some(json) => serde_json::from_str(&json).map_err(|error| error.to_string()),
+      None => Ok(Value::Null),
+    })
+    .collect::<std::result::Result<Vec<_>, _>>()?;
+  let result = catch_unwind(AssertUnwindSafe(|| {
+    module
+      .lock()
+      .map_err(|_| "native module lock poisoned".to_string())?
+      .call(method, args)
+  }))
+  .unwrap_or_else(|_| Err(format!("native module method {method} panicked")))?;
+  serde_json::to_string(&result).map_err(|error| error.to_string())
+}
+
+unsafe fn undefined(env: sys::napi_env, napi: &sys::NapiLibrary) -> sys::napi_value {
+  let mut value = ptr::null_mut();
+  (napi.napi_get_undefined)(env, &mut value);
+  value
+}
+
+unsafe fn json_method(
+  env: sys::napi_env,
+  napi: &sys::NapiLibrary,
+  name: &std::ffi::CStr,
+) -> Option<(sys::napi_value, sys::napi_value)> {
+  let mut global = ptr::null_mut();
+  let mut json = ptr::null_mut();
+  let mut method = ptr::null_mut();
+  ((napi.napi_get_global)(env, &mut global) == sys::napi_ok).then_some(())?;
+  ((napi.napi_get_named_property)(env, global, c"JSON".as_ptr(), &mut json) == sys::napi_ok)
+    .then_some(())?;
+  ((napi.napi_get_named_property)(env, json, name.as_ptr(), &mut method) == sys::napi_ok)
+    .then_some(())?;
+  Some((json, method))
+}
+
+unsafe fn stringify(
+  env: sys::napi_env,
+  napi: &sys::NapiLibrary,
+  value: sys::napi_value,
+) -> Option<String> {
+  let (json, stringify) = json_method(env, napi, c"stringify")?;
+  let mut result = ptr::null_mut();
+  ((napi.napi_call_function)(env, json, stringify, 1, &value, &mut result) == sys::napi_ok)
+    .then_some(())?;
+  let mut len = 0;
+  ((napi.napi_get_value_string_utf8)(env, result, ptr::null_mut(), 0, &mut len) == sys::napi_ok)
+    .then_some(())?;
+  let mut buffer = vec![0_u8; len + 1];
+  ((napi.napi_get_value_string_utf8)(
+    env,
+    result,
+    buffer.as_mut_ptr().cast::<c_char>(),
+    buffer.len(),
+    &mut len,
+  ) == sys::napi_ok)
+    .then_some(())?;
+  buffer.truncate(len);
+  String::from_utf8(buffer).ok()
+}
+
+unsafe fn parse(
+  env: sys::napi_env,
+  napi: &sys::NapiLibrary,
+  json: &str,
+) -> Option<sys::napi_value> {
+  let (json_object, parse) = json_method(env, napi, c"parse")?;
+  let mut text = ptr::null_mut();
+  ((napi.napi_create_string_utf8)(env, json.as_ptr().cast::<c_char>(), json.len(), &mut text)
+    == sys::napi_ok)
+    .then_some(())?;
+  let mut result = ptr::null_mut();
+  ((napi.napi_call_function)(env, json_object, parse, 1, &text, &mut result) == sys::napi_ok)
+    .then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn native_methods_dispatch_by_name() {
+    let mut module = NativeMethods::new()
+      .method("echo", |args| Ok(Value::Array(args)))
+      .method("fail", |_args| Err("denied".into()));
+
+    assert_eq!(module.method_names(), vec!["echo", "fail"]);
+    assert_eq!(
+      module.call("echo", vec![json!(1), json!("two")]),
+      Ok(json!([1, "two"]))
+    );
+    assert_eq!(module.call("fail", Vec::new()), Err("denied".into()));
+    assert_eq!(
+      module.call("missing", Vec::new()),
+      Err("unknown native module method: missing".into())
+    );
+  }
+
+  #[test]
+  fn invoke_round_trips_json_arguments_and_results() {
+    let context = test_context(NativeMethods::new().method("setItem", |args| {
+      Ok(json!({ "key": args[0], "value": args[1], "missing": args[2] }))
+    }));
+
+    let result = invoke(
+      &context,
+      "setItem",
+      vec![
+        Some("\"theme\"".into()),
+        Some("{\"dark\":true}".into()),
+        None,
+      ],
+    )
+    .unwrap();
+
+    assert_eq!(
+      serde_json::from_str::<Value>(&result).unwrap(),
+      json!({ "key": "theme", "value": { "dark": true }, "missing": null })
+    );
+  }
+
+  #[test]
+  fn invoke_reports_invalid_json_errors_and_panics() {
+    let context = test_context(
+      NativeMethods::new()
+        .method("fail", |_args| Err("storage is full".into()))
+        .method("panic", |_args| panic!("module panicked")),
+    );
+
+    assert!(invoke(&context, "fail", vec![Some("{".into())]).is_err());
+    assert_eq!(
+      invoke(&context, "fail", Vec::new()),
+      Err("storage is full".into())
+    );
+    assert_eq!(
+      invoke(&context, "panic", Vec::new()),
+      Err("native module method panic panicked".into())
+    );
+  }
+
+  #[test]
+  fn module_callbacks_without_runtime_return_null() {
+    unsafe {
+      assert!(create_native_module(
+        ptr::null_mut(),
+        ptr::null_mut(),
+        ptr::null(),
+        ptr::null_mut()
+      )
+      .is_null());
+      assert!(call_native_method(ptr::null_mut(), ptr::null_mut()).is_null());
+    }
+  }
+
+  fn test_context(module: impl NativeModule) -> Mutex<Box<dyn NativeModule>> {
+    Mutex::new(Box::new(module))
+  }
+}
@@ -24,11 +24,13 @@ opaque!(lynx_view_client_t);
 opaque!(lynx_extension_module_t);
 opaque!(lynx_vsync_observer_t);
 opaque!(lynx_event_reporter_service_t);
+opaque!(napi_callback_info__);
 
 pub type NativeWindow = *mut c_void;
 pub type napi_env = *mut c_void;
 pub type napi_value = *mut c_void;
 pub type napi_status = c_int;
+pub type napi_callback_info = *mut napi_callback_info__;
 pub type lynx_resource_request_id = u64;
 pub type lynx_cursor_type_e = c_int;
 pub type lynx_windowless_renderer_type_e = c_int;
@@ -40,6 +42,9 @@ pub type lynx_key_event_type_e = c_int;
 pub type lynx_color_type_e = c_int;
 pub type lynx_value_type = u8;
 
+pub const napi_ok: napi_status = 0;
+pub const NAPI_AUTO_LENGTH: usize = usize::MAX;
+
 pub const kRendererTypeSoftware: lynx_windowless_renderer_type_e = 0;
 pub const kRendererTypeGL: lynx_windowless_renderer_type_e = 1;
 pub const kRendererTypeGLDirect: lynx_windowless_renderer_type_e = 2;
@@ -300,6 +305,8 @@ impl Default for lynx_windowless_ui_task_runner_config_t {
 }
 
 pub type napi_finalize = unsafe extern "C" fn(env: napi_env, data: *mut c_void, hint: *mut c_void);
+pub type napi_callback =
+  unsafe extern "C" fn(env: napi_env, info: napi_callback_info) -> napi_value;
 pub type napi_module_creator = unsafe extern "C" fn(
   env: napi_env,
   exports: napi_value,
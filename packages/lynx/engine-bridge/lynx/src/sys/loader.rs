@@ -7,6 +7,7 @@ use std::ffi::{c_char, c_int, c_void, OsString};
 #[cfg(test)]
 use std::mem;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -107,9 +108,78 @@ fn open_library(path: &Path) -> std::result::Result<Library, libloading::Error>
   unsafe { Library::new(path) }
 }
 
+macro_rules! load_symbol {
+  ($library:expr, $name:ident) => {
+    unsafe { $library.symbol(stringify!($name))? }
+  };
+}
+
+/// The Node-API subset used by safe native modules.
+///
+/// These symbols are resolved on first use instead of in
+/// [`LoadedLibrary::load`], so runtimes that do not export Node-API still load
+/// for embedders that only register raw modules.
+pub struct NapiLibrary {
+  pub napi_get_undefined: unsafe extern "C" fn(napi_env, *mut napi_value) -> napi_status,
+  pub napi_get_global: unsafe extern "C" fn(napi_env, *mut napi_value) -> napi_status,
+  pub napi_create_object: unsafe extern "C" fn(napi_env, *mut napi_value) -> napi_status,
+  pub napi_create_string_utf8:
+    unsafe extern "C" fn(napi_env, *const c_char, usize, *mut napi_value) -> napi_status,
+  pub napi_get_value_string_utf8:
+    unsafe extern "C" fn(napi_env, napi_value, *mut c_char, usize, *mut usize) -> napi_status,
+  pub napi_get_named_property:
+    unsafe extern "C" fn(napi_env, napi_value, *const c_char, *mut napi_value) -> napi_status,
+  pub napi_set_named_property:
+    unsafe extern "C" fn(napi_env, napi_value, *const c_char, napi_value) -> napi_status,
+  pub napi_create_function: unsafe extern "C" fn(
+    napi_env,
+    *const c_char,
+    usize,
+    Option<napi_callback>,
+    *mut c_void,
+    *mut napi_value,
+  ) -> napi_status,
+  pub napi_get_cb_info: unsafe extern "C" fn(
+    napi_env,
+    napi_callback_info,
+    *mut usize,
+    *mut napi_value,
+    *mut napi_value,
+    *mut *mut c_void,
+  ) -> napi_status,
+  pub napi_call_function: unsafe extern "C" fn(
+    napi_env,
+    napi_value,
+    napi_value,
+    usize,
+    *const napi_value,
+    *mut napi_value,
+  ) -> napi_status,
+  pub napi_throw_error: unsafe extern "C" fn(napi_env, *const c_char, *const c_char) -> napi_status,
+}
+
+impl NapiLibrary {
+  fn load(library: &DynamicLibrary) -> Result<Self> {
+    Ok(Self {
+      napi_get_undefined: load_symbol!(library, napi_get_undefined),
+      napi_get_global: load_symbol!(library, napi_get_global),
+      napi_create_object: load_symbol!(library, napi_create_object),
+      napi_create_string_utf8: load_symbol!(library, napi_create_string_utf8),
+      napi_get_value_string_utf8: load_symbol!(library, napi_get_value_string_utf8),
+      napi_get_named_property: load_symbol!(library, napi_get_named_property),
+      napi_set_named_property: load_symbol!(library, napi_set_named_property),
+      napi_create_function: load_symbol!(library, napi_create_function),
+      napi_get_cb_info: load_symbol!(library, napi_get_cb_info),
+      napi_call_function: load_symbol!(library, napi_call_function),
+      napi_throw_error: load_symbol!(library, napi_throw_error),
+    })
+  }
+}
+
 #[allow(non_camel_case_types)]
 pub struct LoadedLibrary {
   _library: DynamicLibrary,
+  napi: OnceLock<NapiLibrary>,
   pub path: PathBuf,
 
   pub lynx_env_get_sdk_version: unsafe extern "C" fn() -> *const c_char,
@@ -344,12 +414,6 @@ pub struct LoadedLibrary {
 unsafe impl Send for LoadedLibrary {}
 unsafe impl Sync for LoadedLibrary {}
 
-macro_rules! load_symbol {
-  ($library:expr, $name:ident) => {
-    unsafe { $library.symbol(stringify!($name))? }
-  };
-}
-
 impl LoadedLibrary {
   pub fn load_from_environment() -> Result<Self> {
     let candidates = candidate_library_paths()?;
@@ -655,8 +719,18 @@ impl LoadedLibrary {
       lynx_windowless_renderer_release: load_symbol!(library, lynx_windowless_renderer_release),
       path,
       _library: library,
+      napi: OnceLock::new(),
     })
   }
+
+  /// Resolves the Node-API symbols used by safe native modules.
+  pub fn napi(&self) -> Result<&NapiLibrary> {
+    if let Some(napi) = self.napi.get() {
+      return Ok(napi);
+    }
+    let napi = NapiLibrary::load(&self._library)?;
+    Ok(self.napi.get_or_init(|| napi))
+  }
 }
 
 #[cfg(test)]
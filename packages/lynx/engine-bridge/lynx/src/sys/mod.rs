@@ -12,4 +12,6 @@ mod bindings;
 mod loader;
 
 pub use bindings::*;
-pub use loader::{candidate_library_paths, library_filename, Error, LoadedLibrary, Result};
+pub use loader::{
+  candidate_library_paths, library_filename, Error, LoadedLibrary, NapiLibrary, Result,
+};
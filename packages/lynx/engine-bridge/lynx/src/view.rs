@@ -1,5 +1,7 @@
 use crate::buffer::CByteBuffer;
+use crate::client::{LynxViewClient, ViewClient};
 use crate::group::LynxGroup;
+use crate::module::{NativeModule, NativeModuleRegistration};
 use crate::resource::{GenericResourceFetcher, ResourceFetcher};
 use crate::sys;
 use crate::{c_str_to_string, c_string, Env, Error, Result, WindowlessRenderer};
@@ -17,7 +19,9 @@ pub struct HeadlessViewBuilder {
   icu_data_path: Option<CString>,
   resource_fetcher: Option<GenericResourceFetcher>,
   lynx_group: Option<LynxGroup>,
+  view_clients: Vec<LynxViewClient>,
   native_modules: Vec<RawNativeModule>,
+  rust_native_modules: Vec<NativeModuleRegistration>,
   extension_modules: Vec<RawExtensionModule>,
 }
 
@@ -46,7 +50,9 @@ impl HeadlessViewBuilder {
       icu_data_path: None,
       resource_fetcher: None,
       lynx_group: None,
+      view_clients: Vec::new(),
       native_modules: Vec::new(),
+      rust_native_modules: Vec::new(),
       extension_modules: Vec::new(),
     }
   }
@@ -78,6 +84,27 @@ impl HeadlessViewBuilder {
     self
   }
 
+  /// Registers a lifecycle client on the built view.
+  ///
+  /// Clients are added in registration order right after the view is created
+  /// and stay alive until the view is released.
+  pub fn view_client(mut self, client: impl ViewClient) -> Result<Self> {
+    self
+      .view_clients
+      .push(LynxViewClient::new(&self.env, client)?);
+    Ok(self)
+  }
+
+  /// Registers a Rust [`NativeModule`] as `NativeModules[name]` for this view.
+  ///
+  /// The module state stays alive until the built view is released.
+  pub fn native_module(mut self, name: &str, module: impl NativeModule) -> Result<Self> {
+    self
+      .rust_native_modules
+      .push(NativeModuleRegistration::new(&self.env, name, module)?);
+    Ok(self)
+  }
+
   /// Registers a native module on this view builder.
   ///
   /// # Safety
@@ -101,6 +128,8 @@ impl HeadlessViewBuilder {
 
   /// Registers an extension module on this view builder.
   ///
+  /// Like [`Env::register_extension_module_raw`], it has no safe counterpart.
+  ///
   /// # Safety
   ///
   /// `creator` and `opaque` must obey the extension module ABI expected by
@@ -162,6 +191,14 @@ impl HeadlessViewBuilder {
           module.opaque,
         );
       }
+      for module in &self.rust_native_modules {
+        (sys.lynx_view_builder_register_native_module)(
+          builder,
+          module.name.as_ptr(),
+          Some(module.creator()),
+          module.opaque(),
+        );
+      }
       for module in &self.extension_modules {
         (sys.lynx_view_builder_register_extension_module)(
           builder,
@@ -191,6 +228,11 @@ impl HeadlessViewBuilder {
         "failed to configure headless texture backend".to_string(),
       ));
     }
+    for client in &self.view_clients {
+      unsafe {
+        (sys.lynx_view_add_client)(raw, client.raw());
+      }
+    }
 
     Ok(HeadlessView {
       env: self.env,
@@ -198,6 +240,8 @@ impl HeadlessViewBuilder {
       renderer: self.renderer,
       _resource_fetcher: self.resource_fetcher,
       _lynx_group: self.lynx_group,
+      _view_clients: self.view_clients,
+      _native_modules: self.rust_native_modules,
     })
   }
 }
@@ -224,6 +268,8 @@ pub struct HeadlessView {
   renderer: WindowlessRenderer,
   _resource_fetcher: Option<GenericResourceFetcher>,
   _lynx_group: Option<LynxGroup>,
+  _view_clients: Vec<LynxViewClient>,
+  _native_modules: Vec<NativeModuleRegistration>,
 }
 
 impl HeadlessView {
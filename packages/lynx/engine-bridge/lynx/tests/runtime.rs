@@ -1,7 +1,7 @@
 use lynx::{
-  sys, Env, Error, FetchResponse, GenericResourceFetcher, HeadlessView, LynxGroup, NoopHost,
-  ResourceFetcher, ResourceRequest, ResourceType, SoftwareFrame, SoftwareRenderer,
-  WindowlessRenderer,
+  sys, view_event_channel, Env, Error, FetchResponse, GenericResourceFetcher, HeadlessView,
+  LynxGroup, LynxViewClient, NativeMethods, NoopHost, ResourceFetcher, ResourceRequest,
+  ResourceType, SoftwareFrame, SoftwareRenderer, ViewClient, ViewEvent, WindowlessRenderer,
 };
 use std::env;
 use std::ffi::c_void;
@@ -9,6 +9,7 @@ use std::sync::{
   atomic::{AtomicUsize, Ordering},
   Arc, Mutex, MutexGuard, OnceLock,
 };
+use std::task::{Context, Poll, Waker};
 
 #[test]
 fn public_data_types_work_without_runtime() {
@@ -23,6 +24,16 @@ fn public_data_types_work_without_runtime() {
     ResourceType::Image
   );
   assert_eq!(ResourceType::from(123_456), ResourceType::Unknown(123_456));
+
+  let (mut sender, mut events) = view_event_channel();
+  sender.on_received_error(100, "load failed");
+  assert_eq!(
+    events.try_recv(),
+    Some(ViewEvent::ReceivedError {
+      code: 100,
+      message: "load failed".into(),
+    })
+  );
 }
 
 #[test]
@@ -83,6 +94,10 @@ fn runtime_builds_headless_view_and_validates_bundle_errors() {
     .expect("create software renderer");
   let fetcher = GenericResourceFetcher::new(&env, StaticFetcher).expect("create resource fetcher");
   drop(fetcher);
+  let client = LynxViewClient::new(&env, NoopClient).expect("create view client");
+  drop(client);
+
+  let (sender, mut events) = view_event_channel();
 
   let mut group =
     LynxGroup::with_id(&env, "integration", "runtime").expect("create Lynx group with id");
@@ -97,6 +112,13 @@ fn runtime_builds_headless_view_and_validates_bundle_errors() {
     .resource_fetcher(StaticFetcher)
     .expect("attach resource fetcher")
     .lynx_group(group)
+    .view_client(sender)
+    .expect("attach view client")
+    .native_module(
+      "MockStorage",
+      NativeMethods::new().method("getItem", |_args| Ok("stored".into())),
+    )
+    .expect("register view native module")
     .build()
     .expect("build headless view");
 
@@ -135,6 +157,14 @@ fn runtime_builds_headless_view_and_validates_bundle_errors() {
   assert!(bundle_error
     .to_string()
     .contains("failed to decode template bundle"));
+
+  drop(view);
+  events.drain();
+  assert_eq!(
+    events.poll_next(&mut Context::from_waker(Waker::noop())),
+    Poll::Ready(None),
+    "view clients are released with their view"
+  );
 }
 
 #[test]
@@ -143,6 +173,10 @@ fn runtime_public_methods_reject_interior_nul_before_ffi() {
   let env = configured_env();
 
   assert_interior_nul(LynxGroup::new(&env, "bad\0group").map(|_| ()), "group_name");
+  assert_interior_nul(
+    env.register_native_module("bad\0module", NativeMethods::new()),
+    "native_module_name",
+  );
 
   let renderer = WindowlessRenderer::software(&env, CountingSoftwareRenderer::default(), NoopHost)
     .expect("create software renderer");
@@ -200,6 +234,10 @@ impl SoftwareRenderer for CountingSoftwareRenderer {
   }
 }
 
+struct NoopClient;
+
+impl ViewClient for NoopClient {}
+
 struct StaticFetcher;
 
 impl ResourceFetcher for StaticFetcher {
@@ -0,0 +1,290 @@
+//! Drives the bridge against the in-tree stub runtime, so these tests run
+//! without downloading `libLynx_clay`.
+
+use lynx::{
+  view_event_channel, Env, FetchResponse, HeadlessView, ResourceFetcher, ResourceRequest,
+  ResourceType, SoftwareFrame, SoftwareRenderer, Task, ViewEvent, ViewEvents, WindowlessHost,
+  WindowlessRenderer,
+};
+use std::collections::VecDeque;
+use std::ffi::{c_char, CStr};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use stub_runtime::test_support::{library_path, PAGE};
+
+#[test]
+fn stub_runtime_reports_a_url_load_and_presents_frames() {
+  let _guard = stub_test_guard();
+  let env = stub_env();
+  reset_call_log();
+
+  let tasks = TaskQueue::default();
+  let frames = Frames::default();
+  let renderer = WindowlessRenderer::software(
+    &env,
+    CapturingRenderer {
+      frames: frames.clone(),
+    },
+    tasks.clone(),
+  )
+  .expect("create software renderer");
+  let requests = Arc::new(Mutex::new(Vec::new()));
+  let (sender, mut events) = view_event_channel();
+  let view = HeadlessView::builder(env, renderer)
+    .viewport(4.0, 3.0, 2.0)
+    .resource_fetcher(FixtureFetcher {
+      requests: requests.clone(),
+    })
+    .expect("attach resource fetcher")
+    .view_client(sender)
+    .expect("attach view client")
+    .build()
+    .expect("build headless view");
+
+  view
+    .load_template_from_url("https://fixtures.invalid/page.json", None)
+    .expect("load template");
+  assert!(
+    events.drain().is_empty(),
+    "loads run on the renderer task queue"
+  );
+  tasks.run_all(&view);
+
+  assert_eq!(
+    events.drain(),
+    vec![
+      ViewEvent::PageStart {
+        url: "https://fixtures.invalid/page.json".into(),
+      },
+      ViewEvent::RuntimeReady,
+      ViewEvent::ReceivedError {
+        code: 301,
+        message: "failed to load image resource missing.png (404): not found".into(),
+      },
+      ViewEvent::LoadSuccess,
+      ViewEvent::FirstScreen,
+      ViewEvent::ReceivedError {
+        code: 201,
+        message: "scripted error".into(),
+      },
+    ]
+  );
+  assert_eq!(
+    *requests.lock().unwrap(),
+    vec![
+      (
+        "https://fixtures.invalid/page.json".to_string(),
+        ResourceType::Template
+      ),
+      ("logo.png".to_string(), ResourceType::Image),
+      ("missing.png".to_string(), ResourceType::Image),
+    ]
+  );
+
+  {
+    let frames = frames.lock().unwrap();
+    let (row_bytes, pixels) = frames.last().expect("a frame was presented");
+    assert_eq!((*row_bytes, pixels.len()), (8 * 4, 8 * 6 * 4));
+    let pixel = |x: usize, y: usize| &pixels[y * row_bytes + x * 4..][..4];
+    assert_eq!(pixel(0, 0), [0, 0, 255, 255]);
+    assert_eq!(pixel(2, 2), [255, 0, 0, 255]);
+    assert_eq!(pixel(5, 5), [255, 0, 0, 255]);
+    assert_eq!(pixel(7, 5), [0, 0, 255, 255]);
+  }
+
+  view
+    .update_data_json("{\"count\":1}", None)
+    .expect("update data");
+  view.set_frame(0.0, 0.0, 2.0, 2.0);
+  tasks.run_all(&view);
+  assert_eq!(
+    events.drain(),
+    vec![ViewEvent::DataUpdated, ViewEvent::PageUpdated]
+  );
+  assert_eq!(frames.lock().unwrap().last().unwrap().1.len(), 4 * 4 * 4);
+
+  view.enter_background();
+  view
+    .send_global_event("resume", "{\"ok\":true}")
+    .expect("send global event");
+  drop(view);
+  assert_eq!(
+    events.drain(),
+    vec![ViewEvent::EnterBackground, ViewEvent::Destroy]
+  );
+
+  let log = call_log();
+  for call in [
+    "lynx_rust_view_set_use_texture_backend false",
+    "lynx_view_load_template https://fixtures.invalid/page.json",
+    "lynx_view_update_data {\"count\":1}",
+    "lynx_view_send_global_event resume {\"ok\":true}",
+    "lynx_view_release",
+  ] {
+    assert!(
+      log.lines().any(|line| line == call),
+      "missing {call} in call log:\n{log}"
+    );
+  }
+}
+
+#[test]
+fn stub_runtime_validates_bundles_and_renders_binary_ones_blank() {
+  let _guard = stub_test_guard();
+  let env = stub_env();
+
+  let tasks = TaskQueue::default();
+  let frames = Frames::default();
+  let renderer = WindowlessRenderer::software(
+    &env,
+    CapturingRenderer {
+      frames: frames.clone(),
+    },
+    tasks.clone(),
+  )
+  .expect("create software renderer");
+  let (sender, mut events) = view_event_channel();
+  let view = HeadlessView::builder(env, renderer)
+    .viewport(2.0, 1.0, 1.0)
+    .view_client(sender)
+    .expect("attach view client")
+    .build()
+    .expect("build headless view");
+
+  let error = view
+    .load_template_bundle_bytes("memory://bad.bundle", b"not a template bundle", None)
+    .expect_err("text bundles must be page descriptions");
+  assert!(error
+    .to_string()
+    .contains("failed to decode template bundle: not a template bundle"));
+
+  view
+    .load_template_bundle_bytes("memory://app.bundle", &[0, 1, 2, 3], None)
+    .expect("binary bundles load");
+  view
+    .load_template_bytes("memory://broken.json", b"{\"rects\": 1}", None)
+    .expect("queue a broken page");
+  tasks.run_all(&view);
+
+  let events = drain_loads(&mut events);
+  assert_eq!(events[0].0, "memory://app.bundle");
+  assert!(events[0].1.contains(&ViewEvent::FirstScreen));
+  assert_eq!(events[1].0, "memory://broken.json");
+  assert!(matches!(
+    events[1].1.as_slice(),
+    [ViewEvent::ReceivedError { code: 100, message }]
+      if message.contains("`rects` must be an array")
+  ));
+  assert_eq!(frames.lock().unwrap().last().unwrap().1, vec![255; 8]);
+}
+
+/// Groups events by the load that produced them.
+fn drain_loads(events: &mut ViewEvents) -> Vec<(String, Vec<ViewEvent>)> {
+  let mut loads: Vec<(String, Vec<ViewEvent>)> = Vec::new();
+  for event in events.drain() {
+    match event {
+      ViewEvent::PageStart { url } => loads.push((url, Vec::new())),
+      event => loads
+        .last_mut()
+        .expect("events follow a page start")
+        .1
+        .push(event),
+    }
+  }
+  loads
+}
+
+fn stub_env() -> Env {
+  Env::load_from_path(library_path()).expect("load stub runtime")
+}
+
+fn stub_library() -> &'static libloading::Library {
+  static LIBRARY: OnceLock<libloading::Library> = OnceLock::new();
+  LIBRARY
+    .get_or_init(|| unsafe { libloading::Library::new(library_path()) }.expect("open stub runtime"))
+}
+
+fn call_log() -> String {
+  unsafe {
+    let read = stub_library()
+      .get::<unsafe extern "C" fn() -> *mut c_char>(b"lynx_stub_call_log")
+      .expect("stub exports its call log");
+    let release = stub_library()
+      .get::<unsafe extern "C" fn(*mut c_char)>(b"lynx_stub_string_release")
+      .expect("stub exports its string release");
+    let log = read();
+    let text = CStr::from_ptr(log).to_string_lossy().into_owned();
+    release(log);
+    text
+  }
+}
+
+fn reset_call_log() {
+  unsafe {
+    let reset = stub_library()
+      .get::<unsafe extern "C" fn()>(b"lynx_stub_reset_call_log")
+      .expect("stub exports its call log reset");
+    reset();
+  }
+}
+
+fn stub_test_guard() -> MutexGuard<'static, ()> {
+  static LOCK: Mutex<()> = Mutex::new(());
+  LOCK.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Queues posted tasks so tests decide when the runtime's UI work runs.
+#[derive(Clone, Default)]
+struct TaskQueue {
+  tasks: Arc<Mutex<VecDeque<Task>>>,
+}
+
+impl TaskQueue {
+  fn run_all(&self, view: &HeadlessView) {
+    loop {
+      let Some(task) = self.tasks.lock().unwrap().pop_front() else {
+        break;
+      };
+      view.renderer().run_task(task);
+    }
+  }
+}
+
+impl WindowlessHost for TaskQueue {
+  fn post_task(&mut self, task: Task, _interval_nanoseconds: u64) {
+    self.tasks.lock().unwrap().push_back(task);
+  }
+}
+
+/// Presented frames as `(row_bytes, pixels)`.
+type Frames = Arc<Mutex<Vec<(usize, Vec<u8>)>>>;
+
+struct CapturingRenderer {
+  frames: Frames,
+}
+
+impl SoftwareRenderer for CapturingRenderer {
+  fn present(&mut self, frame: SoftwareFrame) -> bool {
+    let bytes = unsafe { frame.bytes() }.expect("frame bytes").to_vec();
+    self.frames.lock().unwrap().push((frame.row_bytes, bytes));
+    true
+  }
+}
+
+struct FixtureFetcher {
+  requests: Arc<Mutex<Vec<(String, ResourceType)>>>,
+}
+
+impl ResourceFetcher for FixtureFetcher {
+  fn fetch(&mut self, request: ResourceRequest) -> FetchResponse {
+    self
+      .requests
+      .lock()
+      .unwrap()
+      .push((request.url.clone(), request.resource_type));
+    match request.url.as_str() {
+      "https://fixtures.invalid/page.json" => FetchResponse::ok(PAGE),
+      "logo.png" => FetchResponse::ok([0x89, b'P', b'N', b'G']),
+      _ => FetchResponse::error(404, "not found"),
+    }
+  }
+}
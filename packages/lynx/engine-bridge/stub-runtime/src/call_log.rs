@@ -0,0 +1,48 @@
+use crate::lock;
+use std::ffi::{c_char, CString};
+use std::sync::Mutex;
+
+static CALLS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Appends one ABI call. Most entries are the bare symbol name; calls whose
+/// arguments matter to tests append them after a space.
+pub(crate) fn record(call: impl Into<String>) {
+  lock(&CALLS).push(call.into());
+}
+
+/// Returns the calls recorded since the last reset, one per line.
+///
+/// Release the string with [`lynx_stub_string_release`].
+#[no_mangle]
+pub extern "C" fn lynx_stub_call_log() -> *mut c_char {
+  let log = lock(&CALLS).join("\n").replace('\0', " ");
+  CString::new(log).unwrap_or_default().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_stub_reset_call_log() {
+  lock(&CALLS).clear();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_stub_string_release(value: *mut c_char) {
+  if !value.is_null() {
+    drop(CString::from_raw(value));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::ffi::CStr;
+
+  #[test]
+  fn call_log_round_trips_through_c_strings() {
+    record("lynx_view_create");
+    record("lynx_view_send_global_event ready");
+    let log = lynx_stub_call_log();
+    let text = unsafe { CStr::from_ptr(log) }.to_str().unwrap().to_owned();
+    unsafe { lynx_stub_string_release(log) };
+    assert!(text.contains("lynx_view_create\nlynx_view_send_global_event ready"));
+  }
+}
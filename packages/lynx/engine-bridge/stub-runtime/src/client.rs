@@ -0,0 +1,138 @@
+use crate::call_log::record;
+use crate::{borrow, into_raw, lock, release, sys};
+use std::ffi::{c_int, c_void, CString};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Default)]
+struct Callbacks {
+  on_page_start: Option<sys::on_page_start>,
+  on_load_success: Option<sys::on_load_success>,
+  on_first_screen: Option<sys::on_first_screen>,
+  on_page_updated: Option<sys::on_page_updated>,
+  on_data_updated: Option<sys::on_data_updated>,
+  on_destroy: Option<sys::on_destroy>,
+  on_runtime_ready: Option<sys::on_runtime_ready>,
+  on_received_error: Option<sys::on_received_error>,
+  on_timing_setup: Option<sys::on_timing_setup>,
+  on_timing_update: Option<sys::on_timing_update>,
+  on_enter_foreground: Option<sys::on_enter_foreground>,
+  on_enter_background: Option<sys::on_enter_background>,
+  on_frame_timing: Option<sys::on_frame_timing>,
+}
+
+pub(crate) struct Client {
+  user_data: *mut c_void,
+  callbacks: Mutex<Callbacks>,
+}
+
+/// A lifecycle notification the stub reports to view clients.
+pub(crate) enum ClientEvent {
+  PageStart(String),
+  LoadSuccess,
+  FirstScreen,
+  PageUpdated,
+  DataUpdated,
+  RuntimeReady,
+  ReceivedError(c_int, String),
+  EnterForeground,
+  EnterBackground,
+  Destroy,
+}
+
+/// Calls the callback bound for `event`, if any. The callbacks are copied out
+/// first so a client may call back into the stub.
+pub(crate) unsafe fn notify(client: *mut sys::lynx_view_client_t, event: &ClientEvent) {
+  let Some(state) = borrow::<Client, _>(client) else {
+    return;
+  };
+  let callbacks = *lock(&state.callbacks);
+  match event {
+    ClientEvent::PageStart(url) => {
+      if let Some(callback) = callbacks.on_page_start {
+        let url = CString::new(url.as_str()).unwrap_or_default();
+        callback(client, url.as_ptr());
+      }
+    }
+    ClientEvent::LoadSuccess => call(client, callbacks.on_load_success),
+    ClientEvent::FirstScreen => call(client, callbacks.on_first_screen),
+    ClientEvent::PageUpdated => call(client, callbacks.on_page_updated),
+    ClientEvent::DataUpdated => call(client, callbacks.on_data_updated),
+    ClientEvent::RuntimeReady => call(client, callbacks.on_runtime_ready),
+    ClientEvent::ReceivedError(code, message) => {
+      if let Some(callback) = callbacks.on_received_error {
+        let message = CString::new(message.as_str()).unwrap_or_default();
+        callback(client, *code, message.as_ptr());
+      }
+    }
+    ClientEvent::EnterForeground => call(client, callbacks.on_enter_foreground),
+    ClientEvent::EnterBackground => call(client, callbacks.on_enter_background),
+    ClientEvent::Destroy => call(client, callbacks.on_destroy),
+  }
+}
+
+unsafe fn call(
+  client: *mut sys::lynx_view_client_t,
+  callback: Option<unsafe extern "C" fn(*mut sys::lynx_view_client_t)>,
+) {
+  if let Some(callback) = callback {
+    callback(client);
+  }
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_view_client_create(user_data: *mut c_void) -> *mut sys::lynx_view_client_t {
+  record("lynx_view_client_create");
+  into_raw(Client {
+    user_data,
+    callbacks: Mutex::default(),
+  })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_client_get_user_data(
+  client: *mut sys::lynx_view_client_t,
+) -> *mut c_void {
+  record("lynx_view_client_get_user_data");
+  borrow::<Client, _>(client).map_or(std::ptr::null_mut(), |client| client.user_data)
+}
+
+macro_rules! bind_callback {
+  ($export:ident, $field:ident) => {
+    #[no_mangle]
+    pub unsafe extern "C" fn $export(
+      client: *mut sys::lynx_view_client_t,
+      callback: Option<sys::$field>,
+    ) {
+      record(stringify!($export));
+      if let Some(client) = borrow::<Client, _>(client) {
+        lock(&client.callbacks).$field = callback;
+      }
+    }
+  };
+}
+
+bind_callback!(lynx_view_client_bind_on_page_start, on_page_start);
+bind_callback!(lynx_view_client_bind_on_load_success, on_load_success);
+bind_callback!(lynx_view_client_bind_on_first_screen, on_first_screen);
+bind_callback!(lynx_view_client_bind_on_page_updated, on_page_updated);
+bind_callback!(lynx_view_client_bind_on_data_updated, on_data_updated);
+bind_callback!(lynx_view_client_bind_on_destroy, on_destroy);
+bind_callback!(lynx_view_client_bind_on_runtime_ready, on_runtime_ready);
+bind_callback!(lynx_view_client_bind_on_received_error, on_received_error);
+bind_callback!(lynx_view_client_bind_on_timing_setup, on_timing_setup);
+bind_callback!(lynx_view_client_bind_on_timing_update, on_timing_update);
+bind_callback!(
+  lynx_view_client_bind_on_enter_foreground,
+  on_enter_foreground
+);
+bind_callback!(
+  lynx_view_client_bind_on_enter_background,
+  on_enter_background
+);
+bind_callback!(lynx_view_client_bind_on_frame_timing, on_frame_timing);
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_client_release(client: *mut sys::lynx_view_client_t) {
+  record("lynx_view_client_release");
+  release::<Client, _>(client);
+}
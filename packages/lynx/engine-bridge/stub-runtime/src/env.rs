@@ -0,0 +1,164 @@
+use crate::call_log::record;
+use crate::{borrow, c_str_to_string, into_raw, lock, release, sys};
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+const SDK_VERSION: &std::ffi::CStr = c"0.0.0-stub";
+
+static ICU_DATA_PATH: Mutex<Option<CString>> = Mutex::new(None);
+static DEVTOOL_ENABLED: AtomicBool = AtomicBool::new(false);
+static LOGBOX_ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[no_mangle]
+pub extern "C" fn lynx_env_get_sdk_version() -> *const c_char {
+  record("lynx_env_get_sdk_version");
+  SDK_VERSION.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_env_set_icu_data_path(path: *const c_char) {
+  record("lynx_env_set_icu_data_path");
+  *lock(&ICU_DATA_PATH) = CString::new(c_str_to_string(path)).ok();
+}
+
+/// The returned pointer stays valid until the next
+/// `lynx_env_set_icu_data_path` call.
+#[no_mangle]
+pub extern "C" fn lynx_env_get_icu_data_path() -> *const c_char {
+  record("lynx_env_get_icu_data_path");
+  lock(&ICU_DATA_PATH)
+    .as_ref()
+    .map_or(c"".as_ptr(), |path| path.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_env_set_devtool_app_info(name: *const c_char, value: *const c_char) {
+  record(format!(
+    "lynx_env_set_devtool_app_info {}={}",
+    c_str_to_string(name),
+    c_str_to_string(value)
+  ));
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_env_enable_devtool(enabled: c_int) {
+  record("lynx_env_enable_devtool");
+  DEVTOOL_ENABLED.store(enabled != 0, Ordering::Relaxed);
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_env_is_devtool_enabled() -> c_int {
+  record("lynx_env_is_devtool_enabled");
+  c_int::from(DEVTOOL_ENABLED.load(Ordering::Relaxed))
+}
+
+/// The stub has no inspector, so connecting always fails.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_env_connect_devtool(url: *const c_char) -> c_int {
+  record(format!("lynx_env_connect_devtool {}", c_str_to_string(url)));
+  0
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_env_enable_logbox(enabled: c_int) {
+  record("lynx_env_enable_logbox");
+  LOGBOX_ENABLED.store(enabled != 0, Ordering::Relaxed);
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_env_is_logbox_enabled() -> c_int {
+  record("lynx_env_is_logbox_enabled");
+  c_int::from(LOGBOX_ENABLED.load(Ordering::Relaxed))
+}
+
+/// Modules are recorded but never created, because the stub runs no
+/// JavaScript that could require them.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_env_register_native_module(
+  name: *const c_char,
+  _creator: Option<sys::napi_module_creator>,
+  _opaque: *mut c_void,
+) {
+  record(format!(
+    "lynx_env_register_native_module {}",
+    c_str_to_string(name)
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_env_register_extension_module(
+  name: *const c_char,
+  _creator: Option<sys::extension_module_creator>,
+  _is_lazy_create: bool,
+  _opaque: *mut c_void,
+) {
+  record(format!(
+    "lynx_env_register_extension_module {}",
+    c_str_to_string(name)
+  ));
+}
+
+/// Groups only share JavaScript contexts, which the stub does not run, so a
+/// group keeps nothing beyond its name.
+pub(crate) struct Group {
+  _name: String,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_group_create(name: *const c_char) -> *mut sys::lynx_group_t {
+  record("lynx_group_create");
+  new_group(name)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_group_create_with_id(
+  name: *const c_char,
+  id: *const c_char,
+) -> *mut sys::lynx_group_t {
+  record(format!("lynx_group_create_with_id {}", c_str_to_string(id)));
+  new_group(name)
+}
+
+unsafe fn new_group(name: *const c_char) -> *mut sys::lynx_group_t {
+  into_raw(Group {
+    _name: c_str_to_string(name),
+  })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_group_set_preload_js_paths(
+  group: *mut sys::lynx_group_t,
+  paths: *const *const c_char,
+  count: usize,
+) {
+  if borrow::<Group, _>(group).is_none() {
+    return;
+  }
+  let paths: Vec<String> = if paths.is_null() {
+    Vec::new()
+  } else {
+    std::slice::from_raw_parts(paths, count)
+      .iter()
+      .map(|path| c_str_to_string(*path))
+      .collect()
+  };
+  record(format!(
+    "lynx_group_set_preload_js_paths {}",
+    paths.join(",")
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_group_set_enable_js_group_thread(
+  _group: *mut sys::lynx_group_t,
+  enabled: c_int,
+) {
+  record(format!("lynx_group_set_enable_js_group_thread {enabled}"));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_group_release(group: *mut sys::lynx_group_t) {
+  record("lynx_group_release");
+  release::<Group, _>(group);
+}
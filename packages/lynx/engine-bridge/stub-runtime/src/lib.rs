@@ -0,0 +1,93 @@
+//! A pure-Rust stand-in for `libLynx_clay`.
+//!
+//! The library exports every `lynx_*` and `lynx_rust_*` symbol that
+//! `lynx::sys::LoadedLibrary` resolves, so the engine bridge and the tools
+//! built on it can be exercised without the prebuilt runtime or a network
+//! connection. It runs no JavaScript and lays nothing out. Instead it:
+//!
+//! - records every ABI call, readable through [`lynx_stub_call_log`];
+//! - fires view-client callbacks in the order a real load reports them;
+//! - requests URL-loaded templates and the resources a page lists through the
+//!   view's generic resource fetcher;
+//! - presents synthetic frames through the windowless renderer.
+//!
+//! Templates are either compiled bundles, which render a blank white page, or
+//! JSON page descriptions; see [`page`] for the format.
+//!
+//! Loads, data updates, and relayouts run in renderer tasks: the stub posts
+//! them through the renderer's `on_post_task` callback and runs them when the
+//! host calls `lynx_windowless_renderer_run_task`, like the UI thread work of
+//! the real runtime. Renderers without a task callback run that work inline.
+//!
+//! Every export follows the contract of the C header it mirrors, so the
+//! `unsafe` functions below carry no individual safety sections. Objects are
+//! expected to be used and released on one thread at a time.
+
+// The crate is named after the runtime library file it replaces.
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)]
+
+#[path = "../../lynx/src/sys/bindings.rs"]
+#[allow(dead_code, non_camel_case_types, non_upper_case_globals)]
+mod sys;
+
+mod call_log;
+mod client;
+mod env;
+pub mod page;
+mod renderer;
+mod resource;
+pub mod test_support;
+mod view;
+
+pub use call_log::{lynx_stub_call_log, lynx_stub_reset_call_log, lynx_stub_string_release};
+
+use std::ffi::{c_char, CStr};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Locks `mutex` even if a panicking thread poisoned it; unwinding must never
+/// cross the C ABI.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+  mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+unsafe fn c_str_to_string(ptr: *const c_char) -> String {
+  if ptr.is_null() {
+    String::new()
+  } else {
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+  }
+}
+
+/// Moves `value` to the heap and returns it as the opaque ABI type `R`.
+fn into_raw<T, R>(value: T) -> *mut R {
+  Box::into_raw(Box::new(value)).cast()
+}
+
+unsafe fn borrow<'a, T, R>(raw: *mut R) -> Option<&'a T> {
+  raw.cast::<T>().as_ref()
+}
+
+unsafe fn release<T, R>(raw: *mut R) {
+  if !raw.is_null() {
+    drop(Box::from_raw(raw.cast::<T>()));
+  }
+}
+
+/// Copies a caller-owned buffer and hands it back through its destructor.
+unsafe fn take_bytes(
+  content: *mut u8,
+  length: usize,
+  dtor: Option<sys::binary_data_dtor>,
+  opaque: *mut std::ffi::c_void,
+) -> Vec<u8> {
+  let bytes = if content.is_null() {
+    Vec::new()
+  } else {
+    std::slice::from_raw_parts(content, length).to_vec()
+  };
+  if let Some(dtor) = dtor {
+    dtor(content, length, opaque);
+  }
+  bytes
+}
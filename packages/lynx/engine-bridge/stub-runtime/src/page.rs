@@ -0,0 +1,260 @@
+//! Templates the stub understands.
+//!
+//! Bytes that are not UTF-8 text, or that contain a NUL byte, are treated as
+//! an opaque compiled bundle and render a blank white page. Text must be a
+//! JSON page description, which lets tests script what the stub reports:
+//!
+//! ```json
+//! {
+//!   "background": [255, 255, 255],
+//!   "rects": [{ "x": 0, "y": 0, "width": 10, "height": 10, "color": [255, 0, 0, 255] }],
+//!   "resources": [{ "url": "https://example.com/a.png", "type": "image" }],
+//!   "errors": [{ "code": 201, "message": "script error" }]
+//! }
+//! ```
+//!
+//! Every field is optional. Rects are in layout pixels and scale with the
+//! view's pixel ratio. Each listed resource is requested through the view's
+//! resource fetcher after the runtime becomes ready, and each listed error is
+//! reported to view clients after the first screen.
+
+use serde_json::Value;
+
+/// An RGBA color.
+pub type Color = [u8; 4];
+
+const WHITE: Color = [255, 255, 255, 255];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rect {
+  pub x: f32,
+  pub y: f32,
+  pub width: f32,
+  pub height: f32,
+  pub color: Color,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resource {
+  pub url: String,
+  /// The resource type name, such as `image` or `font`; unknown names are
+  /// requested as generic resources.
+  pub resource_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageError {
+  pub code: i32,
+  pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page {
+  pub background: Color,
+  pub rects: Vec<Rect>,
+  pub resources: Vec<Resource>,
+  pub errors: Vec<PageError>,
+}
+
+impl Default for Page {
+  fn default() -> Self {
+    Self {
+      background: WHITE,
+      rects: Vec::new(),
+      resources: Vec::new(),
+      errors: Vec::new(),
+    }
+  }
+}
+
+/// One rendered frame, tightly packed RGBA rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+  pub width: u32,
+  pub height: u32,
+  pub pixels: Vec<u8>,
+}
+
+impl Frame {
+  pub fn row_bytes(&self) -> usize {
+    self.width as usize * 4
+  }
+}
+
+impl Page {
+  pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+    if bytes.is_empty() {
+      return Err("not a template bundle: the template is empty".to_string());
+    }
+    let text = match std::str::from_utf8(bytes) {
+      Ok(text) if !text.contains('\0') => text,
+      _ => return Ok(Self::default()),
+    };
+    let value: Value = serde_json::from_str(text)
+      .map_err(|error| format!("not a template bundle: invalid page description: {error}"))?;
+    let Value::Object(description) = value else {
+      return Err("not a template bundle: the page description must be an object".to_string());
+    };
+
+    let mut page = Self::default();
+    if let Some(background) = description.get("background") {
+      page.background = parse_color(background, "background")?;
+    }
+    for rect in array(&description, "rects")? {
+      page.rects.push(Rect {
+        x: number(rect, "x", 0.0)?,
+        y: number(rect, "y", 0.0)?,
+        width: number(rect, "width", 0.0)?,
+        height: number(rect, "height", 0.0)?,
+        color: match rect.get("color") {
+          Some(color) => parse_color(color, "rect color")?,
+          None => [0, 0, 0, 255],
+        },
+      });
+    }
+    for resource in array(&description, "resources")? {
+      page.resources.push(Resource {
+        url: string(resource, "url")?.ok_or("resource entries need a url")?,
+        resource_type: string(resource, "type")?.unwrap_or_else(|| "generic".to_string()),
+      });
+    }
+    for error in array(&description, "errors")? {
+      page.errors.push(PageError {
+        code: number(error, "code", 0.0)? as i32,
+        message: string(error, "message")?.unwrap_or_default(),
+      });
+    }
+    Ok(page)
+  }
+
+  /// Paints the page into a `width` by `height` frame in physical pixels.
+  pub fn render(&self, width: u32, height: u32, pixel_ratio: f32) -> Frame {
+    let mut pixels = self.background.repeat(width as usize * height as usize);
+    for rect in &self.rects {
+      let left = scale(rect.x, pixel_ratio, width);
+      let top = scale(rect.y, pixel_ratio, height);
+      let right = scale(rect.x + rect.width, pixel_ratio, width);
+      let bottom = scale(rect.y + rect.height, pixel_ratio, height);
+      for y in top..bottom {
+        let row = y as usize * width as usize;
+        for x in left..right {
+          let offset = (row + x as usize) * 4;
+          pixels[offset..offset + 4].copy_from_slice(&rect.color);
+        }
+      }
+    }
+    Frame {
+      width,
+      height,
+      pixels,
+    }
+  }
+}
+
+fn scale(value: f32, pixel_ratio: f32, limit: u32) -> u32 {
+  ((value * pixel_ratio).round().max(0.0) as u32).min(limit)
+}
+
+fn array<'a>(
+  description: &'a serde_json::Map<String, Value>,
+  field: &str,
+) -> Result<&'a [Value], String> {
+  match description.get(field) {
+    None => Ok(&[]),
+    Some(Value::Array(values)) => Ok(values),
+    Some(_) => Err(format!("page description field `{field}` must be an array")),
+  }
+}
+
+fn number(value: &Value, field: &str, default: f32) -> Result<f32, String> {
+  match value.get(field) {
+    None => Ok(default),
+    Some(number) => number
+      .as_f64()
+      .map(|number| number as f32)
+      .ok_or_else(|| format!("page description field `{field}` must be a number")),
+  }
+}
+
+fn string(value: &Value, field: &str) -> Result<Option<String>, String> {
+  match value.get(field) {
+    None => Ok(None),
+    Some(Value::String(text)) => Ok(Some(text.clone())),
+    Some(_) => Err(format!("page description field `{field}` must be a string")),
+  }
+}
+
+fn parse_color(value: &Value, field: &str) -> Result<Color, String> {
+  let channels = value
+    .as_array()
+    .filter(|channels| matches!(channels.len(), 3 | 4))
+    .ok_or_else(|| format!("{field} must be an [r, g, b] or [r, g, b, a] array"))?;
+  let mut color = WHITE;
+  for (slot, channel) in color.iter_mut().zip(channels) {
+    *slot = channel
+      .as_u64()
+      .and_then(|channel| u8::try_from(channel).ok())
+      .ok_or_else(|| format!("{field} channels must be integers from 0 to 255"))?;
+  }
+  Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn binary_bundles_render_a_white_page() {
+    let page = Page::parse(&[0x00, 0x4c, 0x59, 0x4e, 0xff]).unwrap();
+    assert_eq!(page, Page::default());
+    let frame = page.render(2, 1, 1.0);
+    assert_eq!(frame.pixels, vec![255; 8]);
+  }
+
+  #[test]
+  fn empty_and_plain_text_templates_are_rejected() {
+    assert!(Page::parse(b"").unwrap_err().contains("empty"));
+    assert!(Page::parse(b"hello")
+      .unwrap_err()
+      .starts_with("not a template bundle"));
+    assert!(Page::parse(b"[1, 2]").unwrap_err().contains("object"));
+  }
+
+  #[test]
+  fn descriptions_parse_every_field() {
+    let page = Page::parse(
+      br#"{
+        "background": [0, 0, 255],
+        "rects": [{ "x": 1, "y": 0, "width": 1, "height": 1, "color": [255, 0, 0, 128] }],
+        "resources": [{ "url": "a.png", "type": "image" }, { "url": "b.bin" }],
+        "errors": [{ "code": 201, "message": "boom" }]
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(page.background, [0, 0, 255, 255]);
+    assert_eq!(page.rects[0].color, [255, 0, 0, 128]);
+    assert_eq!(page.resources[1].resource_type, "generic");
+    assert_eq!(
+      page.errors,
+      vec![PageError {
+        code: 201,
+        message: "boom".to_string()
+      }]
+    );
+    assert!(Page::parse(br#"{"background": [300, 0, 0]}"#).is_err());
+    assert!(Page::parse(br#"{"resources": [{}]}"#).is_err());
+  }
+
+  #[test]
+  fn rects_scale_with_pixel_ratio_and_clip_to_the_frame() {
+    let page = Page::parse(
+      br#"{"background": [0, 0, 0], "rects": [{ "x": 1, "y": 1, "width": 5, "height": 5, "color": [9, 9, 9] }]}"#,
+    )
+    .unwrap();
+    let frame = page.render(4, 4, 2.0);
+    let pixel = |x: usize, y: usize| &frame.pixels[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+    assert_eq!(pixel(1, 1), &[0, 0, 0, 255]);
+    assert_eq!(pixel(2, 2), &[9, 9, 9, 255]);
+    assert_eq!(pixel(3, 3), &[9, 9, 9, 255]);
+  }
+}
@@ -0,0 +1,341 @@
+use crate::call_log::record;
+use crate::page::Frame;
+use crate::{borrow, into_raw, lock, release, sys};
+use std::collections::HashMap;
+use std::ffi::{c_int, c_void};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Default)]
+struct Callbacks {
+  on_gl_make_current: Option<sys::on_gl_make_current>,
+  on_gl_clear_current: Option<sys::on_gl_clear_current>,
+  on_gl_present: Option<sys::on_gl_present>,
+  on_gl_create_fbo: Option<sys::on_gl_create_fbo>,
+  on_gl_proc_resolver: Option<sys::on_gl_proc_resolver>,
+  on_software_present: Option<sys::on_software_present>,
+  on_accelerated_present: Option<sys::on_accelerated_present>,
+  on_post_task: Option<sys::on_post_task>,
+  get_clipboard_data: Option<sys::get_clipboard_data>,
+  set_clipboard_data: Option<sys::set_clipboard_data>,
+  activate_system_cursor: Option<sys::activate_system_cursor>,
+  show_text_input: Option<sys::show_text_input>,
+  update_caret_position: Option<sys::update_caret_position>,
+  set_cursor_position: Option<sys::set_cursor_position>,
+  set_marked_text_rect: Option<sys::set_marked_text_rect>,
+  set_editable_transform: Option<sys::set_editable_transform>,
+}
+
+pub(crate) struct Renderer {
+  renderer_type: sys::lynx_windowless_renderer_type_e,
+  user_data: *mut c_void,
+  finalizer: Option<sys::lynx_windowless_renderer_finalizer>,
+  callbacks: Mutex<Callbacks>,
+  paint_size: Mutex<Option<(u32, u32)>>,
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct PendingJob {
+  renderer: usize,
+  job: Job,
+}
+
+struct GlobalRunner {
+  user_data: usize,
+  post_task: sys::lynx_windowless_ui_task_runner_post_task_callback,
+}
+
+static JOBS: Mutex<Option<HashMap<u64, PendingJob>>> = Mutex::new(None);
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+static GLOBAL_RUNNER: Mutex<Option<GlobalRunner>> = Mutex::new(None);
+
+/// Schedules `job` on the UI thread of `renderer`.
+///
+/// The job goes to the renderer's `on_post_task` callback, or else to the
+/// global UI task runner; without either it runs before this returns.
+pub(crate) unsafe fn post(renderer: *mut sys::lynx_windowless_renderer_t, job: Job) {
+  let on_post_task =
+    borrow::<Renderer, _>(renderer).and_then(|state| lock(&state.callbacks).on_post_task);
+  let global_post_task = lock(&GLOBAL_RUNNER)
+    .as_ref()
+    .map(|runner| (runner.post_task, runner.user_data));
+  if on_post_task.is_none() && global_post_task.is_none() {
+    job();
+    return;
+  }
+
+  let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+  lock(&JOBS).get_or_insert_with(HashMap::new).insert(
+    id,
+    PendingJob {
+      renderer: renderer as usize,
+      job,
+    },
+  );
+  let task = sys::lynx_task_t {
+    runner: renderer.cast(),
+    task: id,
+  };
+  match (on_post_task, global_post_task) {
+    (Some(on_post_task), _) => on_post_task(renderer, task, 0),
+    (None, Some((post_task, user_data))) => post_task(task, 0, user_data as *mut c_void),
+    (None, None) => unreachable!(),
+  }
+}
+
+/// Runs a posted job; returns `false` when the task is unknown or already ran.
+fn run(task: sys::lynx_task_t) -> bool {
+  let job = lock(&JOBS)
+    .as_mut()
+    .and_then(|jobs| jobs.remove(&task.task));
+  match job {
+    Some(pending) => {
+      (pending.job)();
+      true
+    }
+    None => false,
+  }
+}
+
+/// Shows `frame` through whichever present path the renderer type uses.
+pub(crate) unsafe fn present(
+  renderer: *mut sys::lynx_windowless_renderer_t,
+  frame: &Frame,
+) -> bool {
+  let Some(state) = borrow::<Renderer, _>(renderer) else {
+    return false;
+  };
+  let callbacks = *lock(&state.callbacks);
+  match state.renderer_type {
+    sys::kRendererTypeSoftware => callbacks.on_software_present.is_some_and(|present| {
+      present(
+        renderer,
+        frame.pixels.as_ptr().cast(),
+        frame.row_bytes(),
+        frame.height as usize,
+      )
+    }),
+    sys::kRendererTypeGL | sys::kRendererTypeGLDirect => {
+      let (Some(make_current), Some(present), Some(clear_current)) = (
+        callbacks.on_gl_make_current,
+        callbacks.on_gl_present,
+        callbacks.on_gl_clear_current,
+      ) else {
+        return false;
+      };
+      if !make_current(renderer) {
+        return false;
+      }
+      if let Some(create_fbo) = callbacks.on_gl_create_fbo {
+        create_fbo(renderer, frame.width as c_int, frame.height as c_int);
+      }
+      let presented = present(renderer);
+      clear_current(renderer);
+      presented
+    }
+    sys::kRendererTypeAccelerated => {
+      *lock(&state.paint_size) = Some((frame.width, frame.height));
+      callbacks
+        .on_accelerated_present
+        .is_some_and(|present| present(renderer))
+    }
+    _ => false,
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_windowless_set_global_ui_task_runner(
+  config: *const sys::lynx_windowless_ui_task_runner_config_t,
+) -> bool {
+  record("lynx_windowless_set_global_ui_task_runner");
+  let Some(config) = config.as_ref() else {
+    return false;
+  };
+  let Some(post_task) = config.post_task_callback else {
+    return false;
+  };
+  let mut runner = lock(&GLOBAL_RUNNER);
+  if runner.is_some() {
+    return false;
+  }
+  *runner = Some(GlobalRunner {
+    user_data: config.user_data as usize,
+    post_task,
+  });
+  true
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_windowless_run_ui_task(task: sys::lynx_task_t) -> bool {
+  run(task)
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_windowless_renderer_create_with_finalizer(
+  renderer_type: sys::lynx_windowless_renderer_type_e,
+  user_data: *mut c_void,
+  finalizer: Option<sys::lynx_windowless_renderer_finalizer>,
+) -> *mut sys::lynx_windowless_renderer_t {
+  record(format!(
+    "lynx_windowless_renderer_create_with_finalizer {renderer_type}"
+  ));
+  into_raw(Renderer {
+    renderer_type,
+    user_data,
+    finalizer,
+    callbacks: Mutex::default(),
+    paint_size: Mutex::new(None),
+  })
+}
+
+macro_rules! bind_callback {
+  ($export:ident, $field:ident) => {
+    #[no_mangle]
+    pub unsafe extern "C" fn $export(
+      renderer: *mut sys::lynx_windowless_renderer_t,
+      callback: Option<sys::$field>,
+    ) {
+      record(stringify!($export));
+      if let Some(renderer) = borrow::<Renderer, _>(renderer) {
+        lock(&renderer.callbacks).$field = callback;
+      }
+    }
+  };
+}
+
+bind_callback!(
+  lynx_windowless_renderer_bind_on_gl_make_current,
+  on_gl_make_current
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_on_gl_clear_current,
+  on_gl_clear_current
+);
+bind_callback!(lynx_windowless_renderer_bind_on_gl_present, on_gl_present);
+bind_callback!(
+  lynx_windowless_renderer_bind_on_gl_create_fbo,
+  on_gl_create_fbo
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_on_gl_proc_resolver,
+  on_gl_proc_resolver
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_on_software_present,
+  on_software_present
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_on_accelerated_present,
+  on_accelerated_present
+);
+bind_callback!(lynx_windowless_renderer_bind_on_post_task, on_post_task);
+bind_callback!(
+  lynx_windowless_renderer_bind_get_clipboard_data,
+  get_clipboard_data
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_set_clipboard_data,
+  set_clipboard_data
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_activate_system_cursor,
+  activate_system_cursor
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_show_text_input,
+  show_text_input
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_update_caret_position,
+  update_caret_position
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_set_cursor_position,
+  set_cursor_position
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_set_marked_text_rect,
+  set_marked_text_rect
+);
+bind_callback!(
+  lynx_windowless_renderer_bind_set_editable_transform,
+  set_editable_transform
+);
+
+/// Reports the size of the last accelerated frame. The stub shares no
+/// texture, so the handle is always null.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_windowless_renderer_get_accelerated_paint_info(
+  renderer: *mut sys::lynx_windowless_renderer_t,
+  info: *mut sys::lynx_accelerated_paint_info_t,
+) -> bool {
+  record("lynx_windowless_renderer_get_accelerated_paint_info");
+  let Some(state) = borrow::<Renderer, _>(renderer) else {
+    return false;
+  };
+  if state.renderer_type != sys::kRendererTypeAccelerated {
+    return false;
+  }
+  let (Some((width, height)), Some(info)) = (*lock(&state.paint_size), info.as_mut()) else {
+    return false;
+  };
+  *info = sys::lynx_accelerated_paint_info_t {
+    width,
+    height,
+    ..Default::default()
+  };
+  true
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_windowless_renderer_run_task(
+  _renderer: *mut sys::lynx_windowless_renderer_t,
+  task: sys::lynx_task_t,
+) {
+  run(task);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_windowless_renderer_send_pointer_event(
+  _renderer: *mut sys::lynx_windowless_renderer_t,
+  event: *mut sys::lynx_pointer_event_t,
+) {
+  let Some(event) = event.as_ref() else {
+    return;
+  };
+  record(format!(
+    "lynx_windowless_renderer_send_pointer_event {} {},{}",
+    event.phase, event.x, event.y
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_windowless_renderer_send_key_event(
+  _renderer: *mut sys::lynx_windowless_renderer_t,
+  event: *mut sys::lynx_key_event_t,
+) {
+  let Some(event) = event.as_ref() else {
+    return;
+  };
+  record(format!(
+    "lynx_windowless_renderer_send_key_event {} {}",
+    event.type_, event.logical
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_windowless_renderer_release(
+  renderer: *mut sys::lynx_windowless_renderer_t,
+) {
+  record("lynx_windowless_renderer_release");
+  if let Some(jobs) = lock(&JOBS).as_mut() {
+    jobs.retain(|_, pending| pending.renderer != renderer as usize);
+  }
+  if let Some(state) = borrow::<Renderer, _>(renderer) {
+    if let Some(finalizer) = state.finalizer {
+      finalizer(renderer, state.user_data);
+    }
+  }
+  release::<Renderer, _>(renderer);
+}
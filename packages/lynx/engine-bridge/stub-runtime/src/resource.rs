@@ -0,0 +1,231 @@
+use crate::call_log::record;
+use crate::{borrow, c_str_to_string, into_raw, lock, release, sys, take_bytes};
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Default)]
+struct Callbacks {
+  fetch_resource: Option<sys::fetch_resource_func>,
+  fetch_resource_path: Option<sys::fetch_resource_func>,
+  cancel_fetch: Option<sys::cancel_fetch_func>,
+}
+
+pub(crate) struct Fetcher {
+  user_data: *mut c_void,
+  finalizer: Option<sys::lynx_generic_resource_fetcher_finalizer>,
+  callbacks: Mutex<Callbacks>,
+}
+
+struct Request {
+  id: sys::lynx_resource_request_id,
+  resource_type: sys::lynx_resource_type_e,
+  url: CString,
+}
+
+/// The result of one resource request.
+pub(crate) enum FetchOutcome {
+  Ok(Vec<u8>),
+  Failed { code: c_int, message: String },
+}
+
+type Completion = Box<dyn FnOnce(FetchOutcome) + Send>;
+
+#[derive(Default)]
+struct ResponseState {
+  code: c_int,
+  error_message: Option<String>,
+  data: Option<Vec<u8>>,
+  completion: Option<Completion>,
+}
+
+struct Response {
+  state: Mutex<ResponseState>,
+}
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Requests `url` through `fetcher` and runs `completion` once the host calls
+/// `lynx_resource_response_callback`, which may happen after this returns.
+pub(crate) unsafe fn fetch(
+  fetcher: *mut sys::lynx_generic_resource_fetcher_t,
+  url: &str,
+  resource_type: sys::lynx_resource_type_e,
+  completion: impl FnOnce(FetchOutcome) + Send + 'static,
+) {
+  let fetch_resource =
+    borrow::<Fetcher, _>(fetcher).and_then(|fetcher| lock(&fetcher.callbacks).fetch_resource);
+  let Some(fetch_resource) = fetch_resource else {
+    completion(FetchOutcome::Failed {
+      code: -1,
+      message: format!("no resource fetcher can serve {url}"),
+    });
+    return;
+  };
+  let request = into_raw(Request {
+    id: NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+    resource_type,
+    url: CString::new(url).unwrap_or_default(),
+  });
+  let response = into_raw(Response {
+    state: Mutex::new(ResponseState {
+      completion: Some(Box::new(completion)),
+      ..ResponseState::default()
+    }),
+  });
+  // The host owns both objects from here on and releases them.
+  fetch_resource(fetcher, request, response);
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_generic_resource_fetcher_create_with_finalizer(
+  user_data: *mut c_void,
+  finalizer: Option<sys::lynx_generic_resource_fetcher_finalizer>,
+) -> *mut sys::lynx_generic_resource_fetcher_t {
+  record("lynx_generic_resource_fetcher_create_with_finalizer");
+  into_raw(Fetcher {
+    user_data,
+    finalizer,
+    callbacks: Mutex::default(),
+  })
+}
+
+macro_rules! bind_callback {
+  ($export:ident, $field:ident, $callback:ty) => {
+    #[no_mangle]
+    pub unsafe extern "C" fn $export(
+      fetcher: *mut sys::lynx_generic_resource_fetcher_t,
+      callback: Option<$callback>,
+    ) {
+      record(stringify!($export));
+      if let Some(fetcher) = borrow::<Fetcher, _>(fetcher) {
+        lock(&fetcher.callbacks).$field = callback;
+      }
+    }
+  };
+}
+
+bind_callback!(
+  lynx_generic_resource_fetcher_bind_fetch_resource,
+  fetch_resource,
+  sys::fetch_resource_func
+);
+bind_callback!(
+  lynx_generic_resource_fetcher_bind_fetch_resource_path,
+  fetch_resource_path,
+  sys::fetch_resource_func
+);
+bind_callback!(
+  lynx_generic_resource_fetcher_bind_cancel_fetch,
+  cancel_fetch,
+  sys::cancel_fetch_func
+);
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_generic_resource_fetcher_release(
+  fetcher: *mut sys::lynx_generic_resource_fetcher_t,
+) {
+  record("lynx_generic_resource_fetcher_release");
+  if let Some(state) = borrow::<Fetcher, _>(fetcher) {
+    if let Some(finalizer) = state.finalizer {
+      finalizer(fetcher, state.user_data);
+    }
+  }
+  release::<Fetcher, _>(fetcher);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_request_get_id(
+  request: *mut sys::lynx_resource_request_t,
+) -> sys::lynx_resource_request_id {
+  borrow::<Request, _>(request).map_or(0, |request| request.id)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_request_get_type(
+  request: *mut sys::lynx_resource_request_t,
+) -> sys::lynx_resource_type_e {
+  borrow::<Request, _>(request).map_or(sys::kLynxResourceTypeGeneric, |request| {
+    request.resource_type
+  })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_request_get_url(
+  request: *mut sys::lynx_resource_request_t,
+) -> *const c_char {
+  borrow::<Request, _>(request).map_or(std::ptr::null(), |request| request.url.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_request_release(request: *mut sys::lynx_resource_request_t) {
+  release::<Request, _>(request);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_response_set_code(
+  response: *mut sys::lynx_resource_response_t,
+  code: c_int,
+) {
+  if let Some(response) = borrow::<Response, _>(response) {
+    lock(&response.state).code = code;
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_response_set_error_message(
+  response: *mut sys::lynx_resource_response_t,
+  message: *const c_char,
+) {
+  if let Some(response) = borrow::<Response, _>(response) {
+    lock(&response.state).error_message = Some(c_str_to_string(message));
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_response_set_data(
+  response: *mut sys::lynx_resource_response_t,
+  content: *mut u8,
+  length: usize,
+  dtor: Option<sys::binary_data_dtor>,
+  opaque: *mut c_void,
+) {
+  let data = take_bytes(content, length, dtor, opaque);
+  if let Some(response) = borrow::<Response, _>(response) {
+    lock(&response.state).data = Some(data);
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_response_callback(
+  response: *mut sys::lynx_resource_response_t,
+) {
+  let Some(response) = borrow::<Response, _>(response) else {
+    return;
+  };
+  let (completion, outcome) = {
+    let mut state = lock(&response.state);
+    let outcome = if state.code == 0 {
+      FetchOutcome::Ok(state.data.take().unwrap_or_default())
+    } else {
+      FetchOutcome::Failed {
+        code: state.code,
+        message: state
+          .error_message
+          .take()
+          .unwrap_or_else(|| "resource request failed".to_string()),
+      }
+    };
+    (state.completion.take(), outcome)
+  };
+  if let Some(completion) = completion {
+    completion(outcome);
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_resource_response_release(
+  response: *mut sys::lynx_resource_response_t,
+) {
+  release::<Response, _>(response);
+}
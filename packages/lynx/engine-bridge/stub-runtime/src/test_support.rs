@@ -0,0 +1,63 @@
+//! Helpers for test suites that load the stub through `Env::load_from_path`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// A page with a blue background, one red rect, a resource that tests serve,
+/// one they do not, and a scripted error. Resource URLs are relative, so they
+/// resolve against the page URL where the host does that.
+pub const PAGE: &str = r#"{
+  "background": [0, 0, 255],
+  "rects": [{ "x": 1, "y": 1, "width": 2, "height": 2, "color": [255, 0, 0] }],
+  "resources": [
+    { "url": "logo.png", "type": "image" },
+    { "url": "missing.png", "type": "image" }
+  ],
+  "errors": [{ "code": 201, "message": "scripted error" }]
+}"#;
+
+/// Returns the path of the stub library, building it on first use.
+///
+/// The stub is built into `target/tmp/lynx-stub-runtime`, a private target
+/// directory, so the nested Cargo invocation never waits on the lock held by
+/// the outer test build. Set `LYNX_STUB_LIB_PATH` to use a prebuilt stub
+/// instead.
+pub fn library_path() -> &'static Path {
+  static PATH: OnceLock<PathBuf> = OnceLock::new();
+  PATH.get_or_init(|| {
+    if let Some(path) = std::env::var_os("LYNX_STUB_LIB_PATH") {
+      return PathBuf::from(path);
+    }
+    // Test executables run from `target/<profile>/deps`.
+    let target_dir = std::env::current_exe()
+      .expect("test executable path")
+      .ancestors()
+      .nth(3)
+      .expect("test executable lives under the target directory")
+      .join("tmp")
+      .join("lynx-stub-runtime");
+    let status = Command::new(env!("CARGO"))
+      .args([
+        "build",
+        "--offline",
+        "-p",
+        "lynx-stub-runtime",
+        "--target-dir",
+      ])
+      .arg(&target_dir)
+      .current_dir(env!("CARGO_MANIFEST_DIR"))
+      .status()
+      .expect("run cargo build for the stub runtime");
+    assert!(status.success(), "building the stub runtime failed");
+    target_dir.join("debug").join(library_filename())
+  })
+}
+
+fn library_filename() -> String {
+  format!(
+    "{}Lynx_clay{}",
+    std::env::consts::DLL_PREFIX,
+    std::env::consts::DLL_SUFFIX
+  )
+}
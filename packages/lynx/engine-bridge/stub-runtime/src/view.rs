@@ -0,0 +1,776 @@
+use crate::call_log::record;
+use crate::client::{notify, ClientEvent};
+use crate::page::Page;
+use crate::resource::{fetch, FetchOutcome};
+use crate::{borrow, c_str_to_string, into_raw, lock, release, renderer, sys, take_bytes};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::sync::Mutex;
+
+/// `on_received_error` code for templates that fail to load or decode.
+const ERROR_TEMPLATE: c_int = 100;
+/// `on_received_error` code for page resources that fail to load.
+const ERROR_RESOURCE: c_int = 301;
+
+#[derive(Clone, Copy)]
+struct Metrics {
+  width: f32,
+  height: f32,
+  pixel_ratio: f32,
+}
+
+impl Default for Metrics {
+  fn default() -> Self {
+    Self {
+      width: 0.0,
+      height: 0.0,
+      pixel_ratio: 1.0,
+    }
+  }
+}
+
+struct Builder {
+  metrics: Metrics,
+  renderer: *mut sys::lynx_windowless_renderer_t,
+  fetcher: *mut sys::lynx_generic_resource_fetcher_t,
+}
+
+struct ViewState {
+  metrics: Metrics,
+  clients: Vec<*mut sys::lynx_view_client_t>,
+  url: String,
+  page: Option<Page>,
+}
+
+struct View {
+  renderer: *mut sys::lynx_windowless_renderer_t,
+  fetcher: *mut sys::lynx_generic_resource_fetcher_t,
+  state: Mutex<ViewState>,
+}
+
+/// Addresses of views that have not been released. Posted jobs and fetch
+/// completions only hold an address and drop their work once it is gone.
+static LIVE_VIEWS: Mutex<Option<HashSet<usize>>> = Mutex::new(None);
+
+unsafe fn live_view<'a>(address: usize) -> Option<&'a View> {
+  let live = lock(&LIVE_VIEWS)
+    .as_ref()
+    .is_some_and(|views| views.contains(&address));
+  if live {
+    borrow::<View, _>(address as *mut sys::lynx_view_t)
+  } else {
+    None
+  }
+}
+
+impl View {
+  /// Notifies every client. The client list is copied first so callbacks may
+  /// add or remove clients.
+  unsafe fn notify_all(&self, event: ClientEvent) {
+    let clients = lock(&self.state).clients.clone();
+    for client in clients {
+      notify(client, &event);
+    }
+  }
+
+  /// Renders the loaded page at the current frame size, if there is one.
+  unsafe fn present(&self) -> bool {
+    let frame = {
+      let state = lock(&self.state);
+      let Some(page) = &state.page else {
+        return false;
+      };
+      let metrics = state.metrics;
+      page.render(
+        physical(metrics.width, metrics.pixel_ratio),
+        physical(metrics.height, metrics.pixel_ratio),
+        metrics.pixel_ratio,
+      )
+    };
+    renderer::present(self.renderer, &frame)
+  }
+
+  unsafe fn post(&self, job: impl FnOnce(&View) + Send + 'static) {
+    let address = self as *const View as usize;
+    renderer::post(
+      self.renderer,
+      Box::new(move || {
+        if let Some(view) = live_view(address) {
+          job(view);
+        }
+      }),
+    );
+  }
+
+  /// Starts a load. Templates without bytes are requested from `url` through
+  /// the view's resource fetcher.
+  unsafe fn load(&self, url: String, bytes: Option<Vec<u8>>) {
+    self.notify_all(ClientEvent::PageStart(url.clone()));
+    lock(&self.state).url = url.clone();
+    match bytes {
+      Some(bytes) => self.show(&bytes),
+      None => {
+        let address = self as *const View as usize;
+        let failed_url = url.clone();
+        fetch(
+          self.fetcher,
+          &url,
+          sys::kLynxResourceTypeTemplate,
+          move |outcome| {
+            let Some(view) = live_view(address) else {
+              return;
+            };
+            match outcome {
+              FetchOutcome::Ok(bytes) => view.show(&bytes),
+              FetchOutcome::Failed { message, .. } => view.notify_all(ClientEvent::ReceivedError(
+                ERROR_TEMPLATE,
+                format!("failed to fetch template {failed_url}: {message}"),
+              )),
+            }
+          },
+        );
+      }
+    }
+  }
+
+  /// Runs the rest of a load once the template bytes are available.
+  unsafe fn show(&self, bytes: &[u8]) {
+    let page = match Page::parse(bytes) {
+      Ok(page) => page,
+      Err(message) => {
+        self.notify_all(ClientEvent::ReceivedError(ERROR_TEMPLATE, message));
+        return;
+      }
+    };
+    self.notify_all(ClientEvent::RuntimeReady);
+
+    let address = self as *const View as usize;
+    for resource in &page.resources {
+      let url = resource.url.clone();
+      let resource_type = resource.resource_type.clone();
+      fetch(
+        self.fetcher,
+        &resource.url,
+        resource_type_code(&resource.resource_type),
+        move |outcome| {
+          if let FetchOutcome::Failed { code, message } = outcome {
+            if let Some(view) = live_view(address) {
+              view.notify_all(ClientEvent::ReceivedError(
+                ERROR_RESOURCE,
+                format!("failed to load {resource_type} resource {url} ({code}): {message}"),
+              ));
+            }
+          }
+        },
+      );
+    }
+
+    let errors = page.errors.clone();
+    lock(&self.state).page = Some(page);
+    self.present();
+    self.notify_all(ClientEvent::LoadSuccess);
+    self.notify_all(ClientEvent::FirstScreen);
+    for error in errors {
+      self.notify_all(ClientEvent::ReceivedError(error.code, error.message));
+    }
+  }
+
+  /// Re-presents the page after its metrics change.
+  unsafe fn update_metrics(&self, update: impl FnOnce(&mut Metrics)) {
+    update(&mut lock(&self.state).metrics);
+    self.post(|view| {
+      view.present();
+    });
+  }
+}
+
+fn physical(size: f32, pixel_ratio: f32) -> u32 {
+  (size * pixel_ratio).round().max(0.0) as u32
+}
+
+fn resource_type_code(name: &str) -> sys::lynx_resource_type_e {
+  match name {
+    "image" => sys::kLynxResourceTypeImage,
+    "font" => sys::kLynxResourceTypeFont,
+    "lottie" => sys::kLynxResourceTypeLottie,
+    "video" => sys::kLynxResourceTypeVideo,
+    "svg" => sys::kLynxResourceTypeSVG,
+    "template" => sys::kLynxResourceTypeTemplate,
+    "lazy-bundle" => sys::kLynxResourceTypeLazyBundle,
+    "i18n-text" => sys::kLynxResourceTypeI18NText,
+    "theme" => sys::kLynxResourceTypeTheme,
+    "assets" => sys::kLynxResourceTypeAssets,
+    _ => sys::kLynxResourceTypeGeneric,
+  }
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_view_builder_create() -> *mut sys::lynx_view_builder_t {
+  record("lynx_view_builder_create");
+  into_raw(Builder {
+    metrics: Metrics::default(),
+    renderer: std::ptr::null_mut(),
+    fetcher: std::ptr::null_mut(),
+  })
+}
+
+unsafe fn with_builder(builder: *mut sys::lynx_view_builder_t, update: impl FnOnce(&mut Builder)) {
+  if let Some(builder) = builder.cast::<Builder>().as_mut() {
+    update(builder);
+  }
+}
+
+/// The stub lays out in the frame only, so the screen size is recorded but
+/// only its pixel ratio is used.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_rust_view_builder_set_screen_size(
+  builder: *mut sys::lynx_view_builder_t,
+  width: f32,
+  height: f32,
+  pixel_ratio: f32,
+) {
+  record(format!(
+    "lynx_rust_view_builder_set_screen_size {width}x{height}@{pixel_ratio}"
+  ));
+  with_builder(builder, |builder| builder.metrics.pixel_ratio = pixel_ratio);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_rust_view_builder_set_frame(
+  builder: *mut sys::lynx_view_builder_t,
+  _x: f32,
+  _y: f32,
+  width: f32,
+  height: f32,
+) {
+  record("lynx_rust_view_builder_set_frame");
+  with_builder(builder, |builder| {
+    builder.metrics.width = width;
+    builder.metrics.height = height;
+  });
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_rust_view_builder_set_font_scale(
+  _builder: *mut sys::lynx_view_builder_t,
+  font_scale: f32,
+) {
+  record(format!(
+    "lynx_rust_view_builder_set_font_scale {font_scale}"
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_builder_set_icu_data_path(
+  _builder: *mut sys::lynx_view_builder_t,
+  path: *const c_char,
+) {
+  record(format!(
+    "lynx_view_builder_set_icu_data_path {}",
+    c_str_to_string(path)
+  ));
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_view_builder_set_lynx_group(
+  _builder: *mut sys::lynx_view_builder_t,
+  _group: *mut sys::lynx_group_t,
+) {
+  record("lynx_view_builder_set_lynx_group");
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_builder_set_windowless_renderer(
+  builder: *mut sys::lynx_view_builder_t,
+  renderer: *mut sys::lynx_windowless_renderer_t,
+) {
+  record("lynx_view_builder_set_windowless_renderer");
+  with_builder(builder, |builder| builder.renderer = renderer);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_builder_set_generic_resource_fetcher(
+  builder: *mut sys::lynx_view_builder_t,
+  fetcher: *mut sys::lynx_generic_resource_fetcher_t,
+) {
+  record("lynx_view_builder_set_generic_resource_fetcher");
+  with_builder(builder, |builder| builder.fetcher = fetcher);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_builder_register_native_module(
+  _builder: *mut sys::lynx_view_builder_t,
+  name: *const c_char,
+  _creator: Option<sys::napi_module_creator>,
+  _opaque: *mut c_void,
+) {
+  record(format!(
+    "lynx_view_builder_register_native_module {}",
+    c_str_to_string(name)
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_builder_register_extension_module(
+  _builder: *mut sys::lynx_view_builder_t,
+  name: *const c_char,
+  _creator: Option<sys::extension_module_creator>,
+  _is_lazy_create: bool,
+  _opaque: *mut c_void,
+) {
+  record(format!(
+    "lynx_view_builder_register_extension_module {}",
+    c_str_to_string(name)
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_builder_release(builder: *mut sys::lynx_view_builder_t) {
+  record("lynx_view_builder_release");
+  release::<Builder, _>(builder);
+}
+
+/// Creates a view from the builder's current settings. Views without a
+/// windowless renderer are rejected, since the stub can only run headless.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_create(
+  builder: *mut sys::lynx_view_builder_t,
+  _native_window: *mut c_void,
+) -> *mut sys::lynx_view_t {
+  record("lynx_view_create");
+  let Some(builder) = borrow::<Builder, _>(builder) else {
+    return std::ptr::null_mut();
+  };
+  if builder.renderer.is_null() {
+    return std::ptr::null_mut();
+  }
+  let view = into_raw::<_, sys::lynx_view_t>(View {
+    renderer: builder.renderer,
+    fetcher: builder.fetcher,
+    state: Mutex::new(ViewState {
+      metrics: builder.metrics,
+      clients: Vec::new(),
+      url: String::new(),
+      page: None,
+    }),
+  });
+  lock(&LIVE_VIEWS)
+    .get_or_insert_with(HashSet::new)
+    .insert(view as usize);
+  view
+}
+
+/// Notifies `on_destroy` before the view is freed; work still queued for it
+/// is dropped when it runs.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_release(view: *mut sys::lynx_view_t) {
+  record("lynx_view_release");
+  let Some(state) = live_view(view as usize) else {
+    return;
+  };
+  state.notify_all(ClientEvent::Destroy);
+  if let Some(views) = lock(&LIVE_VIEWS).as_mut() {
+    views.remove(&(view as usize));
+  }
+  release::<View, _>(view);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_add_client(
+  view: *mut sys::lynx_view_t,
+  client: *mut sys::lynx_view_client_t,
+) {
+  record("lynx_view_add_client");
+  if let Some(view) = live_view(view as usize) {
+    let mut state = lock(&view.state);
+    if !client.is_null() && !state.clients.contains(&client) {
+      state.clients.push(client);
+    }
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_remove_client(
+  view: *mut sys::lynx_view_t,
+  client: *mut sys::lynx_view_client_t,
+) {
+  record("lynx_view_remove_client");
+  if let Some(view) = live_view(view as usize) {
+    lock(&view.state)
+      .clients
+      .retain(|existing| *existing != client);
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_load_template(
+  view: *mut sys::lynx_view_t,
+  meta: *mut sys::lynx_load_meta_t,
+) {
+  let Some(meta) = borrow::<LoadMeta, _>(meta) else {
+    return;
+  };
+  record(format!("lynx_view_load_template {}", meta.url));
+  let Some(view) = live_view(view as usize) else {
+    return;
+  };
+  let url = meta.url.clone();
+  let bytes = meta.template.clone();
+  view.post(move |view| view.load(url, bytes));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_update_data(
+  view: *mut sys::lynx_view_t,
+  meta: *mut sys::lynx_update_meta_t,
+) {
+  let Some(meta) = borrow::<UpdateMeta, _>(meta) else {
+    return;
+  };
+  let data = meta
+    .data
+    .as_ref()
+    .map_or_else(String::new, Value::to_string);
+  record(format!("lynx_view_update_data {data}"));
+  let Some(view) = live_view(view as usize) else {
+    return;
+  };
+  view.post(|view| {
+    if lock(&view.state).page.is_none() {
+      return;
+    }
+    view.notify_all(ClientEvent::DataUpdated);
+    view.present();
+    view.notify_all(ClientEvent::PageUpdated);
+  });
+}
+
+/// Re-shows the last page; the stub keeps no template data to reset.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_reload_template(
+  view: *mut sys::lynx_view_t,
+  _data: *mut sys::lynx_template_data_t,
+  _global_props: *mut sys::lynx_template_data_t,
+) {
+  record("lynx_view_reload_template");
+  let Some(view) = live_view(view as usize) else {
+    return;
+  };
+  view.post(|view| {
+    let (url, loaded) = {
+      let state = lock(&view.state);
+      (state.url.clone(), state.page.is_some())
+    };
+    if !loaded {
+      return;
+    }
+    view.notify_all(ClientEvent::PageStart(url));
+    view.present();
+    view.notify_all(ClientEvent::LoadSuccess);
+    view.notify_all(ClientEvent::FirstScreen);
+  });
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_send_global_event(
+  _view: *mut sys::lynx_view_t,
+  name: *const c_char,
+  params: *const c_char,
+) {
+  record(format!(
+    "lynx_view_send_global_event {} {}",
+    c_str_to_string(name),
+    c_str_to_string(params)
+  ));
+}
+
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_send_touch_event(
+  _view: *mut sys::lynx_view_t,
+  name: *const c_char,
+  tag: i32,
+  _x: f32,
+  _y: f32,
+  _client_x: f32,
+  _client_y: f32,
+  _page_x: f32,
+  _page_y: f32,
+) {
+  record(format!(
+    "lynx_view_send_touch_event {} {tag}",
+    c_str_to_string(name)
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_rust_view_update_screen_metrics(
+  view: *mut sys::lynx_view_t,
+  _width: f32,
+  _height: f32,
+  pixel_ratio: f32,
+) {
+  record("lynx_rust_view_update_screen_metrics");
+  if let Some(view) = live_view(view as usize) {
+    view.update_metrics(|metrics| metrics.pixel_ratio = pixel_ratio);
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_rust_view_set_frame(
+  view: *mut sys::lynx_view_t,
+  _x: f32,
+  _y: f32,
+  width: f32,
+  height: f32,
+) {
+  record(format!("lynx_rust_view_set_frame {width}x{height}"));
+  if let Some(view) = live_view(view as usize) {
+    view.update_metrics(|metrics| {
+      metrics.width = width;
+      metrics.height = height;
+    });
+  }
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_rust_view_set_font_scale(_view: *mut sys::lynx_view_t, font_scale: f32) {
+  record(format!("lynx_rust_view_set_font_scale {font_scale}"));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_rust_view_set_use_texture_backend(
+  view: *mut sys::lynx_view_t,
+  use_texture_backend: bool,
+) -> bool {
+  record(format!(
+    "lynx_rust_view_set_use_texture_backend {use_texture_backend}"
+  ));
+  live_view(view as usize).is_some()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_enter_foreground(view: *mut sys::lynx_view_t) {
+  record("lynx_view_enter_foreground");
+  if let Some(view) = live_view(view as usize) {
+    view.notify_all(ClientEvent::EnterForeground);
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_view_enter_background(view: *mut sys::lynx_view_t) {
+  record("lynx_view_enter_background");
+  if let Some(view) = live_view(view as usize) {
+    view.notify_all(ClientEvent::EnterBackground);
+  }
+}
+
+#[derive(Default)]
+struct LoadMeta {
+  url: String,
+  template: Option<Vec<u8>>,
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_load_meta_create() -> *mut sys::lynx_load_meta_t {
+  record("lynx_load_meta_create");
+  into_raw(LoadMeta::default())
+}
+
+unsafe fn with_load_meta(meta: *mut sys::lynx_load_meta_t, update: impl FnOnce(&mut LoadMeta)) {
+  if let Some(meta) = meta.cast::<LoadMeta>().as_mut() {
+    update(meta);
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_load_meta_set_url(
+  meta: *mut sys::lynx_load_meta_t,
+  url: *const c_char,
+) {
+  record("lynx_load_meta_set_url");
+  let url = c_str_to_string(url);
+  with_load_meta(meta, |meta| meta.url = url);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_load_meta_set_binary_data(
+  meta: *mut sys::lynx_load_meta_t,
+  content: *mut u8,
+  length: usize,
+  dtor: Option<sys::binary_data_dtor>,
+  opaque: *mut c_void,
+) {
+  record("lynx_load_meta_set_binary_data");
+  let bytes = take_bytes(content, length, dtor, opaque);
+  with_load_meta(meta, |meta| meta.template = Some(bytes));
+}
+
+/// Copies the bundle bytes, because callers release the bundle as soon as
+/// the load is queued.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_load_meta_set_template_bundle(
+  meta: *mut sys::lynx_load_meta_t,
+  bundle: *mut sys::lynx_template_bundle_t,
+) {
+  record("lynx_load_meta_set_template_bundle");
+  let Some(bundle) = borrow::<TemplateBundle, _>(bundle) else {
+    return;
+  };
+  with_load_meta(meta, |meta| meta.template = Some(bundle.bytes.clone()));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_load_meta_set_initial_data(
+  _meta: *mut sys::lynx_load_meta_t,
+  data: *mut sys::lynx_template_data_t,
+) {
+  record(format!("lynx_load_meta_set_initial_data {}", json_of(data)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_load_meta_set_global_props(
+  _meta: *mut sys::lynx_load_meta_t,
+  data: *mut sys::lynx_template_data_t,
+) {
+  record(format!("lynx_load_meta_set_global_props {}", json_of(data)));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_load_meta_release(meta: *mut sys::lynx_load_meta_t) {
+  record("lynx_load_meta_release");
+  release::<LoadMeta, _>(meta);
+}
+
+#[derive(Default)]
+struct UpdateMeta {
+  data: Option<Value>,
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_update_meta_create() -> *mut sys::lynx_update_meta_t {
+  record("lynx_update_meta_create");
+  into_raw(UpdateMeta::default())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_update_meta_set_update_data(
+  meta: *mut sys::lynx_update_meta_t,
+  data: *mut sys::lynx_template_data_t,
+) {
+  record("lynx_update_meta_set_update_data");
+  let data = borrow::<TemplateData, _>(data).map(|data| data.value.clone());
+  if let Some(meta) = meta.cast::<UpdateMeta>().as_mut() {
+    meta.data = data;
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_update_meta_set_global_props(
+  _meta: *mut sys::lynx_update_meta_t,
+  data: *mut sys::lynx_template_data_t,
+) {
+  record(format!(
+    "lynx_update_meta_set_global_props {}",
+    json_of(data)
+  ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_update_meta_release(meta: *mut sys::lynx_update_meta_t) {
+  record("lynx_update_meta_release");
+  release::<UpdateMeta, _>(meta);
+}
+
+struct TemplateData {
+  value: Value,
+}
+
+/// Serializes template data for the call log; invalid handles log as `null`.
+unsafe fn json_of(data: *mut sys::lynx_template_data_t) -> String {
+  borrow::<TemplateData, _>(data).map_or_else(|| "null".to_string(), |data| data.value.to_string())
+}
+
+/// Returns null when `json` is not valid JSON, matching the real runtime.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_template_data_create_from_json(
+  json: *const c_char,
+) -> *mut sys::lynx_template_data_t {
+  record("lynx_template_data_create_from_json");
+  match serde_json::from_str(&c_str_to_string(json)) {
+    Ok(value) => into_raw(TemplateData { value }),
+    Err(_) => std::ptr::null_mut(),
+  }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_template_data_mark_state(
+  _data: *mut sys::lynx_template_data_t,
+  state: *const c_char,
+) {
+  record(format!(
+    "lynx_template_data_mark_state {}",
+    c_str_to_string(state)
+  ));
+}
+
+#[no_mangle]
+pub extern "C" fn lynx_template_data_set_read_only(
+  _data: *mut sys::lynx_template_data_t,
+  read_only: c_int,
+) {
+  record(format!("lynx_template_data_set_read_only {read_only}"));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_template_data_release(data: *mut sys::lynx_template_data_t) {
+  record("lynx_template_data_release");
+  release::<TemplateData, _>(data);
+}
+
+struct TemplateBundle {
+  bytes: Vec<u8>,
+  error: Option<CString>,
+}
+
+/// Copies and validates the bundle up front, handing the caller's buffer
+/// straight back through `dtor`.
+#[no_mangle]
+pub unsafe extern "C" fn lynx_template_bundle_create(
+  content: *mut u8,
+  length: usize,
+  dtor: Option<sys::binary_data_dtor>,
+  opaque: *mut c_void,
+) -> *mut sys::lynx_template_bundle_t {
+  record("lynx_template_bundle_create");
+  let bytes = take_bytes(content, length, dtor, opaque);
+  let error = Page::parse(&bytes)
+    .err()
+    .map(|message| CString::new(message).unwrap_or_default());
+  into_raw(TemplateBundle { bytes, error })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_template_bundle_is_valid(
+  bundle: *mut sys::lynx_template_bundle_t,
+) -> c_int {
+  record("lynx_template_bundle_is_valid");
+  borrow::<TemplateBundle, _>(bundle).map_or(0, |bundle| c_int::from(bundle.error.is_none()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_template_bundle_get_error_message(
+  bundle: *mut sys::lynx_template_bundle_t,
+) -> *const c_char {
+  record("lynx_template_bundle_get_error_message");
+  borrow::<TemplateBundle, _>(bundle)
+    .and_then(|bundle| bundle.error.as_ref())
+    .map_or(c"".as_ptr(), |error| error.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lynx_template_bundle_release(bundle: *mut sys::lynx_template_bundle_t) {
+  record("lynx_template_bundle_release");
+  release::<TemplateBundle, _>(bundle);
+}
@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+
+use lynx_headless_rust_test_runner::{
+  resolve_input, ConnectOptions, Scenario, ScenarioStep, ScenarioViewport, ScreenshotStep,
+};
+use serde_json::Value;
+
+pub(crate) const USAGE: &str = "\
+Usage: lynx-headless-rust-test-runner [OPTIONS] [BUNDLE]
+
+Loads BUNDLE (a local .lynx.bundle path or URL) and runs an optional scenario.
+Without arguments, runs the built-in React fixture.
+
+Options:
+  --scenario <FILE>         JSON or YAML scenario (.yaml/.yml) to run
+  --width <PX>              viewport width in layout pixels
+  --height <PX>             viewport height in layout pixels
+  --dpr <RATIO>             device pixel ratio
+  --initial-data <JSON>     initial data as JSON, or @FILE to read it
+  --global-props <JSON>     global props as JSON, or @FILE to read it
+  --screenshot <PATH>       write a PNG after all steps pass
+  --timeout-ms <MS>         page load and wait timeout
+  --lynx-core <PATH>        lynx_core.js to install
+  --resources <DIR>         directory served for assets:// URLs
+  --react-fixture           run the built-in React fixture
+  -h, --help                print this help
+";
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Command {
+  Help,
+  ReactFixture,
+  Run(Box<RunCommand>),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct RunCommand {
+  pub connect: ConnectOptions,
+  pub scenario: Scenario,
+  /// Relative scenario paths resolve against this directory.
+  pub base_dir: PathBuf,
+}
+
+#[derive(Default)]
+struct Flags {
+  bundle: Option<String>,
+  scenario: Option<PathBuf>,
+  viewport: ScenarioViewport,
+  initial_data: Option<Value>,
+  global_props: Option<Value>,
+  screenshot: Option<String>,
+  timeout_ms: Option<u64>,
+  lynx_core: Option<PathBuf>,
+  resources: Option<PathBuf>,
+  react_fixture: bool,
+}
+
+pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+  let mut flags = Flags::default();
+  let mut args = args.into_iter();
+  while let Some(arg) = args.next() {
+    let (name, inline) = match arg.split_once('=') {
+      Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+      _ => (arg.clone(), None),
+    };
+    let mut value = || {
+      inline
+        .clone()
+        .or_else(|| args.next())
+        .ok_or_else(|| format!("{name} needs a value"))
+    };
+    match name.as_str() {
+      "-h" | "--help" => return Ok(Command::Help),
+      "--react-fixture" => flags.react_fixture = true,
+      "--scenario" => flags.scenario = Some(PathBuf::from(value()?)),
+      "--width" => flags.viewport.width = Some(number(&name, &value()?)?),
+      "--height" => flags.viewport.height = Some(number(&name, &value()?)?),
+      "--dpr" => flags.viewport.device_pixel_ratio = Some(number(&name, &value()?)?),
+      "--initial-data" => flags.initial_data = Some(json_value(&name, &value()?)?),
+      "--global-props" => flags.global_props = Some(json_value(&name, &value()?)?),
+      "--screenshot" => flags.screenshot = Some(value()?),
+      "--timeout-ms" => flags.timeout_ms = Some(number(&name, &value()?)?),
+      "--lynx-core" => flags.lynx_core = Some(PathBuf::from(value()?)),
+      "--resources" => flags.resources = Some(PathBuf::from(value()?)),
+      flag if flag.starts_with('-') => return Err(format!("unknown option {flag}")),
+      _ if flags.bundle.is_some() => return Err(format!("unexpected argument {arg}")),
+      _ => flags.bundle = Some(arg),
+    }
+  }
+
+  if flags.react_fixture || (flags.bundle.is_none() && flags.scenario.is_none()) {
+    return Ok(Command::ReactFixture);
+  }
+  build_run(flags).map(|run| Command::Run(Box::new(run)))
+}
+
+fn build_run(flags: Flags) -> Result<RunCommand, String> {
+  let cwd = std::env::current_dir().map_err(|error| error.to_string())?;
+  let (mut scenario, base_dir) = match &flags.scenario {
+    Some(path) => {
+      let scenario = Scenario::from_file(path)
+        .map_err(|error| format!("cannot read scenario {}: {error}", path.display()))?;
+      let base_dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map_or_else(|| cwd.clone(), |parent| cwd.join(parent));
+      (scenario, base_dir)
+    }
+    None => (Scenario::default(), cwd.clone()),
+  };
+
+  // Command-line values win over the scenario file; paths given on the
+  // command line are relative to the working directory, not the scenario.
+  if let Some(bundle) = flags.bundle {
+    scenario.url = Some(resolve_input(&cwd, &bundle));
+  }
+  if scenario.url.is_none() {
+    return Err("no bundle to load; pass BUNDLE or set url in the scenario".into());
+  }
+  if flags.initial_data.is_some() {
+    scenario.initial_data = flags.initial_data;
+  }
+  if flags.global_props.is_some() {
+    scenario.global_props = flags.global_props;
+  }
+  if flags.timeout_ms.is_some() {
+    scenario.timeout_ms = flags.timeout_ms;
+  }
+  let viewport = scenario.viewport.unwrap_or_default();
+  scenario.viewport = Some(ScenarioViewport {
+    width: flags.viewport.width.or(viewport.width),
+    height: flags.viewport.height.or(viewport.height),
+    device_pixel_ratio: flags
+      .viewport
+      .device_pixel_ratio
+      .or(viewport.device_pixel_ratio),
+  });
+  if let Some(path) = flags.screenshot {
+    scenario
+      .steps
+      .push(ScenarioStep::Screenshot(ScreenshotStep {
+        path: resolve_input(&cwd, &path),
+        settle_ms: None,
+      }));
+  }
+
+  let mut connect = scenario.connect_options(ConnectOptions::default());
+  connect.lynx_core_path = flags.lynx_core;
+  connect.resources_path = flags.resources;
+  Ok(RunCommand {
+    connect,
+    scenario,
+    base_dir,
+  })
+}
+
+fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+  value
+    .parse()
+    .map_err(|_| format!("{name} expects a number, got {value:?}"))
+}
+
+fn json_value(name: &str, value: &str) -> Result<Value, String> {
+  let source = match value.strip_prefix('@') {
+    Some(path) => {
+      std::fs::read_to_string(path).map_err(|error| format!("cannot read {path}: {error}"))?
+    }
+    None => value.to_string(),
+  };
+  serde_json::from_str(&source).map_err(|error| format!("{name} is not valid JSON: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::*;
+
+  fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+  }
+
+  fn run(values: &[&str]) -> RunCommand {
+    match parse(args(values)).unwrap() {
+      Command::Run(run) => *run,
+      other => panic!("expected a run command, got {other:?}"),
+    }
+  }
+
+  #[test]
+  fn no_arguments_keep_running_the_react_fixture() {
+    assert_eq!(parse(args(&[])), Ok(Command::ReactFixture));
+    assert_eq!(parse(args(&["--help"])), Ok(Command::Help));
+  }
+
+  #[test]
+  fn bundle_flags_build_a_single_load_scenario() {
+    let command = run(&[
+      "https://example.com/main.lynx.bundle",
+      "--width",
+      "390",
+      "--dpr=3",
+      "--initial-data",
+      r#"{"count":1}"#,
+      "--screenshot",
+      "/tmp/out.png",
+      "--timeout-ms",
+      "5000",
+    ]);
+    assert_eq!(
+      command.scenario.url.as_deref(),
+      Some("https://example.com/main.lynx.bundle")
+    );
+    assert_eq!(command.connect.width, 390);
+    assert_eq!(command.connect.height, ConnectOptions::default().height);
+    assert_eq!(command.connect.device_pixel_ratio, 3.0);
+    assert_eq!(command.connect.timeout, Duration::from_secs(5));
+    assert_eq!(
+      command.scenario.initial_data,
+      Some(serde_json::json!({ "count": 1 }))
+    );
+    assert_eq!(
+      command.scenario.steps,
+      [ScenarioStep::Screenshot(ScreenshotStep {
+        path: "/tmp/out.png".into(),
+        settle_ms: None,
+      })]
+    );
+  }
+
+  #[test]
+  fn rejects_bad_arguments_with_readable_errors() {
+    assert_eq!(
+      parse(args(&["a.bundle", "--width", "wide"])),
+      Err(r#"--width expects a number, got "wide""#.into())
+    );
+    assert_eq!(
+      parse(args(&["a.bundle", "--bogus"])),
+      Err("unknown option --bogus".into())
+    );
+    assert_eq!(
+      parse(args(&["a.bundle", "--dpr"])),
+      Err("--dpr needs a value".into())
+    );
+    assert!(parse(args(&["a.bundle", "--global-props", "{"]))
+      .unwrap_err()
+      .starts_with("--global-props is not valid JSON"));
+  }
+}
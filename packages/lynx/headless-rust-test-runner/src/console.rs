@@ -0,0 +1,403 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::future::Future;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::debug_router::Subscription;
+use crate::protocol::CdpNotification;
+use crate::{Error, PageRuntime, Result};
+use lynx::{ViewEvent, ViewEvents};
+use serde_json::Value;
+
+/// Notifications that arrive before the page knows its debug session are
+/// kept up to this count and replayed once the session is attached.
+const MAX_UNCLAIMED_NOTIFICATIONS: usize = 1024;
+
+/// The CDP notifications a page records; the debug router queues no others.
+pub(crate) const RUNTIME_EVENTS: &[&str] = &["Runtime.consoleAPICalled", "Runtime.exceptionThrown"];
+
+/// One `console.*` call reported through CDP `Runtime.consoleAPICalled`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsoleMessage {
+  /// The CDP call type, such as `log`, `info`, `warning`, or `error`.
+  pub level: String,
+  /// The arguments joined by spaces, like a console would print them.
+  pub text: String,
+  /// The raw CDP `RemoteObject` arguments.
+  pub args: Vec<Value>,
+}
+
+/// An error reported by the engine or thrown by page JavaScript.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PageError {
+  /// Reported through the view client's `on_received_error` callback.
+  Engine { code: i32, message: String },
+  /// An uncaught exception reported through CDP `Runtime.exceptionThrown`.
+  Exception { message: String },
+}
+
+impl fmt::Display for PageError {
+  fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Engine { code, message } => write!(formatter, "engine error {code}: {message}"),
+      Self::Exception { message } => write!(formatter, "uncaught exception: {message}"),
+    }
+  }
+}
+
+/// Collects console messages and errors for one page.
+///
+/// Both sources are drained lazily, whenever the page reads them or waits for
+/// navigation, so no callback ever runs outside the page owner thread.
+pub(crate) struct PageLog {
+  view_events: RefCell<ViewEvents>,
+  notifications: RefCell<Subscription>,
+  session_id: Cell<Option<i64>>,
+  unclaimed: RefCell<Vec<CdpNotification>>,
+  console: RefCell<Vec<ConsoleMessage>>,
+  errors: RefCell<Vec<PageError>>,
+}
+
+impl PageLog {
+  pub(crate) fn new(view_events: ViewEvents, notifications: Subscription) -> Self {
+    Self {
+      view_events: RefCell::new(view_events),
+      notifications: RefCell::new(notifications),
+      session_id: Cell::new(None),
+      unclaimed: RefCell::default(),
+      console: RefCell::default(),
+      errors: RefCell::default(),
+    }
+  }
+
+  /// Accepts notifications from `session_id` from now on, including the ones
+  /// that arrived before the session was known.
+  pub(crate) fn attach(&self, session_id: i64) {
+    self.collect();
+    self.session_id.set(Some(session_id));
+    self.notifications.borrow().set_session(Some(session_id));
+    for notification in self.unclaimed.take() {
+      self.record(notification);
+    }
+  }
+
+  /// Holds back notifications until the next [`PageLog::attach`], because a
+  /// navigation may move the page to a new session.
+  pub(crate) fn detach(&self) {
+    self.collect();
+    self.session_id.set(None);
+    self.notifications.borrow().set_session(None);
+  }
+
+  pub(crate) fn errors(&self) -> Vec<PageError> {
+    self.collect();
+    self.errors.borrow().clone()
+  }
+
+  pub(crate) fn error_count(&self) -> usize {
+    self.collect();
+    self.errors.borrow().len()
+  }
+
+  fn first_error_since(&self, index: usize) -> Option<PageError> {
+    self.collect();
+    self.errors.borrow().get(index).cloned()
+  }
+
+  fn console_len(&self) -> usize {
+    self.collect();
+    self.console.borrow().len()
+  }
+
+  fn console_since(&self, index: usize) -> Vec<ConsoleMessage> {
+    self.collect();
+    self
+      .console
+      .borrow()
+      .get(index..)
+      .map(<[ConsoleMessage]>::to_vec)
+      .unwrap_or_default()
+  }
+
+  fn collect(&self) {
+    for event in self.view_events.borrow_mut().drain() {
+      if let ViewEvent::ReceivedError { code, message } = event {
+        self
+          .errors
+          .borrow_mut()
+          .push(PageError::Engine { code, message });
+      }
+    }
+    loop {
+      let notification = self.notifications.borrow_mut().try_recv();
+      let Ok(notification) = notification else {
+        break;
+      };
+      self.record(notification);
+    }
+  }
+
+  fn record(&self, notification: CdpNotification) {
+    match (self.session_id.get(), notification.session_id) {
+      (None, _) => {
+        let mut unclaimed = self.unclaimed.borrow_mut();
+        if unclaimed.len() < MAX_UNCLAIMED_NOTIFICATIONS && is_runtime_event(&notification) {
+          unclaimed.push(notification);
+        }
+        return;
+      }
+      (Some(attached), Some(session_id)) if attached != session_id => return,
+      _ => {}
+    }
+    match notification.method.as_str() {
+      "Runtime.consoleAPICalled" => self
+        .console
+        .borrow_mut()
+        .push(console_message(&notification.params)),
+      "Runtime.exceptionThrown" => self.errors.borrow_mut().push(PageError::Exception {
+        message: exception_message(&notification.params),
+      }),
+      _ => {}
+    }
+  }
+}
+
+impl PageRuntime {
+  /// Runs `operation`, failing early with the first page error reported after
+  /// `errors_before` errors were already recorded.
+  pub(crate) async fn fail_on_page_error<T>(
+    &self,
+    errors_before: usize,
+    operation: impl Future<Output = Result<T>>,
+  ) -> Result<T> {
+    tokio::pin!(operation);
+    loop {
+      tokio::select! {
+        result = &mut operation => return result,
+        _ = tokio::time::sleep(Duration::from_millis(1)) => {
+          if let Some(error) = self.log.first_error_since(errors_before) {
+            return Err(Error::PageError(error));
+          }
+        }
+      }
+    }
+  }
+}
+
+fn is_runtime_event(notification: &CdpNotification) -> bool {
+  RUNTIME_EVENTS.contains(&notification.method.as_str())
+}
+
+fn console_message(params: &Value) -> ConsoleMessage {
+  let args = params
+    .get("args")
+    .and_then(Value::as_array)
+    .cloned()
+    .unwrap_or_default();
+  ConsoleMessage {
+    level: params
+      .get("type")
+      .and_then(Value::as_str)
+      .unwrap_or("log")
+      .to_string(),
+    text: args
+      .iter()
+      .map(remote_object_text)
+      .collect::<Vec<_>>()
+      .join(" "),
+    args,
+  }
+}
+
+/// Prints a CDP `RemoteObject` the way a console would: strings bare,
+/// other values by their description or JSON value.
+fn remote_object_text(object: &Value) -> String {
+  match object.get("value") {
+    Some(Value::String(text)) => text.clone(),
+    Some(value) if object.get("description").is_none() => value.to_string(),
+    _ => object
+      .get("description")
+      .and_then(Value::as_str)
+      .or_else(|| object.get("type").and_then(Value::as_str))
+      .unwrap_or_default()
+      .to_string(),
+  }
+}
+
+fn exception_message(params: &Value) -> String {
+  let details = params.get("exceptionDetails");
+  details
+    .and_then(|details| details.pointer("/exception/description"))
+    .or_else(|| details.and_then(|details| details.get("text")))
+    .and_then(Value::as_str)
+    .unwrap_or("unknown exception")
+    .to_string()
+}
+
+/// A stream of console messages created by [`crate::Page::on_console`].
+///
+/// It observes only the messages logged after it was created.
+pub struct ConsoleMessages {
+  runtime: Rc<PageRuntime>,
+  next: usize,
+}
+
+impl ConsoleMessages {
+  pub(crate) fn new(runtime: Rc<PageRuntime>) -> Self {
+    let next = runtime.log.console_len();
+    Self { runtime, next }
+  }
+
+  /// Pumps the page until the next console message arrives.
+  pub async fn recv(&mut self) -> ConsoleMessage {
+    loop {
+      if let Some(message) = self.try_recv() {
+        return message;
+      }
+      self.runtime.pump_for(Duration::from_millis(5)).await;
+    }
+  }
+
+  /// Returns an already logged message without pumping the page.
+  pub fn try_recv(&mut self) -> Option<ConsoleMessage> {
+    let message = self
+      .runtime
+      .log
+      .console_since(self.next)
+      .into_iter()
+      .next()?;
+    self.next += 1;
+    Some(message)
+  }
+
+  /// Removes and returns every already logged message.
+  pub fn drain(&mut self) -> Vec<ConsoleMessage> {
+    let messages = self.runtime.log.console_since(self.next);
+    self.next += messages.len();
+    messages
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use tokio::sync::mpsc;
+
+  use super::*;
+
+  fn log() -> (
+    PageLog,
+    lynx::ViewEventSender,
+    mpsc::UnboundedSender<CdpNotification>,
+  ) {
+    let (sender, events) = lynx::view_event_channel();
+    let (notify, notifications) = mpsc::unbounded_channel();
+    (
+      PageLog::new(events, Subscription::from_receiver(notifications)),
+      sender,
+      notify,
+    )
+  }
+
+  fn console(session_id: Option<i64>, text: &str) -> CdpNotification {
+    CdpNotification {
+      session_id,
+      method: "Runtime.consoleAPICalled".into(),
+      params: json!({ "type": "warning", "args": [{ "type": "string", "value": text }] }),
+    }
+  }
+
+  #[test]
+  fn formats_console_arguments_like_a_console() {
+    let message = console_message(&json!({
+      "type": "log",
+      "args": [
+        { "type": "string", "value": "count" },
+        { "type": "number", "value": 3, "description": "3" },
+        { "type": "boolean", "value": true },
+        { "type": "object", "description": "Object" },
+        { "type": "undefined" },
+      ],
+    }));
+    assert_eq!(message.level, "log");
+    assert_eq!(message.text, "count 3 true Object undefined");
+    assert_eq!(message.args.len(), 5);
+  }
+
+  #[test]
+  fn prefers_the_exception_description() {
+    assert_eq!(
+      exception_message(&json!({
+        "exceptionDetails": {
+          "text": "Uncaught",
+          "exception": { "description": "TypeError: x is undefined\n    at render" },
+        },
+      })),
+      "TypeError: x is undefined\n    at render"
+    );
+    assert_eq!(
+      exception_message(&json!({ "exceptionDetails": { "text": "Uncaught" } })),
+      "Uncaught"
+    );
+  }
+
+  #[test]
+  fn replays_notifications_for_the_attached_session_only() {
+    let (log, _sender, notify) = log();
+    notify.send(console(Some(1), "early")).unwrap();
+    notify.send(console(Some(2), "other page")).unwrap();
+    notify
+      .send(CdpNotification {
+        session_id: Some(1),
+        method: "Runtime.exceptionThrown".into(),
+        params: json!({ "exceptionDetails": { "text": "boom" } }),
+      })
+      .unwrap();
+    assert_eq!(log.console_len(), 0);
+
+    log.attach(1);
+    notify.send(console(Some(2), "still other")).unwrap();
+    notify.send(console(None, "unlabelled")).unwrap();
+    let texts = log
+      .console_since(0)
+      .into_iter()
+      .map(|message| message.text)
+      .collect::<Vec<_>>();
+    assert_eq!(texts, ["early", "unlabelled"]);
+    assert_eq!(
+      log.errors(),
+      [PageError::Exception {
+        message: "boom".into()
+      }]
+    );
+  }
+
+  #[test]
+  fn navigation_moves_the_log_to_the_next_session() {
+    let (log, _sender, notify) = log();
+    log.attach(1);
+    log.detach();
+    notify.send(console(Some(1), "old page")).unwrap();
+    notify.send(console(Some(2), "new page")).unwrap();
+    log.attach(2);
+    assert_eq!(log.console_since(0)[0].text, "new page");
+    assert_eq!(log.console_len(), 1);
+  }
+
+  #[test]
+  fn records_engine_errors_from_the_view_client() {
+    use lynx::ViewClient;
+
+    let (log, mut sender, _notify) = log();
+    sender.on_page_start("main.lynx.bundle");
+    sender.on_received_error(201, "ReferenceError: App is not defined");
+    assert_eq!(log.error_count(), 1);
+    let error = log.first_error_since(0).unwrap();
+    assert_eq!(
+      error.to_string(),
+      "engine error 201: ReferenceError: App is not defined"
+    );
+    assert_eq!(log.first_error_since(1), None);
+  }
+}
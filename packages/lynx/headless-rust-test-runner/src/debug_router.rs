@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::fmt;
 use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
@@ -8,14 +9,15 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
 use tokio::net::{tcp::OwnedWriteHalf, TcpStream};
+use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::{sleep, sleep_until, timeout, Instant};
 
 use crate::protocol::{
   cdp_request, cdp_response_id, global_switch_request, initialize_request, list_session_request,
-  parse_cdp_response, parse_global_switch_response, parse_initialize_response,
-  parse_session_list_response, read_peertalk_message, session_list_response_id,
-  write_peertalk_message, Session,
+  parse_cdp_notification, parse_cdp_response, parse_global_switch_response,
+  parse_initialize_response, parse_session_list_response, read_peertalk_message,
+  session_list_response_id, write_peertalk_message, CdpNotification, Session,
 };
 use crate::{Error, Result};
 
@@ -32,6 +34,66 @@ pub(crate) struct DebugRouter {
   app_name: String,
   port: u16,
   commands: mpsc::Sender<Command>,
+  listeners: Listeners,
+}
+
+/// Every live [`DebugRouter::subscribe`] receiver. Closed receivers are
+/// pruned on the next notification.
+type Listeners = Arc<Mutex<Vec<Listener>>>;
+
+struct Listener {
+  methods: &'static [&'static str],
+  session_id: Arc<Mutex<Option<i64>>>,
+  sender: mpsc::UnboundedSender<CdpNotification>,
+}
+
+impl Listener {
+  fn wants(&self, notification: &CdpNotification) -> bool {
+    if !self.methods.contains(&notification.method.as_str()) {
+      return false;
+    }
+    let session_id = *self
+      .session_id
+      .lock()
+      .expect("debug-router subscription lock poisoned");
+    match (session_id, notification.session_id) {
+      (Some(subscribed), Some(session_id)) => subscribed == session_id,
+      _ => true,
+    }
+  }
+}
+
+/// A [`DebugRouter::subscribe`] receiver.
+///
+/// The router only queues what the subscriber asked for, so a page that is
+/// not read for a while does not buffer the traffic of the other pages.
+pub(crate) struct Subscription {
+  session_id: Arc<Mutex<Option<i64>>>,
+  receiver: mpsc::UnboundedReceiver<CdpNotification>,
+}
+
+impl Subscription {
+  #[cfg(test)]
+  pub(crate) fn from_receiver(receiver: mpsc::UnboundedReceiver<CdpNotification>) -> Self {
+    Self {
+      session_id: Arc::default(),
+      receiver,
+    }
+  }
+
+  /// Receives only the notifications of `session_id` from now on, or those
+  /// of every session for `None`. Notifications without a session always
+  /// pass.
+  pub(crate) fn set_session(&self, session_id: Option<i64>) {
+    *self
+      .session_id
+      .lock()
+      .expect("debug-router subscription lock poisoned") = session_id;
+  }
+
+  pub(crate) fn try_recv(&mut self) -> std::result::Result<CdpNotification, TryRecvError> {
+    self.receiver.try_recv()
+  }
 }
 
 impl fmt::Debug for DebugRouter {
@@ -49,6 +111,26 @@ impl DebugRouter {
     Self::start_actor(app_name, connect_timeout).await
   }
 
+  /// Receives the CDP notifications named in `methods` that the router reads
+  /// from now on, from all sessions until [`Subscription::set_session`].
+  pub(crate) fn subscribe(&self, methods: &'static [&'static str]) -> Subscription {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let session_id = Arc::<Mutex<Option<i64>>>::default();
+    self
+      .listeners
+      .lock()
+      .expect("debug-router listener lock poisoned")
+      .push(Listener {
+        methods,
+        session_id: session_id.clone(),
+        sender,
+      });
+    Subscription {
+      session_id,
+      receiver,
+    }
+  }
+
   pub(crate) async fn list_sessions(&self) -> Result<Vec<Session>> {
     let (reply, response) = oneshot::channel();
     self
@@ -107,6 +189,8 @@ impl DebugRouter {
     let (commands, command_receiver) = mpsc::channel(COMMAND_CHANNEL_CAPACITY);
     let (ready, ready_receiver) = oneshot::channel();
     let actor_app_name = app_name.to_string();
+    let listeners = Listeners::default();
+    let actor_listeners = listeners.clone();
 
     thread::Builder::new()
       .name("lynx-debug-router".into())
@@ -120,7 +204,7 @@ impl DebugRouter {
             match establish_connection(&actor_app_name, connect_timeout).await {
               Ok((port, stream)) => {
                 if ready.send(Ok(port)).is_ok() {
-                  run_actor(port, stream, command_receiver).await;
+                  run_actor(port, stream, command_receiver, actor_listeners).await;
                 }
               }
               Err(error) => {
@@ -141,6 +225,7 @@ impl DebugRouter {
       app_name: app_name.to_string(),
       port,
       commands,
+      listeners,
     })
   }
 }
@@ -172,14 +257,16 @@ struct ActorState {
   next_id: u32,
   list: Option<PendingList>,
   cdp: HashMap<u32, PendingCdp>,
+  listeners: Listeners,
 }
 
 impl ActorState {
-  fn new() -> Self {
+  fn new(listeners: Listeners) -> Self {
     Self {
       next_id: FIRST_MESSAGE_ID,
       list: None,
       cdp: HashMap::new(),
+      listeners,
     }
   }
 
@@ -236,6 +323,20 @@ impl ActorState {
     }
   }
 
+  fn notify(&self, notification: CdpNotification) {
+    let mut listeners = self
+      .listeners
+      .lock()
+      .expect("debug-router listener lock poisoned");
+    listeners.retain(|listener| {
+      if listener.wants(&notification) {
+        listener.sender.send(notification.clone()).is_ok()
+      } else {
+        !listener.sender.is_closed()
+      }
+    });
+  }
+
   fn fail_all(&mut self, message: &str) {
     if let Some(pending) = self.list.take() {
       for reply in pending.replies {
@@ -250,7 +351,12 @@ impl ActorState {
   }
 }
 
-async fn run_actor(port: u16, stream: TcpStream, mut commands: mpsc::Receiver<Command>) {
+async fn run_actor(
+  port: u16,
+  stream: TcpStream,
+  mut commands: mpsc::Receiver<Command>,
+  listeners: Listeners,
+) {
   let (mut reader, mut writer) = stream.into_split();
   let (incoming, mut messages) = mpsc::channel(INCOMING_CHANNEL_CAPACITY);
   let reader_task = tokio::spawn(async move {
@@ -262,7 +368,7 @@ async fn run_actor(port: u16, stream: TcpStream, mut commands: mpsc::Receiver<Co
       }
     }
   });
-  let mut state = ActorState::new();
+  let mut state = ActorState::new(listeners);
 
   loop {
     let deadline = state.next_deadline();
@@ -371,9 +477,13 @@ fn handle_message(state: &mut ActorState, message: Value) {
     Ok(None) => {}
   }
 
-  // Notifications have no id and are intentionally ignored. Responses for
-  // every outstanding id are dispatched here, so an event or a response for
-  // another caller can no longer be consumed by the wrong request.
+  // Notifications have no id and go to subscribers. Responses for every
+  // outstanding id are dispatched here, so an event or a response for another
+  // caller can no longer be consumed by the wrong request.
+  if let Ok(Some(notification)) = parse_cdp_notification(&message) {
+    state.notify(notification);
+    return;
+  }
   let Ok(Some(id)) = cdp_response_id(&message) else {
     return;
   };
@@ -491,12 +601,14 @@ mod tests {
     let (server, _) = listener.accept().await.unwrap();
     let (commands, command_receiver) = mpsc::channel(COMMAND_CHANNEL_CAPACITY);
     let port = address.port();
-    tokio::spawn(run_actor(port, client, command_receiver));
+    let listeners = Listeners::default();
+    tokio::spawn(run_actor(port, client, command_receiver, listeners.clone()));
     (
       DebugRouter {
         app_name: "test".into(),
         port,
         commands,
+        listeners,
       },
       server,
     )
@@ -556,6 +668,70 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn forwards_notifications_to_subscribers() {
+    let (router, mut server) = connected_test_router().await;
+    let mut first = router.subscribe(&["Runtime.exceptionThrown"]);
+    let second = router.subscribe(&["Runtime.exceptionThrown"]);
+    drop(second);
+    let notification = json!({
+      "event": "Customized",
+      "data": {
+        "type": "CDP",
+        "data": {
+          "session_id": 4,
+          "message": json!({
+            "method": "Runtime.exceptionThrown",
+            "params": { "exceptionDetails": { "text": "boom" } },
+          }).to_string(),
+        },
+      },
+    });
+    write_peertalk_message(&mut server, &notification)
+      .await
+      .unwrap();
+    let received = timeout(REQUEST_TIMEOUT, first.receiver.recv())
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(received.session_id, Some(4));
+    assert_eq!(received.method, "Runtime.exceptionThrown");
+    assert_eq!(router.listeners.lock().unwrap().len(), 1);
+  }
+
+  #[tokio::test]
+  async fn queues_only_the_subscribed_methods_of_the_subscribed_session() {
+    let (router, mut server) = connected_test_router().await;
+    let mut subscription = router.subscribe(&["Runtime.consoleAPICalled"]);
+    subscription.set_session(Some(4));
+    for (session_id, method) in [
+      (5, "Runtime.consoleAPICalled"),
+      (4, "DOM.documentUpdated"),
+      (4, "Runtime.consoleAPICalled"),
+    ] {
+      let notification = json!({
+        "event": "Customized",
+        "data": {
+          "type": "CDP",
+          "data": {
+            "session_id": session_id,
+            "message": json!({ "method": method }).to_string(),
+          },
+        },
+      });
+      write_peertalk_message(&mut server, &notification)
+        .await
+        .unwrap();
+    }
+    let received = timeout(REQUEST_TIMEOUT, subscription.receiver.recv())
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(received.session_id, Some(4));
+    assert_eq!(received.method, "Runtime.consoleAPICalled");
+    assert!(subscription.try_recv().is_err());
+  }
+
   #[tokio::test(flavor = "current_thread")]
   async fn shared_handle_accepts_requests_from_multiple_os_threads() {
     let (router, mut server) = connected_test_router().await;
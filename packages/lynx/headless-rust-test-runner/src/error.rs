@@ -34,10 +34,22 @@ pub enum Error {
   ThreadAffinity { owner: String, current: String },
   #[error("CDP request error: {0}")]
   Cdp(String),
+  #[error("page failed: {0}")]
+  PageError(crate::PageError),
   #[error("operation timed out: {0}")]
   Timeout(String),
   #[error("page is not loaded; call goto().await first")]
   PageNotLoaded,
   #[error("no rendered frame is available")]
   FrameNotAvailable,
+  #[error("missing screenshot baseline {0}; rerun with LYNX_UPDATE_SCREENSHOTS=1 to create it")]
+  MissingScreenshotBaseline(PathBuf),
+  #[error("screenshot {name} does not match its baseline: {message}")]
+  ScreenshotMismatch { name: String, message: String },
+  #[error("invalid scenario: {0}")]
+  Scenario(String),
+  #[error("assertion failed: {0}")]
+  Assertion(String),
+  #[error("cannot type key or text: {0}")]
+  UnknownKey(String),
 }
@@ -1,7 +1,7 @@
-use std::io::Cursor;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+use crate::png_encoder::{decode_png, DecodedPng};
 use crate::{ConnectOptions, Error, GotoOptions, Lynx, Page, Result, ScreenshotOptions};
 
 const VIEWPORT_WIDTH: usize = 800;
@@ -128,7 +128,7 @@ async fn assert_node_id_tap(page: &mut Page) -> Result<()> {
 
 async fn wait_for_expected_screenshot(
   page: &Page,
-) -> Result<(Vec<u8>, CapturedFrame, ScreenshotStats)> {
+) -> Result<(Vec<u8>, DecodedPng, ScreenshotStats)> {
   let expectation = ScreenshotExpectation::react_fixture();
   let deadline = Instant::now() + FIXTURE_TIMEOUT;
   let mut latest_mismatch = None;
@@ -151,38 +151,6 @@ async fn wait_for_expected_screenshot(
   )))
 }
 
-struct CapturedFrame {
-  width: usize,
-  height: usize,
-  rgba: Vec<u8>,
-}
-
-fn decode_png(bytes: &[u8]) -> Result<CapturedFrame> {
-  let decoder = png::Decoder::new(Cursor::new(bytes));
-  let mut reader = decoder
-    .read_info()
-    .map_err(|error| Error::Protocol(format!("failed to decode screenshot: {error}")))?;
-  let buffer_size = reader
-    .output_buffer_size()
-    .ok_or_else(|| Error::Protocol("decoded screenshot is too large".into()))?;
-  let mut buffer = vec![0; buffer_size];
-  let info = reader
-    .next_frame(&mut buffer)
-    .map_err(|error| Error::Protocol(format!("failed to decode screenshot: {error}")))?;
-  if info.color_type != png::ColorType::Rgba || info.bit_depth != png::BitDepth::Eight {
-    return Err(Error::Protocol(format!(
-      "expected an 8-bit RGBA screenshot, got {:?} {:?}",
-      info.color_type, info.bit_depth
-    )));
-  }
-  buffer.truncate(info.buffer_size());
-  Ok(CapturedFrame {
-    width: info.width as usize,
-    height: info.height as usize,
-    rgba: buffer,
-  })
-}
-
 struct ScreenshotExpectation {
   width: usize,
   height: usize,
@@ -215,7 +183,7 @@ impl ScreenshotExpectation {
     }
   }
 
-  fn assert_matches(&self, frame: &CapturedFrame) -> std::result::Result<ScreenshotStats, String> {
+  fn assert_matches(&self, frame: &DecodedPng) -> std::result::Result<ScreenshotStats, String> {
     if frame.width != self.width || frame.height != self.height {
       return Err(format!(
         "expected {}x{} React fixture frame, got {}x{}",
@@ -242,7 +210,7 @@ impl ScreenshotExpectation {
   }
 }
 
-fn screenshot_stats(frame: &CapturedFrame) -> ScreenshotStats {
+fn screenshot_stats(frame: &DecodedPng) -> ScreenshotStats {
   let mut stats = ScreenshotStats {
     visible_pixels: 0,
     white_pixels: 0,
@@ -0,0 +1,344 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use lynx::{sys, PointerEvent};
+
+const DEFAULT_GESTURE_DURATION: Duration = Duration::from_millis(300);
+const DEFAULT_GESTURE_STEPS: usize = 12;
+const DEFAULT_LONG_PRESS_DURATION: Duration = Duration::from_millis(800);
+const DRAG_PICKUP_DELAY: Duration = Duration::from_millis(200);
+const SCROLL_SETTLE_DELAY: Duration = Duration::from_millis(120);
+const TAP_HOLD: Duration = Duration::from_millis(50);
+const TOUCH_DEVICE: i32 = 1;
+
+/// A point in page layout pixels, the coordinate space of
+/// [`crate::BoundingBox`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+  pub x: f64,
+  pub y: f64,
+}
+
+impl Point {
+  pub fn new(x: f64, y: f64) -> Self {
+    Self { x, y }
+  }
+
+  fn lerp(self, to: Point, t: f64) -> Point {
+    Point {
+      x: self.x + (to.x - self.x) * t,
+      y: self.y + (to.y - self.y) * t,
+    }
+  }
+
+  fn offset(self, delta_x: f64, delta_y: f64) -> Point {
+    Point {
+      x: self.x + delta_x,
+      y: self.y + delta_y,
+    }
+  }
+}
+
+/// Controls how a moving gesture is interpolated.
+///
+/// `duration` is the time between the pointer going down and the final move;
+/// `steps` is the number of move events spread evenly over that duration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GestureOptions {
+  pub duration: Duration,
+  pub steps: usize,
+}
+
+impl Default for GestureOptions {
+  fn default() -> Self {
+    Self {
+      duration: DEFAULT_GESTURE_DURATION,
+      steps: DEFAULT_GESTURE_STEPS,
+    }
+  }
+}
+
+/// One pointer event and the delay to wait before dispatching it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct GestureStep {
+  pub delay: Duration,
+  pub phase: sys::lynx_pointer_phase_e,
+  pub point: Point,
+}
+
+impl GestureStep {
+  fn new(delay: Duration, phase: sys::lynx_pointer_phase_e, point: Point) -> Self {
+    Self {
+      delay,
+      phase,
+      point,
+    }
+  }
+}
+
+pub(crate) fn tap_steps(point: Point) -> Vec<GestureStep> {
+  press_steps(point, TAP_HOLD)
+}
+
+pub(crate) fn long_press_steps(point: Point, duration: Option<Duration>) -> Vec<GestureStep> {
+  press_steps(point, duration.unwrap_or(DEFAULT_LONG_PRESS_DURATION))
+}
+
+/// A quick move that keeps its release velocity, so scroll containers fling.
+pub(crate) fn swipe_steps(from: Point, to: Point, options: GestureOptions) -> Vec<GestureStep> {
+  let mut steps = vec![
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseAdd, from),
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseDown, from),
+  ];
+  steps.extend(move_steps(from, to, options));
+  steps.push(GestureStep::new(
+    Duration::ZERO,
+    sys::kLynxPointerPhaseUp,
+    to,
+  ));
+  steps.push(GestureStep::new(
+    Duration::ZERO,
+    sys::kLynxPointerPhaseRemove,
+    to,
+  ));
+  steps
+}
+
+/// A move that rests before release, so the content stops where the finger
+/// stopped and the scroll distance matches the requested delta.
+pub(crate) fn scroll_steps(
+  at: Point,
+  delta_x: f64,
+  delta_y: f64,
+  options: GestureOptions,
+) -> Vec<GestureStep> {
+  // Content follows the finger, so scrolling content by +delta moves the
+  // finger by -delta.
+  let to = at.offset(-delta_x, -delta_y);
+  let mut steps = vec![
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseAdd, at),
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseDown, at),
+  ];
+  steps.extend(move_steps(at, to, options));
+  steps.push(GestureStep::new(
+    SCROLL_SETTLE_DELAY,
+    sys::kLynxPointerPhaseMove,
+    to,
+  ));
+  steps.push(GestureStep::new(
+    Duration::ZERO,
+    sys::kLynxPointerPhaseUp,
+    to,
+  ));
+  steps.push(GestureStep::new(
+    Duration::ZERO,
+    sys::kLynxPointerPhaseRemove,
+    to,
+  ));
+  steps
+}
+
+/// A press that is held long enough to pick up draggable content before it
+/// moves, then rests at the target before release.
+pub(crate) fn drag_steps(from: Point, to: Point, options: GestureOptions) -> Vec<GestureStep> {
+  let mut steps = vec![
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseAdd, from),
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseDown, from),
+  ];
+  let mut moves = move_steps(from, to, options);
+  if let Some(first) = moves.first_mut() {
+    first.delay += DRAG_PICKUP_DELAY;
+  }
+  steps.extend(moves);
+  steps.push(GestureStep::new(
+    SCROLL_SETTLE_DELAY,
+    sys::kLynxPointerPhaseUp,
+    to,
+  ));
+  steps.push(GestureStep::new(
+    Duration::ZERO,
+    sys::kLynxPointerPhaseRemove,
+    to,
+  ));
+  steps
+}
+
+fn press_steps(point: Point, hold: Duration) -> Vec<GestureStep> {
+  vec![
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseAdd, point),
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseDown, point),
+    GestureStep::new(hold, sys::kLynxPointerPhaseUp, point),
+    GestureStep::new(Duration::ZERO, sys::kLynxPointerPhaseRemove, point),
+  ]
+}
+
+fn move_steps(from: Point, to: Point, options: GestureOptions) -> Vec<GestureStep> {
+  let count = options.steps.max(1);
+  let interval = options.duration / count as u32;
+  (1..=count)
+    .map(|index| {
+      GestureStep::new(
+        interval,
+        sys::kLynxPointerPhaseMove,
+        from.lerp(to, index as f64 / count as f64),
+      )
+    })
+    .collect()
+}
+
+/// Converts a planned step to the runtime pointer event. Layout pixels are
+/// scaled by the device pixel ratio because the windowless renderer receives
+/// physical coordinates.
+pub(crate) fn pointer_event(step: &GestureStep, device_pixel_ratio: f32) -> PointerEvent {
+  let scale = f64::from(device_pixel_ratio);
+  PointerEvent {
+    phase: step.phase,
+    timestamp: timestamp_micros(),
+    x: step.point.x * scale,
+    y: step.point.y * scale,
+    device: TOUCH_DEVICE,
+    device_kind: sys::kLynxPointerDeviceKindTouch,
+    ..PointerEvent::default()
+  }
+}
+
+pub(crate) fn timestamp_micros() -> usize {
+  static START: OnceLock<Instant> = OnceLock::new();
+  START.get_or_init(Instant::now).elapsed().as_micros() as usize
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn phases(steps: &[GestureStep]) -> Vec<sys::lynx_pointer_phase_e> {
+    steps.iter().map(|step| step.phase).collect()
+  }
+
+  #[test]
+  fn tap_and_long_press_hold_before_release() {
+    let point = Point::new(10.0, 20.0);
+    let tap = tap_steps(point);
+    assert_eq!(
+      phases(&tap),
+      [
+        sys::kLynxPointerPhaseAdd,
+        sys::kLynxPointerPhaseDown,
+        sys::kLynxPointerPhaseUp,
+        sys::kLynxPointerPhaseRemove,
+      ]
+    );
+    assert_eq!(tap[2].delay, TAP_HOLD);
+    assert_eq!(
+      long_press_steps(point, None)[2].delay,
+      DEFAULT_LONG_PRESS_DURATION
+    );
+    assert_eq!(
+      long_press_steps(point, Some(Duration::from_secs(2)))[2].delay,
+      Duration::from_secs(2)
+    );
+    assert!(tap.iter().all(|step| step.point == point));
+  }
+
+  #[test]
+  fn swipe_interpolates_moves_over_the_requested_duration() {
+    let steps = swipe_steps(
+      Point::new(0.0, 100.0),
+      Point::new(40.0, 20.0),
+      GestureOptions {
+        duration: Duration::from_millis(400),
+        steps: 4,
+      },
+    );
+
+    let moves = steps
+      .iter()
+      .filter(|step| step.phase == sys::kLynxPointerPhaseMove)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      moves.iter().map(|step| step.point).collect::<Vec<_>>(),
+      [
+        Point::new(10.0, 80.0),
+        Point::new(20.0, 60.0),
+        Point::new(30.0, 40.0),
+        Point::new(40.0, 20.0),
+      ]
+    );
+    assert!(moves
+      .iter()
+      .all(|step| step.delay == Duration::from_millis(100)));
+    assert_eq!(steps.last().unwrap().phase, sys::kLynxPointerPhaseRemove);
+    assert_eq!(
+      steps[steps.len() - 2].delay,
+      Duration::ZERO,
+      "swipes release immediately so they keep their velocity"
+    );
+  }
+
+  #[test]
+  fn scroll_moves_the_finger_against_the_content_delta_and_settles() {
+    let steps = scroll_steps(
+      Point::new(100.0, 300.0),
+      0.0,
+      200.0,
+      GestureOptions::default(),
+    );
+    let release = &steps[steps.len() - 2];
+    assert_eq!(release.phase, sys::kLynxPointerPhaseUp);
+    assert_eq!(release.point, Point::new(100.0, 100.0));
+    let settle = &steps[steps.len() - 3];
+    assert_eq!(settle.phase, sys::kLynxPointerPhaseMove);
+    assert_eq!(settle.point, release.point);
+    assert_eq!(settle.delay, SCROLL_SETTLE_DELAY);
+  }
+
+  #[test]
+  fn drag_waits_for_pickup_before_moving() {
+    let options = GestureOptions {
+      duration: Duration::from_millis(100),
+      steps: 1,
+    };
+    let steps = drag_steps(Point::new(0.0, 0.0), Point::new(5.0, 5.0), options);
+    assert_eq!(steps[2].phase, sys::kLynxPointerPhaseMove);
+    assert_eq!(
+      steps[2].delay,
+      Duration::from_millis(100) + DRAG_PICKUP_DELAY
+    );
+    assert_eq!(steps[3].phase, sys::kLynxPointerPhaseUp);
+    assert_eq!(steps[3].delay, SCROLL_SETTLE_DELAY);
+  }
+
+  #[test]
+  fn zero_steps_still_reach_the_target() {
+    let steps = swipe_steps(
+      Point::new(0.0, 0.0),
+      Point::new(8.0, 0.0),
+      GestureOptions {
+        duration: Duration::ZERO,
+        steps: 0,
+      },
+    );
+    assert!(steps
+      .iter()
+      .any(|step| step.phase == sys::kLynxPointerPhaseMove && step.point == Point::new(8.0, 0.0)));
+  }
+
+  #[test]
+  fn pointer_events_use_touch_devices_and_physical_pixels() {
+    let event = pointer_event(
+      &GestureStep::new(
+        Duration::ZERO,
+        sys::kLynxPointerPhaseDown,
+        Point::new(10.0, 20.0),
+      ),
+      2.0,
+    );
+    assert_eq!(event.phase, sys::kLynxPointerPhaseDown);
+    assert_eq!(event.device_kind, sys::kLynxPointerDeviceKindTouch);
+    assert_eq!((event.x, event.y), (20.0, 40.0));
+    assert_eq!(
+      event.struct_size,
+      std::mem::size_of::<sys::lynx_pointer_event_t>()
+    );
+  }
+}
@@ -9,6 +9,7 @@ use lynx::{
   Env, HeadlessView, SoftwareFrame, SoftwareRenderer, Task, WindowlessHost, WindowlessRenderer,
 };
 
+use crate::keyboard::{rect, TextInputRecorder, TextInputRequest};
 use crate::{Error, Result};
 
 #[cfg(target_os = "macos")]
@@ -71,11 +72,12 @@ fn drain_ready_at(queue: &mut Vec<ScheduledTask>, now: Instant) -> Vec<Task> {
 
 pub(crate) struct QueueingHost {
   tasks: SharedTasks,
+  text_input: TextInputRecorder,
 }
 
 impl QueueingHost {
-  pub(crate) fn new(tasks: SharedTasks) -> Self {
-    Self { tasks }
+  pub(crate) fn new(tasks: SharedTasks, text_input: TextInputRecorder) -> Self {
+    Self { tasks, text_input }
   }
 }
 
@@ -85,6 +87,34 @@ impl WindowlessHost for QueueingHost {
       .tasks
       .push(task, Duration::from_nanos(interval_nanoseconds));
   }
+
+  fn show_text_input(&mut self, show: bool) {
+    self
+      .text_input
+      .record(TextInputRequest::ShowTextInput(show));
+  }
+
+  fn update_caret_position(&mut self, x: f32, y: f32, width: f32, height: f32) {
+    self
+      .text_input
+      .record(TextInputRequest::UpdateCaretPosition(rect(
+        x, y, width, height,
+      )));
+  }
+
+  fn set_cursor_position(&mut self, position: i32) {
+    self
+      .text_input
+      .record(TextInputRequest::SetCursorPosition(position));
+  }
+
+  fn set_marked_text_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+    self
+      .text_input
+      .record(TextInputRequest::SetMarkedTextRect(rect(
+        x, y, width, height,
+      )));
+  }
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -307,7 +337,11 @@ unsafe extern "C" {
 
 #[cfg(test)]
 mod tests {
+  use lynx::{view_event_channel, ViewEvent};
+  use stub_runtime::test_support::{library_path, PAGE};
+
   use super::*;
+  use crate::resource::ResourceContext;
 
   fn assert_send_sync<T: Send + Sync>() {}
 
@@ -316,4 +350,69 @@ mod tests {
     assert_send_sync::<CapturedFrame>();
     assert_send_sync::<FrameStore>();
   }
+
+  #[tokio::test(flavor = "current_thread")]
+  async fn renders_a_stub_runtime_page_through_the_page_harness() {
+    let env = Env::load_from_path(library_path()).expect("load stub runtime");
+    let dir = std::env::temp_dir().join(format!("lynx-harness-stub-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("page.json"), PAGE).unwrap();
+    std::fs::write(dir.join("logo.png"), b"png").unwrap();
+
+    let global_tasks = initialize_platform(&env).expect("initialize the platform");
+    let renderer_tasks = SharedTasks::new();
+    let frames = FrameStore::default();
+    let renderer = WindowlessRenderer::software(
+      &env,
+      frames.clone(),
+      QueueingHost::new(renderer_tasks.clone(), TextInputRecorder::default()),
+    )
+    .expect("create software renderer");
+    let resources = ResourceContext::new(None, dir.join("lynx_core.js"));
+    let (view_client, mut events) = view_event_channel();
+    let view = HeadlessView::builder(env.clone(), renderer)
+      .viewport(4.0, 3.0, 2.0)
+      .resource_fetcher(resources.fetcher())
+      .expect("attach resource fetcher")
+      .view_client(view_client)
+      .expect("attach view client")
+      .build()
+      .expect("build headless view");
+    let pump = TaskPump::new(env, renderer_tasks, global_tasks);
+
+    let page = dir.join("page.json");
+    let (url, bytes) = resources
+      .read_template(page.to_str().unwrap())
+      .await
+      .expect("read the template");
+    resources.set_base_url(&url);
+    view
+      .load_template_bytes_with_global_props(&url, &bytes, Some("{}"), None)
+      .expect("load the template");
+    let frame = pump
+      .wait_for_frame(&view, &frames, 0, Duration::from_secs(5))
+      .await
+      .expect("the stub presents a frame");
+
+    assert_eq!((frame.width, frame.height), (8, 6));
+    let pixel = |x: usize, y: usize| &frame.rgba[(y * frame.width + x) * 4..][..4];
+    assert_eq!(pixel(0, 0), [0, 0, 255, 255]);
+    assert_eq!(pixel(2, 2), [255, 0, 0, 255]);
+    // `logo.png` resolves against the page URL, so only the resource missing
+    // from the directory and the scripted error are reported.
+    let events = events.drain();
+    assert!(events.contains(&ViewEvent::FirstScreen), "{events:?}");
+    let errors: Vec<_> = events
+      .iter()
+      .filter_map(|event| match event {
+        ViewEvent::ReceivedError { code, message } => Some((*code, message.as_str())),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(errors.len(), 2, "{events:?}");
+    assert!(errors[0].1.contains("missing.png"), "{events:?}");
+    assert_eq!(errors[1], (201, "scripted error"));
+    drop(view);
+    std::fs::remove_dir_all(dir).unwrap();
+  }
 }
@@ -0,0 +1,243 @@
+use std::convert::Infallible;
+
+use thiserror::Error;
+
+/// The color of differing pixels in [`BlockDiff::diff_rgba`].
+pub const DIFF_PIXEL: [u8; 4] = [255, 0, 0, 255];
+
+/// How [`diff_rgba_blocks`] decides that two images differ.
+///
+/// A pixel differs when its normalized RGBA distance exceeds
+/// `pixel_tolerance`, and a block differs when more than `block_threshold` of
+/// its pixels differ.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlockDiffOptions {
+  pub pixel_tolerance: f64,
+  pub block_size: usize,
+  pub block_threshold: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockDiff {
+  pub different_blocks: usize,
+  pub total_blocks: usize,
+  /// The actual image with differing pixels painted [`DIFF_PIXEL`].
+  pub diff_rgba: Vec<u8>,
+}
+
+impl BlockDiff {
+  /// The share of blocks that match, from `0.0` to `1.0`.
+  pub fn similarity(&self) -> f64 {
+    if self.total_blocks == 0 {
+      1.0
+    } else {
+      (1.0 - self.different_blocks as f64 / self.total_blocks as f64).clamp(0.0, 1.0)
+    }
+  }
+}
+
+/// Why [`try_diff_rgba_blocks`] did not compare two images.
+#[derive(Debug, Error, PartialEq)]
+pub enum BlockDiffError<E = Infallible> {
+  /// A buffer does not hold `width` x `height` RGBA pixels.
+  #[error("the {image} image has {len} bytes, but {width}x{height} RGBA needs {expected}")]
+  SizeMismatch {
+    image: &'static str,
+    len: usize,
+    width: usize,
+    height: usize,
+    expected: usize,
+  },
+  /// The `check` callback stopped the comparison.
+  #[error("{0}")]
+  Check(E),
+}
+
+/// Compares two `width` x `height` RGBA buffers block by block.
+pub fn diff_rgba_blocks(
+  expected: &[u8],
+  actual: &[u8],
+  width: usize,
+  height: usize,
+  options: &BlockDiffOptions,
+) -> Result<BlockDiff, BlockDiffError> {
+  try_diff_rgba_blocks(expected, actual, width, height, options, || {
+    Ok::<(), Infallible>(())
+  })
+}
+
+/// Like [`diff_rgba_blocks`], but calls `check` before every row so a long
+/// comparison can be cancelled.
+pub fn try_diff_rgba_blocks<E>(
+  expected: &[u8],
+  actual: &[u8],
+  width: usize,
+  height: usize,
+  options: &BlockDiffOptions,
+  mut check: impl FnMut() -> Result<(), E>,
+) -> Result<BlockDiff, BlockDiffError<E>> {
+  for (image, buffer) in [("expected", expected), ("actual", actual)] {
+    if buffer.len() != width * height * 4 {
+      return Err(BlockDiffError::SizeMismatch {
+        image,
+        len: buffer.len(),
+        width,
+        height,
+        expected: width * height * 4,
+      });
+    }
+  }
+  let block_size = options.block_size.max(1);
+  let tolerance_squared = options.pixel_tolerance * options.pixel_tolerance;
+  let block_columns = width.div_ceil(block_size);
+  let block_rows = height.div_ceil(block_size);
+  let mut different_pixels = vec![0usize; block_columns * block_rows];
+  let mut diff_rgba = Vec::with_capacity(actual.len());
+
+  for y in 0..height {
+    check().map_err(BlockDiffError::Check)?;
+    for x in 0..width {
+      let offset = (y * width + x) * 4;
+      let expected_pixel = &expected[offset..offset + 4];
+      let actual_pixel = &actual[offset..offset + 4];
+      if normalized_rgba_distance_squared(expected_pixel, actual_pixel) > tolerance_squared {
+        different_pixels[(y / block_size) * block_columns + x / block_size] += 1;
+        diff_rgba.extend_from_slice(&DIFF_PIXEL);
+      } else {
+        diff_rgba.extend_from_slice(actual_pixel);
+      }
+    }
+  }
+
+  let different_blocks = different_pixels
+    .iter()
+    .enumerate()
+    .filter(|(index, different)| {
+      let block_width = block_size.min(width - (index % block_columns) * block_size);
+      let block_height = block_size.min(height - (index / block_columns) * block_size);
+      **different as f64 / (block_width * block_height) as f64 > options.block_threshold
+    })
+    .count();
+
+  Ok(BlockDiff {
+    different_blocks,
+    total_blocks: block_columns * block_rows,
+    diff_rgba,
+  })
+}
+
+fn normalized_rgba_distance_squared(expected: &[u8], actual: &[u8]) -> f64 {
+  let sum_squares = expected
+    .iter()
+    .zip(actual)
+    .map(|(expected, actual)| {
+      let delta = f64::from(*expected) - f64::from(*actual);
+      delta * delta
+    })
+    .sum::<f64>();
+  sum_squares / (4.0 * 255.0 * 255.0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn solid(width: usize, height: usize, rgba: [u8; 4]) -> Vec<u8> {
+    rgba.repeat(width * height)
+  }
+
+  fn paint(image: &mut [u8], width: usize, x: usize, y: usize, rgba: [u8; 4]) {
+    let offset = (y * width + x) * 4;
+    image[offset..offset + 4].copy_from_slice(&rgba);
+  }
+
+  fn options(block_size: usize) -> BlockDiffOptions {
+    BlockDiffOptions {
+      pixel_tolerance: 0.1,
+      block_size,
+      block_threshold: 0.1,
+    }
+  }
+
+  #[test]
+  fn identical_images_have_no_different_blocks() {
+    let image = solid(5, 3, [10, 20, 30, 255]);
+    let diff = diff_rgba_blocks(&image, &image, 5, 3, &options(2)).unwrap();
+    assert_eq!(diff.different_blocks, 0);
+    assert_eq!(diff.total_blocks, 6);
+    assert_eq!(diff.similarity(), 1.0);
+    assert_eq!(diff.diff_rgba, image);
+  }
+
+  #[test]
+  fn pixels_within_tolerance_do_not_count() {
+    let expected = solid(4, 4, [100, 100, 100, 255]);
+    let actual = solid(4, 4, [110, 100, 100, 255]);
+    assert_eq!(
+      diff_rgba_blocks(&expected, &actual, 4, 4, &options(2))
+        .unwrap()
+        .different_blocks,
+      0
+    );
+  }
+
+  #[test]
+  fn block_threshold_uses_the_partial_edge_block_size() {
+    let expected = solid(3, 1, [0, 0, 0, 255]);
+    let mut actual = solid(3, 1, [0, 0, 0, 255]);
+    // The trailing block holds one pixel, so one change makes it 100% different.
+    paint(&mut actual, 3, 2, 0, [255, 255, 255, 255]);
+    let diff = diff_rgba_blocks(&expected, &actual, 3, 1, &options(2)).unwrap();
+    assert_eq!(diff.total_blocks, 2);
+    assert_eq!(diff.different_blocks, 1);
+    assert_eq!(&diff.diff_rgba[8..12], &DIFF_PIXEL);
+    assert_eq!(diff.similarity(), 0.5);
+  }
+
+  #[test]
+  fn sparse_changes_stay_under_the_block_threshold() {
+    let expected = solid(4, 4, [0, 0, 0, 255]);
+    let mut actual = solid(4, 4, [0, 0, 0, 255]);
+    paint(&mut actual, 4, 0, 0, [255, 255, 255, 255]);
+    let lenient = options(4);
+    assert_eq!(
+      diff_rgba_blocks(&expected, &actual, 4, 4, &lenient)
+        .unwrap()
+        .different_blocks,
+      0
+    );
+    let strict = BlockDiffOptions {
+      block_threshold: 0.0,
+      ..lenient
+    };
+    assert_eq!(
+      diff_rgba_blocks(&expected, &actual, 4, 4, &strict)
+        .unwrap()
+        .different_blocks,
+      1
+    );
+  }
+
+  #[test]
+  fn check_stops_the_comparison() {
+    let image = solid(2, 2, [0, 0, 0, 255]);
+    let result = try_diff_rgba_blocks(&image, &image, 2, 2, &options(2), || Err("cancelled"));
+    assert_eq!(result, Err(BlockDiffError::Check("cancelled")));
+  }
+
+  #[test]
+  fn buffers_of_the_wrong_size_are_an_error() {
+    let expected = solid(2, 2, [0, 0, 0, 255]);
+    let actual = solid(2, 1, [0, 0, 0, 255]);
+    assert_eq!(
+      diff_rgba_blocks(&expected, &actual, 2, 2, &options(2)),
+      Err(BlockDiffError::SizeMismatch {
+        image: "actual",
+        len: 8,
+        width: 2,
+        height: 2,
+        expected: 16,
+      })
+    );
+  }
+}
@@ -0,0 +1,267 @@
+use std::ffi::CString;
+use std::sync::{Arc, Mutex};
+
+use lynx::{sys, KeyEvent};
+
+use crate::gesture::timestamp_micros;
+use crate::{BoundingBox, Error, Result};
+
+const LOGICAL_SPECIAL: u64 = 0x0100000000;
+
+/// Named keys accepted by [`crate::Page::press_key`], with their USB HID
+/// physical usage and runtime logical key id.
+const NAMED_KEYS: &[(&str, u64, u64)] = &[
+  ("Backspace", 0x0007002a, LOGICAL_SPECIAL | 0x008),
+  ("Tab", 0x0007002b, LOGICAL_SPECIAL | 0x009),
+  ("Enter", 0x00070028, LOGICAL_SPECIAL | 0x00d),
+  ("Escape", 0x00070029, LOGICAL_SPECIAL | 0x01b),
+  ("Delete", 0x0007004c, LOGICAL_SPECIAL | 0x07f),
+  ("ArrowDown", 0x00070051, LOGICAL_SPECIAL | 0x301),
+  ("ArrowLeft", 0x00070050, LOGICAL_SPECIAL | 0x302),
+  ("ArrowRight", 0x0007004f, LOGICAL_SPECIAL | 0x303),
+  ("ArrowUp", 0x00070052, LOGICAL_SPECIAL | 0x304),
+  ("End", 0x0007004d, LOGICAL_SPECIAL | 0x305),
+  ("Home", 0x0007004a, LOGICAL_SPECIAL | 0x306),
+  ("PageDown", 0x0007004e, LOGICAL_SPECIAL | 0x307),
+  ("PageUp", 0x0007004b, LOGICAL_SPECIAL | 0x308),
+];
+
+const PUNCTUATION_KEYS: &[(char, u64)] = &[
+  ('-', 0x0007002d),
+  ('=', 0x0007002e),
+  ('[', 0x0007002f),
+  (']', 0x00070030),
+  ('\\', 0x00070031),
+  (';', 0x00070033),
+  ('\'', 0x00070034),
+  ('`', 0x00070035),
+  (',', 0x00070036),
+  ('.', 0x00070037),
+  ('/', 0x00070038),
+];
+
+/// One key press: a down event carrying `character`, then an up event.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct KeyStroke {
+  pub physical: u64,
+  pub logical: u64,
+  pub character: Option<CString>,
+  pub synthesized: bool,
+}
+
+impl KeyStroke {
+  /// Builds the event for one phase. The returned event borrows
+  /// `self.character`, so the stroke must outlive the dispatch.
+  pub(crate) fn event(&self, event_type: sys::lynx_key_event_type_e) -> KeyEvent {
+    let character = match (&self.character, event_type) {
+      (Some(character), sys::kLynxKeyEventTypeDown) => character.as_ptr(),
+      _ => std::ptr::null(),
+    };
+    KeyEvent {
+      timestamp: timestamp_micros() as f64,
+      type_: event_type,
+      physical: self.physical,
+      logical: self.logical,
+      character,
+      synthesized: self.synthesized,
+      ..KeyEvent::default()
+    }
+  }
+}
+
+pub(crate) fn key_for_name(name: &str) -> Result<KeyStroke> {
+  if let Some((_, physical, logical)) = NAMED_KEYS.iter().find(|(key, ..)| *key == name) {
+    return Ok(KeyStroke {
+      physical: *physical,
+      logical: *logical,
+      character: None,
+      synthesized: false,
+    });
+  }
+  let mut chars = name.chars();
+  match (chars.next(), chars.next()) {
+    (Some(character), None) => key_for_char(character),
+    _ if name == "Space" => key_for_char(' '),
+    _ => Err(Error::UnknownKey(name.to_string())),
+  }
+}
+
+pub(crate) fn text_strokes(text: &str) -> Result<Vec<KeyStroke>> {
+  text.chars().map(key_for_char).collect()
+}
+
+pub(crate) fn key_for_char(character: char) -> Result<KeyStroke> {
+  match character {
+    '\n' | '\r' => return key_for_name("Enter"),
+    '\t' => return key_for_name("Tab"),
+    _ => {}
+  }
+  let lower = character.to_ascii_lowercase();
+  let physical = match lower {
+    'a'..='z' => 0x00070004 + (lower as u64 - 'a' as u64),
+    '1'..='9' => 0x0007001e + (lower as u64 - '1' as u64),
+    '0' => 0x00070027,
+    ' ' => 0x0007002c,
+    _ => PUNCTUATION_KEYS
+      .iter()
+      .find_map(|(key, physical)| (*key == lower).then_some(*physical))
+      .unwrap_or(0),
+  };
+  Ok(KeyStroke {
+    physical,
+    logical: u64::from(lower),
+    character: Some(text_cstring(&character.to_string())?),
+    synthesized: false,
+  })
+}
+
+/// Text committed by an input method arrives as a single synthesized key
+/// event that carries the whole string, with no physical key behind it.
+pub(crate) fn committed_text(text: &str) -> Result<KeyStroke> {
+  Ok(KeyStroke {
+    physical: 0,
+    logical: 0,
+    character: Some(text_cstring(text)?),
+    synthesized: true,
+  })
+}
+
+fn text_cstring(text: &str) -> Result<CString> {
+  CString::new(text).map_err(|_| Error::UnknownKey(text.escape_debug().to_string()))
+}
+
+/// A text-input request the engine made to the host.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextInputRequest {
+  ShowTextInput(bool),
+  UpdateCaretPosition(BoundingBox),
+  SetCursorPosition(i32),
+  SetMarkedTextRect(BoundingBox),
+}
+
+/// The host-side text-input state, as last requested by the engine.
+///
+/// Rects are reported in the renderer's coordinate space, unscaled.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextInputState {
+  pub keyboard_visible: bool,
+  pub caret_rect: Option<BoundingBox>,
+  pub marked_text_rect: Option<BoundingBox>,
+  pub cursor_position: Option<i32>,
+  /// Every request in arrival order, since the page was created.
+  pub requests: Vec<TextInputRequest>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct TextInputRecorder {
+  state: Arc<Mutex<TextInputState>>,
+}
+
+impl TextInputRecorder {
+  pub(crate) fn snapshot(&self) -> TextInputState {
+    self
+      .state
+      .lock()
+      .expect("text input state lock poisoned")
+      .clone()
+  }
+
+  pub(crate) fn keyboard_visible(&self) -> bool {
+    self
+      .state
+      .lock()
+      .expect("text input state lock poisoned")
+      .keyboard_visible
+  }
+
+  pub(crate) fn record(&self, request: TextInputRequest) {
+    let mut state = self.state.lock().expect("text input state lock poisoned");
+    match request {
+      TextInputRequest::ShowTextInput(show) => state.keyboard_visible = show,
+      TextInputRequest::UpdateCaretPosition(rect) => state.caret_rect = Some(rect),
+      TextInputRequest::SetCursorPosition(position) => state.cursor_position = Some(position),
+      TextInputRequest::SetMarkedTextRect(rect) => state.marked_text_rect = Some(rect),
+    }
+    state.requests.push(request);
+  }
+}
+
+pub(crate) fn rect(x: f32, y: f32, width: f32, height: f32) -> BoundingBox {
+  BoundingBox {
+    x: f64::from(x),
+    y: f64::from(y),
+    width: f64::from(width),
+    height: f64::from(height),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn named_keys_use_special_logical_ids() {
+    let enter = key_for_name("Enter").unwrap();
+    assert_eq!(enter.physical, 0x00070028);
+    assert_eq!(enter.logical, 0x010000000d);
+    assert_eq!(enter.character, None);
+    assert_eq!(key_for_name("Space").unwrap().logical, 0x20);
+    assert!(matches!(
+      key_for_name("Hyper"),
+      Err(Error::UnknownKey(name)) if name == "Hyper"
+    ));
+  }
+
+  #[test]
+  fn characters_map_to_physical_keys_when_one_exists() {
+    let upper = key_for_char('B').unwrap();
+    assert_eq!(upper.physical, 0x00070005);
+    assert_eq!(upper.logical, u64::from('b'));
+    assert_eq!(upper.character.as_deref(), Some(c"B"));
+    assert_eq!(key_for_char('0').unwrap().physical, 0x00070027);
+    assert_eq!(key_for_char('.').unwrap().physical, 0x00070037);
+    assert_eq!(key_for_char('\n').unwrap(), key_for_name("Enter").unwrap());
+
+    let han = key_for_char('字').unwrap();
+    assert_eq!(han.physical, 0);
+    assert_eq!(han.logical, u64::from('字'));
+  }
+
+  #[test]
+  fn only_down_events_carry_characters() {
+    let stroke = key_for_char('a').unwrap();
+    let down = stroke.event(sys::kLynxKeyEventTypeDown);
+    let up = stroke.event(sys::kLynxKeyEventTypeUp);
+    assert!(!down.character.is_null());
+    assert!(up.character.is_null());
+    assert_eq!(up.type_, sys::kLynxKeyEventTypeUp);
+  }
+
+  #[test]
+  fn committed_text_is_synthesized() {
+    let stroke = committed_text("你好").unwrap();
+    assert!(stroke.synthesized);
+    assert_eq!(stroke.physical, 0);
+    assert_eq!(stroke.character.as_deref(), Some(c"你好"));
+    assert!(committed_text("a\0b").is_err());
+  }
+
+  #[test]
+  fn recorder_tracks_latest_state_and_history() {
+    let recorder = TextInputRecorder::default();
+    recorder.record(TextInputRequest::ShowTextInput(true));
+    recorder.record(TextInputRequest::UpdateCaretPosition(rect(
+      1.0, 2.0, 3.0, 4.0,
+    )));
+    recorder.record(TextInputRequest::SetCursorPosition(3));
+    recorder.record(TextInputRequest::ShowTextInput(false));
+
+    let state = recorder.snapshot();
+    assert!(!state.keyboard_visible);
+    assert!(!recorder.keyboard_visible());
+    assert_eq!(state.caret_rect, Some(rect(1.0, 2.0, 3.0, 4.0)));
+    assert_eq!(state.cursor_position, Some(3));
+    assert_eq!(state.marked_text_rect, None);
+    assert_eq!(state.requests.len(), 4);
+  }
+}
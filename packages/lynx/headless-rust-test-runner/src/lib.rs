@@ -1,10 +1,17 @@
+mod console;
 mod debug_router;
 mod error;
 mod fixture;
+mod gesture;
 mod harness;
+mod image_diff;
+mod keyboard;
 mod png_encoder;
 mod protocol;
 mod resource;
+mod scenario;
+mod snapshot;
+mod wait;
 
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -12,11 +19,20 @@ use std::rc::Rc;
 use std::sync::{Arc, Mutex as StdMutex, Weak};
 use std::time::{Duration, Instant};
 
+use console::PageLog;
+pub use console::{ConsoleMessage, ConsoleMessages, PageError};
 use debug_router::DebugRouter;
 pub use error::{Error, Result};
 pub use fixture::{run_react_fixture, RunReport};
+use gesture::GestureStep;
+pub use gesture::{GestureOptions, Point};
 use harness::{initialize_platform, FrameStore, QueueingHost, SharedTasks, TaskPump};
-use lynx::{Env, HeadlessView, WindowlessRenderer};
+pub use image_diff::{
+  diff_rgba_blocks, try_diff_rgba_blocks, BlockDiff, BlockDiffError, BlockDiffOptions, DIFF_PIXEL,
+};
+use keyboard::{KeyStroke, TextInputRecorder};
+pub use keyboard::{TextInputRequest, TextInputState};
+use lynx::{view_event_channel, Env, HeadlessView, WindowlessRenderer};
 use png_encoder::encode_png_async;
 pub use protocol::NodeInfo;
 use protocol::{
@@ -24,7 +40,15 @@ use protocol::{
   GetDocumentResult, QuerySelectorResult, Session,
 };
 use resource::ResourceContext;
+pub use scenario::{
+  resolve_input, run_scenario, AssertAttributeStep, AssertStyleStep, ExpectScreenshotStep,
+  GotoStep, Scenario, ScenarioReport, ScenarioStep, ScenarioViewport, ScreenshotStep, StepOutcome,
+  StepReport, WaitForSelectorStep,
+};
 use serde_json::{json, Value};
+pub use snapshot::{ExpectScreenshotOptions, ScreenshotComparison, UPDATE_SCREENSHOTS_ENV};
+use wait::{Deadline, POLL_INTERVAL};
+pub use wait::{LocatorAssertions, WaitForSelectorOptions, WaitForState};
 
 const DEFAULT_VIEWPORT_WIDTH: usize = 800;
 const DEFAULT_VIEWPORT_HEIGHT: usize = 600;
@@ -32,7 +56,7 @@ const DEFAULT_DEVICE_PIXEL_RATIO: f32 = 1.0;
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 const APP_NAME: &str = "HeadlessRustTestRunner";
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ConnectOptions {
   pub width: usize,
   pub height: usize,
@@ -78,6 +102,12 @@ pub struct BoundingBox {
   pub height: f64,
 }
 
+impl BoundingBox {
+  pub fn center(&self) -> Point {
+    Point::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+  }
+}
+
 struct LynxProcess {
   env: Env,
   debug_router: DebugRouter,
@@ -160,15 +190,21 @@ impl Lynx {
     let global_tasks = initialize_platform(&self.process.env)?;
     let renderer_tasks = SharedTasks::new();
     let frames = FrameStore::default();
+    let text_input = TextInputRecorder::default();
     let renderer = WindowlessRenderer::software(
       &self.process.env,
       frames.clone(),
-      QueueingHost::new(renderer_tasks.clone()),
+      QueueingHost::new(renderer_tasks.clone(), text_input.clone()),
     )?;
     let resources = ResourceContext::new(
       self.options.resources_path.clone(),
       self.lynx_core_path.clone(),
     );
+    let (view_client, view_events) = view_event_channel();
+    let log = PageLog::new(
+      view_events,
+      self.process.debug_router.subscribe(console::RUNTIME_EVENTS),
+    );
     let view = HeadlessView::builder(self.process.env.clone(), renderer)
       .viewport(
         self.options.width as f32,
@@ -176,6 +212,7 @@ impl Lynx {
         self.options.device_pixel_ratio,
       )
       .resource_fetcher(resources.fetcher())?
+      .view_client(view_client)?
       .build()?;
     view.enter_foreground();
     let pump = TaskPump::new(self.process.env.clone(), renderer_tasks, global_tasks);
@@ -183,6 +220,8 @@ impl Lynx {
       view,
       pump,
       frames,
+      text_input,
+      log,
       debug_router: self.process.debug_router.clone(),
       session_locks: Arc::clone(&self.process.session_locks),
       resources,
@@ -243,6 +282,8 @@ struct PageRuntime {
   view: HeadlessView,
   pump: TaskPump,
   frames: FrameStore,
+  text_input: TextInputRecorder,
+  log: PageLog,
   debug_router: DebugRouter,
   session_locks: Arc<SessionLocks>,
   resources: ResourceContext,
@@ -291,6 +332,46 @@ impl PageRuntime {
     Ok(())
   }
 
+  /// Replays planned pointer events through the windowless renderer, pumping
+  /// the runtime between events so timing-based recognizers observe the
+  /// requested delays.
+  async fn dispatch_gesture(&self, steps: Vec<GestureStep>) {
+    for step in steps {
+      if !step.delay.is_zero() {
+        self.pump_for(step.delay).await;
+      }
+      self
+        .view
+        .renderer()
+        .send_pointer_event(gesture::pointer_event(&step, self.device_pixel_ratio));
+    }
+    self.pump_for(Duration::from_millis(50)).await;
+  }
+
+  async fn dispatch_keys(&self, strokes: &[KeyStroke]) {
+    let renderer = self.view.renderer();
+    for stroke in strokes {
+      renderer.send_key_event(stroke.event(lynx::sys::kLynxKeyEventTypeDown));
+      self.pump_for(Duration::from_millis(10)).await;
+      if !stroke.synthesized {
+        renderer.send_key_event(stroke.event(lynx::sys::kLynxKeyEventTypeUp));
+        self.pump_for(Duration::from_millis(10)).await;
+      }
+    }
+    self.pump_for(Duration::from_millis(50)).await;
+  }
+
+  async fn wait_for_keyboard(&self, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    while !self.text_input.keyboard_visible() {
+      if Instant::now() >= deadline {
+        return Err(Error::Timeout("waiting for a focused text input".into()));
+      }
+      self.pump_for(Duration::from_millis(5)).await;
+    }
+    Ok(())
+  }
+
   async fn pump_for(&self, duration: Duration) {
     self.pump.pump_for(&self.view, duration).await;
   }
@@ -346,6 +427,9 @@ impl Page {
       .global_props_json
       .unwrap_or_else(|| self.default_global_props_json());
     let previous_sequence = self.runtime.frames.sequence();
+    self.runtime.log.detach();
+    let errors_before = self.runtime.log.error_count();
+    let runtime = Rc::clone(&self.runtime);
 
     self.runtime.view.load_template_bytes_with_global_props(
       &url,
@@ -360,22 +444,28 @@ impl Page {
       self.runtime.width as f32,
       self.runtime.height as f32,
     );
-    self
-      .runtime
-      .pump
-      .wait_for_frame(
-        &self.runtime.view,
-        &self.runtime.frames,
-        previous_sequence,
-        timeout,
+    runtime
+      .fail_on_page_error(
+        errors_before,
+        runtime
+          .pump
+          .wait_for_frame(&runtime.view, &runtime.frames, previous_sequence, timeout),
       )
       .await?;
 
     if attach_dom {
-      let session = self
-        .wait_for_session(&url, &existing_session_ids, timeout)
+      let session = runtime
+        .fail_on_page_error(
+          errors_before,
+          self.wait_for_session(&url, &existing_session_ids, timeout),
+        )
+        .await?;
+      runtime
+        .fail_on_page_error(
+          errors_before,
+          self.attach_to_session(session.session_id, timeout),
+        )
         .await?;
-      self.attach_to_session(session.session_id, timeout).await?;
     } else {
       self.root_node_id = None;
       self.session_id = None;
@@ -384,19 +474,108 @@ impl Page {
     Ok(())
   }
 
+  /// Streams the page's `console.*` calls, starting with the next one.
+  pub fn on_console(&self) -> ConsoleMessages {
+    ConsoleMessages::new(Rc::clone(&self.runtime))
+  }
+
+  /// Every engine error and uncaught exception the page has reported so far.
+  pub fn errors(&self) -> Vec<PageError> {
+    self.runtime.log.errors()
+  }
+
   pub fn url(&self) -> &str {
     &self.url
   }
 
   pub async fn content(&self) -> Result<String> {
+    let document = self.document().await?;
+    let mut buffer = String::new();
+    content_to_string(&mut buffer, &document);
+    Ok(buffer)
+  }
+
+  /// Returns a snapshot of the whole DOM tree.
+  pub async fn document(&self) -> Result<NodeInfo> {
     let session_id = self.session_id()?;
     let document: GetDocumentResult = self
       .runtime
       .send_cdp(session_id, "DOM.getDocument", json!({ "depth": -1 }))
       .await?;
-    let mut buffer = String::new();
-    content_to_string(&mut buffer, &document.root);
-    Ok(buffer)
+    Ok(document.root)
+  }
+
+  /// Polls `selector` until it reaches `options.state`.
+  ///
+  /// Returns the matched node for [`WaitForState::Attached`] and
+  /// [`WaitForState::Visible`], and `None` for the detached and hidden states.
+  pub async fn wait_for_selector(
+    &mut self,
+    selector: &str,
+    options: WaitForSelectorOptions,
+  ) -> Result<Option<ElementNode>> {
+    let timeout = options.timeout.unwrap_or(self.runtime.timeout);
+    let deadline = Deadline::after(timeout);
+    loop {
+      let node = self.locator(selector).await?;
+      let reached = match (&node, options.state) {
+        (Some(_), WaitForState::Attached) | (None, WaitForState::Detached) => true,
+        (Some(node), WaitForState::Visible) => wait::is_visible(node).await?,
+        (Some(node), WaitForState::Hidden) => !wait::is_visible(node).await?,
+        (None, WaitForState::Hidden) => true,
+        (None, WaitForState::Attached | WaitForState::Visible)
+        | (Some(_), WaitForState::Detached) => false,
+      };
+      if reached {
+        return Ok(node.filter(|_| {
+          matches!(
+            options.state,
+            WaitForState::Attached | WaitForState::Visible
+          )
+        }));
+      }
+      if deadline.expired() {
+        return Err(Error::Timeout(format!(
+          "waiting for selector {selector} to be {:?}",
+          options.state
+        )));
+      }
+      self.wait_for_timeout(POLL_INTERVAL).await;
+    }
+  }
+
+  /// Polls DOM snapshots until `predicate` accepts one, and returns it.
+  pub async fn wait_for_function<F>(
+    &self,
+    mut predicate: F,
+    timeout: Option<Duration>,
+  ) -> Result<NodeInfo>
+  where
+    F: FnMut(&NodeInfo) -> bool,
+  {
+    let timeout = timeout.unwrap_or(self.runtime.timeout);
+    let deadline = Deadline::after(timeout);
+    loop {
+      let document = self.document().await?;
+      if predicate(&document) {
+        return Ok(document);
+      }
+      if deadline.expired() {
+        return Err(Error::Timeout("waiting for a DOM predicate".into()));
+      }
+      self.wait_for_timeout(POLL_INTERVAL).await;
+    }
+  }
+
+  /// Starts an auto-retrying assertion about the node `selector` matches,
+  /// bounded by [`ConnectOptions::timeout`].
+  ///
+  /// ```ignore
+  /// page.expect(".Title").to_have_attribute("class", "Title").await?;
+  /// ```
+  pub fn expect(&mut self, selector: &str) -> LocatorAssertions<'_> {
+    let timeout = self.runtime.timeout;
+    LocatorAssertions::new(self, selector, timeout)
   }
 
   pub async fn locator(&mut self, selector: &str) -> Result<Option<ElementNode>> {
@@ -441,10 +620,111 @@ impl Page {
     Ok(png)
   }
 
+  /// Compares a screenshot against the stored baseline `name`.png.
+  ///
+  /// On mismatch, `name`.actual.png and `name`.diff.png are written next to
+  /// the baseline, with differing pixels painted red in the diff. Set
+  /// [`UPDATE_SCREENSHOTS_ENV`] to write the current screenshot as the new
+  /// baseline instead.
+  pub async fn expect_screenshot(
+    &self,
+    name: &str,
+    options: ExpectScreenshotOptions,
+  ) -> Result<ScreenshotComparison> {
+    let png = self
+      .screenshot(ScreenshotOptions {
+        path: None,
+        settle: options.settle,
+      })
+      .await?;
+    snapshot::expect_png(name, png, &options).await
+  }
+
   pub async fn wait_for_timeout(&self, duration: Duration) {
     self.runtime.pump_for(duration).await;
   }
 
+  /// Presses and releases one key in the focused text input. `key` is either
+  /// a single character or a named key such as `Enter`, `Backspace`,
+  /// `ArrowLeft`, or `Space`.
+  pub async fn press_key(&self, key: &str) -> Result<()> {
+    let stroke = keyboard::key_for_name(key)?;
+    self.runtime.dispatch_keys(&[stroke]).await;
+    Ok(())
+  }
+
+  /// Types `text` into the focused text input, one key press per character.
+  pub async fn type_text(&self, text: &str) -> Result<()> {
+    let strokes = keyboard::text_strokes(text)?;
+    self.runtime.dispatch_keys(&strokes).await;
+    Ok(())
+  }
+
+  /// Commits `text` the way an input method does at the end of a
+  /// composition: as one synthesized event carrying the whole string.
+  ///
+  /// The windowless ABI has no entry point for in-progress (marked) text, so
+  /// only the commit can be simulated; the engine's marked-text rect is still
+  /// observable through [`Page::text_input_state`].
+  pub async fn commit_composition(&self, text: &str) -> Result<()> {
+    let stroke = keyboard::committed_text(text)?;
+    self.runtime.dispatch_keys(&[stroke]).await;
+    Ok(())
+  }
+
+  /// Returns what the engine last asked the host to do for text input:
+  /// keyboard visibility, caret and marked-text rects, and cursor position.
+  pub fn text_input_state(&self) -> TextInputState {
+    self.runtime.text_input.snapshot()
+  }
+
+  /// Presses and releases a touch pointer at `point`, in page layout pixels.
+  ///
+  /// Unlike [`ElementNode::tap`], this goes through hit testing, so it also
+  /// reaches content that is not addressable by a selector.
+  pub async fn touch_at(&self, point: Point) {
+    self
+      .runtime
+      .dispatch_gesture(gesture::tap_steps(point))
+      .await;
+  }
+
+  /// Holds a touch pointer at `point`. `None` holds long enough to trigger
+  /// `longpress` handlers.
+  pub async fn long_press(&self, point: Point, duration: Option<Duration>) {
+    self
+      .runtime
+      .dispatch_gesture(gesture::long_press_steps(point, duration))
+      .await;
+  }
+
+  /// Moves a touch pointer from `from` to `to` and releases it while still
+  /// moving, so scroll containers fling and swipe recognizers fire.
+  pub async fn swipe(&self, from: Point, to: Point, options: GestureOptions) {
+    self
+      .runtime
+      .dispatch_gesture(gesture::swipe_steps(from, to, options))
+      .await;
+  }
+
+  /// Scrolls the content under `at` by `delta_x`/`delta_y` layout pixels
+  /// with a touch drag that rests before release, so no fling follows.
+  pub async fn scroll_by(&self, at: Point, delta_x: f64, delta_y: f64, options: GestureOptions) {
+    self
+      .runtime
+      .dispatch_gesture(gesture::scroll_steps(at, delta_x, delta_y, options))
+      .await;
+  }
+
+  /// Presses at `from`, waits for the content to be picked up, then moves to
+  /// `to` and releases.
+  pub async fn drag_to(&self, from: Point, to: Point, options: GestureOptions) {
+    self
+      .runtime
+      .dispatch_gesture(gesture::drag_steps(from, to, options))
+      .await;
+  }
+
   fn default_global_props_json(&self) -> String {
     json!({
       "initialPage": "home",
@@ -514,6 +794,13 @@ impl Page {
         Ok(root_node_id) => {
           self.root_node_id = Some(root_node_id);
           self.session_id = Some(session_id);
+          self.runtime.log.attach(session_id);
+          // Console and exception events are best effort; DOM access must
+          // not depend on the Runtime domain.
+          let _ = self
+            .runtime
+            .send_cdp::<Value, _>(session_id, "Runtime.enable", json!({}))
+            .await;
           return Ok(());
         }
         Err(error) => {
@@ -576,6 +863,73 @@ impl ElementNode {
     self.runtime.tap_node(self.node_id).await
   }
 
+  /// Focuses this node with a touch and types `text` once the engine shows
+  /// the keyboard for it.
+  pub async fn type_text(&self, text: &str) -> Result<()> {
+    self.touch().await?;
+    self.runtime.wait_for_keyboard(self.runtime.timeout).await?;
+    let strokes = keyboard::text_strokes(text)?;
+    self.runtime.dispatch_keys(&strokes).await;
+    Ok(())
+  }
+
+  /// Presses and releases a touch pointer at the center of this node.
+  pub async fn touch(&self) -> Result<()> {
+    let center = self.center().await?;
+    self
+      .runtime
+      .dispatch_gesture(gesture::tap_steps(center))
+      .await;
+    Ok(())
+  }
+
+  /// Holds a touch pointer at the center of this node.
+  pub async fn long_press(&self, duration: Option<Duration>) -> Result<()> {
+    let center = self.center().await?;
+    self
+      .runtime
+      .dispatch_gesture(gesture::long_press_steps(center, duration))
+      .await;
+    Ok(())
+  }
+
+  /// Swipes from the center of this node by `delta_x`/`delta_y`.
+  pub async fn swipe_by(&self, delta_x: f64, delta_y: f64, options: GestureOptions) -> Result<()> {
+    let from = self.center().await?;
+    let to = Point::new(from.x + delta_x, from.y + delta_y);
+    self
+      .runtime
+      .dispatch_gesture(gesture::swipe_steps(from, to, options))
+      .await;
+    Ok(())
+  }
+
+  /// Scrolls this node's content by `delta_x`/`delta_y`, starting from its
+  /// center. See [`Page::scroll_by`].
+  pub async fn scroll_by(&self, delta_x: f64, delta_y: f64, options: GestureOptions) -> Result<()> {
+    let at = self.center().await?;
+    self
+      .runtime
+      .dispatch_gesture(gesture::scroll_steps(at, delta_x, delta_y, options))
+      .await;
+    Ok(())
+  }
+
+  /// Drags this node's center onto the center of `target`.
+  pub async fn drag_to(&self, target: &ElementNode, options: GestureOptions) -> Result<()> {
+    let from = self.center().await?;
+    let to = target.center().await?;
+    self
+      .runtime
+      .dispatch_gesture(gesture::drag_steps(from, to, options))
+      .await;
+    Ok(())
+  }
+
+  async fn center(&self) -> Result<Point> {
+    Ok(self.bounding_box().await?.center())
+  }
+
   pub async fn bounding_box(&self) -> Result<BoundingBox> {
     let result: GetBoxModelResult = self
       .runtime
@@ -1,5 +1,52 @@
+mod cli;
+
+use std::process::ExitCode;
+
+use cli::Command;
+use lynx_headless_rust_test_runner::{run_scenario, Lynx};
+
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> ExitCode {
+  let command = match cli::parse(std::env::args().skip(1)) {
+    Ok(command) => command,
+    Err(message) => {
+      eprintln!("error: {message}\n\n{}", cli::USAGE);
+      return ExitCode::from(2);
+    }
+  };
+  let result = match command {
+    Command::Help => {
+      print!("{}", cli::USAGE);
+      return ExitCode::SUCCESS;
+    }
+    Command::ReactFixture => run_react_fixture().await,
+    Command::Run(command) => run(*command).await,
+  };
+  match result {
+    Ok(code) => code,
+    Err(error) => {
+      eprintln!("error: {error}");
+      ExitCode::from(2)
+    }
+  }
+}
+
+async fn run(command: cli::RunCommand) -> Result<ExitCode, Box<dyn std::error::Error>> {
+  let lynx = Lynx::connect(command.connect).await?;
+  let mut page = lynx.new_page()?;
+  let report = run_scenario(&mut page, &command.scenario, &command.base_dir).await;
+  drop(page);
+  lynx.close();
+  if report.passed() {
+    println!("{report}");
+    Ok(ExitCode::SUCCESS)
+  } else {
+    eprintln!("{report}");
+    Ok(ExitCode::FAILURE)
+  }
+}
+
+async fn run_react_fixture() -> Result<ExitCode, Box<dyn std::error::Error>> {
   let report = lynx_headless_rust_test_runner::run_react_fixture().await?;
   println!(
     "captured {}x{} software frame: visible_pixels={} white_pixels={} gradient_pixels={} logo_pixels={} arrow_pixels={} screenshot={}",
@@ -12,5 +59,5 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     report.arrow_pixels,
     report.screenshot_path.display()
   );
-  Ok(())
+  Ok(ExitCode::SUCCESS)
 }
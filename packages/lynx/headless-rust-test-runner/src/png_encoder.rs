@@ -72,6 +72,39 @@ pub(crate) async fn encode_png_async(
   pool()?.encode(width, height, rgba).await
 }
 
+#[derive(Debug)]
+pub(crate) struct DecodedPng {
+  pub width: usize,
+  pub height: usize,
+  pub rgba: Vec<u8>,
+}
+
+pub(crate) fn decode_png(bytes: &[u8]) -> Result<DecodedPng> {
+  let decoder = png::Decoder::new(Cursor::new(bytes));
+  let mut reader = decoder
+    .read_info()
+    .map_err(|error| Error::Protocol(format!("failed to decode screenshot: {error}")))?;
+  let buffer_size = reader
+    .output_buffer_size()
+    .ok_or_else(|| Error::Protocol("decoded screenshot is too large".into()))?;
+  let mut buffer = vec![0; buffer_size];
+  let info = reader
+    .next_frame(&mut buffer)
+    .map_err(|error| Error::Protocol(format!("failed to decode screenshot: {error}")))?;
+  if info.color_type != png::ColorType::Rgba || info.bit_depth != png::BitDepth::Eight {
+    return Err(Error::Protocol(format!(
+      "expected an 8-bit RGBA screenshot, got {:?} {:?}",
+      info.color_type, info.bit_depth
+    )));
+  }
+  buffer.truncate(info.buffer_size());
+  Ok(DecodedPng {
+    width: info.width as usize,
+    height: info.height as usize,
+    rgba: buffer,
+  })
+}
+
 fn encode_png(width: usize, height: usize, rgba: &[u8]) -> Result<Vec<u8>> {
   let expected = width
     .checked_mul(height)
@@ -107,7 +140,11 @@ mod tests {
     let rgba: Arc<[u8]> = Arc::from([
       255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 255,
     ]);
-    let png = encode_png_async(2, 2, rgba).await.unwrap();
+    let png = encode_png_async(2, 2, Arc::clone(&rgba)).await.unwrap();
     assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    let decoded = decode_png(&png).unwrap();
+    assert_eq!((decoded.width, decoded.height), (2, 2));
+    assert_eq!(&decoded.rgba[..], &rgba[..]);
   }
 }
@@ -25,6 +25,15 @@ pub(crate) struct Session {
   pub url: String,
 }
 
+/// A CDP message without an `id`, such as `Runtime.consoleAPICalled`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CdpNotification {
+  /// `None` when the router did not say which session emitted the event.
+  pub session_id: Option<i64>,
+  pub method: String,
+  pub params: Value,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct NodeInfo {
   #[serde(rename = "nodeId")]
@@ -254,6 +263,32 @@ pub(crate) fn cdp_response_id(value: &Value) -> Result<Option<u32>> {
   Ok(Some(id))
 }
 
+pub(crate) fn parse_cdp_notification(value: &Value) -> Result<Option<CdpNotification>> {
+  let Some(mut message) = cdp_message(value)? else {
+    return Ok(None);
+  };
+  if message.get("id").is_some() {
+    return Ok(None);
+  }
+  let Some(method) = message.get("method").and_then(Value::as_str) else {
+    return Ok(None);
+  };
+  let method = method.to_string();
+  let session_id = value
+    .get("data")
+    .and_then(|data| data.get("data"))
+    .and_then(|data| data.get("session_id"))
+    .and_then(Value::as_i64);
+  Ok(Some(CdpNotification {
+    session_id,
+    method,
+    params: message
+      .get_mut("params")
+      .map(Value::take)
+      .unwrap_or(Value::Null),
+  }))
+}
+
 fn cdp_message(value: &Value) -> Result<Option<Value>> {
   if customized_type(value) != Some("CDP") {
     return Ok(None);
@@ -367,6 +402,33 @@ mod tests {
     ));
   }
 
+  #[test]
+  fn parses_cdp_notifications_with_their_session() {
+    let notification = json!({
+      "event": "Customized",
+      "data": {
+        "type": "CDP",
+        "data": {
+          "session_id": 3,
+          "message": json!({
+            "method": "Runtime.consoleAPICalled",
+            "params": { "type": "log" },
+          }).to_string(),
+        },
+      },
+    });
+    assert_eq!(
+      parse_cdp_notification(&notification).unwrap(),
+      Some(CdpNotification {
+        session_id: Some(3),
+        method: "Runtime.consoleAPICalled".into(),
+        params: json!({ "type": "log" }),
+      })
+    );
+    let response = cdp_response(json!({ "id": 42, "result": {} }));
+    assert!(parse_cdp_notification(&response).unwrap().is_none());
+  }
+
   #[test]
   fn parses_initialize_response() {
     let response = json!({
@@ -0,0 +1,531 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::{
+  ConnectOptions, Error, ExpectScreenshotOptions, GotoOptions, Page, Result, ScreenshotOptions,
+  WaitForSelectorOptions, WaitForState,
+};
+
+/// A declarative page script, loaded from JSON or YAML.
+///
+/// ```yaml
+/// url: ./dist/main.lynx.bundle
+/// viewport: { width: 390, height: 844, devicePixelRatio: 3 }
+/// steps:
+///   - tap: "#submit"
+///   - waitForSelector: ".toast"
+///   - assertAttribute: { selector: ".toast", name: "class", contains: "success" }
+///   - screenshot: out/toast.png
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Scenario {
+  /// Loaded before the first step when set.
+  pub url: Option<String>,
+  pub viewport: Option<ScenarioViewport>,
+  pub initial_data: Option<Value>,
+  pub global_props: Option<Value>,
+  pub timeout_ms: Option<u64>,
+  #[serde(default)]
+  pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScenarioViewport {
+  pub width: Option<usize>,
+  pub height: Option<usize>,
+  pub device_pixel_ratio: Option<f32>,
+}
+
+/// One scenario step. String shorthands are accepted where a step has a
+/// single required field, for example `goto: ./main.lynx.bundle`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum ScenarioStep {
+  Goto(#[serde(deserialize_with = "shorthand")] GotoStep),
+  Tap(String),
+  WaitForSelector(#[serde(deserialize_with = "shorthand")] WaitForSelectorStep),
+  /// Pumps the page for the given number of milliseconds.
+  Wait(u64),
+  AssertAttribute(AssertAttributeStep),
+  AssertStyle(AssertStyleStep),
+  Screenshot(#[serde(deserialize_with = "shorthand")] ScreenshotStep),
+  ExpectScreenshot(#[serde(deserialize_with = "shorthand")] ExpectScreenshotStep),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GotoStep {
+  pub url: String,
+  pub initial_data: Option<Value>,
+  pub global_props: Option<Value>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WaitForSelectorStep {
+  pub selector: String,
+  #[serde(default)]
+  pub state: WaitForState,
+  pub timeout_ms: Option<u64>,
+}
+
+/// Without `equals` or `contains`, only checks that the attribute exists.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AssertAttributeStep {
+  pub selector: String,
+  pub name: String,
+  pub equals: Option<String>,
+  pub contains: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AssertStyleStep {
+  pub selector: String,
+  pub property: String,
+  pub equals: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScreenshotStep {
+  pub path: String,
+  pub settle_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ExpectScreenshotStep {
+  pub name: String,
+  pub snapshot_dir: Option<String>,
+  pub max_different_blocks: Option<usize>,
+  pub settle_ms: Option<u64>,
+}
+
+macro_rules! impl_shorthand {
+  ($($step:ident => $field:ident),* $(,)?) => {
+    $(impl From<String> for $step {
+      fn from($field: String) -> Self {
+        Self {
+          $field,
+          ..Self::default()
+        }
+      }
+    })*
+  };
+}
+
+impl_shorthand! {
+  GotoStep => url,
+  WaitForSelectorStep => selector,
+  ScreenshotStep => path,
+  ExpectScreenshotStep => name,
+}
+
+fn shorthand<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+  D: Deserializer<'de>,
+  T: Deserialize<'de> + From<String>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum Shorthand<T> {
+    Short(String),
+    Full(T),
+  }
+
+  Ok(match Shorthand::<T>::deserialize(deserializer)? {
+    Shorthand::Short(value) => T::from(value),
+    Shorthand::Full(step) => step,
+  })
+}
+
+impl Scenario {
+  /// Parses a scenario, choosing YAML for `.yaml`/`.yml` files and JSON
+  /// otherwise.
+  pub fn from_file(path: &Path) -> Result<Self> {
+    let source = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+      Some("yaml" | "yml") => Self::from_yaml(&source),
+      _ => Ok(serde_json::from_str(&source)?),
+    }
+  }
+
+  /// YAML goes through a JSON value so steps keep the `{ tap: ... }` map
+  /// shape; serde_norway would otherwise expect `!tap` tags for enum variants.
+  pub fn from_yaml(source: &str) -> Result<Self> {
+    let value: Value =
+      serde_norway::from_str(source).map_err(|error| Error::Scenario(error.to_string()))?;
+    Ok(serde_json::from_value(value)?)
+  }
+
+  /// Applies the scenario viewport on top of `options`.
+  pub fn connect_options(&self, mut options: ConnectOptions) -> ConnectOptions {
+    if let Some(viewport) = self.viewport {
+      options.width = viewport.width.unwrap_or(options.width);
+      options.height = viewport.height.unwrap_or(options.height);
+      options.device_pixel_ratio = viewport
+        .device_pixel_ratio
+        .unwrap_or(options.device_pixel_ratio);
+    }
+    if let Some(timeout_ms) = self.timeout_ms {
+      options.timeout = Duration::from_millis(timeout_ms);
+    }
+    options
+  }
+
+  fn planned_steps(&self) -> Vec<ScenarioStep> {
+    let initial = self.url.as_ref().map(|url| {
+      ScenarioStep::Goto(GotoStep {
+        url: url.clone(),
+        initial_data: self.initial_data.clone(),
+        global_props: self.global_props.clone(),
+      })
+    });
+    initial.into_iter().chain(self.steps.clone()).collect()
+  }
+}
+
+impl fmt::Display for ScenarioStep {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Goto(step) => write!(f, "goto {}", step.url),
+      Self::Tap(selector) => write!(f, "tap {selector}"),
+      Self::WaitForSelector(step) => match step.state {
+        WaitForState::Attached => write!(f, "waitForSelector {}", step.selector),
+        state => write!(
+          f,
+          "waitForSelector {} {}",
+          step.selector,
+          format!("{state:?}").to_lowercase()
+        ),
+      },
+      Self::Wait(duration_ms) => write!(f, "wait {duration_ms} ms"),
+      Self::AssertAttribute(step) => {
+        write!(f, "assertAttribute {} [{}]", step.selector, step.name)?;
+        if let Some(expected) = &step.equals {
+          write!(f, " == {expected:?}")?;
+        }
+        if let Some(expected) = &step.contains {
+          write!(f, " contains {expected:?}")?;
+        }
+        Ok(())
+      }
+      Self::AssertStyle(step) => write!(
+        f,
+        "assertStyle {} {} == {:?}",
+        step.selector, step.property, step.equals
+      ),
+      Self::Screenshot(step) => write!(f, "screenshot {}", step.path),
+      Self::ExpectScreenshot(step) => write!(f, "expectScreenshot {}", step.name),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepOutcome {
+  Passed,
+  Failed(String),
+  /// Not run because an earlier step failed.
+  Skipped,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepReport {
+  pub description: String,
+  pub outcome: StepOutcome,
+  pub duration: Duration,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScenarioReport {
+  pub steps: Vec<StepReport>,
+}
+
+impl ScenarioReport {
+  pub fn passed(&self) -> bool {
+    self
+      .steps
+      .iter()
+      .all(|step| step.outcome == StepOutcome::Passed)
+  }
+
+  fn count(&self, matches: impl Fn(&StepOutcome) -> bool) -> usize {
+    self
+      .steps
+      .iter()
+      .filter(|step| matches(&step.outcome))
+      .count()
+  }
+}
+
+impl fmt::Display for ScenarioReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (index, step) in self.steps.iter().enumerate() {
+      let number = index + 1;
+      let elapsed = step.duration.as_millis();
+      match &step.outcome {
+        StepOutcome::Passed => writeln!(f, "ok   {number}. {} ({elapsed} ms)", step.description)?,
+        StepOutcome::Failed(message) => {
+          writeln!(f, "FAIL {number}. {} ({elapsed} ms)", step.description)?;
+          for line in message.lines() {
+            writeln!(f, "       {line}")?;
+          }
+        }
+        StepOutcome::Skipped => writeln!(f, "skip {number}. {}", step.description)?,
+      }
+    }
+    write!(
+      f,
+      "{} passed, {} failed, {} skipped",
+      self.count(|outcome| *outcome == StepOutcome::Passed),
+      self.count(|outcome| matches!(outcome, StepOutcome::Failed(_))),
+      self.count(|outcome| *outcome == StepOutcome::Skipped),
+    )
+  }
+}
+
+/// Runs every step in order and stops at the first failure; later steps are
+/// reported as skipped. Relative bundle and output paths resolve against
+/// `base_dir`.
+pub async fn run_scenario(page: &mut Page, scenario: &Scenario, base_dir: &Path) -> ScenarioReport {
+  let mut report = ScenarioReport::default();
+  let mut failed = false;
+  for step in scenario.planned_steps() {
+    let description = step.to_string();
+    if failed {
+      report.steps.push(StepReport {
+        description,
+        outcome: StepOutcome::Skipped,
+        duration: Duration::ZERO,
+      });
+      continue;
+    }
+    let started = Instant::now();
+    let outcome = match run_step(page, &step, base_dir).await {
+      Ok(()) => StepOutcome::Passed,
+      Err(error) => {
+        failed = true;
+        StepOutcome::Failed(error.to_string())
+      }
+    };
+    report.steps.push(StepReport {
+      description,
+      outcome,
+      duration: started.elapsed(),
+    });
+  }
+  report
+}
+
+async fn run_step(page: &mut Page, step: &ScenarioStep, base_dir: &Path) -> Result<()> {
+  match step {
+    ScenarioStep::Goto(step) => {
+      let url = resolve_input(base_dir, &step.url);
+      page
+        .goto(
+          &url,
+          GotoOptions {
+            timeout: None,
+            initial_data_json: step.initial_data.as_ref().map(Value::to_string),
+            global_props_json: step.global_props.as_ref().map(Value::to_string),
+          },
+        )
+        .await
+    }
+    ScenarioStep::Tap(selector) => {
+      let node = page
+        .wait_for_selector(selector, WaitForSelectorOptions::default())
+        .await?
+        .ok_or_else(|| Error::Assertion(format!("selector did not match a node: {selector}")))?;
+      node.tap().await
+    }
+    ScenarioStep::WaitForSelector(step) => {
+      page
+        .wait_for_selector(
+          &step.selector,
+          WaitForSelectorOptions {
+            state: step.state,
+            timeout: step.timeout_ms.map(Duration::from_millis),
+          },
+        )
+        .await?;
+      Ok(())
+    }
+    ScenarioStep::Wait(duration_ms) => {
+      page
+        .wait_for_timeout(Duration::from_millis(*duration_ms))
+        .await;
+      Ok(())
+    }
+    ScenarioStep::AssertAttribute(step) => {
+      page
+        .expect(&step.selector)
+        .attribute_matches(&step.name, step.equals.as_deref(), step.contains.as_deref())
+        .await?;
+      Ok(())
+    }
+    ScenarioStep::AssertStyle(step) => {
+      page
+        .expect(&step.selector)
+        .to_have_style(&step.property, &step.equals)
+        .await?;
+      Ok(())
+    }
+    ScenarioStep::Screenshot(step) => {
+      page
+        .screenshot(ScreenshotOptions {
+          path: Some(base_dir.join(&step.path)),
+          settle: Duration::from_millis(step.settle_ms.unwrap_or(0)),
+        })
+        .await?;
+      Ok(())
+    }
+    ScenarioStep::ExpectScreenshot(step) => {
+      let defaults = ExpectScreenshotOptions::default();
+      page
+        .expect_screenshot(
+          &step.name,
+          ExpectScreenshotOptions {
+            snapshot_dir: step.snapshot_dir.as_ref().map(|dir| base_dir.join(dir)),
+            max_different_blocks: step
+              .max_different_blocks
+              .unwrap_or(defaults.max_different_blocks),
+            settle: Duration::from_millis(step.settle_ms.unwrap_or(0)),
+            ..defaults
+          },
+        )
+        .await?;
+      Ok(())
+    }
+  }
+}
+
+/// Leaves URLs untouched and anchors relative file paths at `base_dir`.
+pub fn resolve_input(base_dir: &Path, input: &str) -> String {
+  if input.contains("://") || Path::new(input).is_absolute() {
+    return input.to_string();
+  }
+  let path: PathBuf = base_dir.join(input);
+  path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_yaml_shorthands_and_full_forms() {
+    let scenario = Scenario::from_yaml(
+      r##"
+url: ./main.lynx.bundle
+viewport: { width: 390, devicePixelRatio: 3 }
+initialData: { theme: dark }
+steps:
+  - tap: "#submit"
+  - waitForSelector: ".toast"
+  - waitForSelector: { selector: ".done", state: detached, timeoutMs: 500 }
+  - wait: 100
+  - assertAttribute: { selector: ".toast", name: class, contains: success }
+  - assertStyle: { selector: ".toast", property: color, equals: "rgb(0, 0, 0)" }
+  - screenshot: out/toast.png
+  - expectScreenshot: { name: toast, maxDifferentBlocks: 2 }
+"##,
+    )
+    .unwrap();
+
+    let steps = scenario.planned_steps();
+    assert_eq!(steps.len(), 9);
+    assert_eq!(
+      steps[0],
+      ScenarioStep::Goto(GotoStep {
+        url: "./main.lynx.bundle".into(),
+        initial_data: Some(serde_json::json!({ "theme": "dark" })),
+        global_props: None,
+      })
+    );
+    assert_eq!(
+      steps[2],
+      ScenarioStep::WaitForSelector(WaitForSelectorStep {
+        selector: ".toast".into(),
+        state: WaitForState::Attached,
+        timeout_ms: None,
+      })
+    );
+    assert_eq!(
+      steps[3],
+      ScenarioStep::WaitForSelector(WaitForSelectorStep {
+        selector: ".done".into(),
+        state: WaitForState::Detached,
+        timeout_ms: Some(500),
+      })
+    );
+    assert_eq!(steps[3].to_string(), "waitForSelector .done detached");
+    assert_eq!(steps[7].to_string(), "screenshot out/toast.png");
+
+    let options = scenario.connect_options(ConnectOptions::default());
+    assert_eq!((options.width, options.height), (390, 600));
+    assert_eq!(options.device_pixel_ratio, 3.0);
+  }
+
+  #[test]
+  fn json_scenarios_reject_unknown_steps() {
+    let error = serde_json::from_str::<Scenario>(r##"{ "steps": [{ "hover": "#a" }] }"##)
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("hover"), "{error}");
+    let error = serde_json::from_str::<Scenario>(r#"{ "step": [] }"#)
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("step"), "{error}");
+  }
+
+  #[test]
+  fn reports_summarize_outcomes() {
+    let report = ScenarioReport {
+      steps: vec![
+        StepReport {
+          description: "tap #a".into(),
+          outcome: StepOutcome::Passed,
+          duration: Duration::from_millis(3),
+        },
+        StepReport {
+          description: "assertStyle #a color == \"red\"".into(),
+          outcome: StepOutcome::Failed("expected red\ngot blue".into()),
+          duration: Duration::from_millis(1),
+        },
+        StepReport {
+          description: "screenshot out.png".into(),
+          outcome: StepOutcome::Skipped,
+          duration: Duration::ZERO,
+        },
+      ],
+    };
+    assert!(!report.passed());
+    assert_eq!(
+      report.to_string(),
+      "ok   1. tap #a (3 ms)\nFAIL 2. assertStyle #a color == \"red\" (1 ms)\n       expected red\n       got blue\nskip 3. screenshot out.png\n1 passed, 1 failed, 1 skipped"
+    );
+  }
+
+  #[test]
+  fn relative_inputs_resolve_against_the_scenario_dir() {
+    let base = Path::new("/work/scenarios");
+    assert_eq!(
+      resolve_input(base, "main.lynx.bundle"),
+      "/work/scenarios/main.lynx.bundle"
+    );
+    assert_eq!(resolve_input(base, "/abs/a.bundle"), "/abs/a.bundle");
+    assert_eq!(
+      resolve_input(base, "https://example.com/a.bundle"),
+      "https://example.com/a.bundle"
+    );
+  }
+}
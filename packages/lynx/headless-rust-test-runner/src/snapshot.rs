@@ -0,0 +1,246 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::image_diff::{diff_rgba_blocks, BlockDiffOptions};
+use crate::png_encoder::{decode_png, encode_png_async};
+use crate::{Error, Result};
+
+/// Set to any value other than empty, `0`, or `false` to rewrite baselines
+/// instead of comparing against them.
+pub const UPDATE_SCREENSHOTS_ENV: &str = "LYNX_UPDATE_SCREENSHOTS";
+
+const DEFAULT_SNAPSHOT_DIR: &str = "__screenshots__";
+const DEFAULT_BLOCK_SIZE: usize = 32;
+const DEFAULT_PIXEL_TOLERANCE: f64 = 0.1;
+const DEFAULT_BLOCK_THRESHOLD: f64 = 0.1;
+
+/// Options for [`crate::Page::expect_screenshot`].
+///
+/// The comparison is the block diff ui-judge uses for reference images, see
+/// [`BlockDiffOptions`].
+#[derive(Clone, Debug)]
+pub struct ExpectScreenshotOptions {
+  /// Baseline directory. Defaults to `__screenshots__` under the current
+  /// directory, which is the package root under `cargo test`.
+  pub snapshot_dir: Option<PathBuf>,
+  pub pixel_tolerance: f64,
+  pub block_size: usize,
+  pub block_threshold: f64,
+  /// Number of differing blocks that still passes.
+  pub max_different_blocks: usize,
+  pub settle: Duration,
+}
+
+impl Default for ExpectScreenshotOptions {
+  fn default() -> Self {
+    Self {
+      snapshot_dir: None,
+      pixel_tolerance: DEFAULT_PIXEL_TOLERANCE,
+      block_size: DEFAULT_BLOCK_SIZE,
+      block_threshold: DEFAULT_BLOCK_THRESHOLD,
+      max_different_blocks: 0,
+      settle: Duration::ZERO,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScreenshotComparison {
+  pub baseline_path: PathBuf,
+  pub different_blocks: usize,
+  pub total_blocks: usize,
+  pub similarity: f64,
+  /// The baseline was written by this call instead of being compared.
+  pub updated: bool,
+}
+
+pub(crate) async fn expect_png(
+  name: &str,
+  png: Vec<u8>,
+  options: &ExpectScreenshotOptions,
+) -> Result<ScreenshotComparison> {
+  let dir = options
+    .snapshot_dir
+    .clone()
+    .unwrap_or_else(|| PathBuf::from(DEFAULT_SNAPSHOT_DIR));
+  let paths = SnapshotPaths::new(&dir, name)?;
+
+  if update_requested() {
+    write_file(&paths.baseline, &png).await?;
+    remove_stale(&paths).await?;
+    return Ok(ScreenshotComparison {
+      baseline_path: paths.baseline,
+      different_blocks: 0,
+      total_blocks: 0,
+      similarity: 1.0,
+      updated: true,
+    });
+  }
+
+  let baseline = match tokio::fs::read(&paths.baseline).await {
+    Ok(baseline) => baseline,
+    Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+      write_file(&paths.actual, &png).await?;
+      return Err(Error::MissingScreenshotBaseline(paths.baseline));
+    }
+    Err(error) => return Err(error.into()),
+  };
+  let expected = decode_png(&baseline)?;
+  let actual = decode_png(&png)?;
+  if (expected.width, expected.height) != (actual.width, actual.height) {
+    write_file(&paths.actual, &png).await?;
+    return Err(Error::ScreenshotMismatch {
+      name: name.to_string(),
+      message: format!(
+        "baseline is {}x{} but the screenshot is {}x{}; actual written to {}",
+        expected.width,
+        expected.height,
+        actual.width,
+        actual.height,
+        paths.actual.display()
+      ),
+    });
+  }
+
+  let diff = diff_rgba_blocks(
+    &expected.rgba,
+    &actual.rgba,
+    actual.width,
+    actual.height,
+    &BlockDiffOptions {
+      pixel_tolerance: options.pixel_tolerance,
+      block_size: options.block_size,
+      block_threshold: options.block_threshold,
+    },
+  )
+  .map_err(|error| Error::ScreenshotMismatch {
+    name: name.to_string(),
+    message: error.to_string(),
+  })?;
+  let comparison = ScreenshotComparison {
+    baseline_path: paths.baseline.clone(),
+    different_blocks: diff.different_blocks,
+    total_blocks: diff.total_blocks,
+    similarity: diff.similarity(),
+    updated: false,
+  };
+  if diff.different_blocks <= options.max_different_blocks {
+    remove_stale(&paths).await?;
+    return Ok(comparison);
+  }
+
+  let diff_png = encode_png_async(actual.width, actual.height, Arc::from(diff.diff_rgba)).await?;
+  write_file(&paths.actual, &png).await?;
+  write_file(&paths.diff, &diff_png).await?;
+  Err(Error::ScreenshotMismatch {
+    name: name.to_string(),
+    message: format!(
+      "{} of {} blocks differ (allowed {}); diff written to {}, rerun with {UPDATE_SCREENSHOTS_ENV}=1 to accept",
+      diff.different_blocks,
+      diff.total_blocks,
+      options.max_different_blocks,
+      paths.diff.display()
+    ),
+  })
+}
+
+fn update_requested() -> bool {
+  std::env::var(UPDATE_SCREENSHOTS_ENV)
+    .map(|value| !matches!(value.as_str(), "" | "0" | "false"))
+    .unwrap_or(false)
+}
+
+struct SnapshotPaths {
+  baseline: PathBuf,
+  actual: PathBuf,
+  diff: PathBuf,
+}
+
+impl SnapshotPaths {
+  fn new(dir: &Path, name: &str) -> Result<Self> {
+    let stem = name.strip_suffix(".png").unwrap_or(name);
+    let relative = Path::new(stem);
+    if stem.is_empty()
+      || !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+      return Err(Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("screenshot name must be a relative path without `..`: {name}"),
+      )));
+    }
+    Ok(Self {
+      baseline: dir.join(format!("{stem}.png")),
+      actual: dir.join(format!("{stem}.actual.png")),
+      diff: dir.join(format!("{stem}.diff.png")),
+    })
+  }
+}
+
+async fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+  if let Some(parent) = path.parent() {
+    tokio::fs::create_dir_all(parent).await?;
+  }
+  tokio::fs::write(path, bytes).await?;
+  Ok(())
+}
+
+async fn remove_stale(paths: &SnapshotPaths) -> Result<()> {
+  for path in [&paths.actual, &paths.diff] {
+    match tokio::fs::remove_file(path).await {
+      Ok(()) => {}
+      Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+      Err(error) => return Err(error.into()),
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn snapshot_names_stay_inside_the_snapshot_dir() {
+    let paths = SnapshotPaths::new(Path::new("shots"), "home/hero.png").unwrap();
+    assert_eq!(paths.baseline, Path::new("shots/home/hero.png"));
+    assert_eq!(paths.actual, Path::new("shots/home/hero.actual.png"));
+    assert_eq!(paths.diff, Path::new("shots/home/hero.diff.png"));
+    for name in ["", "../escape", "/abs", "./hero"] {
+      assert!(
+        SnapshotPaths::new(Path::new("shots"), name).is_err(),
+        "{name}"
+      );
+    }
+  }
+
+  #[tokio::test(flavor = "current_thread")]
+  async fn mismatches_write_actual_and_diff_images() {
+    let dir = std::env::temp_dir().join(format!("lynx-snapshot-test-{}", std::process::id()));
+    let options = ExpectScreenshotOptions {
+      snapshot_dir: Some(dir.clone()),
+      block_size: 2,
+      ..ExpectScreenshotOptions::default()
+    };
+    let black = encode_png_async(2, 2, Arc::from([0, 0, 0, 255].repeat(4)))
+      .await
+      .unwrap();
+    let white = encode_png_async(2, 2, Arc::from([255; 16])).await.unwrap();
+
+    let missing = expect_png("case", black.clone(), &options).await;
+    assert!(matches!(missing, Err(Error::MissingScreenshotBaseline(_))));
+    std::fs::rename(dir.join("case.actual.png"), dir.join("case.png")).unwrap();
+
+    let matched = expect_png("case", black, &options).await.unwrap();
+    assert_eq!((matched.different_blocks, matched.total_blocks), (0, 1));
+    assert!(!matched.updated);
+
+    let mismatch = expect_png("case", white, &options).await;
+    assert!(matches!(mismatch, Err(Error::ScreenshotMismatch { .. })));
+    assert!(dir.join("case.actual.png").is_file());
+    assert!(dir.join("case.diff.png").is_file());
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
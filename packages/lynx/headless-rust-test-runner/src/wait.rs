@@ -0,0 +1,281 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::{ElementNode, Error, Page, Result};
+
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The condition [`Page::wait_for_selector`] waits for.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitForState {
+  /// The selector matches a node.
+  #[default]
+  Attached,
+  /// The selector matches no node.
+  Detached,
+  /// The selector matches a node with a non-empty box that is neither
+  /// `visibility: hidden` nor `display: none`.
+  Visible,
+  /// The selector matches no node, or only an invisible one.
+  Hidden,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WaitForSelectorOptions {
+  pub state: WaitForState,
+  /// Defaults to [`crate::ConnectOptions::timeout`].
+  pub timeout: Option<Duration>,
+}
+
+/// `None` means the timeout is too large to represent and never expires.
+pub(crate) struct Deadline {
+  at: Option<Instant>,
+}
+
+impl Deadline {
+  pub(crate) fn after(timeout: Duration) -> Self {
+    Self {
+      at: Instant::now().checked_add(timeout),
+    }
+  }
+
+  pub(crate) fn expired(&self) -> bool {
+    self.at.is_some_and(|at| Instant::now() >= at)
+  }
+}
+
+pub(crate) async fn is_visible(node: &ElementNode) -> Result<bool> {
+  let Ok(bounds) = node.bounding_box().await else {
+    return Ok(false);
+  };
+  if bounds.width <= 0.0 || bounds.height <= 0.0 {
+    return Ok(false);
+  }
+  let styles = node.computed_style_map().await?;
+  Ok(
+    styles.get("visibility").map(String::as_str) != Some("hidden")
+      && styles.get("display").map(String::as_str) != Some("none"),
+  )
+}
+
+/// Auto-retrying assertions about the node a selector matches, created by
+/// [`Page::expect`].
+///
+/// Each assertion re-runs the selector and re-reads the node until it holds
+/// or the timeout expires, so it tolerates nodes that are re-created or
+/// updated asynchronously.
+pub struct LocatorAssertions<'a> {
+  page: &'a mut Page,
+  selector: String,
+  timeout: Duration,
+}
+
+#[derive(Clone, Copy)]
+enum Expectation<'e> {
+  Attribute {
+    name: &'e str,
+    equals: Option<&'e str>,
+    contains: Option<&'e str>,
+  },
+  Style {
+    property: &'e str,
+    equals: &'e str,
+  },
+  Visible,
+}
+
+impl<'a> LocatorAssertions<'a> {
+  pub(crate) fn new(page: &'a mut Page, selector: &str, timeout: Duration) -> Self {
+    Self {
+      page,
+      selector: selector.to_string(),
+      timeout,
+    }
+  }
+
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  pub async fn to_have_attribute(self, name: &str, value: &str) -> Result<ElementNode> {
+    self.attribute_matches(name, Some(value), None).await
+  }
+
+  pub async fn to_have_attribute_containing(self, name: &str, part: &str) -> Result<ElementNode> {
+    self.attribute_matches(name, None, Some(part)).await
+  }
+
+  pub async fn to_have_style(self, property: &str, value: &str) -> Result<ElementNode> {
+    self
+      .wait_for(Expectation::Style {
+        property,
+        equals: value,
+      })
+      .await
+  }
+
+  pub async fn to_be_visible(self) -> Result<ElementNode> {
+    self.wait_for(Expectation::Visible).await
+  }
+
+  /// Without `equals` or `contains`, only requires the attribute to exist.
+  pub(crate) async fn attribute_matches(
+    self,
+    name: &str,
+    equals: Option<&str>,
+    contains: Option<&str>,
+  ) -> Result<ElementNode> {
+    self
+      .wait_for(Expectation::Attribute {
+        name,
+        equals,
+        contains,
+      })
+      .await
+  }
+
+  async fn wait_for(self, expectation: Expectation<'_>) -> Result<ElementNode> {
+    let deadline = Deadline::after(self.timeout);
+    loop {
+      let mismatch = match self.page.locator(&self.selector).await? {
+        Some(node) => match check(&node, expectation).await? {
+          Ok(()) => return Ok(node),
+          Err(mismatch) => mismatch,
+        },
+        None => format!("selector did not match a node: {}", self.selector),
+      };
+      if deadline.expired() {
+        return Err(Error::Assertion(format!(
+          "{} did not hold within {} ms: {mismatch}",
+          self.selector,
+          self.timeout.as_millis()
+        )));
+      }
+      self.page.wait_for_timeout(POLL_INTERVAL).await;
+    }
+  }
+}
+
+async fn check(
+  node: &ElementNode,
+  expectation: Expectation<'_>,
+) -> Result<std::result::Result<(), String>> {
+  Ok(match expectation {
+    Expectation::Attribute {
+      name,
+      equals,
+      contains,
+    } => check_attribute(
+      name,
+      equals,
+      contains,
+      node.get_attribute(name).await?.as_deref(),
+    ),
+    Expectation::Style { property, equals } => {
+      let styles = node.computed_style_map().await?;
+      check_style(property, equals, styles.get(property).map(String::as_str))
+    }
+    Expectation::Visible => {
+      if is_visible(node).await? {
+        Ok(())
+      } else {
+        Err("node is not visible".to_string())
+      }
+    }
+  })
+}
+
+pub(crate) fn check_attribute(
+  name: &str,
+  equals: Option<&str>,
+  contains: Option<&str>,
+  actual: Option<&str>,
+) -> std::result::Result<(), String> {
+  let Some(value) = actual else {
+    return Err(format!("attribute {name} is missing"));
+  };
+  if let Some(expected) = equals {
+    if value != expected {
+      return Err(format!(
+        "expected attribute {name} to be {expected:?}, got {value:?}"
+      ));
+    }
+  }
+  if let Some(expected) = contains {
+    if !value.contains(expected) {
+      return Err(format!(
+        "expected attribute {name} to contain {expected:?}, got {value:?}"
+      ));
+    }
+  }
+  Ok(())
+}
+
+pub(crate) fn check_style(
+  property: &str,
+  expected: &str,
+  actual: Option<&str>,
+) -> std::result::Result<(), String> {
+  match actual {
+    Some(value) if value == expected => Ok(()),
+    Some(value) => Err(format!(
+      "expected {property} to be {expected:?}, got {value:?}"
+    )),
+    None => Err(format!(
+      "expected {property} to be {expected:?}, got nothing"
+    )),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn attribute_checks_explain_mismatches() {
+    assert_eq!(
+      check_attribute("class", Some("Title"), None, Some("Title")),
+      Ok(())
+    );
+    assert_eq!(
+      check_attribute("class", Some("Title"), None, Some("Other")),
+      Err(r#"expected attribute class to be "Title", got "Other""#.into())
+    );
+    assert_eq!(
+      check_attribute("class", None, Some("card"), Some("card active")),
+      Ok(())
+    );
+    assert_eq!(
+      check_attribute("class", None, Some("hidden"), Some("card")),
+      Err(r#"expected attribute class to contain "hidden", got "card""#.into())
+    );
+    assert_eq!(check_attribute("id", None, None, Some("")), Ok(()));
+    assert_eq!(
+      check_attribute("id", None, None, None),
+      Err("attribute id is missing".into())
+    );
+  }
+
+  #[test]
+  fn style_checks_explain_mismatches() {
+    assert_eq!(check_style("color", "red", Some("red")), Ok(()));
+    assert_eq!(
+      check_style("color", "red", Some("blue")),
+      Err(r#"expected color to be "red", got "blue""#.into())
+    );
+    assert_eq!(
+      check_style("color", "red", None),
+      Err(r#"expected color to be "red", got nothing"#.into())
+    );
+  }
+
+  #[test]
+  fn deadlines_expire() {
+    assert!(Deadline::after(Duration::ZERO).expired());
+    assert!(!Deadline::after(Duration::from_secs(60)).expired());
+    assert!(!Deadline::after(Duration::MAX).expired());
+  }
+}
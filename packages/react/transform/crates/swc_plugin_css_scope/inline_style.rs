@@ -0,0 +1,265 @@
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use swc_core::{
+  common::DUMMY_SP,
+  ecma::{
+    ast::*,
+    visit::{VisitMut, VisitMutWith},
+  },
+};
+use swc_plugins_shared::{css::get_string_inline_style_from_literal, jsx_helpers::jsx_is_custom};
+
+const DEFAULT_CLASS_NAME_PREFIX: &str = "lynx-inline-";
+const CLASS_NAME_HASH_HEX_LEN: usize = 8;
+
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StaticInlineStyleVisitorConfig {
+  /// The prefix of the generated class names. Defaults to `lynx-inline-`.
+  pub class_name_prefix: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtractedStyleRule {
+  pub class_name: String,
+  /// The declarations of the rule, as `__SetInlineStyles` would receive them.
+  pub declarations: String,
+}
+
+/*
+ * The classes generated for the static inline styles of a module.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtractedStyleSheet {
+  /// The `@jsxCSSId` of the module, the rules belong to that CSS scope.
+  /// Set by the `CSSScopeVisitor` sharing the collector.
+  pub css_id: Option<u32>,
+  /// One rule per distinct style, in source order.
+  pub rules: Vec<ExtractedStyleRule>,
+}
+
+impl ExtractedStyleSheet {
+  pub fn to_css(&self) -> String {
+    self
+      .rules
+      .iter()
+      .map(|rule| format!(".{}{{{}}}\n", rule.class_name, rule.declarations))
+      .collect()
+  }
+}
+
+pub type ExtractedStylesCollector = Rc<RefCell<ExtractedStyleSheet>>;
+
+/*
+ * Replaces the static `style={{...}}` objects of builtin elements with content-hashed
+ * classes, collected into an `ExtractedStyleSheet`.
+ *
+ * Only objects whose values are all strings are extracted: a unitless number has no
+ * meaning in a stylesheet. Elements with a spread, any class or an id are left alone:
+ * an inline style wins over the rules selecting them, an extracted one would not.
+ * Rules selecting an element otherwise, like `.parent text`, still beat the class.
+ *
+ * The CSS scope of the rules is recorded by a `CSSScopeVisitor` given the same
+ * collector with `with_extracted_styles_collector`.
+ */
+pub struct StaticInlineStyleVisitor {
+  cfg: StaticInlineStyleVisitorConfig,
+  collector: ExtractedStylesCollector,
+  class_names: HashSet<String>,
+}
+
+impl StaticInlineStyleVisitor {
+  pub fn new(cfg: StaticInlineStyleVisitorConfig, collector: ExtractedStylesCollector) -> Self {
+    StaticInlineStyleVisitor {
+      cfg,
+      collector,
+      class_names: HashSet::new(),
+    }
+  }
+
+  fn class_name(&self, declarations: &str) -> String {
+    let hash = hex::encode(Sha1::digest(declarations.as_bytes()));
+    format!(
+      "{}{}",
+      self
+        .cfg
+        .class_name_prefix
+        .as_deref()
+        .unwrap_or(DEFAULT_CLASS_NAME_PREFIX),
+      &hash[..CLASS_NAME_HASH_HEX_LEN]
+    )
+  }
+
+  fn extract(&mut self, n: &mut JSXOpeningElement) {
+    let mut style_index = None;
+    for (index, attr) in n.attrs.iter().enumerate() {
+      let JSXAttrOrSpread::JSXAttr(JSXAttr {
+        name: JSXAttrName::Ident(name),
+        ..
+      }) = attr
+      else {
+        // a spread may set `style` or `className` at runtime
+        return;
+      };
+      match name.sym.as_ref() {
+        "style" => style_index = Some(index),
+        // the inline style must keep winning over the rules selecting the
+        // element by its classes or id, which a class rule cannot guarantee
+        "class" | "className" | "id" => return,
+        _ => {}
+      }
+    }
+
+    let Some(style_index) = style_index else {
+      return;
+    };
+    let Some(declarations) = static_style_declarations(&n.attrs[style_index]) else {
+      return;
+    };
+
+    let class_name = self.class_name(&declarations);
+    if self.class_names.insert(class_name.clone()) {
+      self.collector.borrow_mut().rules.push(ExtractedStyleRule {
+        class_name: class_name.clone(),
+        declarations,
+      });
+    }
+
+    n.attrs[style_index] = JSXAttrOrSpread::JSXAttr(JSXAttr {
+      span: DUMMY_SP,
+      name: JSXAttrName::Ident(IdentName::new("className".into(), DUMMY_SP)),
+      value: Some(JSXAttrValue::Str(class_name.into())),
+    });
+  }
+}
+
+fn static_style_declarations(attr: &JSXAttrOrSpread) -> Option<String> {
+  let JSXAttrOrSpread::JSXAttr(JSXAttr {
+    value:
+      Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+        expr: JSXExpr::Expr(expr),
+        span,
+      })),
+    ..
+  }) = attr
+  else {
+    return None;
+  };
+  let Expr::Object(object) = &**expr else {
+    return None;
+  };
+  let is_static = !object.props.is_empty()
+    && object.props.iter().all(|prop| match prop {
+      PropOrSpread::Prop(prop) => matches!(
+        &**prop,
+        Prop::KeyValue(KeyValueProp { key: PropName::Ident(_) | PropName::Str(_), value })
+          if matches!(&**value, Expr::Lit(Lit::Str(_)))
+      ),
+      PropOrSpread::Spread(_) => false,
+    });
+  if !is_static {
+    return None;
+  }
+  get_string_inline_style_from_literal(expr, span).filter(|declarations| !declarations.is_empty())
+}
+
+impl VisitMut for StaticInlineStyleVisitor {
+  fn visit_mut_jsx_element(&mut self, n: &mut JSXElement) {
+    n.visit_mut_children_with(self);
+    if !jsx_is_custom(n) {
+      self.extract(&mut n.opening);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use swc_core::ecma::{
+    parser::{EsSyntax, Syntax},
+    transforms::testing::{test, Tester},
+    visit::visit_mut_pass,
+  };
+
+  use super::{ExtractedStyleSheet, StaticInlineStyleVisitor};
+  use crate::{CSSScope, CSSScopeVisitor, CSSScopeVisitorConfig};
+
+  test!(
+    module,
+    Syntax::Es(EsSyntax {
+      jsx: true,
+      ..Default::default()
+    }),
+    |_| visit_mut_pass(StaticInlineStyleVisitor::new(
+      Default::default(),
+      Default::default(),
+    )),
+    should_extract_static_inline_styles,
+    r#"
+    <view style={{ backgroundColor: "red", width: "100px" }}>
+      <text className="title" style={{ backgroundColor: "red", width: "100px" }} />
+      <text class={"a b"} style={{ color: "blue" }} />
+      <text id="x" style={{ color: "blue" }} />
+      <text style={{ width: 100 }} />
+      <text className={cls} style={{ color: "blue" }} />
+      <text {...props} style={{ color: "blue" }} />
+      <text style={{ color }} />
+      <Foo style={{ color: "blue" }} />
+    </view>
+    "#
+  );
+
+  #[test]
+  fn should_collect_one_rule_per_style_in_the_css_scope_of_the_module() {
+    Tester::run(|tester| {
+      let collector = Rc::new(RefCell::new(ExtractedStyleSheet::default()));
+      let css_scope = CSSScopeVisitor::new(
+        CSSScopeVisitorConfig {
+          mode: CSSScope::All,
+          filename: "input.js".into(),
+        },
+        Some(tester.comments.clone()),
+      )
+      .with_extracted_styles_collector(collector.clone());
+      let css_id = css_scope.css_id;
+      tester.apply_transform(
+        (
+          visit_mut_pass(css_scope),
+          visit_mut_pass(StaticInlineStyleVisitor::new(
+            Default::default(),
+            collector.clone(),
+          )),
+        ),
+        "input.js",
+        Syntax::Es(EsSyntax {
+          jsx: true,
+          ..Default::default()
+        }),
+        Some(true),
+        r#"import "./input.css";
+        const a = <view style={{ backgroundColor: "red" }} />;
+        const b = <image style={{ backgroundColor: "red" }} />;
+        const c = <text style={{ color: "blue" }} />;
+        "#,
+      )?;
+
+      let sheet = collector.borrow();
+      assert_eq!(sheet.css_id, Some(css_id as u32));
+      assert_eq!(sheet.rules.len(), 2);
+      assert_eq!(sheet.rules[0].declarations, "background-color:red");
+      assert!(sheet.rules[0].class_name.starts_with("lynx-inline-"));
+      assert_eq!(
+        sheet.to_css(),
+        format!(
+          ".{}{{background-color:red}}\n.{}{{color:blue}}\n",
+          sheet.rules[0].class_name, sheet.rules[1].class_name
+        )
+      );
+
+      Ok(())
+    });
+  }
+}
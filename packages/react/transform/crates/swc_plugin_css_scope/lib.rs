@@ -12,9 +12,15 @@ use swc_core::{
 };
 use swc_plugins_shared::utils::calc_hash;
 
+mod inline_style;
 #[cfg(feature = "napi")]
 pub mod napi;
 
+pub use inline_style::{
+  ExtractedStyleRule, ExtractedStyleSheet, ExtractedStylesCollector, StaticInlineStyleVisitor,
+  StaticInlineStyleVisitorConfig,
+};
+
 /// CSSScope refers to the
 ///
 /// - `CSSScope::All`: Similar to setting `enableRemoveCSSScope: false`. All CSS files are treated as scoped CSS.
@@ -80,6 +86,8 @@ where
   css_id: usize,
 
   has_jsx: bool,
+
+  extracted_styles_collector: Option<ExtractedStylesCollector>,
 }
 
 impl<C> CSSScopeVisitor<C>
@@ -96,8 +104,15 @@ where
       comments,
       cfg,
       has_jsx: false,
+      extracted_styles_collector: None,
     }
   }
+
+  /// Records the `@jsxCSSId` of the module in the stylesheet of its extracted inline styles.
+  pub fn with_extracted_styles_collector(mut self, collector: ExtractedStylesCollector) -> Self {
+    self.extracted_styles_collector = Some(collector);
+    self
+  }
 }
 
 impl<C> VisitMut for CSSScopeVisitor<C>
@@ -175,6 +190,9 @@ where
           text: format!("@jsxCSSId {}", self.css_id).into(),
         },
       );
+      if let Some(collector) = &self.extracted_styles_collector {
+        collector.borrow_mut().css_id = Some(self.css_id as u32);
+      }
     }
   }
 }
@@ -1,5 +1,8 @@
 use crate::{
   CSSScope as CoreCSSScope, CSSScopeVisitor as CoreVisitor, CSSScopeVisitorConfig as CoreConfig,
+  ExtractedStyleSheet as CoreExtractedStyleSheet, ExtractedStylesCollector,
+  StaticInlineStyleVisitor as CoreStaticInlineStyleVisitor,
+  StaticInlineStyleVisitorConfig as CoreStaticInlineStyleVisitorConfig,
 };
 use napi_derive::napi;
 use swc_core::{
@@ -128,6 +131,11 @@ where
       inner: CoreVisitor::new(cfg.into(), comments),
     }
   }
+
+  pub fn with_extracted_styles_collector(mut self, collector: ExtractedStylesCollector) -> Self {
+    self.inner = self.inner.with_extracted_styles_collector(collector);
+    self
+  }
 }
 
 impl<C> VisitMut for CSSScopeVisitor<C>
@@ -142,3 +150,67 @@ where
     self.inner.visit_mut_module(n);
   }
 }
+
+/// @internal
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct StaticInlineStyleVisitorConfig {
+  /// The prefix of the generated class names.
+  ///
+  /// @defaultValue `'lynx-inline-'`
+  pub class_name_prefix: Option<String>,
+}
+
+impl From<StaticInlineStyleVisitorConfig> for CoreStaticInlineStyleVisitorConfig {
+  fn from(val: StaticInlineStyleVisitorConfig) -> Self {
+    CoreStaticInlineStyleVisitorConfig {
+      class_name_prefix: val.class_name_prefix,
+    }
+  }
+}
+
+impl From<CoreStaticInlineStyleVisitorConfig> for StaticInlineStyleVisitorConfig {
+  fn from(val: CoreStaticInlineStyleVisitorConfig) -> Self {
+    StaticInlineStyleVisitorConfig {
+      class_name_prefix: val.class_name_prefix,
+    }
+  }
+}
+
+/// The stylesheet of the classes that replaced static inline styles.
+/// @internal
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct ExtractedStyleSheet {
+  /// The CSS scope of the rules, the `@jsxCSSId` of the module if it is scoped.
+  #[napi(js_name = "cssId")]
+  pub css_id: Option<u32>,
+  pub code: String,
+}
+
+impl From<CoreExtractedStyleSheet> for ExtractedStyleSheet {
+  fn from(val: CoreExtractedStyleSheet) -> Self {
+    ExtractedStyleSheet {
+      css_id: val.css_id,
+      code: val.to_css(),
+    }
+  }
+}
+
+pub struct StaticInlineStyleVisitor {
+  inner: CoreStaticInlineStyleVisitor,
+}
+
+impl StaticInlineStyleVisitor {
+  pub fn new(cfg: StaticInlineStyleVisitorConfig, collector: ExtractedStylesCollector) -> Self {
+    Self {
+      inner: CoreStaticInlineStyleVisitor::new(cfg.into(), collector),
+    }
+  }
+}
+
+impl VisitMut for StaticInlineStyleVisitor {
+  fn visit_mut_module(&mut self, n: &mut Module) {
+    self.inner.visit_mut_module(n);
+  }
+}
@@ -0,0 +1,27 @@
+<view className="lynx-inline-a8918f18">
+      <text className="title" style={{
+    backgroundColor: "red",
+    width: "100px"
+}}/>
+      <text class={"a b"} style={{
+    color: "blue"
+}}/>
+      <text id="x" style={{
+    color: "blue"
+}}/>
+      <text style={{
+    width: 100
+}}/>
+      <text className={cls} style={{
+    color: "blue"
+}}/>
+      <text {...props} style={{
+    color: "blue"
+}}/>
+      <text style={{
+    color
+}}/>
+      <Foo style={{
+    color: "blue"
+}}/>
+    </view>;
@@ -1,6 +1,7 @@
 use serde::Serialize;
 use swc_core::common::comments::Comments;
 
+use super::compiled_template::{TemplateAttribute, TemplateNode, COMPILED_TEMPLATE_SCHEMA_VERSION};
 use super::JSXTransformer;
 
 #[derive(Serialize, Debug, Clone)]
@@ -9,7 +10,9 @@ pub struct ElementTemplateAsset {
   // output keeps using a synthetic component tag so existing React/SWC passes can
   // continue to own expression lowering and runtime value transport.
   pub template_id: String,
-  pub compiled_template: serde_json::Value,
+  /// The `COMPILED_TEMPLATE_SCHEMA_VERSION` that `compiled_template` follows.
+  pub schema_version: u32,
+  pub compiled_template: TemplateNode,
   pub source_file: String,
 }
 
@@ -23,18 +26,15 @@ where
   fn builtin_raw_text_template_asset(&self) -> ElementTemplateAsset {
     ElementTemplateAsset {
       template_id: BUILTIN_RAW_TEXT_TEMPLATE_ID.to_string(),
-      compiled_template: serde_json::json!({
-        "kind": "element",
-        "type": "raw-text",
-        "attributesArray": [
-          {
-            "kind": "slot",
-            "key": "text",
-            "attrSlotIndex": 0,
-          }
-        ],
-        "children": [],
-      }),
+      schema_version: COMPILED_TEMPLATE_SCHEMA_VERSION,
+      compiled_template: TemplateNode::Element {
+        tag: "raw-text".to_string(),
+        attributes_array: vec![TemplateAttribute::Slot {
+          key: "text".to_string(),
+          attr_slot_index: 0,
+        }],
+        children: vec![],
+      },
       source_file: BUILTIN_SOURCE_FILE.to_string(),
     }
   }
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+
+/// The version of the compiled template shape, bumped on breaking changes.
+pub const COMPILED_TEMPLATE_SCHEMA_VERSION: u32 = 1;
+
+/// The JSON Schema of a compiled template, published as
+/// `@lynx-js/react-transform/compiled-template.schema.json`.
+pub const COMPILED_TEMPLATE_JSON_SCHEMA: &str = include_str!("../../compiled-template.schema.json");
+
+/*
+ * A node of a compiled template. The root of a template is always an `element`.
+ * Slots are filled at runtime with the `$N` children of the template element.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(
+  tag = "kind",
+  rename_all = "camelCase",
+  rename_all_fields = "camelCase"
+)]
+pub enum TemplateNode {
+  Element {
+    #[serde(rename = "type")]
+    tag: String,
+    attributes_array: Vec<TemplateAttribute>,
+    children: Vec<TemplateNode>,
+  },
+  /// Dynamic children. `slot_kind` tells what fills the slot when it is known at
+  /// compile time, older consumers can ignore it and treat every slot alike.
+  ElementSlot {
+    #[serde(rename = "type")]
+    slot_type: SlotType,
+    element_slot_index: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slot_kind: Option<SlotKind>,
+    /// The templates of the items when `slot_kind` is `list`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    item_template_ids: Option<Vec<String>>,
+    /// The templates a `conditional` slot renders, one at a time or none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    branch_template_ids: Option<Vec<String>>,
+  },
+}
+
+impl TemplateNode {
+  /// Adds the slot hints of `other`, a template with the same identity, so that
+  /// a deduplicated template lists the templates of all its occurrences.
+  pub(super) fn merge_slot_hints(&mut self, other: &TemplateNode) {
+    match (self, other) {
+      (
+        TemplateNode::Element { children, .. },
+        TemplateNode::Element {
+          children: other_children,
+          ..
+        },
+      ) => {
+        for (child, other_child) in children.iter_mut().zip(other_children) {
+          child.merge_slot_hints(other_child);
+        }
+      }
+      (
+        TemplateNode::ElementSlot {
+          slot_kind,
+          item_template_ids,
+          branch_template_ids,
+          ..
+        },
+        TemplateNode::ElementSlot {
+          slot_kind: other_slot_kind,
+          item_template_ids: other_item_template_ids,
+          branch_template_ids: other_branch_template_ids,
+          ..
+        },
+      ) => {
+        if slot_kind != other_slot_kind {
+          // the occurrences disagree, so nothing is known about the slot
+          *slot_kind = None;
+          *item_template_ids = None;
+          *branch_template_ids = None;
+          return;
+        }
+        merge_template_ids(item_template_ids, other_item_template_ids);
+        merge_template_ids(branch_template_ids, other_branch_template_ids);
+      }
+      _ => {}
+    }
+  }
+}
+
+fn merge_template_ids(template_ids: &mut Option<Vec<String>>, other: &Option<Vec<String>>) {
+  if let (Some(template_ids), Some(other)) = (template_ids, other) {
+    for template_id in other {
+      if !template_ids.contains(template_id) {
+        template_ids.push(template_id.clone());
+      }
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SlotKind {
+  /// The children of a `<list>`, instances of `item_template_ids`.
+  List,
+  /// A ternary, a logical expression or a keyed subtree.
+  Conditional,
+}
+
+/// Every slot is created as a `slot` element, whatever fills it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlotType {
+  #[default]
+  #[serde(rename = "slot")]
+  Slot,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(
+  tag = "kind",
+  rename_all = "camelCase",
+  rename_all_fields = "camelCase"
+)]
+pub enum TemplateAttribute {
+  Static {
+    key: String,
+    value: StaticAttributeValue,
+  },
+  Slot {
+    key: String,
+    attr_slot_index: u32,
+  },
+  Spread {
+    attr_slot_index: u32,
+  },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum StaticAttributeValue {
+  Bool(bool),
+  Number(f64),
+  String(String),
+  Null,
+}
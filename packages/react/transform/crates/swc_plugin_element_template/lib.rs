@@ -19,6 +19,7 @@ use swc_core::{
 
 mod asset;
 mod attr_name;
+mod compiled_template;
 mod extractor;
 mod lowering;
 mod template_attribute;
@@ -28,13 +29,17 @@ mod template_slot;
 
 pub use self::asset::ElementTemplateAsset;
 use self::attr_name::AttrName;
+pub use self::compiled_template::{
+  SlotKind, SlotType, StaticAttributeValue, TemplateAttribute, TemplateNode,
+  COMPILED_TEMPLATE_JSON_SCHEMA, COMPILED_TEMPLATE_SCHEMA_VERSION,
+};
 use self::extractor::{DynamicAttributePart, ElementTemplateExtractor, ExtractedTemplateParts};
 use self::lowering::LoweredRuntimeJsx;
 use self::template_attribute::template_attribute_descriptor_key;
 use self::template_identity::{
-  template_identity_from_compiled_template, TemplateIdentityCollisionGuard,
+  identity_value, template_identity_from_compiled_template, TemplateIdentityCollisionGuard,
 };
-use self::template_slot::ET_SLOT_PLACEHOLDER_TAG;
+use self::template_slot::{template_element_slots, ET_SLOT_PLACEHOLDER_TAG};
 
 pub type ElementTemplateTransformerConfig = JSXTransformerConfig;
 pub type ElementTemplateTransformer<C> = JSXTransformer<C>;
@@ -461,6 +466,7 @@ where
       })
       .collect::<Vec<_>>()
       .join("|");
+    let element_slots = template_element_slots(&dynamic_children);
 
     let LoweredRuntimeJsx {
       attrs: rendered_attrs,
@@ -480,22 +486,21 @@ where
     }
 
     let mut dynamic_attr_slot_cursor: usize = 0;
-    let mut element_slot_index: i32 = 0;
     // Attribute slot indices come from ElementTemplateExtractor so runtime
     // values and Template Definition descriptors share one compile-time source.
-    let template_expr = self.element_template_from_jsx_element(
+    let compiled_template = self.element_template_from_jsx_element(
       node,
       &dynamic_attr_slots,
       &mut dynamic_attr_slot_cursor,
-      &mut element_slot_index,
+      &element_slots,
     );
     assert_eq!(
       dynamic_attr_slot_cursor,
       dynamic_attr_slots.len(),
       "Template Definition must consume every ET attr slot produced by extractor"
     );
-    let compiled_template = self.element_template_to_json(&template_expr);
-    let template_identity = template_identity_from_compiled_template(&compiled_template);
+    let template_identity =
+      template_identity_from_compiled_template(&identity_value(&compiled_template));
     self
       .template_identity_collision_guard
       .register(&template_identity);
@@ -609,10 +614,19 @@ where
       if let Some(element_templates) = &self.element_templates {
         element_templates.borrow_mut().push(ElementTemplateAsset {
           template_id: template_uid.clone(),
+          schema_version: COMPILED_TEMPLATE_SCHEMA_VERSION,
           compiled_template,
           source_file: self.cfg.filename.clone(),
         });
       }
+    } else if let Some(element_templates) = &self.element_templates {
+      if let Some(asset) = element_templates
+        .borrow_mut()
+        .iter_mut()
+        .find(|asset| asset.template_id == template_uid)
+      {
+        asset.compiled_template.merge_slot_hints(&compiled_template);
+      }
     }
 
     let rendered_children_is_empty = rendered_children.is_empty();
@@ -20,6 +20,9 @@ pub struct ElementTemplateAsset {
   /// @internal
   #[napi(js_name = "templateId")]
   pub template_id: String,
+  /// The schema version of `compiledTemplate`, see `compiled-template.schema.json`.
+  #[napi(js_name = "schemaVersion")]
+  pub schema_version: u32,
   /// @internal
   #[napi(js_name = "compiledTemplate", ts_type = "unknown")]
   pub compiled_template: serde_json::Value,
@@ -32,7 +35,9 @@ impl From<CoreElementTemplateAsset> for ElementTemplateAsset {
   fn from(val: CoreElementTemplateAsset) -> Self {
     Self {
       template_id: val.template_id,
-      compiled_template: val.compiled_template,
+      schema_version: val.schema_version,
+      compiled_template: serde_json::to_value(val.compiled_template)
+        .expect("Template Definition must serialize"),
       source_file: val.source_file,
     }
   }
@@ -1,147 +1,107 @@
-use swc_core::{
-  common::{comments::Comments, DUMMY_SP},
-  ecma::ast::*,
-  quote,
-};
+use swc_core::{atoms::wtf8::Wtf8, common::comments::Comments, ecma::ast::*};
 use swc_plugins_shared::jsx_helpers::{jsx_name, jsx_text_to_str, transform_jsx_attr_str};
 
 use super::{
-  i32_to_expr,
+  compiled_template::{SlotKind, SlotType, StaticAttributeValue, TemplateAttribute, TemplateNode},
   template_attribute::{template_attribute_descriptor_key, TemplateAttributeSlot},
-  template_slot::{is_slot_placeholder, slot_placeholder_index},
+  template_slot::{is_slot_placeholder, slot_placeholder_index, TemplateElementSlot},
   JSXTransformer,
 };
 
+fn slot_index_to_u32(slot_index: i32) -> u32 {
+  u32::try_from(slot_index).expect("ET slot index must be non-negative")
+}
+
+fn wtf8_to_string(value: &Wtf8) -> String {
+  value.as_str().unwrap_or("").to_string()
+}
+
 impl<C> JSXTransformer<C>
 where
   C: Comments + Clone,
 {
-  // Template Definition owns ET's serialized tree/descriptor contract.
-  pub(super) fn element_template_to_json(&self, expr: &Expr) -> serde_json::Value {
-    // Build descriptors with SWC Expr helpers first so the golden tests can inspect
-    // the same shape as codegen. Serialization intentionally accepts only the
-    // literal/object/array subset that belongs in the native Template Definition.
-    match expr {
-      Expr::Lit(lit) => match lit {
-        Lit::Str(s) => serde_json::Value::String(s.value.as_str().unwrap_or("").to_string()),
-        Lit::Num(n) => serde_json::Number::from_f64(n.value)
-          .map(serde_json::Value::Number)
-          .unwrap_or(serde_json::Value::Null),
-        Lit::Bool(b) => serde_json::Value::Bool(b.value),
-        Lit::Null(_) => serde_json::Value::Null,
-        _ => serde_json::Value::Null,
-      },
-      Expr::Array(arr) => {
-        let elems: Vec<serde_json::Value> = arr
-          .elems
-          .iter()
-          .map(|elem| {
-            if let Some(elem) = elem {
-              self.element_template_to_json(&elem.expr)
-            } else {
-              serde_json::Value::Null
-            }
-          })
-          .collect();
-        serde_json::Value::Array(elems)
-      }
-      Expr::Object(obj) => {
-        let mut map = serde_json::Map::new();
-        for prop in &obj.props {
-          if let PropOrSpread::Prop(prop) = prop {
-            if let Prop::KeyValue(kv) = &**prop {
-              let key = match &kv.key {
-                PropName::Ident(ident) => ident.sym.as_str().to_string(),
-                PropName::Str(s) => s.value.as_str().unwrap_or("").to_string(),
-                _ => continue,
-              };
-              let value = self.element_template_to_json(&kv.value);
-              map.insert(key, value);
-            }
-          }
-        }
-        serde_json::Value::Object(map)
-      }
-      _ => serde_json::Value::Null,
+  // Template Definition owns ET's serialized tree/descriptor contract, see
+  // `compiled_template` for its schema.
+  fn element_template_static_attribute_descriptor(
+    &self,
+    key: &str,
+    value: StaticAttributeValue,
+  ) -> TemplateAttribute {
+    TemplateAttribute::Static {
+      key: key.to_string(),
+      value,
     }
   }
 
-  fn element_template_string_expr(&self, value: &str) -> Expr {
-    Expr::Lit(Lit::Str(Str {
-      span: DUMMY_SP,
-      raw: None,
-      value: value.into(),
-    }))
-  }
-
-  fn element_template_css_id_attribute_descriptor(&self, css_id: f64) -> Expr {
-    self.element_template_static_attribute_descriptor(
-      "css-id",
-      Expr::Lit(Lit::Num(Number {
-        span: DUMMY_SP,
-        value: css_id,
-        raw: None,
-      })),
-    )
+  fn element_template_css_id_attribute_descriptor(&self, css_id: f64) -> TemplateAttribute {
+    self
+      .element_template_static_attribute_descriptor("css-id", StaticAttributeValue::Number(css_id))
   }
 
-  fn element_template_array_expr(&self, items: Vec<Expr>) -> Expr {
-    Expr::Array(ArrayLit {
-      span: DUMMY_SP,
-      elems: items
-        .into_iter()
-        .map(|expr| {
-          Some(ExprOrSpread {
-            spread: None,
-            expr: Box::new(expr),
-          })
-        })
-        .collect(),
-    })
-  }
-
-  fn element_template_static_attribute_descriptor(&self, key: &str, value: Expr) -> Expr {
-    quote!(
-      r#"{ kind: "static", key: $key, value: $value }"# as Expr,
-      key: Expr = self.element_template_string_expr(key),
-      value: Expr = value,
-    )
-  }
-
-  fn element_template_attribute_slot_descriptor(&self, key: &str, attr_slot_index: i32) -> Expr {
-    quote!(
-      r#"{ kind: "slot", key: $key, attrSlotIndex: $attr_slot_index }"# as Expr,
-      key: Expr = self.element_template_string_expr(key),
-      attr_slot_index: Expr = i32_to_expr(&attr_slot_index),
-    )
+  fn element_template_attribute_slot_descriptor(
+    &self,
+    key: &str,
+    attr_slot_index: i32,
+  ) -> TemplateAttribute {
+    TemplateAttribute::Slot {
+      key: key.to_string(),
+      attr_slot_index: slot_index_to_u32(attr_slot_index),
+    }
   }
 
-  fn element_template_spread_slot_descriptor(&self, attr_slot_index: i32) -> Expr {
-    quote!(
-      r#"{ kind: "spread", attrSlotIndex: $attr_slot_index }"# as Expr,
-      attr_slot_index: Expr = i32_to_expr(&attr_slot_index),
-    )
+  fn element_template_spread_slot_descriptor(&self, attr_slot_index: i32) -> TemplateAttribute {
+    TemplateAttribute::Spread {
+      attr_slot_index: slot_index_to_u32(attr_slot_index),
+    }
   }
 
-  fn element_template_element_slot(&self, element_slot_index: i32) -> Expr {
-    quote!(
-      r#"{ kind: "elementSlot", type: "slot", elementSlotIndex: $element_slot_index }"# as Expr,
-      element_slot_index: Expr = i32_to_expr(&element_slot_index),
-    )
+  fn element_template_element_slot(
+    &self,
+    element_slot_index: i32,
+    element_slots: &[TemplateElementSlot],
+  ) -> TemplateNode {
+    let slot = element_slots
+      .get(slot_index_to_u32(element_slot_index) as usize)
+      .unwrap_or_else(|| {
+        panic!(
+          "Template Definition requested element slot {}, but extractor produced only {} slots",
+          element_slot_index,
+          element_slots.len()
+        )
+      });
+    let (slot_kind, item_template_ids, branch_template_ids) = match slot {
+      TemplateElementSlot::Element => (None, None, None),
+      TemplateElementSlot::List { item_template_ids } => {
+        (Some(SlotKind::List), Some(item_template_ids.clone()), None)
+      }
+      TemplateElementSlot::Conditional {
+        branch_template_ids,
+      } => (
+        Some(SlotKind::Conditional),
+        None,
+        Some(branch_template_ids.clone()),
+      ),
+    };
+    TemplateNode::ElementSlot {
+      slot_type: SlotType::Slot,
+      element_slot_index: slot_index_to_u32(element_slot_index),
+      slot_kind,
+      item_template_ids,
+      branch_template_ids,
+    }
   }
 
   fn element_template_element_node(
     &self,
     tag: &str,
-    attributes: Vec<Expr>,
-    children: Vec<Expr>,
-  ) -> Expr {
-    quote!(
-      r#"{ kind: "element", type: $tag, attributesArray: $attributes, children: $children }"# as Expr,
-      tag: Expr = self.element_template_string_expr(tag),
-      attributes: Expr = self.element_template_array_expr(attributes),
-      children: Expr = self.element_template_array_expr(children),
-    )
+    attributes: Vec<TemplateAttribute>,
+    children: Vec<TemplateNode>,
+  ) -> TemplateNode {
+    TemplateNode::Element {
+      tag: tag.to_string(),
+      attributes_array: attributes,
+      children,
+    }
   }
 
   fn next_dynamic_attribute_slot<'a>(
@@ -167,9 +127,9 @@ where
     children: &[JSXElementChild],
     dynamic_attr_slots: &[TemplateAttributeSlot],
     dynamic_attr_slot_cursor: &mut usize,
-    element_slot_index: &mut i32,
-  ) -> Vec<Expr> {
-    let mut out: Vec<Expr> = vec![];
+    element_slots: &[TemplateElementSlot],
+  ) -> Vec<TemplateNode> {
+    let mut out: Vec<TemplateNode> = vec![];
 
     for child in children {
       match child {
@@ -181,7 +141,7 @@ where
 
           let mut attributes = vec![self.element_template_static_attribute_descriptor(
             "text",
-            self.element_template_string_expr(s.as_ref()),
+            StaticAttributeValue::String(s.to_string()),
           )];
           if let Some(css_id) = self.css_id_value {
             attributes.push(self.element_template_css_id_attribute_descriptor(css_id));
@@ -193,14 +153,14 @@ where
           el,
           dynamic_attr_slots,
           dynamic_attr_slot_cursor,
-          element_slot_index,
+          element_slots,
         )),
         JSXElementChild::JSXFragment(frag) => {
           out.extend(self.element_template_from_jsx_children(
             &frag.children,
             dynamic_attr_slots,
             dynamic_attr_slot_cursor,
-            element_slot_index,
+            element_slots,
           ));
         }
         JSXElementChild::JSXExprContainer(JSXExprContainer {
@@ -227,12 +187,12 @@ where
     n: &JSXElement,
     dynamic_attr_slots: &[TemplateAttributeSlot],
     dynamic_attr_slot_cursor: &mut usize,
-    element_slot_index: &mut i32,
-  ) -> Expr {
+    element_slots: &[TemplateElementSlot],
+  ) -> TemplateNode {
     if is_slot_placeholder(n) {
       let idx =
         slot_placeholder_index(n).expect("ET slot placeholder should always carry a slot index");
-      return self.element_template_element_slot(idx);
+      return self.element_template_element_slot(idx, element_slots);
     }
 
     let tag_expr = jsx_name(n.opening.name.clone());
@@ -241,7 +201,7 @@ where
       _ => "".into(),
     };
 
-    let mut attribute_descriptors: Vec<Expr> = vec![];
+    let mut attribute_descriptors: Vec<TemplateAttribute> = vec![];
 
     for attr in &n.opening.attrs {
       match attr {
@@ -253,23 +213,22 @@ where
           }
 
           let static_value = match &attr.value {
-            None => Some(Expr::Lit(Lit::Bool(Bool {
-              span: DUMMY_SP,
-              value: true,
-            }))),
-            Some(JSXAttrValue::Str(s)) => Some(Expr::Lit(Lit::Str(Str {
-              span: s.span,
-              value: transform_jsx_attr_str(&s.value).into(),
-              raw: None,
-            }))),
+            None => Some(StaticAttributeValue::Bool(true)),
+            Some(JSXAttrValue::Str(s)) => Some(StaticAttributeValue::String(wtf8_to_string(
+              &transform_jsx_attr_str(&s.value),
+            ))),
             Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
               expr: JSXExpr::Expr(expr),
               ..
             })) => match &**expr {
-              Expr::Lit(Lit::Str(s)) => Some(Expr::Lit(Lit::Str(s.clone()))),
-              Expr::Lit(Lit::Num(n)) if n.value.is_finite() => Some(Expr::Lit(Lit::Num(n.clone()))),
-              Expr::Lit(Lit::Bool(b)) => Some(Expr::Lit(Lit::Bool(*b))),
-              Expr::Lit(Lit::Null(n)) => Some(Expr::Lit(Lit::Null(*n))),
+              Expr::Lit(Lit::Str(s)) => {
+                Some(StaticAttributeValue::String(wtf8_to_string(&s.value)))
+              }
+              Expr::Lit(Lit::Num(n)) if n.value.is_finite() => {
+                Some(StaticAttributeValue::Number(n.value))
+              }
+              Expr::Lit(Lit::Bool(b)) => Some(StaticAttributeValue::Bool(b.value)),
+              Expr::Lit(Lit::Null(_)) => Some(StaticAttributeValue::Null),
               // TODO: Support complex static values (Object, Array, Template Literal without expressions)
               // See ElementTemplate/Todo-StaticAttributesOpts.md
               _ => None,
@@ -347,7 +306,7 @@ where
           let s = jsx_text_to_str(&txt.value);
           attribute_descriptors.push(self.element_template_static_attribute_descriptor(
             "text",
-            self.element_template_string_expr(s.as_ref()),
+            StaticAttributeValue::String(s.to_string()),
           ));
           text_child_optimized = true;
         }
@@ -361,7 +320,7 @@ where
         &n.children,
         dynamic_attr_slots,
         dynamic_attr_slot_cursor,
-        element_slot_index,
+        element_slots,
       )
     };
 
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
 use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
-use serde_json::Value;
+use serde_json::{Number, Value};
 use sha2::{Digest, Sha256};
 
-const TEMPLATE_ID_PREFIX: &str = "_et_";
+use super::compiled_template::TemplateNode;
+
+pub(super) const TEMPLATE_ID_PREFIX: &str = "_et_";
 const TEMPLATE_ID_HASH_HEX_LEN: usize = 12;
 const TEMPLATE_ID_HASH_BYTES: usize = TEMPLATE_ID_HASH_HEX_LEN / 2;
 
@@ -24,6 +26,35 @@ pub(super) fn canonical_template_content(value: &Value) -> String {
     .expect("Template Definition canonical content must serialize")
 }
 
+// Slot hints are left out and slot indices keep the float encoding that the
+// first template ids were hashed with, so ids do not change with the schema.
+pub(super) fn identity_value(compiled_template: &TemplateNode) -> Value {
+  let mut value =
+    serde_json::to_value(compiled_template).expect("Template Definition must serialize");
+  strip_to_identity(&mut value);
+  value
+}
+
+fn strip_to_identity(value: &mut Value) {
+  match value {
+    Value::Object(map) => {
+      for hint in ["slotKind", "itemTemplateIds", "branchTemplateIds"] {
+        map.remove(hint);
+      }
+      for key in ["attrSlotIndex", "elementSlotIndex"] {
+        if let Some(Value::Number(index)) = map.get_mut(key) {
+          if let Some(float) = index.as_f64().and_then(Number::from_f64) {
+            *index = float;
+          }
+        }
+      }
+      map.values_mut().for_each(strip_to_identity);
+    }
+    Value::Array(items) => items.iter_mut().for_each(strip_to_identity),
+    _ => {}
+  }
+}
+
 pub(super) fn template_identity_from_compiled_template(value: &Value) -> TemplateIdentity {
   let canonical_content = canonical_template_content(value);
   let hash = Sha256::digest(canonical_content.as_bytes());
@@ -105,9 +136,10 @@ mod tests {
   use serde_json::json;
 
   use super::{
-    canonical_template_content, template_identity_from_compiled_template, TemplateIdentity,
-    TemplateIdentityCollisionGuard,
+    canonical_template_content, identity_value, template_identity_from_compiled_template,
+    TemplateIdentity, TemplateIdentityCollisionGuard,
   };
+  use crate::compiled_template::{SlotKind, SlotType, TemplateAttribute, TemplateNode};
 
   #[test]
   fn canonical_content_sorts_object_keys_and_preserves_array_order() {
@@ -143,6 +175,37 @@ mod tests {
     assert_eq!(identity.template_id, "_et_d3626ac30a87");
   }
 
+  #[test]
+  fn identity_hashes_slot_indices_as_floats_without_slot_hints() {
+    let slot = |slot_kind, branch_template_ids| TemplateNode::ElementSlot {
+      slot_type: SlotType::Slot,
+      element_slot_index: 0,
+      slot_kind,
+      item_template_ids: None,
+      branch_template_ids,
+    };
+    let template = |child| TemplateNode::Element {
+      tag: "view".to_string(),
+      attributes_array: vec![TemplateAttribute::Slot {
+        key: "id".to_string(),
+        attr_slot_index: 1,
+      }],
+      children: vec![child],
+    };
+
+    let plain = identity_value(&template(slot(None, None)));
+    let hinted = identity_value(&template(slot(
+      Some(SlotKind::Conditional),
+      Some(vec!["_et_a".to_string()]),
+    )));
+
+    assert_eq!(plain, hinted);
+    assert_eq!(
+      canonical_template_content(&plain),
+      r#"{"attributesArray":[{"attrSlotIndex":1.0,"key":"id","kind":"slot"}],"children":[{"elementSlotIndex":0.0,"kind":"elementSlot","type":"slot"}],"kind":"element","type":"view"}"#
+    );
+  }
+
   #[test]
   #[should_panic(expected = "ET Template Definition hash collision")]
   fn collision_guard_rejects_same_id_with_different_canonical_content() {
@@ -1,7 +1,13 @@
 use swc_core::{
   common::{SyntaxContext, DUMMY_SP},
-  ecma::ast::*,
+  ecma::{
+    ast::*,
+    visit::{Visit, VisitWith},
+  },
 };
+use swc_plugins_shared::jsx_helpers::jsx_has_dynamic_key;
+
+use super::{extractor::DynamicElementPart, template_identity::TEMPLATE_ID_PREFIX};
 
 pub(super) const ET_SLOT_PLACEHOLDER_TAG: &str = "__et_slot_placeholder";
 const ET_SLOT_PLACEHOLDER_INDEX_ATTR: &str = "__et_slot_index";
@@ -79,3 +85,79 @@ pub(super) fn slot_placeholder_index(n: &JSXElement) -> Option<i32> {
     Some(*value as i32)
   })
 }
+
+// What fills an element slot, as far as it is known at compile time.
+#[derive(Debug, Clone)]
+pub(super) enum TemplateElementSlot {
+  Element,
+  List { item_template_ids: Vec<String> },
+  Conditional { branch_template_ids: Vec<String> },
+}
+
+// The templates directly rendered by a slot. Slot content has already been
+// lowered, so templates show up as `<_et_xxx />` elements; the templates nested
+// in them belong to their own definitions.
+#[derive(Default)]
+struct SlotTemplateIds {
+  template_ids: Vec<String>,
+}
+
+impl Visit for SlotTemplateIds {
+  fn visit_jsx_element(&mut self, n: &JSXElement) {
+    if let JSXElementName::Ident(ident) = &n.opening.name {
+      if ident.sym.starts_with(TEMPLATE_ID_PREFIX) {
+        let template_id = ident.sym.to_string();
+        if !self.template_ids.contains(&template_id) {
+          self.template_ids.push(template_id);
+        }
+        return;
+      }
+    }
+    n.visit_children_with(self);
+  }
+}
+
+fn slot_template_ids(expr: &Expr) -> Vec<String> {
+  let mut collector = SlotTemplateIds::default();
+  expr.visit_with(&mut collector);
+  collector.template_ids
+}
+
+fn is_conditional_slot_content(expr: &Expr) -> bool {
+  match expr.unwrap_parens() {
+    Expr::Cond(_) => true,
+    Expr::Bin(BinExpr { op, .. }) => matches!(
+      op,
+      BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing
+    ),
+    // a new key remounts the subtree, like switching to another branch
+    Expr::JSXElement(n) => jsx_has_dynamic_key(n),
+    _ => false,
+  }
+}
+
+pub(super) fn template_element_slots(
+  dynamic_children: &[DynamicElementPart],
+) -> Vec<TemplateElementSlot> {
+  let mut slots = vec![TemplateElementSlot::Element; dynamic_children.len()];
+  for part in dynamic_children {
+    let (expr, slot_index) = match part {
+      DynamicElementPart::Slot(expr, slot_index)
+      | DynamicElementPart::ListSlot(expr, slot_index) => (expr, *slot_index),
+    };
+    let slot = match part {
+      DynamicElementPart::ListSlot(..) => TemplateElementSlot::List {
+        item_template_ids: slot_template_ids(expr),
+      },
+      DynamicElementPart::Slot(..) if is_conditional_slot_content(expr) => {
+        TemplateElementSlot::Conditional {
+          branch_template_ids: slot_template_ids(expr),
+        }
+      }
+      DynamicElementPart::Slot(..) => TemplateElementSlot::Element,
+    };
+    let idx = usize::try_from(slot_index).expect("ET element slot index must be non-negative");
+    slots[idx] = slot;
+  }
+  slots
+}
@@ -186,7 +186,11 @@ fn assert_attribute_slots_match_template(code: &str, templates: &[ElementTemplat
   let expected = templates
     .iter()
     .filter(|template| template.template_id != BUILTIN_RAW_TEXT_TEMPLATE_ID)
-    .filter_map(|template| max_attr_slot_index(&template.compiled_template))
+    .filter_map(|template| {
+      max_attr_slot_index(
+        &serde_json::to_value(&template.compiled_template).expect("compiled template json"),
+      )
+    })
     .max()
     .map(|slot| slot + 1);
 
@@ -10,7 +10,10 @@ use swc_core::common::{
 use swc_core::ecma::codegen::{text_writer::JsWriter, Emitter};
 use swc_core::ecma::parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax};
 use swc_core::ecma::visit::VisitMutWith;
-use swc_plugin_element_template::{ElementTemplateAsset, JSXTransformer, JSXTransformerConfig};
+use swc_plugin_element_template::{
+  ElementTemplateAsset, JSXTransformer, JSXTransformerConfig, TemplateNode,
+  COMPILED_TEMPLATE_JSON_SCHEMA, COMPILED_TEMPLATE_SCHEMA_VERSION,
+};
 use swc_plugins_shared::transform_mode::TransformMode;
 
 const BUILTIN_RAW_TEXT_TEMPLATE_ID: &str = "_et_builtin_raw_text";
@@ -124,6 +127,27 @@ fn first_user_template_json_with_code(input: &str, cfg: JSXTransformerConfig) ->
   (code, template)
 }
 
+fn first_user_template_json_from_view(templates: &[ElementTemplateAsset]) -> Value {
+  templates
+    .iter()
+    .map(|template| serde_json::to_value(&template.compiled_template).expect("compiled template"))
+    .find(|template| template["type"] == "view")
+    .expect("root view template")
+}
+
+fn template_id_where(
+  templates: &[ElementTemplateAsset],
+  predicate: impl Fn(&Value) -> bool,
+) -> String {
+  templates
+    .iter()
+    .find(|template| {
+      predicate(&serde_json::to_value(&template.compiled_template).expect("compiled template"))
+    })
+    .map(|template| template.template_id.clone())
+    .expect("should collect a matching template")
+}
+
 fn without_whitespace(value: &str) -> String {
   value.chars().filter(|ch| !ch.is_whitespace()).collect()
 }
@@ -886,6 +910,7 @@ fn should_extract_dynamic_key_child_as_element_slot() {
     "#,
     element_template_config(),
   );
+  let text_template_id = template_id_where(&templates, |template| template["type"] == "text");
   let template = templates
     .into_iter()
     .map(|template| {
@@ -899,6 +924,172 @@ fn should_extract_dynamic_key_child_as_element_slot() {
   assert_eq!(children[0]["kind"], "elementSlot");
   assert_eq!(children[0]["elementSlotIndex"].as_f64(), Some(0.0));
   assert_eq!(children[0]["type"], "slot");
+  assert_eq!(children[0]["slotKind"], "conditional");
+  assert_eq!(
+    children[0]["branchTemplateIds"],
+    serde_json::json!([text_template_id])
+  );
+}
+
+#[test]
+fn should_extract_ternary_child_as_conditional_slot_with_branch_templates() {
+  let templates = transform_to_templates(
+    r#"
+      <view>
+        {loading ? <image src="spinner.png" /> : <text>{content}</text>}
+        <text>title</text>
+        {error && <text>failed</text>}
+        <image />
+        {fallback}
+      </view>
+    "#,
+    element_template_config(),
+  );
+  let image_template_id = template_id_where(&templates, |template| {
+    template["type"] == "image" && template["attributesArray"][0]["key"] == "src"
+  });
+  let content_template_id = template_id_where(&templates, |template| {
+    template["type"] == "text" && template["children"][0]["kind"] == "elementSlot"
+  });
+  let failed_template_id = template_id_where(&templates, |template| {
+    template["type"] == "text" && template["attributesArray"][0]["value"] == "failed"
+  });
+  let template = first_user_template_json_from_view(&templates);
+
+  let children = template["children"].as_array().expect("children array");
+  // consecutive expression children share one slot, so elements keep them apart
+  assert_eq!(children[0]["kind"], "elementSlot");
+  assert_eq!(children[0]["slotKind"], "conditional");
+  assert_eq!(children[0]["elementSlotIndex"], 0);
+  assert_eq!(
+    children[0]["branchTemplateIds"],
+    serde_json::json!([image_template_id, content_template_id])
+  );
+  assert_eq!(children[1]["kind"], "element");
+  assert_eq!(children[2]["kind"], "elementSlot");
+  assert_eq!(children[2]["slotKind"], "conditional");
+  assert_eq!(children[2]["elementSlotIndex"], 1);
+  assert_eq!(
+    children[2]["branchTemplateIds"],
+    serde_json::json!([failed_template_id])
+  );
+  assert_eq!(children[3]["kind"], "element");
+  assert_eq!(children[4]["kind"], "elementSlot");
+  assert_eq!(children[4]["elementSlotIndex"], 2);
+  assert!(children[4].get("slotKind").is_none());
+  assert!(children[4].get("branchTemplateIds").is_none());
+}
+
+#[test]
+fn should_extract_nested_list_as_list_slot_with_item_templates() {
+  let templates = transform_to_templates(
+    r#"
+      <view>
+        <list>
+          {items.map((item) => (
+            <list-item key={item.id} item-key={item.id}>
+              <text>{item.name}</text>
+            </list-item>
+          ))}
+        </list>
+      </view>
+    "#,
+    element_template_config(),
+  );
+  let item_template_id = template_id_where(&templates, |template| template["type"] == "list-item");
+  let template = first_user_template_json_from_view(&templates);
+
+  let children = template["children"].as_array().expect("children array");
+  assert_eq!(children.len(), 1);
+  assert_eq!(children[0]["kind"], "elementSlot");
+  assert_eq!(children[0]["slotKind"], "list");
+  assert_eq!(children[0]["type"], "slot");
+  assert_eq!(children[0]["elementSlotIndex"], 0);
+  assert_eq!(
+    children[0]["itemTemplateIds"],
+    serde_json::json!([item_template_id])
+  );
+}
+
+#[test]
+fn should_keep_template_ids_and_merge_slot_hints_of_deduplicated_templates() {
+  let templates = transform_to_templates(
+    r#"
+      const first = <view>{a ? <image /> : null}</view>;
+      const second = <view>{b ? <text>b</text> : null}</view>;
+    "#,
+    element_template_config(),
+  );
+  let image_template_id = template_id_where(&templates, |template| template["type"] == "image");
+  let text_template_id = template_id_where(&templates, |template| template["type"] == "text");
+  let slot_templates = templates
+    .iter()
+    .map(|template| {
+      (
+        template.template_id.clone(),
+        serde_json::to_value(&template.compiled_template).expect("compiled template"),
+      )
+    })
+    .filter(|(_, template)| {
+      template["type"] == "view" && template["children"][0]["kind"] == "elementSlot"
+    })
+    .collect::<Vec<_>>();
+
+  // slot hints do not take part in the id, both views share one template
+  // whose id did not change when the hints were added
+  assert_eq!(slot_templates.len(), 1);
+  let (template_id, template) = &slot_templates[0];
+  assert_eq!(template_id, "_et_d7c09654e316");
+  assert_eq!(template["children"][0]["slotKind"], "conditional");
+  assert_eq!(
+    template["children"][0]["branchTemplateIds"],
+    serde_json::json!([image_template_id, text_template_id])
+  );
+}
+
+#[test]
+fn should_match_the_published_compiled_template_schema() {
+  let schema: Value =
+    serde_json::from_str(COMPILED_TEMPLATE_JSON_SCHEMA).expect("compiled template schema json");
+  assert_eq!(schema["version"], COMPILED_TEMPLATE_SCHEMA_VERSION);
+
+  let kinds_of = |def: &str| {
+    schema["$defs"][def]["oneOf"]
+      .as_array()
+      .expect("oneOf")
+      .iter()
+      .map(|variant| {
+        let name = variant["$ref"]
+          .as_str()
+          .and_then(|reference| reference.strip_prefix("#/$defs/"))
+          .expect("local ref");
+        schema["$defs"][name]["properties"]["kind"]["const"]
+          .as_str()
+          .expect("kind const")
+          .to_string()
+      })
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(kinds_of("node"), ["element", "elementSlot"]);
+  assert_eq!(kinds_of("attribute"), ["static", "slot", "spread"]);
+
+  let (templates, _) = transform_fixture(
+    r#"
+      <view id="root" className={cls} {...rest} hidden>
+        {cond ? <text>a</text> : null}
+        <list>{items.map((item) => <list-item key={item.id} />)}</list>
+        {children}
+      </view>
+    "#,
+    element_template_config(),
+  );
+  for template in templates {
+    assert_eq!(template.schema_version, COMPILED_TEMPLATE_SCHEMA_VERSION);
+    let json = serde_json::to_value(&template.compiled_template).expect("compiled template json");
+    let node: TemplateNode = serde_json::from_value(json).expect("typed compiled template");
+    assert_eq!(node, template.compiled_template);
+    assert!(matches!(node, TemplateNode::Element { .. }));
+  }
 }
 
 #[test]
@@ -24,6 +24,8 @@ use swc_core::{
 
 mod attr_name;
 mod legacy_slot;
+mod snapshot_identity;
+mod static_subtree;
 
 #[cfg(feature = "napi")]
 pub mod napi;
@@ -41,7 +43,16 @@ use swc_plugins_shared::{
   utils::{calc_hash, calc_hash_number},
 };
 
-use self::attr_name::AttrName;
+use self::{
+  attr_name::AttrName,
+  snapshot_identity::{
+    snapshot_identity, subtree_identity, SnapshotIdentityRegistry, SnapshotLookup,
+    SUBTREE_UID_PREFIX,
+  },
+  static_subtree::{
+    hoist_static_subtrees, subtree_define_items, HoistedSubtree, StaticSubtree, SubtreeIdReplacer,
+  },
+};
 
 // impl From<i32> for Expr {
 //     fn from(value: i32) -> Self {
@@ -276,6 +287,10 @@ where
   key: Option<JSXAttrValue>,
   enable_ui_source_map: bool,
   node_index_fn: F,
+  hoist_static_subtrees: bool,
+  list_count: usize,
+  static_subtrees: Vec<StaticSubtree>,
+  hoisted_subtrees: Vec<HoistedSubtree>,
 }
 
 impl<'a, V, F> DynamicPartExtractor<'a, V, F>
@@ -303,9 +318,19 @@ where
       key: None,
       enable_ui_source_map,
       node_index_fn,
+      hoist_static_subtrees: false,
+      list_count: 0,
+      static_subtrees: vec![],
+      hoisted_subtrees: vec![],
     }
   }
 
+  /// Moves static subtrees of two or more elements into creators of their own.
+  fn with_hoisted_static_subtrees(mut self, hoist_static_subtrees: bool) -> Self {
+    self.hoist_static_subtrees = hoist_static_subtrees;
+    self
+  }
+
   fn node_index_expr_from_span(&self, span: Span) -> Expr {
     (self.node_index_fn)(span)
   }
@@ -413,6 +438,7 @@ where
           );
         }
         "list" => {
+          self.list_count += 1;
           static_stmt = quote!(
               r#"const $element = $runtime_id.snapshotCreateList($page_id, $si_id, $element_index)"#
                   as Stmt,
@@ -542,6 +568,9 @@ where
 
       let el = private_ident!("el");
       self.element_ids.insert(self.element_index, el.clone());
+      let first_element_index = self.element_index;
+      let first_dynamic_part = self.dynamic_parts.len();
+      let list_count = self.list_count;
 
       if (jsx_has_dynamic_key(n)) && self.parent_element.is_some() {
         n.visit_mut_with(self.dynamic_part_visitor);
@@ -562,7 +591,9 @@ where
 
       let static_stmt = self.static_stmt_from_jsx_element(n, el.clone());
       let static_stmt = RefCell::new(static_stmt);
+      let first_stmt = self.static_stmts.len();
       self.static_stmts.push(static_stmt.clone());
+      let mut append_stmt = None;
 
       {
         let mut flatten = None;
@@ -988,6 +1019,7 @@ where
       }
 
       if let Some(parent_el) = &self.parent_element {
+        append_stmt = Some(self.static_stmts.len());
         self.static_stmts.push(RefCell::new(quote!(
             r#"__AppendElement($parent, $child)"# as Stmt,
             parent: Ident = parent_el.clone(),
@@ -1020,7 +1052,36 @@ where
         self.element_index += 1;
       }
 
+      if let Some(append_stmt) = append_stmt {
+        if self.hoist_static_subtrees
+          && self.dynamic_parts.len() == first_dynamic_part
+          && self.list_count == list_count
+          && self.element_index - first_element_index > 1
+        {
+          self.static_subtrees.push(StaticSubtree {
+            stmts: first_stmt..self.static_stmts.len(),
+            append_stmt,
+            elements: first_element_index..self.element_index,
+          });
+        }
+      }
+
       if self.parent_element.is_none() {
+        let mut stmts: Vec<Stmt> = self
+          .static_stmts
+          .take()
+          .into_iter()
+          .map(|mut stmt| stmt.get_mut().take())
+          .collect();
+        if !self.static_subtrees.is_empty() {
+          self.hoisted_subtrees = hoist_static_subtrees(
+            &mut stmts,
+            self.static_subtrees.take(),
+            &self.element_ids,
+            &self.page_id,
+          );
+        }
+
         let elements = Expr::Array(ArrayLit {
           span: DUMMY_SP,
           elems: (0..self.element_ids.len())
@@ -1035,10 +1096,10 @@ where
             .collect(),
         });
 
-        self.static_stmts.push(RefCell::new(quote!(
+        stmts.push(quote!(
           r#"return $elements;"# as Stmt,
           elements: Expr = elements,
-        )));
+        ));
 
         self.snapshot_creator = Some(Function {
           ctxt: SyntaxContext::default(),
@@ -1058,12 +1119,7 @@ where
           body: Some(BlockStmt {
             ctxt: SyntaxContext::default(),
             span: DUMMY_SP,
-            stmts: self
-              .static_stmts
-              .take()
-              .into_iter()
-              .map(|mut stmt| stmt.get_mut().take())
-              .collect(),
+            stmts,
           }),
           is_generator: false,
           is_async: false,
@@ -1141,6 +1197,17 @@ pub struct JSXTransformerConfig {
   /// @internal
   #[serde(default)]
   pub legacy_slot: Option<bool>,
+  /// Key snapshots by a hash of their main thread definition instead of their
+  /// position in the file, so identical snapshots of different modules share
+  /// one id and one define, and repeated ones in a module share a definition.
+  /// Static subtrees of two or more elements are hoisted into creators keyed
+  /// the same way, so different snapshots sharing one call the same creator.
+  /// Not done for dev builds, whose creators are stringified for HMR.
+  /// Ignored when `enable_ui_source_map` is set, since node indices are per
+  /// location.
+  /// @internal
+  #[serde(default)]
+  pub dedupe_across_modules: Option<bool>,
 }
 
 impl Default for JSXTransformerConfig {
@@ -1155,6 +1222,7 @@ impl Default for JSXTransformerConfig {
       is_dynamic_component: Some(false),
       is_external_bundle: Some(false),
       legacy_slot: Some(false),
+      dedupe_across_modules: Some(false),
     }
   }
 }
@@ -1236,6 +1304,8 @@ where
   snapshot_counter: u32,
   current_snapshot_defs: Vec<ModuleItem>,
   current_snapshot_id: Option<Ident>,
+  snapshot_identities: SnapshotIdentityRegistry,
+  subtree_identities: SnapshotIdentityRegistry,
   defines_collector: Option<DefinesCollector>,
   comments: Option<C>,
   pub ui_source_map_records: Rc<RefCell<Vec<UISourceMapRecord>>>,
@@ -1281,6 +1351,8 @@ where
       snapshot_counter: 0,
       current_snapshot_defs: vec![],
       current_snapshot_id: None,
+      snapshot_identities: Default::default(),
+      subtree_identities: Default::default(),
       defines_collector: None,
       comments,
       ui_source_map_records: Rc::new(RefCell::new(vec![])),
@@ -1392,11 +1464,11 @@ where
 
     self.snapshot_counter += 1;
 
-    let snapshot_uid = format!(
+    let mut snapshot_uid = format!(
       "__snapshot_{}_{}_{}",
       self.filename_hash, self.content_hash, self.snapshot_counter
     );
-    let snapshot_id = Ident::new(
+    let mut snapshot_id = Ident::new(
       // format!("__snapshot_{}", snapshot_uid).into(),
       snapshot_uid.clone().into(),
       DUMMY_SP,
@@ -1404,7 +1476,11 @@ where
     );
 
     let target = self.cfg.target;
-    let collecting = self.defines_collector.is_some();
+    let dedupe =
+      matches!(self.cfg.dedupe_across_modules, Some(true)) && !self.cfg.enable_ui_source_map;
+    // Whether a deduped snapshot is already defined in this module.
+    let mut is_defined = false;
+    let collecting = self.defines_collector.is_some() || dedupe;
     let runtime_id = self.runtime_id.clone();
     // In dev the creator arrow is stringified for cross-thread HMR
     // (`DEV_ONLY_AddSnapshot`), so everything inside it references the runtime
@@ -1460,12 +1536,16 @@ where
       }))
     };
 
+    // Dev creators are stringified for HMR, so they cannot call a creator
+    // defined outside of them.
+    let hoist_static_subtrees = dedupe && !self.dev_creator_param;
     let mut dynamic_part_extractor = DynamicPartExtractor::new(
       creator_runtime_expr.clone(),
       self,
       self.cfg.enable_ui_source_map,
       node_index_fn,
-    );
+    )
+    .with_hoisted_static_subtrees(hoist_static_subtrees);
 
     node.visit_mut_with(&mut dynamic_part_extractor);
 
@@ -1613,7 +1693,61 @@ where
       }
     };
 
-    let snapshot_creator_fn = dynamic_part_extractor.snapshot_creator.unwrap();
+    let mut snapshot_creator_fn = dynamic_part_extractor.snapshot_creator.unwrap();
+    for (subtree_index, subtree) in dynamic_part_extractor
+      .hoisted_subtrees
+      .into_iter()
+      .enumerate()
+    {
+      // Subtrees are matched like whole snapshots, by their definition.
+      let identity = subtree_identity(subtree_define_items(
+        &private_ident!("__snapshot_subtree"),
+        &subtree.creator,
+      ));
+      let subtree_id = match self.subtree_identities.get(&identity) {
+        SnapshotLookup::Defined(subtree_id) => subtree_id,
+        lookup => {
+          let is_collision = matches!(lookup, SnapshotLookup::Collision);
+          let subtree_uid = if is_collision {
+            format!(
+              "{}{}_{}_{}_{}",
+              SUBTREE_UID_PREFIX,
+              self.filename_hash,
+              self.content_hash,
+              self.snapshot_counter,
+              subtree_index
+            )
+          } else {
+            identity.snapshot_uid.clone()
+          };
+          let subtree_id = Ident::new(
+            subtree_uid.clone().into(),
+            DUMMY_SP,
+            SyntaxContext::default().apply_mark(Mark::fresh(Mark::root())),
+          );
+          let items = subtree_define_items(&subtree_id, &subtree.creator);
+          collect_define(
+            &self.defines_collector,
+            DefineKind::Snapshot,
+            subtree_uid,
+            items.clone(),
+          );
+          if target != TransformTarget::JS {
+            self.current_snapshot_defs.extend(items);
+          }
+          if !is_collision {
+            self
+              .subtree_identities
+              .register(identity, subtree_id.clone());
+          }
+          subtree_id
+        }
+      };
+      snapshot_creator_fn.visit_mut_with(&mut SubtreeIdReplacer {
+        placeholder_id: subtree.placeholder_id.to_id(),
+        subtree_id,
+      });
+    }
     let snapshot_creator_mt = collecting.then(|| {
       Expr::Fn(FnExpr {
         ident: None,
@@ -1666,70 +1800,108 @@ where
       }),
     };
 
-    let snapshot_create_call_mt = snapshot_creator_mt.map(|snapshot_creator_mt| {
-      build_snapshot_registration(
-        self.dev_creator_param,
-        self.runtime_id.clone(),
-        creator_runtime_expr.clone(),
-        creator_runtime_id.clone(),
-        snapshot_id.clone(),
-        snapshot_creator_mt,
-        snapshot_dynamic_parts_def_mt,
-        slot_expr.clone(),
-        css_id.clone(),
-        entry_name.clone(),
-        snapshot_refs_and_spread_index.clone(),
-      )
-    });
-    let snapshot_create_call = build_snapshot_registration(
-      self.dev_creator_param,
-      self.runtime_id.clone(),
-      creator_runtime_expr,
-      creator_runtime_id,
-      snapshot_id.clone(),
-      snapshot_creator,
-      snapshot_dynamic_parts_def,
-      slot_expr,
-      css_id,
-      entry_name,
-      snapshot_refs_and_spread_index,
-    );
-
-    let mut entry_snapshot_uid = quote!("$snapshot_uid" as Expr, snapshot_uid: Expr = Expr::Lit(Lit::Str(snapshot_uid.clone().into())));
-    if matches!(self.cfg.is_dynamic_component, Some(true)) {
-      entry_snapshot_uid = quote!("`${globDynamicComponentEntry}:${$snapshot_uid}`" as Expr, snapshot_uid: Expr = Expr::Lit(Lit::Str(snapshot_uid.clone().into())));
-    }
-
-    let entry_snapshot_uid_def = ModuleItem::Stmt(quote!(
-        r#"const $snapshot_id = $entry_snapshot_uid"#
-            as Stmt,
-        snapshot_id = snapshot_id.clone(),
-        entry_snapshot_uid: Expr = entry_snapshot_uid.clone(),
-    ));
-    let snapshot_def = ModuleItem::Stmt(quote!(
-        r#"$snapshot_create_call"#
-            as Stmt,
-        snapshot_create_call: Expr = snapshot_create_call,
-    ));
-
-    if let Some(snapshot_create_call_mt) = snapshot_create_call_mt {
-      collect_define(
-        &self.defines_collector,
-        DefineKind::Snapshot,
-        snapshot_uid,
+    let is_dynamic_component = matches!(self.cfg.is_dynamic_component, Some(true));
+    let entry_snapshot_uid_def = |snapshot_id: &Ident, snapshot_uid: &str| {
+      let mut entry_snapshot_uid = Expr::Lit(Lit::Str(snapshot_uid.into()));
+      if is_dynamic_component {
+        entry_snapshot_uid = quote!("`${globDynamicComponentEntry}:${$snapshot_uid}`" as Expr, snapshot_uid: Expr = entry_snapshot_uid);
+      }
+      ModuleItem::Stmt(quote!(
+          r#"const $snapshot_id = $entry_snapshot_uid"#
+              as Stmt,
+          snapshot_id = snapshot_id.clone(),
+          entry_snapshot_uid: Expr = entry_snapshot_uid,
+      ))
+    };
+    let dev_creator_param = self.dev_creator_param;
+    let snapshot_define_items = |snapshot_id: &Ident, snapshot_uid: &str| {
+      snapshot_creator_mt.as_ref().map(|snapshot_creator_mt| {
+        let snapshot_create_call_mt = build_snapshot_registration(
+          dev_creator_param,
+          runtime_id.clone(),
+          creator_runtime_expr.clone(),
+          creator_runtime_id.clone(),
+          snapshot_id.clone(),
+          snapshot_creator_mt.clone(),
+          snapshot_dynamic_parts_def_mt.clone(),
+          slot_expr.clone(),
+          css_id.clone(),
+          entry_name.clone(),
+          snapshot_refs_and_spread_index.clone(),
+        );
         vec![
-          entry_snapshot_uid_def.clone(),
+          entry_snapshot_uid_def(snapshot_id, snapshot_uid),
           ModuleItem::Stmt(quote!(
             r#"$snapshot_create_call"# as Stmt,
             snapshot_create_call: Expr = snapshot_create_call_mt,
           )),
-        ],
+        ]
+      })
+    };
+
+    if dedupe {
+      // The main thread definition with a placeholder id is what makes two
+      // snapshots the same, whatever their module and target.
+      let identity = snapshot_identity(
+        snapshot_define_items(&private_ident!("__snapshot"), "__snapshot")
+          .expect("deduped snapshots always build the main thread definition"),
+      );
+      match self.snapshot_identities.get(&identity) {
+        SnapshotLookup::Defined(defined_snapshot_id) => {
+          snapshot_id = defined_snapshot_id;
+          is_defined = true;
+        }
+        SnapshotLookup::New => {
+          snapshot_uid = identity.snapshot_uid.clone();
+          snapshot_id = Ident::new(
+            snapshot_uid.clone().into(),
+            DUMMY_SP,
+            SyntaxContext::default().apply_mark(Mark::fresh(Mark::root())),
+          );
+          self
+            .snapshot_identities
+            .register(identity, snapshot_id.clone());
+        }
+        // keep the positional uid, which cannot clash with the snapshot
+        // already holding the hash
+        SnapshotLookup::Collision => {}
+      }
+    }
+
+    if !is_defined {
+      if let Some(items) = snapshot_define_items(&snapshot_id, &snapshot_uid) {
+        collect_define(
+          &self.defines_collector,
+          DefineKind::Snapshot,
+          snapshot_uid.clone(),
+          items,
+        );
+      }
+
+      let snapshot_create_call = build_snapshot_registration(
+        self.dev_creator_param,
+        self.runtime_id.clone(),
+        creator_runtime_expr,
+        creator_runtime_id,
+        snapshot_id.clone(),
+        snapshot_creator,
+        snapshot_dynamic_parts_def,
+        slot_expr,
+        css_id,
+        entry_name,
+        snapshot_refs_and_spread_index,
       );
+      self
+        .current_snapshot_defs
+        .push(entry_snapshot_uid_def(&snapshot_id, &snapshot_uid));
+      self.current_snapshot_defs.push(ModuleItem::Stmt(quote!(
+          r#"$snapshot_create_call"#
+              as Stmt,
+          snapshot_create_call: Expr = snapshot_create_call,
+      )));
     }
 
     self.current_snapshot_id = Some(snapshot_id.clone());
-    self.current_snapshot_defs.push(entry_snapshot_uid_def);
-    self.current_snapshot_defs.push(snapshot_def);
 
     *node =
       JSXElement {
@@ -1857,8 +2029,9 @@ where
 #[cfg(test)]
 mod tests {
   use swc_core::{
-    common::{comments::SingleThreadedComments, Mark},
+    common::{comments::SingleThreadedComments, Mark, DUMMY_SP},
     ecma::{
+      ast::{Module, Program},
       parser::{EsSyntax, Syntax},
       transforms::{
         base::resolver,
@@ -1871,11 +2044,12 @@ mod tests {
 
   use std::{cell::RefCell, rc::Rc};
 
-  use crate::JSXTransformer;
+  use crate::{snapshot_identity::SUBTREE_UID_PREFIX, JSXTransformer};
   use swc_plugins_shared::{
     defines::{DefineKind, DefinesCollector},
     target::TransformTarget,
     transform_mode::TransformMode,
+    utils::calc_hash,
   };
 
   #[test]
@@ -1973,6 +2147,199 @@ mod tests {
     });
   }
 
+  type PrintedDefines = Vec<(String, String)>;
+
+  fn transform_deduped(
+    tester: &mut Tester,
+    filename: &str,
+    target: TransformTarget,
+    source: &str,
+  ) -> Result<(String, PrintedDefines), ()> {
+    let top_level_mark = Mark::new();
+    let unresolved_mark = Mark::new();
+    let collector: DefinesCollector = Rc::new(RefCell::new(vec![]));
+    let program = tester.apply_transform(
+      (
+        resolver(unresolved_mark, top_level_mark, true),
+        visit_mut_pass(
+          JSXTransformer::<&SingleThreadedComments>::new(
+            super::JSXTransformerConfig {
+              preserve_jsx: false,
+              filename: filename.into(),
+              target,
+              dedupe_across_modules: Some(true),
+              ..Default::default()
+            },
+            None,
+            TransformMode::Test,
+            Some(tester.cm.clone()),
+          )
+          .with_content_hash(calc_hash(source))
+          .with_defines_collector(collector.clone()),
+        ),
+      ),
+      filename,
+      Syntax::Es(EsSyntax {
+        jsx: true,
+        ..Default::default()
+      }),
+      Some(true),
+      source,
+    )?;
+
+    let comments = Rc::new(SingleThreadedComments::default());
+    let defines = collector
+      .borrow_mut()
+      .drain(..)
+      .map(|define| {
+        let code = tester.print(
+          &Program::Module(Module {
+            span: DUMMY_SP,
+            body: define.items,
+            shebang: None,
+          }),
+          &comments,
+        );
+        (define.id, code)
+      })
+      .collect();
+    Ok((tester.print(&program, &comments), defines))
+  }
+
+  fn split_subtree_defines(defines: PrintedDefines) -> (PrintedDefines, PrintedDefines) {
+    defines
+      .into_iter()
+      .partition(|(id, _)| id.starts_with(SUBTREE_UID_PREFIX))
+  }
+
+  #[test]
+  fn should_share_snapshot_defines_across_modules_when_deduping() {
+    Tester::run(|tester| {
+      let (_, a) = transform_deduped(
+        tester,
+        "a.js",
+        TransformTarget::LEPUS,
+        r#"function A() { return <view class="card"><text>hi</text>{a}</view>; }"#,
+      )?;
+      let (_, b) = transform_deduped(
+        tester,
+        "nested/b.js",
+        TransformTarget::JS,
+        r#"export const B = ({ b }) => <view class="card"><text>hi</text>{b}</view>;"#,
+      )?;
+
+      assert_eq!(a, b);
+      let (_, a) = split_subtree_defines(a);
+      assert_eq!(a.len(), 1);
+      assert!(a[0].0.starts_with("__snapshot_"));
+      assert!(a[0]
+        .1
+        .contains(&format!("const {} = \"{}\"", a[0].0, a[0].0)));
+
+      let (_, c) = transform_deduped(
+        tester,
+        "a.js",
+        TransformTarget::LEPUS,
+        r#"function A() { return <view class="card"><text>bye</text>{a}</view>; }"#,
+      )?;
+      let (_, c) = split_subtree_defines(c);
+      assert_ne!(a[0].0, c[0].0);
+
+      Ok(())
+    });
+  }
+
+  #[test]
+  fn should_define_a_repeated_snapshot_once_per_module_when_deduping() {
+    Tester::run(|tester| {
+      let (emitted, defines) = transform_deduped(
+        tester,
+        "input.js",
+        TransformTarget::JS,
+        r#"
+        function A() { return <view><text>hi</text></view>; }
+        function B() { return <view><text>hi</text></view>; }
+        function C() { return <view><text>bye</text></view>; }
+        "#,
+      )?;
+
+      let (_, defines) = split_subtree_defines(defines);
+      assert_eq!(defines.len(), 2);
+      assert_eq!(emitted.matches("snapshotCreatorMap[").count(), 2);
+      assert_eq!(emitted.matches(&format!("<{}", defines[0].0)).count(), 2);
+      assert_eq!(emitted.matches(&format!("<{}", defines[1].0)).count(), 1);
+
+      Ok(())
+    });
+  }
+
+  #[test]
+  fn should_hoist_static_subtrees_shared_by_different_snapshots_when_deduping() {
+    Tester::run(|tester| {
+      let (emitted, defines) = transform_deduped(
+        tester,
+        "a.js",
+        TransformTarget::LEPUS,
+        r#"
+        function A() { return <view class="a"><view class="row"><text>hi</text><image /></view>{a}</view>; }
+        function B() { return <view class="b" id={b}><view class="row"><text>hi</text><image /></view></view>; }
+        "#,
+      )?;
+      let (subtrees, snapshots) = split_subtree_defines(defines);
+
+      assert_eq!(subtrees.len(), 1);
+      assert_eq!(snapshots.len(), 2);
+      let (subtree_id, subtree) = &subtrees[0];
+      assert!(subtree.contains(&format!("var {subtree_id} = function(pageId)")));
+      assert!(subtree.contains("__SetClasses(el, \"row\")"));
+      let call = format!("= {subtree_id}(pageId);");
+      assert!(snapshots
+        .iter()
+        .all(|(_, snapshot)| snapshot.contains(&call)
+          && !snapshot.contains("__SetClasses(el, \"row\")")));
+      assert_eq!(emitted.matches(&format!("var {subtree_id} =")).count(), 1);
+      assert_eq!(emitted.matches(&call).count(), 2);
+
+      let (_, b) = transform_deduped(
+        tester,
+        "nested/b.js",
+        TransformTarget::JS,
+        r#"export const C = () => <view class="c"><view class="row"><text>hi</text><image /></view></view>;"#,
+      )?;
+      let (b_subtrees, _) = split_subtree_defines(b);
+      assert_eq!(b_subtrees, subtrees);
+
+      Ok(())
+    });
+  }
+
+  #[test]
+  fn should_not_hoist_subtrees_with_dynamic_parts_when_deduping() {
+    Tester::run(|tester| {
+      let (_, defines) = transform_deduped(
+        tester,
+        "input.js",
+        TransformTarget::JS,
+        r#"
+        function A() {
+          return <view>
+            <view class={a}><text>hi</text></view>
+            <view><text>{a}</text></view>
+            <view><list><list-item item-key="a" /></list></view>
+          </view>;
+        }
+        "#,
+      )?;
+      let (subtrees, _) = split_subtree_defines(defines);
+
+      assert_eq!(subtrees.len(), 1);
+      assert!(subtrees[0].1.contains("__CreateText(pageId)"));
+      assert!(!subtrees[0].1.contains("__CreateView(pageId)"));
+
+      Ok(())
+    });
+  }
+
   #[test]
   fn should_keep_jsx_in_children_prop_map_callback_scope() {
     Tester::run(|tester| {
@@ -37,6 +37,8 @@ pub struct JSXTransformerConfig {
   pub is_external_bundle: Option<bool>,
   /// @internal
   pub legacy_slot: Option<bool>,
+  /// @internal
+  pub dedupe_across_modules: Option<bool>,
 }
 
 /// @internal
@@ -85,6 +87,7 @@ impl Default for JSXTransformerConfig {
       is_dynamic_component: Some(false),
       is_external_bundle: Some(false),
       legacy_slot: Some(false),
+      dedupe_across_modules: Some(false),
     }
   }
 }
@@ -101,6 +104,7 @@ impl From<JSXTransformerConfig> for CoreJSXTransformerConfig {
       is_dynamic_component: val.is_dynamic_component,
       is_external_bundle: val.is_external_bundle,
       legacy_slot: val.legacy_slot,
+      dedupe_across_modules: val.dedupe_across_modules,
     }
   }
 }
@@ -117,6 +121,7 @@ impl From<CoreJSXTransformerConfig> for JSXTransformerConfig {
       is_dynamic_component: val.is_dynamic_component,
       is_external_bundle: val.is_external_bundle,
       legacy_slot: val.legacy_slot,
+      dedupe_across_modules: val.dedupe_across_modules,
     }
   }
 }
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use sha1::{Digest, Sha1};
+use swc_core::{
+  common::{sync::Lrc, Mark, SourceMap, DUMMY_SP},
+  ecma::{
+    ast::*,
+    codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+    transforms::base::{
+      fixer::fixer,
+      hygiene::{hygiene_with_config, Config},
+    },
+  },
+};
+
+const SNAPSHOT_UID_PREFIX: &str = "__snapshot_";
+pub(crate) const SUBTREE_UID_PREFIX: &str = "__snapshot_subtree_";
+const SNAPSHOT_UID_HASH_HEX_LEN: usize = 12;
+
+/*
+ * A snapshot keyed by its main thread definition. Two snapshots with the same
+ * `canonical_content` are the same snapshot, whichever module they come from.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SnapshotIdentity {
+  pub snapshot_uid: String,
+  pub canonical_content: String,
+}
+
+pub(crate) enum SnapshotLookup {
+  /// The same snapshot is already defined in this module.
+  Defined(Ident),
+  /// No snapshot of this module has the same uid.
+  New,
+  /// Another snapshot of this module has the same uid, but a different definition.
+  Collision,
+}
+
+/*
+ * The snapshots already defined in a module, so that repeated subtrees reuse
+ * the first definition.
+ */
+#[derive(Default)]
+pub(crate) struct SnapshotIdentityRegistry {
+  snapshots: HashMap<String, (String, Ident)>,
+}
+
+/*
+ * Computes the identity of a snapshot from its main thread definition, built
+ * with a placeholder snapshot id. Locally bound names are renamed the way
+ * `hygiene` renames them in the output, so the content does not depend on the
+ * syntax contexts of the module the snapshot was found in.
+ */
+pub(crate) fn snapshot_identity(items: Vec<ModuleItem>) -> SnapshotIdentity {
+  content_identity(items, SNAPSHOT_UID_PREFIX)
+}
+
+/*
+ * Computes the identity of a hoisted static subtree from its definition, the
+ * same way as `snapshot_identity`.
+ */
+pub(crate) fn subtree_identity(items: Vec<ModuleItem>) -> SnapshotIdentity {
+  content_identity(items, SUBTREE_UID_PREFIX)
+}
+
+fn content_identity(items: Vec<ModuleItem>, uid_prefix: &str) -> SnapshotIdentity {
+  let program = Program::Module(Module {
+    span: DUMMY_SP,
+    body: items,
+    shebang: None,
+  })
+  .apply((
+    hygiene_with_config(Config {
+      top_level_mark: Mark::new(),
+      ..Default::default()
+    }),
+    fixer(None),
+  ));
+
+  let cm: Lrc<SourceMap> = Default::default();
+  let mut buf = vec![];
+  let mut emitter = Emitter {
+    cfg: CodegenConfig::default().with_minify(true),
+    cm: cm.clone(),
+    comments: None,
+    wr: JsWriter::new(cm, "\n", &mut buf, None),
+  };
+  emitter
+    .emit_program(&program)
+    .expect("snapshot definition must print");
+  let canonical_content = String::from_utf8(buf).expect("snapshot definition must print as utf8");
+
+  let hash = hex::encode(Sha1::digest(canonical_content.as_bytes()));
+  SnapshotIdentity {
+    snapshot_uid: format!("{}{}", uid_prefix, &hash[..SNAPSHOT_UID_HASH_HEX_LEN]),
+    canonical_content,
+  }
+}
+
+impl SnapshotIdentityRegistry {
+  pub(crate) fn get(&self, identity: &SnapshotIdentity) -> SnapshotLookup {
+    match self.snapshots.get(&identity.snapshot_uid) {
+      None => SnapshotLookup::New,
+      Some((canonical_content, snapshot_id))
+        if canonical_content == &identity.canonical_content =>
+      {
+        SnapshotLookup::Defined(snapshot_id.clone())
+      }
+      Some(_) => SnapshotLookup::Collision,
+    }
+  }
+
+  pub(crate) fn register(&mut self, identity: SnapshotIdentity, snapshot_id: Ident) {
+    self.snapshots.insert(
+      identity.snapshot_uid,
+      (identity.canonical_content, snapshot_id),
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use swc_core::{common::DUMMY_SP, ecma::ast::Ident};
+
+  use super::{SnapshotIdentity, SnapshotIdentityRegistry, SnapshotLookup};
+
+  #[test]
+  fn registry_reports_a_hash_collision_instead_of_reusing_the_snapshot() {
+    let identity = |canonical_content: &str| SnapshotIdentity {
+      snapshot_uid: "__snapshot_collision".into(),
+      canonical_content: canonical_content.into(),
+    };
+    let mut registry = SnapshotIdentityRegistry::default();
+    assert!(matches!(registry.get(&identity("a")), SnapshotLookup::New));
+
+    let snapshot_id = Ident::new_no_ctxt("__snapshot_collision".into(), DUMMY_SP);
+    registry.register(identity("a"), snapshot_id.clone());
+    assert!(
+      matches!(registry.get(&identity("a")), SnapshotLookup::Defined(id) if id == snapshot_id)
+    );
+    assert!(matches!(
+      registry.get(&identity("b")),
+      SnapshotLookup::Collision
+    ));
+  }
+}
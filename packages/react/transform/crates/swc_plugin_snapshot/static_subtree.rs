@@ -0,0 +1,157 @@
+use std::{cmp::Reverse, collections::HashMap, ops::Range};
+
+use swc_core::{
+  common::{SyntaxContext, DUMMY_SP},
+  ecma::{ast::*, utils::private_ident, visit::VisitMut},
+  quote,
+};
+
+/*
+ * A subtree of a snapshot without dynamic parts, found while extracting them.
+ * `stmts` are the statements creating it, `append_stmt` is the one appending
+ * its root to the parent, and `elements` are the element indices it covers.
+ */
+pub(crate) struct StaticSubtree {
+  pub stmts: Range<usize>,
+  pub append_stmt: usize,
+  pub elements: Range<i32>,
+}
+
+/*
+ * The creator of a hoisted subtree. The snapshot creator calls it through
+ * `placeholder_id` until the subtree gets its id from its content.
+ */
+pub(crate) struct HoistedSubtree {
+  pub placeholder_id: Ident,
+  pub creator: Function,
+}
+
+/*
+ * Moves the statements of the outermost static subtrees out of a snapshot
+ * creator into creators of their own, which return the elements of the
+ * subtree. In the snapshot creator they are replaced by a call destructuring
+ * those elements, followed by the append of the subtree root.
+ */
+pub(crate) fn hoist_static_subtrees(
+  stmts: &mut Vec<Stmt>,
+  mut subtrees: Vec<StaticSubtree>,
+  element_ids: &HashMap<i32, Ident>,
+  page_id: &Ident,
+) -> Vec<HoistedSubtree> {
+  // subtrees are found children first, only the outermost ones are hoisted
+  subtrees.sort_by_key(|subtree| (subtree.stmts.start, Reverse(subtree.stmts.end)));
+  let mut outermost: Vec<StaticSubtree> = vec![];
+  for subtree in subtrees {
+    match outermost.last() {
+      Some(last) if subtree.stmts.start < last.stmts.end => {}
+      _ => outermost.push(subtree),
+    }
+  }
+
+  let mut hoisted = vec![];
+  for subtree in outermost.into_iter().rev() {
+    let mut body: Vec<Stmt> = stmts.drain(subtree.stmts.clone()).collect();
+    let append_stmt = body.remove(subtree.append_stmt - subtree.stmts.start);
+    let elements: Vec<Ident> = subtree
+      .elements
+      .map(|element_index| element_ids[&element_index].clone())
+      .collect();
+
+    body.push(quote!(
+      r#"return $elements;"# as Stmt,
+      elements: Expr = Expr::Array(ArrayLit {
+        span: DUMMY_SP,
+        elems: elements
+          .iter()
+          .map(|element| Some(Expr::Ident(element.clone()).into()))
+          .collect(),
+      }),
+    ));
+
+    let placeholder_id = private_ident!("__snapshot_subtree");
+    let call = Stmt::Decl(Decl::Var(Box::new(VarDecl {
+      ctxt: SyntaxContext::default(),
+      span: DUMMY_SP,
+      kind: VarDeclKind::Const,
+      declare: false,
+      decls: vec![VarDeclarator {
+        span: DUMMY_SP,
+        definite: false,
+        name: Pat::Array(ArrayPat {
+          span: DUMMY_SP,
+          elems: elements
+            .into_iter()
+            .map(|element| Some(Pat::Ident(element.into())))
+            .collect(),
+          optional: false,
+          type_ann: None,
+        }),
+        init: Some(Box::new(quote!(
+          "$subtree($page_id)" as Expr,
+          subtree = placeholder_id.clone(),
+          page_id = page_id.clone(),
+        ))),
+      }],
+    })));
+    stmts.splice(
+      subtree.stmts.start..subtree.stmts.start,
+      [call, append_stmt],
+    );
+
+    hoisted.push(HoistedSubtree {
+      placeholder_id,
+      creator: Function {
+        ctxt: SyntaxContext::default(),
+        params: vec![Param {
+          span: DUMMY_SP,
+          decorators: vec![],
+          pat: Pat::Ident(page_id.clone().into()),
+        }],
+        decorators: vec![],
+        span: DUMMY_SP,
+        body: Some(BlockStmt {
+          ctxt: SyntaxContext::default(),
+          span: DUMMY_SP,
+          stmts: body,
+        }),
+        is_generator: false,
+        is_async: false,
+        type_params: None,
+        return_type: None,
+      },
+    });
+  }
+  hoisted.reverse();
+  hoisted
+}
+
+/*
+ * The definition of a hoisted subtree. A `var` so that the snapshot defines
+ * of a bundle, each rendered in its own block, can all reach it.
+ */
+pub(crate) fn subtree_define_items(subtree_id: &Ident, creator: &Function) -> Vec<ModuleItem> {
+  vec![ModuleItem::Stmt(quote!(
+    r#"var $subtree_id = $creator"# as Stmt,
+    subtree_id = subtree_id.clone(),
+    creator: Expr = Expr::Fn(FnExpr {
+      ident: None,
+      function: Box::new(creator.clone()),
+    }),
+  ))]
+}
+
+/*
+ * Points the calls of a hoisted subtree at its final id.
+ */
+pub(crate) struct SubtreeIdReplacer {
+  pub placeholder_id: Id,
+  pub subtree_id: Ident,
+}
+
+impl VisitMut for SubtreeIdReplacer {
+  fn visit_mut_ident(&mut self, n: &mut Ident) {
+    if n.to_id() == self.placeholder_id {
+      *n = self.subtree_id.clone();
+    }
+  }
+}
@@ -104,6 +104,63 @@ impl ExtractingIdentsCollector {
     take(&mut self.js_fns_to_extract)
   }
 
+  pub fn idents(&self) -> &[Ident] {
+    &self.idents_to_extract
+  }
+
+  pub fn js_fns(&self) -> &[(IdentName, Box<Expr>)] {
+    &self.js_fns_to_extract
+  }
+
+  /*
+   * The member paths captured into `_c`, e.g. `["a", "b"]` for `a.b`.
+   */
+  pub fn value_paths(&self) -> Vec<Vec<String>> {
+    let mut paths = vec![];
+    Self::collect_paths(&self.values_extracted, &mut vec![], &mut paths);
+    paths
+  }
+
+  /*
+   * The member paths read from `this`, without the leading `this`.
+   */
+  pub fn this_paths(&self) -> Vec<Vec<String>> {
+    let mut paths = vec![];
+    Self::collect_paths(&self.this_expr_to_extract, &mut vec![], &mut paths);
+    paths
+  }
+
+  fn collect_paths(root: &Expr, prefix: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    let Some(obj) = root.as_object() else {
+      return;
+    };
+    for prop in obj.props.iter().filter_map(|prop| prop.as_prop()) {
+      match &**prop {
+        Prop::Shorthand(ident) => {
+          prefix.push(ident.sym.to_string());
+          paths.push(prefix.clone());
+          prefix.pop();
+        }
+        Prop::KeyValue(KeyValueProp { key, value }) => {
+          let name = match key {
+            PropName::Ident(id) => id.sym.to_string(),
+            PropName::Str(str) => str.value.to_string_lossy().into_owned(),
+            _ => continue,
+          };
+          prefix.push(name);
+          // extracted values are identifiers or member expressions, objects are nested paths
+          if value.is_object() {
+            Self::collect_paths(value, prefix, paths);
+          } else {
+            paths.push(prefix.clone());
+          }
+          prefix.pop();
+        }
+        _ => {}
+      }
+    }
+  }
+
   fn is_at_global(&self, s: &str) -> bool {
     DEFAULT_GLOBALS.contains(s)
       || LYNX_GLOBALS.contains(s)
@@ -3,11 +3,13 @@ mod extract_ident;
 mod gen_stmt;
 mod globals;
 mod hash;
+mod report;
 mod worklet_type;
 
 use extract_ident::{ExtractingIdentsCollector, ExtractingIdentsCollectorConfig};
 use gen_stmt::StmtGen;
 use hash::WorkletHash;
+use report::{report_captures, CaptureBindings};
 use rustc_hash::FxHashSet;
 use serde::Deserialize;
 use std::collections::HashSet;
@@ -30,6 +32,10 @@ use swc_plugins_shared::{
 #[cfg(feature = "napi")]
 pub mod napi;
 
+pub use report::{
+  WorkletCaptureLintConfig, WorkletCaptureReport, WorkletJsFnReport, WorkletReportsCollector,
+};
+
 #[derive(Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkletVisitorConfig {
@@ -45,6 +51,14 @@ pub struct WorkletVisitorConfig {
   /// @internal
   pub target: TransformTarget,
   pub runtime_pkg: String,
+  /// @public
+  /// Warns about captured values that cannot be sent to the main thread, such as classes,
+  /// functions, `Map`/`Set` instances and background refs, and about worklets capturing
+  /// a large payload.
+  pub capture_lint: Option<WorkletCaptureLintConfig>,
+  /// @public
+  /// Returns what each worklet captures as `workletReports` of the transform output.
+  pub capture_report: Option<bool>,
 }
 
 impl Default for WorkletVisitorConfig {
@@ -54,6 +68,8 @@ impl Default for WorkletVisitorConfig {
       target: TransformTarget::LEPUS,
       custom_global_ident_names: None,
       runtime_pkg: "NoDiff".into(),
+      capture_lint: None,
+      capture_report: None,
     }
   }
 }
@@ -69,6 +85,8 @@ pub struct WorkletVisitor {
   worklet_runtime_loaded: bool,
   worklet_runtime_loaded_ident: Ident,
   defines_collector: Option<DefinesCollector>,
+  reports_collector: Option<WorkletReportsCollector>,
+  capture_bindings: CaptureBindings,
 }
 
 impl Default for WorkletVisitor {
@@ -110,6 +128,16 @@ impl VisitMut for WorkletVisitor {
             || collector.has_extracted_js_fns());
 
         let hash = self.hasher.gen(&self.cfg.filename, &self.content_hash);
+        self.report_captures(
+          &collector,
+          &hash,
+          n.as_method()
+            .unwrap()
+            .key
+            .as_ident()
+            .map(|key| key.sym.as_ref()),
+          n.span(),
+        );
         let collect_main_thread = self.defines_collector.is_some();
         let collected_hash = collect_main_thread.then(|| hash.clone());
         let m = n.as_method().unwrap().clone();
@@ -247,6 +275,12 @@ impl VisitMut for WorkletVisitor {
           || collector.has_extracted_js_fns();
 
         let hash = self.hasher.gen(&self.cfg.filename, &self.content_hash);
+        self.report_captures(
+          &collector,
+          &hash,
+          p.key.as_ident().map(|key| key.sym.as_ref()),
+          p.span,
+        );
         let collect_main_thread = self.defines_collector.is_some();
         let collected_hash = collect_main_thread.then(|| hash.clone());
         let (worklet_object_expr, register_worklet_stmt, main_thread_stmt) =
@@ -341,6 +375,12 @@ impl VisitMut for WorkletVisitor {
     n.visit_mut_with(&mut collector);
 
     let hash = self.hasher.gen(&self.cfg.filename, &self.content_hash);
+    self.report_captures(
+      &collector,
+      &hash,
+      Some(n.as_fn_decl().unwrap().ident.sym.as_ref()),
+      n.span(),
+    );
     let collect_main_thread = self.defines_collector.is_some();
     let collected_hash = collect_main_thread.then(|| hash.clone());
     let (worklet_object_expr, register_worklet_stmt, main_thread_stmt) = StmtGen::transform_worklet(
@@ -397,6 +437,7 @@ impl VisitMut for WorkletVisitor {
         n.visit_mut_with(&mut collector);
 
         let hash = self.hasher.gen(&self.cfg.filename, &self.content_hash);
+        self.report_captures(&collector, &hash, None, n.span());
         let collect_main_thread = self.defines_collector.is_some();
         let collected_hash = collect_main_thread.then(|| hash.clone());
         let (worklet_object_expr, register_worklet_stmt, main_thread_stmt) =
@@ -462,6 +503,16 @@ impl VisitMut for WorkletVisitor {
         n.visit_mut_with(&mut collector);
 
         let hash = self.hasher.gen(&self.cfg.filename, &self.content_hash);
+        self.report_captures(
+          &collector,
+          &hash,
+          n.as_fn_expr()
+            .unwrap()
+            .ident
+            .as_ref()
+            .map(|ident| ident.sym.as_ref()),
+          n.span(),
+        );
         let collect_main_thread = self.defines_collector.is_some();
         let collected_hash = collect_main_thread.then(|| hash.clone());
         let (worklet_object_expr, register_worklet_stmt, main_thread_stmt) =
@@ -544,6 +595,19 @@ impl VisitMut for WorkletVisitor {
       .visit_mut_with(&mut collector);
 
     let hash = self.hasher.gen(&self.cfg.filename, &self.content_hash);
+    self.report_captures(
+      &collector,
+      &hash,
+      n.as_export_default_decl()
+        .unwrap()
+        .decl
+        .as_fn_expr()
+        .unwrap()
+        .ident
+        .as_ref()
+        .map(|ident| ident.sym.as_ref()),
+      n.span(),
+    );
     let collect_main_thread = self.defines_collector.is_some();
     let collected_hash = collect_main_thread.then(|| hash.clone());
     let (worklet_object_expr, register_worklet_stmt, main_thread_stmt) = StmtGen::transform_worklet(
@@ -584,6 +648,13 @@ impl VisitMut for WorkletVisitor {
     n.visit_mut_children_with(self);
   }
 
+  fn visit_mut_script(&mut self, n: &mut Script) {
+    if self.reports_collector.is_some() || self.cfg.capture_lint.is_some() {
+      self.capture_bindings = CaptureBindings::collect(n);
+    }
+    n.visit_mut_children_with(self);
+  }
+
   fn visit_mut_module(&mut self, n: &mut Module) {
     // First process imports to detect shared-runtime modules
     for item in &n.body {
@@ -608,6 +679,10 @@ impl VisitMut for WorkletVisitor {
       }
     }
 
+    if self.reports_collector.is_some() || self.cfg.capture_lint.is_some() {
+      self.capture_bindings = CaptureBindings::collect(n);
+    }
+
     n.visit_mut_children_with(self);
 
     // Add global loadWorkletRuntime call if needed
@@ -768,6 +843,8 @@ impl WorkletVisitor {
       worklet_runtime_loaded: false,
       worklet_runtime_loaded_ident: private_ident!("__workletRuntimeLoaded"),
       defines_collector: None,
+      reports_collector: None,
+      capture_bindings: CaptureBindings::default(),
     }
   }
 
@@ -776,6 +853,34 @@ impl WorkletVisitor {
     self
   }
 
+  pub fn with_reports_collector(mut self, collector: WorkletReportsCollector) -> Self {
+    self.reports_collector = Some(collector);
+    self
+  }
+
+  fn report_captures(
+    &self,
+    collector: &ExtractingIdentsCollector,
+    hash: &str,
+    name: Option<&str>,
+    span: Span,
+  ) {
+    if self.reports_collector.is_none() && self.cfg.capture_lint.is_none() {
+      return;
+    }
+    let report = report_captures(
+      collector,
+      &self.capture_bindings,
+      hash,
+      name,
+      span,
+      self.cfg.capture_lint.as_ref(),
+    );
+    if let Some(reports_collector) = &self.reports_collector {
+      reports_collector.borrow_mut().push(report);
+    }
+  }
+
   fn collect_worklet_define(
     &mut self,
     hash: Option<String>,
@@ -846,6 +951,8 @@ mod tests {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -875,6 +982,8 @@ function worklet(event: Event) {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -904,6 +1013,8 @@ function worklet(event: Event) {
           target: TransformTarget::MIXED,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -933,6 +1044,8 @@ function worklet(event: Event) {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -962,6 +1075,8 @@ function worklet(event: Event) {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1001,6 +1116,8 @@ function X(event) {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1040,6 +1157,8 @@ function X(event) {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1074,6 +1193,8 @@ function worklet(event: Event) {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1108,6 +1229,8 @@ function worklet(event: Event) {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1142,6 +1265,8 @@ function worklet(event: Event) {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1175,6 +1300,8 @@ function worklet(event: Event) {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1211,6 +1338,8 @@ import { sharedRuntime as sr } from './utils.js' with {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1244,6 +1373,8 @@ function worklet(event: Event) {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1275,6 +1406,8 @@ function Y(event) {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1308,6 +1441,8 @@ function App() {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1335,6 +1470,8 @@ let X = (event) => {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1362,6 +1499,8 @@ let X = function (event) {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1393,6 +1532,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1424,6 +1565,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1454,6 +1597,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1484,6 +1629,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1514,6 +1661,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1545,6 +1694,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1576,6 +1727,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1606,6 +1759,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1636,6 +1791,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1666,6 +1823,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1698,6 +1857,8 @@ function X() {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1735,6 +1896,8 @@ function A() {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1771,6 +1934,8 @@ class Bpp extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1801,6 +1966,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1829,6 +1996,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1859,6 +2028,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1889,6 +2060,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: Some(vec!["myCustomGlobal".to_string()]),
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1920,6 +2093,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1952,6 +2127,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -1981,6 +2158,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2010,6 +2189,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2050,6 +2231,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2090,6 +2273,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2119,6 +2304,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2147,6 +2334,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2175,6 +2364,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2227,6 +2418,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2254,6 +2447,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2281,6 +2476,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2319,6 +2516,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2360,6 +2559,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2401,6 +2602,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2444,6 +2647,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2487,6 +2692,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2593,6 +2800,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2699,6 +2908,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2729,6 +2940,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2758,6 +2971,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2787,6 +3002,8 @@ class App extends Component {
           target: TransformTarget::JS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2816,6 +3033,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2846,6 +3065,8 @@ class App extends Component {
           target: TransformTarget::LEPUS,
           custom_global_ident_names: None,
           runtime_pkg: "@lynx-js/react".into(),
+          capture_lint: None,
+          capture_report: None,
         }
       )),
       hygiene()
@@ -2860,4 +3081,152 @@ class App extends Component {
       }
     "#
   );
+
+  fn transform_with_reports(
+    input: &str,
+    capture_lint: Option<crate::WorkletCaptureLintConfig>,
+  ) -> (Vec<crate::WorkletCaptureReport>, Vec<String>) {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use swc_core::common::errors::{DiagnosticBuilder, Emitter, Handler, HANDLER};
+    use swc_core::common::{FileName, Globals, SourceMap, GLOBALS};
+    use swc_core::ecma::ast::Pass;
+    use swc_core::ecma::parser::{lexer::Lexer, Parser, StringInput};
+
+    struct DiagnosticCollector {
+      messages: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Emitter for DiagnosticCollector {
+      fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        self.messages.lock().unwrap().push(db.message().to_string());
+      }
+    }
+
+    GLOBALS.set(&Globals::new(), || {
+      let cm: Arc<SourceMap> = Arc::new(SourceMap::default());
+      let fm = cm.new_source_file(FileName::Anon.into(), input.to_string());
+      let diagnostics = Arc::new(Mutex::new(vec![]));
+      let handler = Handler::with_emitter(
+        true,
+        false,
+        Box::new(DiagnosticCollector {
+          messages: diagnostics.clone(),
+        }),
+      );
+      let lexer = Lexer::new(
+        Syntax::Es(Default::default()),
+        Default::default(),
+        StringInput::from(&*fm),
+        None,
+      );
+      let mut program = Parser::new_from(lexer)
+        .parse_program()
+        .expect("Failed to parse module");
+
+      let reports = Rc::new(RefCell::new(vec![]));
+      let visitor = WorkletVisitor::new(
+        TransformMode::Test,
+        WorkletVisitorConfig {
+          filename: "index.js".into(),
+          target: TransformTarget::LEPUS,
+          custom_global_ident_names: None,
+          runtime_pkg: "@lynx-js/react".into(),
+          capture_lint,
+          capture_report: Some(true),
+        },
+      )
+      .with_reports_collector(reports.clone());
+      HANDLER.set(&handler, || {
+        (
+          resolver(Mark::new(), Mark::new(), false),
+          visit_mut_pass(visitor),
+        )
+          .process(&mut program);
+      });
+
+      let reports = reports.borrow_mut().drain(..).collect();
+      let messages = diagnostics.lock().unwrap().clone();
+      (reports, messages)
+    })
+  }
+
+  #[test]
+  fn should_report_captures_of_each_worklet() {
+    let (reports, messages) = transform_with_reports(
+      r#"
+const theme = { colors: { primary: "red", secondary: "blue" }, spacing: 4 };
+const cache = new Map();
+function onTapBackground() {}
+function handleTap(event) {
+  "main thread";
+  console.log(theme.colors.primary, cache.size, this.count);
+  runOnBackground(onTapBackground)();
+}
+export const onScroll = () => {
+  "main thread";
+  cache.clear();
+  return cache;
+};
+      "#,
+      None,
+    );
+
+    assert!(messages.is_empty());
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].name.as_deref(), Some("handleTap"));
+    assert_eq!(
+      reports[0].captures,
+      vec!["theme.colors.primary", "cache.size"]
+    );
+    assert_eq!(reports[0].this_captures, vec!["this.count"]);
+    assert_eq!(reports[0].js_fns.len(), 1);
+    assert_eq!(reports[0].js_fns[0].id, "_jsFn1");
+    assert_eq!(
+      reports[0].js_fns[0].name.as_deref(),
+      Some("onTapBackground")
+    );
+    assert!(reports[0].non_serializable.is_empty());
+    // `themecolorsprimary`, the source of `"red"`, `cachesize`, `count` and `_jsFn1`
+    assert_eq!(reports[0].estimated_payload_bytes, 18 + 5 + 9 + 5 + 6);
+    assert_eq!(reports[1].name, None);
+    assert_eq!(reports[1].captures, vec!["cache"]);
+    assert_eq!(reports[1].non_serializable, vec!["cache"]);
+    assert_ne!(reports[0].worklet_id, reports[1].worklet_id);
+  }
+
+  #[test]
+  fn should_warn_about_non_serializable_and_large_captures() {
+    let (reports, messages) = transform_with_reports(
+      r#"
+class Store {}
+const seen = new Set();
+const ref = useRef(null);
+const table = ["a very long string that makes the captured payload large"];
+function helper() {}
+function mainThreadHelper() {
+  "main thread";
+}
+function handleTap() {
+  "main thread";
+  console.log(Store, seen, ref.current, table, helper, mainThreadHelper, Store.name);
+}
+      "#,
+      Some(crate::WorkletCaptureLintConfig {
+        max_payload_bytes: Some(32),
+      }),
+    );
+
+    assert_eq!(
+      reports[1].non_serializable,
+      vec!["Store", "seen", "ref", "helper"]
+    );
+    assert_eq!(messages.len(), 5);
+    assert!(messages[0].starts_with("`Store` is a class"));
+    assert!(messages[1].starts_with("`seen` is a Set"));
+    assert!(messages[2].starts_with("`ref` is a background ref"));
+    assert!(messages[3].starts_with("`helper` is a function"));
+    assert!(messages[4].starts_with("This main thread function captures about"));
+  }
 }
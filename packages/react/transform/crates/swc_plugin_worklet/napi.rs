@@ -3,11 +3,39 @@ use napi_derive::napi;
 use swc_core::ecma::ast::*;
 use swc_core::ecma::visit::{noop_visit_mut_type, VisitMut};
 
-use crate::{WorkletVisitor as CoreVisitor, WorkletVisitorConfig as CoreConfig};
+use crate::{
+  WorkletCaptureLintConfig as CoreCaptureLintConfig, WorkletCaptureReport as CoreCaptureReport,
+  WorkletJsFnReport as CoreJsFnReport, WorkletReportsCollector, WorkletVisitor as CoreVisitor,
+  WorkletVisitorConfig as CoreConfig,
+};
 use swc_plugins_shared::{
   defines::DefinesCollector, target_napi::TransformTarget, transform_mode_napi::TransformMode,
 };
 
+#[derive(Clone, Debug, Default)]
+#[napi(object)]
+pub struct WorkletCaptureLintConfig {
+  /// Warn when the estimated payload of a worklet is larger than this many bytes.
+  /// Defaults to 8 KiB.
+  pub max_payload_bytes: Option<u32>,
+}
+
+impl From<WorkletCaptureLintConfig> for CoreCaptureLintConfig {
+  fn from(val: WorkletCaptureLintConfig) -> Self {
+    CoreCaptureLintConfig {
+      max_payload_bytes: val.max_payload_bytes,
+    }
+  }
+}
+
+impl From<CoreCaptureLintConfig> for WorkletCaptureLintConfig {
+  fn from(val: CoreCaptureLintConfig) -> Self {
+    WorkletCaptureLintConfig {
+      max_payload_bytes: val.max_payload_bytes,
+    }
+  }
+}
+
 #[derive(Clone, Debug)]
 #[napi(object)]
 pub struct WorkletVisitorConfig {
@@ -24,6 +52,14 @@ pub struct WorkletVisitorConfig {
   #[napi(ts_type = "'LEPUS' | 'JS' | 'MIXED'")]
   pub target: TransformTarget,
   pub runtime_pkg: String,
+  /// @public
+  /// Warns about captured values that cannot be sent to the main thread, such as classes,
+  /// functions, `Map`/`Set` instances and background refs, and about worklets capturing
+  /// a large payload.
+  pub capture_lint: Option<WorkletCaptureLintConfig>,
+  /// @public
+  /// Returns what each worklet captures as `workletReports` of the transform output.
+  pub capture_report: Option<bool>,
 }
 
 impl Default for WorkletVisitorConfig {
@@ -33,6 +69,8 @@ impl Default for WorkletVisitorConfig {
       target: TransformTarget::LEPUS,
       custom_global_ident_names: None,
       runtime_pkg: "NoDiff".into(),
+      capture_lint: None,
+      capture_report: None,
     }
   }
 }
@@ -44,6 +82,8 @@ impl From<WorkletVisitorConfig> for CoreConfig {
       target: val.target.into(),
       custom_global_ident_names: val.custom_global_ident_names,
       runtime_pkg: val.runtime_pkg,
+      capture_lint: val.capture_lint.map(Into::into),
+      capture_report: val.capture_report,
     }
   }
 }
@@ -55,6 +95,57 @@ impl From<CoreConfig> for WorkletVisitorConfig {
       target: val.target.into(),
       custom_global_ident_names: val.custom_global_ident_names,
       runtime_pkg: val.runtime_pkg,
+      capture_lint: val.capture_lint.map(Into::into),
+      capture_report: val.capture_report,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct WorkletJsFnReport {
+  /// The key of the function in `_jsFn`, e.g. `_jsFn1`.
+  pub id: String,
+  /// The background function passed to `runOnBackground`, when it is an identifier or a member path.
+  pub name: Option<String>,
+}
+
+impl From<CoreJsFnReport> for WorkletJsFnReport {
+  fn from(val: CoreJsFnReport) -> Self {
+    WorkletJsFnReport {
+      id: val.id,
+      name: val.name,
+    }
+  }
+}
+
+/// What a worklet takes with it across threads.
+#[napi(object)]
+pub struct WorkletCaptureReport {
+  /// The `_wkltId` of the worklet.
+  pub worklet_id: String,
+  pub name: Option<String>,
+  /// The captured values in `_c`, e.g. `a.b`.
+  pub captures: Vec<String>,
+  /// The captured properties of `this`, e.g. `this.a`.
+  pub this_captures: Vec<String>,
+  pub js_fns: Vec<WorkletJsFnReport>,
+  /// The captures that are bound to classes, functions, collections or background refs.
+  pub non_serializable: Vec<String>,
+  /// The size of the property names plus the source size of the captured literals.
+  /// Values only known at runtime count as their names, so this is a lower bound.
+  pub estimated_payload_bytes: u32,
+}
+
+impl From<CoreCaptureReport> for WorkletCaptureReport {
+  fn from(val: CoreCaptureReport) -> Self {
+    WorkletCaptureReport {
+      worklet_id: val.worklet_id,
+      name: val.name,
+      captures: val.captures,
+      this_captures: val.this_captures,
+      js_fns: val.js_fns.into_iter().map(Into::into).collect(),
+      non_serializable: val.non_serializable,
+      estimated_payload_bytes: val.estimated_payload_bytes,
     }
   }
 }
@@ -92,6 +183,10 @@ impl VisitMut for WorkletVisitor {
     self.inner.visit_mut_module_items(n);
   }
 
+  fn visit_mut_script(&mut self, n: &mut Script) {
+    self.inner.visit_mut_script(n);
+  }
+
   fn visit_mut_module(&mut self, n: &mut Module) {
     self.inner.visit_mut_module(n);
   }
@@ -108,6 +203,11 @@ impl WorkletVisitor {
     self
   }
 
+  pub fn with_reports_collector(mut self, collector: WorkletReportsCollector) -> Self {
+    self.inner.reports_collector = Some(collector);
+    self
+  }
+
   pub fn new(mode: TransformMode, cfg: WorkletVisitorConfig) -> Self {
     Self {
       inner: CoreVisitor::new(mode.into(), cfg.into()),
@@ -0,0 +1,355 @@
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+use swc_core::common::{errors::HANDLER, Span, Spanned};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::visit::{noop_visit_type, Visit, VisitWith};
+
+use crate::extract_ident::ExtractingIdentsCollector;
+use crate::worklet_type::WorkletType;
+
+const DEFAULT_MAX_PAYLOAD_BYTES: u32 = 8 * 1024;
+
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkletCaptureLintConfig {
+  /// Warn when the estimated payload of a worklet is larger than this many bytes.
+  /// Defaults to 8 KiB.
+  pub max_payload_bytes: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkletJsFnReport {
+  /// The key of the function in `_jsFn`, e.g. `_jsFn1`.
+  pub id: String,
+  /// The background function passed to `runOnBackground`, when it is an identifier or a member path.
+  pub name: Option<String>,
+}
+
+/*
+ * What a worklet takes with it across threads.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkletCaptureReport {
+  /// The `_wkltId` of the worklet.
+  pub worklet_id: String,
+  pub name: Option<String>,
+  /// The captured values in `_c`, e.g. `a.b`.
+  pub captures: Vec<String>,
+  /// The captured properties of `this`, e.g. `this.a`.
+  pub this_captures: Vec<String>,
+  pub js_fns: Vec<WorkletJsFnReport>,
+  /// The captures that are bound to classes, functions, collections or background refs.
+  pub non_serializable: Vec<String>,
+  /// The size of the property names plus the source size of the captured literals.
+  /// Values only known at runtime count as their names, so this is a lower bound.
+  pub estimated_payload_bytes: u32,
+}
+
+pub type WorkletReportsCollector = Rc<RefCell<Vec<WorkletCaptureReport>>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NonSerializableKind {
+  Class,
+  Function,
+  Collection(&'static str),
+  BackgroundRef,
+}
+
+impl NonSerializableKind {
+  fn warning(&self, name: &str) -> String {
+    match self {
+      NonSerializableKind::Class => format!(
+        "`{name}` is a class and cannot be sent to the main thread. Capture plain objects, arrays or primitives instead."
+      ),
+      NonSerializableKind::Function => format!(
+        "`{name}` is a function and cannot be sent to the main thread. Mark it as a \"main thread\" function, or call it with `runOnBackground`."
+      ),
+      NonSerializableKind::Collection(collection) => format!(
+        "`{name}` is a {collection} and cannot be sent to the main thread. Capture plain objects, arrays or primitives instead."
+      ),
+      NonSerializableKind::BackgroundRef => format!(
+        "`{name}` is a background ref, its `current` is not kept in sync with the main thread. Use `useMainThreadRef` for values and elements used on the main thread."
+      ),
+    }
+  }
+}
+
+enum CapturedBinding {
+  NonSerializable(NonSerializableKind),
+  Literal(LiteralSpan),
+}
+
+/*
+ * The source span of a captured literal, and of the values of its object properties
+ * so that a captured member path like `a.b` is measured without keeping the expression.
+ */
+struct LiteralSpan {
+  span: Span,
+  props: FxHashMap<String, LiteralSpan>,
+}
+
+impl LiteralSpan {
+  fn new(expr: &Expr) -> Self {
+    let mut props = FxHashMap::default();
+    if let Some(object) = expr.unwrap_parens().as_object() {
+      for prop in object.props.iter().filter_map(|prop| prop.as_prop()) {
+        if let Prop::KeyValue(KeyValueProp { key, value }) = &**prop {
+          if let Some(name) = prop_name(key) {
+            // later properties override earlier ones, like at runtime
+            props.insert(name, LiteralSpan::new(value));
+          }
+        }
+      }
+    }
+    LiteralSpan {
+      span: expr.span(),
+      props,
+    }
+  }
+
+  fn at(&self, path: &[String]) -> Option<&LiteralSpan> {
+    let Some((name, rest)) = path.split_first() else {
+      return Some(self);
+    };
+    self.props.get(name)?.at(rest)
+  }
+}
+
+/*
+ * The bindings of a module whose initializer tells whether capturing them is safe,
+ * and how large they are.
+ */
+#[derive(Default)]
+pub struct CaptureBindings {
+  bindings: FxHashMap<Id, CapturedBinding>,
+}
+
+impl CaptureBindings {
+  pub fn collect<N: VisitWith<Self>>(program: &N) -> Self {
+    let mut bindings = CaptureBindings::default();
+    program.visit_with(&mut bindings);
+    bindings
+  }
+
+  fn non_serializable_kind(&self, ident: &Ident) -> Option<NonSerializableKind> {
+    match self.bindings.get(&ident.to_id()) {
+      Some(CapturedBinding::NonSerializable(kind)) => Some(*kind),
+      _ => None,
+    }
+  }
+
+  fn literal_size(&self, ident: &Ident, path: &[String]) -> u32 {
+    let Some(CapturedBinding::Literal(literal)) = self.bindings.get(&ident.to_id()) else {
+      return 0;
+    };
+    literal.at(path).map_or(0, |literal| {
+      literal.span.hi.0.saturating_sub(literal.span.lo.0)
+    })
+  }
+}
+
+impl Visit for CaptureBindings {
+  noop_visit_type!();
+
+  fn visit_var_declarator(&mut self, n: &VarDeclarator) {
+    if let (Pat::Ident(name), Some(init)) = (&n.name, &n.init) {
+      if let Some(binding) = classify(init) {
+        self.bindings.insert(name.to_id(), binding);
+      }
+    }
+    n.visit_children_with(self);
+  }
+
+  fn visit_class_decl(&mut self, n: &ClassDecl) {
+    self.bindings.insert(
+      n.ident.to_id(),
+      CapturedBinding::NonSerializable(NonSerializableKind::Class),
+    );
+    n.visit_children_with(self);
+  }
+
+  fn visit_fn_decl(&mut self, n: &FnDecl) {
+    if !is_worklet_body(n.function.body.as_ref()) {
+      self.bindings.insert(
+        n.ident.to_id(),
+        CapturedBinding::NonSerializable(NonSerializableKind::Function),
+      );
+    }
+    n.visit_children_with(self);
+  }
+}
+
+fn classify(expr: &Expr) -> Option<CapturedBinding> {
+  let mut expr = expr;
+  loop {
+    expr = match expr {
+      Expr::Paren(ParenExpr { expr, .. })
+      | Expr::TsAs(TsAsExpr { expr, .. })
+      | Expr::TsConstAssertion(TsConstAssertion { expr, .. })
+      | Expr::TsSatisfies(TsSatisfiesExpr { expr, .. })
+      | Expr::TsNonNull(TsNonNullExpr { expr, .. }) => expr,
+      _ => break,
+    };
+  }
+  let kind = match expr {
+    Expr::Class(_) => NonSerializableKind::Class,
+    Expr::Fn(FnExpr { function, .. }) if !is_worklet_body(function.body.as_ref()) => {
+      NonSerializableKind::Function
+    }
+    Expr::Arrow(ArrowExpr { body, .. }) if !is_worklet_body(body.as_block_stmt()) => {
+      NonSerializableKind::Function
+    }
+    Expr::New(NewExpr { callee, .. }) => {
+      let collection = match &**callee {
+        Expr::Ident(ident) => match ident.sym.as_ref() {
+          "Map" => "Map",
+          "Set" => "Set",
+          "WeakMap" => "WeakMap",
+          "WeakSet" => "WeakSet",
+          _ => return None,
+        },
+        _ => return None,
+      };
+      NonSerializableKind::Collection(collection)
+    }
+    Expr::Call(CallExpr {
+      callee: Callee::Expr(callee),
+      ..
+    }) if callee
+      .as_ident()
+      .is_some_and(|ident| ident.sym == "useRef" || ident.sym == "createRef") =>
+    {
+      NonSerializableKind::BackgroundRef
+    }
+    Expr::Object(_) | Expr::Array(_) | Expr::Lit(_) | Expr::Tpl(_) => {
+      return Some(CapturedBinding::Literal(LiteralSpan::new(expr)));
+    }
+    _ => return None,
+  };
+  Some(CapturedBinding::NonSerializable(kind))
+}
+
+fn is_worklet_body(body: Option<&BlockStmt>) -> bool {
+  let Some(Stmt::Expr(ExprStmt { expr, .. })) = body.and_then(|body| body.stmts.first()) else {
+    return false;
+  };
+  match &**expr {
+    Expr::Lit(Lit::Str(str)) => {
+      WorkletType::from_directive(str.value.to_string_lossy().into_owned()).is_some()
+    }
+    _ => false,
+  }
+}
+
+fn prop_name(key: &PropName) -> Option<String> {
+  match key {
+    PropName::Ident(id) => Some(id.sym.to_string()),
+    PropName::Str(str) => Some(str.value.to_string_lossy().into_owned()),
+    _ => None,
+  }
+}
+
+fn expr_path(expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Ident(ident) => Some(ident.sym.to_string()),
+    Expr::This(_) => Some("this".into()),
+    Expr::Member(MemberExpr {
+      obj,
+      prop: MemberProp::Ident(prop),
+      ..
+    }) => expr_path(obj).map(|obj| format!("{obj}.{}", prop.sym)),
+    _ => None,
+  }
+}
+
+fn path_size(path: &[String]) -> u32 {
+  path.iter().map(|name| name.len() as u32).sum()
+}
+
+/*
+ * Builds the report of a worklet from its collected captures, before they are taken
+ * by `StmtGen::transform_worklet`, and warns about them if `lint` is set.
+ */
+pub fn report_captures(
+  collector: &ExtractingIdentsCollector,
+  bindings: &CaptureBindings,
+  worklet_id: &str,
+  name: Option<&str>,
+  span: Span,
+  lint: Option<&WorkletCaptureLintConfig>,
+) -> WorkletCaptureReport {
+  let mut estimated_payload_bytes = 0;
+  let mut captures = vec![];
+  let mut non_serializable = vec![];
+  for path in collector.value_paths() {
+    let root = collector.idents().iter().find(|ident| ident.sym == path[0]);
+    if let Some(root) = root {
+      estimated_payload_bytes += bindings.literal_size(root, &path[1..]);
+      // a property of a class or a collection is fine, only the whole binding is not
+      let kind = bindings
+        .non_serializable_kind(root)
+        .filter(|_| path.len() == 1);
+      if let Some(kind) = kind {
+        if lint.is_some() {
+          HANDLER.with(|handler| {
+            handler
+              .struct_span_warn(root.span, &kind.warning(&path[0]))
+              .emit()
+          });
+        }
+        non_serializable.push(path[0].clone());
+      }
+    }
+    estimated_payload_bytes += path_size(&path);
+    captures.push(path.join("."));
+  }
+
+  let this_captures = collector
+    .this_paths()
+    .into_iter()
+    .map(|path| {
+      estimated_payload_bytes += path_size(&path);
+      format!("this.{}", path.join("."))
+    })
+    .collect();
+
+  let js_fns = collector
+    .js_fns()
+    .iter()
+    .map(|(id, expr)| {
+      estimated_payload_bytes += id.sym.len() as u32;
+      WorkletJsFnReport {
+        id: id.sym.to_string(),
+        name: expr_path(expr),
+      }
+    })
+    .collect();
+
+  if let Some(lint) = lint {
+    let max_payload_bytes = lint.max_payload_bytes.unwrap_or(DEFAULT_MAX_PAYLOAD_BYTES);
+    if estimated_payload_bytes > max_payload_bytes {
+      HANDLER.with(|handler| {
+        handler
+          .struct_span_warn(
+            span,
+            &format!(
+              "This main thread function captures about {estimated_payload_bytes} bytes, more than {max_payload_bytes} bytes. Captured values are copied every time the function is sent to the main thread."
+            ),
+          )
+          .emit()
+      });
+    }
+  }
+
+  WorkletCaptureReport {
+    worklet_id: worklet_id.to_string(),
+    name: name.map(str::to_string),
+    captures,
+    this_captures,
+    js_fns,
+    non_serializable,
+    estimated_payload_bytes,
+  }
+}
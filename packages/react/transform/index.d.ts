@@ -406,6 +406,51 @@ export interface CssScopeVisitorConfig {
   /** @public */
   filename: string
 }
+/** @internal */
+export interface StaticInlineStyleVisitorConfig {
+  /**
+   * The prefix of the generated class names.
+   *
+   * @defaultValue `'lynx-inline-'`
+   */
+  classNamePrefix?: string
+}
+/**
+ * The stylesheet of the classes that replaced static inline styles.
+ * @internal
+ */
+export interface ExtractedStyleSheet {
+  /** The CSS scope of the rules, the `@jsxCSSId` of the module if it is scoped. */
+  cssId?: number
+  code: string
+}
+export interface ProfileOptions {
+  /** Also return the phases as Chrome trace events, see `TransformProfile.chromeTrace`. */
+  chromeTrace?: boolean
+}
+export interface PhaseProfile {
+  /** `parse`, `print`, or the name of a pass, e.g. `snapshot`. */
+  name: string
+  /** Milliseconds since the transform started. */
+  startMs: number
+  durationMs: number
+  /** The number of AST nodes before the phase. */
+  nodesBefore?: number
+  /** The number of AST nodes after the phase. */
+  nodesAfter?: number
+}
+/** Where the time of a transform went. Durations are 0 in the wasm build, which has no clock. */
+export interface TransformProfile {
+  /** `parse`, every enabled pass in the order they run, then `print`. */
+  phases: Array<PhaseProfile>
+  totalMs: number
+  /** The size of the emitted `code` in bytes. */
+  codeBytes: number
+  /** The size of the emitted `map` in bytes. */
+  mapBytes?: number
+  /** The phases as a Chrome trace-event JSON, to be opened in Perfetto or `chrome://tracing`. */
+  chromeTrace?: string
+}
 /**
  * {@inheritdoc PluginReactLynxOptions.defineDCE}
  * @public
@@ -643,6 +688,8 @@ export interface JsxTransformerConfig {
   isExternalBundle?: boolean
   /** @internal */
   legacySlot?: boolean
+  /** @internal */
+  dedupeAcrossModules?: boolean
 }
 /** @internal */
 export interface ElementTemplateConfig {
@@ -661,6 +708,13 @@ export interface ElementTemplateConfig {
   /** @internal */
   isExternalBundle?: boolean
 }
+export interface WorkletCaptureLintConfig {
+  /**
+   * Warn when the estimated payload of a worklet is larger than this many bytes.
+   * Defaults to 8 KiB.
+   */
+  maxPayloadBytes?: number
+}
 export interface WorkletVisitorConfig {
   /**
    * @public
@@ -676,6 +730,42 @@ export interface WorkletVisitorConfig {
   /** @internal */
   target: 'LEPUS' | 'JS' | 'MIXED'
   runtimePkg: string
+  /**
+   * @public
+   * Warns about captured values that cannot be sent to the main thread, such as classes,
+   * functions, `Map`/`Set` instances and background refs, and about worklets capturing
+   * a large payload.
+   */
+  captureLint?: WorkletCaptureLintConfig
+  /**
+   * @public
+   * Returns what each worklet captures as `workletReports` of the transform output.
+   */
+  captureReport?: boolean
+}
+export interface WorkletJsFnReport {
+  /** The key of the function in `_jsFn`, e.g. `_jsFn1`. */
+  id: string
+  /** The background function passed to `runOnBackground`, when it is an identifier or a member path. */
+  name?: string
+}
+/** What a worklet takes with it across threads. */
+export interface WorkletCaptureReport {
+  /** The `_wkltId` of the worklet. */
+  workletId: string
+  name?: string
+  /** The captured values in `_c`, e.g. `a.b`. */
+  captures: Array<string>
+  /** The captured properties of `this`, e.g. `this.a`. */
+  thisCaptures: Array<string>
+  jsFns: Array<WorkletJsFnReport>
+  /** The captures that are bound to classes, functions, collections or background refs. */
+  nonSerializable: Array<string>
+  /**
+   * The size of the property names plus the source size of the captured literals.
+   * Values only known at runtime count as their names, so this is a lower bound.
+   */
+  estimatedPayloadBytes: number
 }
 /**
  * Serializable rules for transforming builtin element attribute names.
@@ -749,9 +839,31 @@ export interface TransformNodiffOptions {
    * @experimental
    */
   experimental_transformBuiltinAttributeNames?: boolean | TransformBuiltinAttributeNamesOptions
+  /**
+   * Replace static `style={{...}}` objects of builtin elements with generated,
+   * content-hashed classes, returned as `extractedStyles`.
+   *
+   * Only objects whose values are all strings are extracted. The classes
+   * belong to the CSS scope of the module when `cssScope` scopes it.
+   *
+   * Extraction changes specificity: an inline style wins over every rule of
+   * the stylesheet, an extracted class does not. Elements with a class or an
+   * id are skipped, but rules like `.parent text` or `[attr] text` now beat
+   * the extracted style.
+   *
+   * No bundler plugin emits the classes yet, so the option stays internal.
+   *
+   * @internal
+   */
+  experimental_extractStaticInlineStyles?: boolean | StaticInlineStyleVisitorConfig
   /** @internal */
   inject?: boolean | InjectVisitorConfig
   inputSourceMap?: string
+  /**
+   * Return the wall time and the AST size of every pass as `profile`, to find
+   * which pass makes a build slow.
+   */
+  profile?: boolean | ProfileOptions
 }
 export interface TransformNodiffOutput {
   code: string
@@ -765,6 +877,18 @@ export interface TransformNodiffOutput {
   definesForSnapshot?: Array<Define>
   /** @internal */
   definesForWorklet?: Array<Define>
+  /**
+   * What each worklet of the module captures when `worklet.captureReport` is set,
+   * see `WorkletCaptureReport`.
+   */
+  workletReports?: Array<WorkletCaptureReport>
+  /**
+   * The classes that replaced static inline styles, see `experimental_extractStaticInlineStyles`.
+   * @internal
+   */
+  extractedStyles?: ExtractedStyleSheet
+  /** Where the time of the transform went, see `TransformNodiffOptions.profile`. */
+  profile?: TransformProfile
 }
 /**
  * @internal
@@ -795,6 +919,8 @@ export interface Define {
 export interface ElementTemplateAsset {
   /** @internal */
   templateId: string
+  /** The schema version of `compiledTemplate`, see `compiled-template.schema.json`. */
+  schemaVersion: number
   /** @internal */
   compiledTemplate: unknown
   /** @internal */
@@ -5,6 +5,7 @@
 extern crate napi_derive;
 mod bundle;
 mod esbuild;
+mod profile;
 mod swc_plugin_compat_post;
 mod swc_plugin_extract_str;
 mod swc_plugin_refresh;
@@ -53,7 +54,11 @@ use swc_core::{
 use swc_plugin_background_only::BackgroundOnlyVisitor;
 use swc_plugin_compat::napi::{CompatVisitor, CompatVisitorConfig};
 use swc_plugin_compat_post::CompatPostVisitor;
-use swc_plugin_css_scope::napi::{CSSScopeVisitor, CSSScopeVisitorConfig};
+use swc_plugin_css_scope::napi::{
+  CSSScopeVisitor, CSSScopeVisitorConfig, ExtractedStyleSheet, StaticInlineStyleVisitor,
+  StaticInlineStyleVisitorConfig,
+};
+use swc_plugin_css_scope::ExtractedStylesCollector;
 use swc_plugin_define_dce::napi::DefineDCEVisitorConfig;
 use swc_plugin_directive_dce::napi::{DirectiveDCEVisitor, DirectiveDCEVisitorConfig};
 use swc_plugin_dynamic_import::napi::{DynamicImportVisitor, DynamicImportVisitorConfig};
@@ -74,7 +79,11 @@ use swc_plugin_transform_builtin_attribute_names::TransformBuiltinAttributeNames
 pub use swc_plugin_transform_builtin_attribute_names::{
   TransformBuiltinAttributeNamesMode, TransformBuiltinAttributeNamesOptions,
 };
-use swc_plugin_worklet::napi::{WorkletVisitor, WorkletVisitorConfig};
+use swc_plugin_worklet::napi::{WorkletCaptureReport, WorkletVisitor, WorkletVisitorConfig};
+use swc_plugin_worklet::WorkletReportsCollector;
+
+use crate::profile::{count_nodes, Profiler};
+pub use crate::profile::{PhaseProfile, ProfileOptions, TransformProfile};
 use swc_plugins_shared::defines::{DefineKind, DefinesCollector};
 use swc_plugins_shared::{
   engine_version::is_engine_version_ge,
@@ -245,9 +254,37 @@ pub struct TransformNodiffOptions {
   )]
   pub experimental_transform_builtin_attribute_names:
     Option<Either<bool, TransformBuiltinAttributeNamesOptions>>,
+  /**
+   * Replace static `style={{...}}` objects of builtin elements with generated,
+   * content-hashed classes, returned as `extractedStyles`.
+   *
+   * Only objects whose values are all strings are extracted. The classes
+   * belong to the CSS scope of the module when `cssScope` scopes it.
+   *
+   * Extraction changes specificity: an inline style wins over every rule of
+   * the stylesheet, an extracted class does not. Elements with a class or an
+   * id are skipped, but rules like `.parent text` or `[attr] text` now beat
+   * the extracted style.
+   *
+   * No bundler plugin emits the classes yet, so the option stays internal.
+   *
+   * @internal
+   */
+  #[napi(
+    js_name = "experimental_extractStaticInlineStyles",
+    ts_type = "boolean | StaticInlineStyleVisitorConfig"
+  )]
+  pub experimental_extract_static_inline_styles:
+    Option<Either<bool, StaticInlineStyleVisitorConfig>>,
   /// @internal
   pub inject: Option<Either<bool, InjectVisitorConfig>>,
   pub input_source_map: Option<String>,
+  /**
+   * Return the wall time and the AST size of every pass as `profile`, to find
+   * which pass makes a build slow.
+   */
+  #[napi(ts_type = "boolean | ProfileOptions")]
+  pub profile: Option<Either<bool, ProfileOptions>>,
 }
 
 impl Default for TransformNodiffOptions {
@@ -274,8 +311,10 @@ impl Default for TransformNodiffOptions {
       worklet: Either::A(false),
       dynamic_import: Some(Either::B(Default::default())),
       experimental_transform_builtin_attribute_names: None,
+      experimental_extract_static_inline_styles: None,
       inject: Some(Either::A(false)),
       input_source_map: None,
+      profile: None,
     }
   }
 }
@@ -320,6 +359,16 @@ pub struct TransformNodiffOutput {
   /// @internal
   #[napi(js_name = "definesForWorklet")]
   pub defines_for_worklet: Option<Vec<Define>>,
+  /// What each worklet of the module captures when `worklet.captureReport` is set,
+  /// see `WorkletCaptureReport`.
+  #[napi(js_name = "workletReports")]
+  pub worklet_reports: Option<Vec<WorkletCaptureReport>>,
+  /// The classes that replaced static inline styles, see `experimental_extractStaticInlineStyles`.
+  /// @internal
+  #[napi(js_name = "extractedStyles")]
+  pub extracted_styles: Option<ExtractedStyleSheet>,
+  /// Where the time of the transform went, see `TransformNodiffOptions.profile`.
+  pub profile: Option<TransformProfile>,
 }
 
 fn print_define(
@@ -450,7 +499,14 @@ fn transform_react_lynx_inner(
   let snapshot_ui_source_map_records: Rc<RefCell<Vec<SnapshotCoreUISourceMapRecord>>> =
     Rc::new(RefCell::new(vec![]));
 
+  let profiler = Profiler::new(match options.profile.clone() {
+    Some(Either::A(true)) => Some(Default::default()),
+    Some(Either::B(options)) => Some(options),
+    None | Some(Either::A(false)) => None,
+  });
+
   let result = GLOBALS.set(&Default::default(), || {
+    let parse_start = profiler.now();
     let program = c.parse_js(
       fm,
       &handler,
@@ -460,7 +516,11 @@ fn transform_react_lynx_inner(
       Some(&comments),
     );
     let program = match program {
-      Ok(program) => program,
+      Ok(program) => {
+        let nodes = profiler.is_enabled().then(|| count_nodes(&program));
+        profiler.record("parse", parse_start, None, nodes);
+        program
+      }
       Err(_) => {
         return TransformNodiffOutput {
           code: "".into(),
@@ -471,6 +531,9 @@ fn transform_react_lynx_inner(
           element_templates: None,
           defines_for_snapshot: None,
           defines_for_worklet: None,
+          worklet_reports: None,
+          extracted_styles: None,
+          profile: None,
         };
       }
     };
@@ -530,16 +593,20 @@ fn transform_react_lynx_inner(
       )
     };
 
+    let extracted_styles_collector: ExtractedStylesCollector = Default::default();
     let css_scope_plugin = match options.css_scope {
       Either::A(enabled) => Optional::new(
-        visit_mut_pass(CSSScopeVisitor::new(
-          CSSScopeVisitorConfig::default(),
-          Some(&comments),
-        )),
+        visit_mut_pass(
+          CSSScopeVisitor::new(CSSScopeVisitorConfig::default(), Some(&comments))
+            .with_extracted_styles_collector(extracted_styles_collector.clone()),
+        ),
         enabled,
       ),
       Either::B(config) => Optional::new(
-        visit_mut_pass(CSSScopeVisitor::new(config, Some(&comments))),
+        visit_mut_pass(
+          CSSScopeVisitor::new(config, Some(&comments))
+            .with_extracted_styles_collector(extracted_styles_collector.clone()),
+        ),
         true,
       ),
     };
@@ -641,6 +708,11 @@ fn transform_react_lynx_inner(
     );
 
     let defines_collector: DefinesCollector = Rc::new(RefCell::new(vec![]));
+    let worklet_reports_collector: WorkletReportsCollector = Rc::new(RefCell::new(vec![]));
+    let worklet_reports_enabled = matches!(
+      &options.worklet,
+      Either::B(config) if config.capture_report == Some(true)
+    );
 
     let snapshot_plugin = if use_snapshot_plugin {
       let transformer = SnapshotJSXTransformer::new(
@@ -746,6 +818,20 @@ fn transform_react_lynx_inner(
       ),
     };
 
+    let (static_inline_style_config, enable_static_inline_style) =
+      match options.experimental_extract_static_inline_styles.clone() {
+        Some(Either::B(config)) => (config, true),
+        Some(Either::A(enabled)) => (Default::default(), enabled),
+        None => (Default::default(), false),
+      };
+    let static_inline_style_plugin = Optional::new(
+      visit_mut_pass(StaticInlineStyleVisitor::new(
+        static_inline_style_config,
+        extracted_styles_collector.clone(),
+      )),
+      enable_static_inline_style,
+    );
+
     let transform_builtin_attribute_names = options
       .experimental_transform_builtin_attribute_names
       .clone();
@@ -798,16 +884,19 @@ fn transform_react_lynx_inner(
     let worklet_plugin = match options.worklet {
       Either::A(config) => {
         let visitor = WorkletVisitor::default().with_content_hash(content_hash);
-        let visitor =
-          visitor.with_defines_collector(defines_collector.clone());
+        let visitor = visitor.with_defines_collector(defines_collector.clone());
         Optional::new(visit_mut_pass(visitor), config)
       }
       Either::B(config) => {
         let visitor =
           WorkletVisitor::new(options.mode.unwrap_or(TransformMode::Production), config)
             .with_content_hash(content_hash);
-        let visitor =
-          visitor.with_defines_collector(defines_collector.clone());
+        let visitor = visitor.with_defines_collector(defines_collector.clone());
+        let visitor = if worklet_reports_enabled {
+          visitor.with_reports_collector(worklet_reports_collector.clone())
+        } else {
+          visitor
+        };
         Optional::new(visit_mut_pass(visitor), true)
       }
     };
@@ -841,46 +930,66 @@ fn transform_react_lynx_inner(
       ),
     };
 
+    let p = &profiler;
     let pass = (
-      &mut fixer(Some(&comments)),
-      resolver(unresolved_mark, top_level_mark, true),
-      typescript::typescript(
-        typescript::Config {
-          verbatim_module_syntax: false,
-          import_not_used_as_values: typescript::ImportsNotUsedAsValues::Remove,
-          ..Default::default()
-        },
-        unresolved_mark,
-        top_level_mark,
+      p.pass("fixer_pre", fixer(Some(&comments))),
+      p.pass("resolver_pre", resolver(unresolved_mark, top_level_mark, true)),
+      p.pass(
+        "typescript",
+        typescript::typescript(
+          typescript::Config {
+            verbatim_module_syntax: false,
+            import_not_used_as_values: typescript::ImportsNotUsedAsValues::Remove,
+            ..Default::default()
+          },
+          unresolved_mark,
+          top_level_mark,
+        ),
       ),
-      (background_only_plugin, dynamic_import_plugin),
-      refresh_plugin,
-      (compat_plugin, transform_builtin_attribute_names_plugin),
-      worklet_plugin,
-      css_scope_plugin,
       (
-        text_plugin,
-        list_plugin,
-        legacy_list_plugin,
-        snapshot_plugin,
-        element_template_plugin,
+        p.optional("background_only", background_only_plugin),
+        p.optional("dynamic_import", dynamic_import_plugin),
       ),
-      directive_dce_plugin,
-      define_dce_plugin,
-      simplify_pass_1, // do simplify after DCE above to make shake below works better
+      p.optional("refresh", refresh_plugin),
       (
-        shake_plugin,
-        simplify_pass,
-        react_transformer,
+        p.optional("compat", compat_plugin),
+        p.optional(
+          "transform_builtin_attribute_names",
+          transform_builtin_attribute_names_plugin,
+        ),
+      ),
+      p.optional("worklet", worklet_plugin),
+      // after `css_scope_plugin`, which records the CSS scope of the extracted classes
+      (
+        p.optional("css_scope", css_scope_plugin),
+        p.optional("static_inline_style", static_inline_style_plugin),
+      ),
+      (
+        p.optional("text", text_plugin),
+        p.optional("list", list_plugin),
+        p.optional("legacy_list", legacy_list_plugin),
+        p.optional("snapshot", snapshot_plugin),
+        p.optional("element_template", element_template_plugin),
+      ),
+      p.optional("directive_dce", directive_dce_plugin),
+      p.optional("define_dce", define_dce_plugin),
+      p.optional("simplify_pre_shake", simplify_pass_1), // do simplify after DCE above to make shake below works better
+      (
+        p.optional("shake", shake_plugin),
+        p.pass("simplify", simplify_pass),
+        p.optional("react", react_transformer),
         // TODO(hongzhiyuan.hzy): if `ident` we added above is correctly marked, this pass will be unnecessary
-        resolver(unresolved_mark, top_level_mark, true),
-        compat_post_plugin,
-        inject_plugin,
-        hygiene_with_config(Config {
-          top_level_mark,
-          ..Default::default()
-        }),
-        &mut fixer(Some(&comments)),
+        p.pass("resolver_post", resolver(unresolved_mark, top_level_mark, true)),
+        p.optional("compat_post", compat_post_plugin),
+        p.optional("inject", inject_plugin),
+        p.pass(
+          "hygiene",
+          hygiene_with_config(Config {
+            top_level_mark,
+            ..Default::default()
+          }),
+        ),
+        p.pass("fixer_post", fixer(Some(&comments))),
       ),
     );
 
@@ -888,6 +997,8 @@ fn transform_react_lynx_inner(
       HANDLER.set(&handler, || program.apply(pass))
     });
 
+    let print_start = profiler.now();
+    let nodes = profiler.is_enabled().then(|| count_nodes(&program));
     let result = c.print(
       &program,
       PrintArgs {
@@ -921,12 +1032,15 @@ fn transform_react_lynx_inner(
       },
     );
 
+    profiler.record("print", print_start, nodes, None);
+
     match result {
       Ok(result) => {
         // Drain after the whole SWC pass finishes: dynamic-component transforms
         // can discover multiple template assets while walking one module, and
         // the caller expects one stable array per transform invocation.
         let element_templates = take_element_templates(element_templates_collector);
+        let profile = profiler.finish(&options.filename, &result.code, result.map.as_deref());
 
         let mut define_errors: Vec<esbuild::PartialMessage> = vec![];
         let mut defines_for_snapshot: Vec<Define> = vec![];
@@ -974,6 +1088,16 @@ fn transform_react_lynx_inner(
           element_templates,
           defines_for_snapshot: Some(defines_for_snapshot),
           defines_for_worklet: Some(defines_for_worklet),
+          worklet_reports: worklet_reports_enabled.then(|| {
+            worklet_reports_collector
+              .borrow_mut()
+              .drain(..)
+              .map(Into::into)
+              .collect()
+          }),
+          extracted_styles: enable_static_inline_style
+            .then(|| extracted_styles_collector.take().into()),
+          profile,
         }
       }
       Err(_) => {
@@ -991,6 +1115,9 @@ fn transform_react_lynx_inner(
           element_templates,
           defines_for_snapshot: None,
           defines_for_worklet: None,
+          worklet_reports: None,
+          extracted_styles: None,
+          profile: None,
         };
       }
     }
@@ -1010,6 +1137,9 @@ fn transform_react_lynx_inner(
     element_templates: result.element_templates,
     defines_for_snapshot: result.defines_for_snapshot,
     defines_for_worklet: result.defines_for_worklet,
+    worklet_reports: result.worklet_reports,
+    extracted_styles: result.extracted_styles,
+    profile: result.profile,
   };
 
   r
@@ -1181,7 +1311,12 @@ mod tests {
   fn should_drain_partial_element_templates() {
     let collector = Rc::new(RefCell::new(vec![CoreElementTemplateAsset {
       template_id: "_et_test".into(),
-      compiled_template: serde_json::json!({ "kind": "element", "type": "view" }),
+      schema_version: swc_plugin_element_template::COMPILED_TEMPLATE_SCHEMA_VERSION,
+      compiled_template: swc_plugin_element_template::TemplateNode::Element {
+        tag: "view".into(),
+        attributes_array: vec![],
+        children: vec![],
+      },
       source_file: "test.js".into(),
     }]));
 
@@ -1194,4 +1329,108 @@ mod tests {
       "collector should be drained exactly once"
     );
   }
+  #[test]
+  fn should_extract_static_inline_styles_into_the_css_scope_of_the_module() {
+    let result = transform_react_lynx_inner(
+      r#"
+import './app.css'
+export const App = () => <view style={{ backgroundColor: "red" }}><text style={{ color }} /><text className="title" style={{ width: "1px" }} /></view>
+"#
+      .into(),
+      TransformNodiffOptions {
+        mode: Some(TransformMode::Test),
+        filename: "app.jsx".into(),
+        css_scope: Either::B(CSSScopeVisitorConfig {
+          mode: swc_plugin_css_scope::napi::CSSScope::All,
+          filename: "app.jsx".into(),
+        }),
+        snapshot: Some(Either::A(true)),
+        experimental_extract_static_inline_styles: Some(Either::A(true)),
+        ..Default::default()
+      },
+    );
+
+    let extracted_styles = result.extracted_styles.unwrap();
+    let class_name = extracted_styles.code.split('{').next().unwrap()[1..].to_string();
+    assert!(class_name.starts_with("lynx-inline-"));
+    assert_eq!(
+      extracted_styles.code,
+      format!(".{class_name}{{background-color:red}}\n")
+    );
+    // the snapshot is created in the CSS scope the classes belong to
+    let css_id = extracted_styles.css_id.unwrap();
+    assert!(result
+      .code
+      .contains(&format!(", {css_id}, globDynamicComponentEntry")));
+    assert!(result
+      .code
+      .contains(&format!("__SetClasses(el, \"{class_name}\")")));
+    // the dynamic style of `<text>` and the style of an element with a class stay inline
+    assert!(!result.code.contains("backgroundColor"));
+    assert_eq!(result.code.matches("__SetInlineStyles").count(), 2);
+  }
+
+  #[test]
+  fn should_profile_the_enabled_passes() {
+    let result = transform_react_lynx_inner(
+      "export const App = () => <view><text>Hello</text></view>".into(),
+      TransformNodiffOptions {
+        mode: Some(TransformMode::Test),
+        filename: "app.jsx".into(),
+        snapshot: Some(Either::A(true)),
+        profile: Some(Either::B(ProfileOptions {
+          chrome_trace: Some(true),
+        })),
+        ..Default::default()
+      },
+    );
+
+    let profile = result.profile.unwrap();
+    let names = profile
+      .phases
+      .iter()
+      .map(|phase| phase.name.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(names.first(), Some(&"parse"));
+    assert_eq!(names.last(), Some(&"print"));
+    assert!(names.contains(&"snapshot"));
+    // disabled passes are not profiled
+    assert!(!names.contains(&"element_template"));
+    assert!(!names.contains(&"inject"));
+    // every phase can be told apart by its name
+    let mut unique_names = names.clone();
+    unique_names.sort_unstable();
+    unique_names.dedup();
+    assert_eq!(unique_names.len(), names.len());
+    assert!(names.contains(&"fixer_pre") && names.contains(&"fixer_post"));
+
+    let snapshot = profile
+      .phases
+      .iter()
+      .find(|phase| phase.name == "snapshot")
+      .unwrap();
+    assert!(snapshot.nodes_before.unwrap() > 0);
+    assert!(snapshot.nodes_after.unwrap() > 0);
+    assert_eq!(profile.code_bytes as usize, result.code.len());
+
+    let trace: serde_json::Value = serde_json::from_str(&profile.chrome_trace.unwrap()).unwrap();
+    assert_eq!(
+      trace["traceEvents"].as_array().unwrap().len(),
+      profile.phases.len() + 1
+    );
+  }
+
+  #[test]
+  fn should_not_profile_by_default() {
+    let result = transform_react_lynx_inner(
+      "export const App = () => <view />".into(),
+      TransformNodiffOptions {
+        mode: Some(TransformMode::Test),
+        filename: "app.jsx".into(),
+        ..Default::default()
+      },
+    );
+
+    assert!(result.profile.is_none());
+  }
 }
@@ -0,0 +1,287 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use serde_json::json;
+use swc_core::{
+  common::pass::Optional,
+  ecma::{
+    ast::*,
+    visit::{noop_visit_type, Visit, VisitWith},
+  },
+};
+
+// `std::time::Instant` panics on `wasm32-unknown-unknown`, which has no clock.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+#[derive(Clone, Copy)]
+struct Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl Instant {
+  fn now() -> Self {
+    Instant
+  }
+
+  fn elapsed(&self) -> Duration {
+    Duration::ZERO
+  }
+
+  fn duration_since(&self, _earlier: Instant) -> Duration {
+    Duration::ZERO
+  }
+}
+
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct ProfileOptions {
+  /// Also return the phases as Chrome trace events, see `TransformProfile.chromeTrace`.
+  #[napi(js_name = "chromeTrace")]
+  pub chrome_trace: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct PhaseProfile {
+  /// `parse`, `print`, or the name of a pass, e.g. `snapshot`.
+  pub name: String,
+  /// Milliseconds since the transform started.
+  #[napi(js_name = "startMs")]
+  pub start_ms: f64,
+  #[napi(js_name = "durationMs")]
+  pub duration_ms: f64,
+  /// The number of AST nodes before the phase, see `count_nodes`.
+  #[napi(js_name = "nodesBefore")]
+  pub nodes_before: Option<u32>,
+  /// The number of AST nodes after the phase.
+  #[napi(js_name = "nodesAfter")]
+  pub nodes_after: Option<u32>,
+}
+
+/*
+ * Where the time of a transform went. Durations are 0 in the wasm build, which has no clock.
+ */
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct TransformProfile {
+  /// `parse`, every enabled pass in the order they run, then `print`.
+  pub phases: Vec<PhaseProfile>,
+  #[napi(js_name = "totalMs")]
+  pub total_ms: f64,
+  /// The size of the emitted `code` in bytes.
+  #[napi(js_name = "codeBytes")]
+  pub code_bytes: u32,
+  /// The size of the emitted `map` in bytes.
+  #[napi(js_name = "mapBytes")]
+  pub map_bytes: Option<u32>,
+  /// The phases as a Chrome trace-event JSON, to be opened in Perfetto or `chrome://tracing`.
+  #[napi(js_name = "chromeTrace")]
+  pub chrome_trace: Option<String>,
+}
+
+struct ProfilerState {
+  start: Instant,
+  phases: Vec<PhaseProfile>,
+}
+
+/*
+ * Records the phases of one transform. A disabled profiler neither counts nor times.
+ */
+#[derive(Clone)]
+pub(crate) struct Profiler {
+  state: Option<Rc<RefCell<ProfilerState>>>,
+  chrome_trace: bool,
+}
+
+impl Profiler {
+  pub fn new(options: Option<ProfileOptions>) -> Self {
+    Profiler {
+      chrome_trace: options
+        .as_ref()
+        .and_then(|options| options.chrome_trace)
+        .unwrap_or(false),
+      state: options.map(|_| {
+        Rc::new(RefCell::new(ProfilerState {
+          start: Instant::now(),
+          phases: vec![],
+        }))
+      }),
+    }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.state.is_some()
+  }
+
+  pub fn now(&self) -> Option<Instant> {
+    self.state.as_ref().map(|_| Instant::now())
+  }
+
+  /// Records a phase that started at `start`, a value of `now`, and ends now.
+  pub fn record(
+    &self,
+    name: &str,
+    start: Option<Instant>,
+    nodes_before: Option<u32>,
+    nodes_after: Option<u32>,
+  ) {
+    self.record_until(name, start, self.now(), nodes_before, nodes_after);
+  }
+
+  fn record_until(
+    &self,
+    name: &str,
+    start: Option<Instant>,
+    end: Option<Instant>,
+    nodes_before: Option<u32>,
+    nodes_after: Option<u32>,
+  ) {
+    let (Some(state), Some(start), Some(end)) = (&self.state, start, end) else {
+      return;
+    };
+    let mut state = state.borrow_mut();
+    let start_ms = to_ms(start.duration_since(state.start));
+    state.phases.push(PhaseProfile {
+      name: name.to_string(),
+      start_ms,
+      duration_ms: to_ms(end.duration_since(start)),
+      nodes_before,
+      nodes_after,
+    });
+  }
+
+  pub fn pass<P: Pass>(&self, name: &'static str, pass: P) -> ProfiledPass<P> {
+    ProfiledPass {
+      name,
+      pass,
+      enabled: true,
+      profiler: self.clone(),
+    }
+  }
+
+  /// Profiles `pass` only when it is enabled.
+  pub fn optional<P: Pass>(
+    &self,
+    name: &'static str,
+    pass: Optional<P>,
+  ) -> ProfiledPass<Optional<P>> {
+    ProfiledPass {
+      name,
+      enabled: pass.enabled,
+      pass,
+      profiler: self.clone(),
+    }
+  }
+
+  pub fn finish(&self, filename: &str, code: &str, map: Option<&str>) -> Option<TransformProfile> {
+    let state = self.state.as_ref()?.borrow();
+    let total_ms = to_ms(state.start.elapsed());
+    Some(TransformProfile {
+      chrome_trace: self
+        .chrome_trace
+        .then(|| chrome_trace(filename, total_ms, &state.phases)),
+      phases: state.phases.clone(),
+      total_ms,
+      code_bytes: code.len() as u32,
+      map_bytes: map.map(|map| map.len() as u32),
+    })
+  }
+}
+
+pub(crate) struct ProfiledPass<P> {
+  name: &'static str,
+  pass: P,
+  enabled: bool,
+  profiler: Profiler,
+}
+
+impl<P: Pass> Pass for ProfiledPass<P> {
+  fn process(&mut self, program: &mut Program) {
+    if !self.enabled || !self.profiler.is_enabled() {
+      return self.pass.process(program);
+    }
+    let nodes_before = count_nodes(program);
+    let start = self.profiler.now();
+    self.pass.process(program);
+    // counting the nodes is not part of the pass
+    let end = self.profiler.now();
+    let nodes_after = count_nodes(program);
+    self
+      .profiler
+      .record_until(self.name, start, end, Some(nodes_before), Some(nodes_after));
+  }
+}
+
+fn to_ms(duration: Duration) -> f64 {
+  duration.as_secs_f64() * 1000.0
+}
+
+fn chrome_trace(filename: &str, total_ms: f64, phases: &[PhaseProfile]) -> String {
+  let to_us = |ms: f64| (ms * 1000.0).round();
+  let mut events = vec![json!({
+    "name": filename,
+    "cat": "transform",
+    "ph": "X",
+    "ts": 0,
+    "dur": to_us(total_ms),
+    "pid": 1,
+    "tid": 1,
+  })];
+  events.extend(phases.iter().map(|phase| {
+    json!({
+      "name": phase.name,
+      "cat": "pass",
+      "ph": "X",
+      "ts": to_us(phase.start_ms),
+      "dur": to_us(phase.duration_ms),
+      "pid": 1,
+      "tid": 1,
+      "args": {
+        "nodesBefore": phase.nodes_before,
+        "nodesAfter": phase.nodes_after,
+      },
+    })
+  }));
+  json!({ "traceEvents": events, "displayTimeUnit": "ms" }).to_string()
+}
+
+/*
+ * Counts the statements, declarations, expressions, patterns, properties and JSX
+ * elements and attributes of a program. Types are skipped.
+ */
+pub(crate) fn count_nodes(program: &Program) -> u32 {
+  let mut counter = NodeCounter::default();
+  program.visit_with(&mut counter);
+  counter.count
+}
+
+#[derive(Default)]
+struct NodeCounter {
+  count: u32,
+}
+
+macro_rules! count {
+  ($($visit:ident: $node:ty),* $(,)?) => {
+    $(
+      fn $visit(&mut self, n: &$node) {
+        self.count += 1;
+        n.visit_children_with(self);
+      }
+    )*
+  };
+}
+
+impl Visit for NodeCounter {
+  noop_visit_type!();
+
+  count!(
+    visit_module_decl: ModuleDecl,
+    visit_stmt: Stmt,
+    visit_decl: Decl,
+    visit_expr: Expr,
+    visit_pat: Pat,
+    visit_prop: Prop,
+    visit_jsx_element: JSXElement,
+    visit_jsx_attr: JSXAttr,
+  );
+}
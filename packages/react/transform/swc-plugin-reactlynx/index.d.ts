@@ -27,6 +27,8 @@ export interface JsxTransformerConfig {
   isDynamicComponent?: boolean;
   /** @internal */
   legacySlot?: boolean;
+  /** @internal */
+  dedupeAcrossModules?: boolean;
 }
 
 /** @internal */
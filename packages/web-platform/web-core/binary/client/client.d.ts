@@ -1,6 +1,44 @@
 /* tslint:disable */
 /* eslint-disable */
 
+/**
+ *
+ * * One problem found while transforming a declaration.
+ * * For style text, `offset` is the byte offset of the declaration in the source.
+ * * Key/value inline styles are measured as if they were written as `key:value;` pairs.
+ * * For the style info section, which has no source text, `offset` points into the decoded
+ * * style content where the declaration is (or would have been) written.
+ *
+ */
+export class CssDiagnostic {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    kind: CssDiagnosticKind;
+    offset: number;
+    property: string;
+    reason: string;
+}
+
+export enum CssDiagnosticKind {
+    /**
+     * The declaration is malformed and was dropped.
+     */
+    BadDeclaration = 0,
+    /**
+     * The property is not a known CSS property; the declaration is kept as written.
+     */
+    UnknownProperty = 1,
+    /**
+     * The value closes a block or function it never opened, or leaves one open.
+     */
+    UnbalancedBlock = 2,
+    /**
+     * `!important` does not end the value, or appears where the browser ignores it.
+     */
+    IgnoredImportant = 3,
+}
+
 /**
  *
  * * for return of __GetEvents
@@ -62,6 +100,63 @@ export class MainThreadWasmContext {
     update_css_og_style(unique_id: number, entry_name?: string | null): void;
 }
 
+/**
+ *
+ * * The element templates of a bundle, keyed by template id.
+ * * Each template is a list of operations building its element tree depth first:
+ * * `CreateElement` opens an element as a child of the open one, `EndElement` closes it,
+ * * and every other operation applies to the innermost open element.
+ *
+ */
+export class RawElementTemplate {
+    free(): void;
+    [Symbol.dispose](): void;
+    add_class(template_id: string, class_name: string): void;
+    /**
+     *
+     *   * Binds a cross thread event handler to the open element.
+     *   * @param event_type - The event type, e.g. "bindEvent" or "catchEvent".
+     *   * @param name - The event name, e.g. "tap".
+     *   * @param value - The handler identifier.
+     *
+     */
+    add_event(template_id: string, event_type: string, name: string, value: string): void;
+    constructor();
+    /**
+     *
+     *   * Opens an element in the template identified by `template_id`.
+     *   * If the template does not exist, it is created and the element becomes its root.
+     *   * @param template_id - The ID of the element template.
+     *   * @param tag_name - The tag written to the document, e.g. "x-view".
+     *
+     */
+    create_element(template_id: string, tag_name: string): void;
+    /**
+     *
+     *   * Decodes an element template section, failing on truncated or malformed buffers.
+     *
+     */
+    static decode(buffer: Uint8Array): RawElementTemplate;
+    /**
+     *
+     *   * Closes the innermost open element of the template.
+     *   * @param template_id - The ID of the element template.
+     *
+     */
+    end_element(template_id: string): void;
+    has_template(template_id: string): boolean;
+    set_attribute(template_id: string, key: string, value: string): void;
+    set_builtin_attribute(template_id: string, key: string, value: string): void;
+    /**
+     *
+     *   * Sets a dataset entry of the open element.
+     *   * @param value - The string value, the dataset of a template only holds strings.
+     *
+     */
+    set_dataset(template_id: string, key: string, value: string): void;
+    set_id(template_id: string, id: string): void;
+}
+
 export class RawStyleInfo {
     free(): void;
     [Symbol.dispose](): void;
@@ -92,7 +187,8 @@ export class Rule {
     /**
      *
      *   * Creates a new Rule with the specified type.
-     *   * @param rule_type - The type of the rule (e.g., "StyleRule", "FontFaceRule", "KeyframesRule").
+     *   * @param rule_type - The type of the rule (e.g., "StyleRule", "FontFaceRule", "KeyframesRule", "MediaRule", "SupportsRule", "LayerRule", "ContainerRule").
+     *   * Media, supports, layer and container rules carry their style rules as nested rules.
      *
      */
     constructor(rule_type: string);
@@ -127,6 +223,7 @@ export class Rule {
  * * Depending on the RuleType
  * * If it is SelectorList, then selectors is a list of Selector
  * * If it is KeyFramesPrelude, then selectors has only one selector which is Prelude text, its simple_selectors is empty
+ * * If the parent is Media, Supports, Layer or Container, then selectors holds the at-rule prelude text the same way; an anonymous Layer has no selector
  * * If the parent is FontFace, then selectors is empty
  *
  */
@@ -163,6 +260,58 @@ export class StyleSheetResource {
     constructor(buffer: Uint8Array, _document: any);
 }
 
+/**
+ *
+ * * Links one rule of the decoded style content back to the style sheet it came from.
+ * * `start` and `end` are byte offsets into the style content, or into the font face
+ * * content if `is_font_face` is set.
+ *
+ */
+export class StyleSourceMapping {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    css_id: number;
+    end: number;
+    is_font_face: boolean;
+    /**
+     * The index of the rule in its style sheet, followed by the index in every
+     * enclosing @media, @supports, @layer or @container block.
+     */
+    rule_path: Uint32Array;
+    /**
+     * The selector list as encoded, before scoping, or the at-rule prelude like `@keyframes name`.
+     */
+    selector: string;
+    start: number;
+}
+
+/**
+ *
+ * * Custom dimension units, consulted before the built-in `rpx`, `ppx`, `rem`, `vw` and `vh` rewrites.
+ * * A unit listed here replaces the built-in rewrite of the same unit, so mapping `rpx` to a fixed
+ * * factor lets a fixed-width device render without the `--rpx-unit` variable.
+ *
+ */
+export class UnitTable {
+    free(): void;
+    [Symbol.dispose](): void;
+    constructor();
+    /**
+     *
+     *   * `2<unit>` becomes `<2 * factor><target_unit>`, like `1px` for `2rpx` with a factor of 0.5.
+     *   * `calc(2 * var(--<unit>-unit))` in a value is folded the same way.
+     *
+     */
+    set_fixed(unit: string, factor: number, target_unit: string): void;
+    /**
+     *
+     *   * `2<unit>` becomes `calc(2 * var(<variable>))`.
+     *
+     */
+    set_variable(unit: string, variable: string): void;
+}
+
 /**
  *
  * * The key could be string or number
@@ -171,7 +320,41 @@ export class StyleSheetResource {
  */
 export function add_inline_style_raw_string_key(dom: HTMLElement, key: string, value?: string | null): void;
 
-export function decode_style_info(buffer: Uint8Array, entry_name: string | null | undefined, config_enable_css_selector: boolean, transform_vw: boolean, transform_vh: boolean, transform_rem: boolean): Uint8Array;
+/**
+ *
+ * * Decodes the style info section into style content.
+ * * Units in `unit_table` are rewritten with the table instead of the built-in rules;
+ * * the table is consumed by the call.
+ * * With `minify`, whitespace is collapsed, repeated declarations are removed and
+ * * adjacent rules with identical declarations are merged.
+ * * With `source_map`, the result also links every rule back to its css id and source rule,
+ * * see `get_style_source_map`. It cannot be combined with `minify`, which moves the rules.
+ *
+ */
+export function decode_style_info(buffer: Uint8Array, entry_name: string | null | undefined, config_enable_css_selector: boolean, transform_vw: boolean, transform_vh: boolean, transform_rem: boolean, unit_table?: UnitTable | null, minify?: boolean | null, source_map?: boolean | null): Uint8Array;
+
+/**
+ *
+ * * Like `diagnose_inline_style_string`, for styles given as a flat `[key, value, ...]` list.
+ *
+ */
+export function diagnose_inline_style_key_value_vec(k_v_vec: string[], transform_vw: boolean, transform_vh: boolean, transform_rem: boolean): CssDiagnostic[];
+
+/**
+ *
+ * * Reports the declarations of an inline style string that would be dropped or
+ * * passed through untransformed.
+ *
+ */
+export function diagnose_inline_style_string(styles: string, transform_vw: boolean, transform_vh: boolean, transform_rem: boolean): CssDiagnostic[];
+
+/**
+ *
+ * * Decodes the style info section like `decode_style_info`, but returns the diagnostics
+ * * for declarations that were dropped or could not be fully understood instead of the styles.
+ *
+ */
+export function diagnose_style_info(buffer: Uint8Array, entry_name: string | null | undefined, config_enable_css_selector: boolean, transform_vw: boolean, transform_vh: boolean, transform_rem: boolean, unit_table?: UnitTable | null): CssDiagnostic[];
 
 export function encode_legacy_json_generated_raw_style_info(raw_style_info: RawStyleInfo, config_enable_css_selector: boolean, entry_name: string | null | undefined, transform_vw: boolean, transform_vh: boolean, transform_rem: boolean): Uint8Array;
 
@@ -179,6 +362,13 @@ export function get_font_face_content(buffer: Uint8Array): string;
 
 export function get_style_content(buffer: Uint8Array): string;
 
+/**
+ *
+ * * Returns the source map of style data decoded with `source_map` enabled, or an empty list.
+ *
+ */
+export function get_style_source_map(buffer: Uint8Array): StyleSourceMapping[];
+
 export function set_inline_styles_in_key_value_vec(dom: HTMLElement, k_v_vec: string[], transform_vw: boolean, transform_vh: boolean, transform_rem: boolean): void;
 
 export function set_inline_styles_in_str(dom: HTMLElement, styles: string, transform_vw: boolean, transform_vh: boolean, transform_rem: boolean): boolean;
@@ -189,24 +379,52 @@ export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembl
 
 export interface InitOutput {
     readonly memory: WebAssembly.Memory;
+    readonly __wbg_cssdiagnostic_free: (a: number, b: number) => void;
     readonly __wbg_eventinfo_free: (a: number, b: number) => void;
+    readonly __wbg_get_cssdiagnostic_kind: (a: number) => number;
+    readonly __wbg_get_cssdiagnostic_offset: (a: number) => number;
+    readonly __wbg_get_cssdiagnostic_property: (a: number) => [number, number];
+    readonly __wbg_get_cssdiagnostic_reason: (a: number) => [number, number];
     readonly __wbg_get_eventinfo_event_handler: (a: number) => any;
     readonly __wbg_get_eventinfo_event_name: (a: number) => [number, number];
     readonly __wbg_get_eventinfo_event_type: (a: number) => [number, number];
+    readonly __wbg_get_stylesourcemapping_css_id: (a: number) => number;
+    readonly __wbg_get_stylesourcemapping_end: (a: number) => number;
+    readonly __wbg_get_stylesourcemapping_is_font_face: (a: number) => number;
+    readonly __wbg_get_stylesourcemapping_rule_path: (a: number) => [number, number];
+    readonly __wbg_get_stylesourcemapping_selector: (a: number) => [number, number];
+    readonly __wbg_get_stylesourcemapping_start: (a: number) => number;
     readonly __wbg_mainthreadwasmcontext_free: (a: number, b: number) => void;
+    readonly __wbg_rawelementtemplate_free: (a: number, b: number) => void;
     readonly __wbg_rawstyleinfo_free: (a: number, b: number) => void;
     readonly __wbg_rule_free: (a: number, b: number) => void;
     readonly __wbg_ruleprelude_free: (a: number, b: number) => void;
     readonly __wbg_selector_free: (a: number, b: number) => void;
+    readonly __wbg_set_cssdiagnostic_kind: (a: number, b: number) => void;
+    readonly __wbg_set_cssdiagnostic_offset: (a: number, b: number) => void;
+    readonly __wbg_set_cssdiagnostic_property: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_cssdiagnostic_reason: (a: number, b: number, c: number) => void;
     readonly __wbg_set_eventinfo_event_handler: (a: number, b: any) => void;
     readonly __wbg_set_eventinfo_event_name: (a: number, b: number, c: number) => void;
     readonly __wbg_set_eventinfo_event_type: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_stylesourcemapping_css_id: (a: number, b: number) => void;
+    readonly __wbg_set_stylesourcemapping_end: (a: number, b: number) => void;
+    readonly __wbg_set_stylesourcemapping_is_font_face: (a: number, b: number) => void;
+    readonly __wbg_set_stylesourcemapping_rule_path: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_stylesourcemapping_selector: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_stylesourcemapping_start: (a: number, b: number) => void;
     readonly __wbg_stylesheetresource_free: (a: number, b: number) => void;
+    readonly __wbg_stylesourcemapping_free: (a: number, b: number) => void;
+    readonly __wbg_unittable_free: (a: number, b: number) => void;
     readonly add_inline_style_raw_string_key: (a: any, b: number, c: number, d: number, e: number) => void;
-    readonly decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+    readonly decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number, number];
+    readonly diagnose_inline_style_key_value_vec: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+    readonly diagnose_inline_style_string: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+    readonly diagnose_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => [number, number, number, number];
     readonly encode_legacy_json_generated_raw_style_info: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
     readonly get_font_face_content: (a: any) => [number, number, number, number];
     readonly get_style_content: (a: any) => [number, number, number, number];
+    readonly get_style_source_map: (a: any) => [number, number, number, number];
     readonly mainthreadwasmcontext_add_closure_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => void;
     readonly mainthreadwasmcontext_add_cross_thread_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => void;
     readonly mainthreadwasmcontext_add_dataset: (a: number, b: number, c: any, d: any) => [number, number];
@@ -235,6 +453,17 @@ export interface InitOutput {
     readonly mainthreadwasmcontext_update_component_css_id: (a: number, b: number, c: number) => [number, number];
     readonly mainthreadwasmcontext_update_component_id: (a: number, b: number, c: number, d: number) => [number, number];
     readonly mainthreadwasmcontext_update_css_og_style: (a: number, b: number, c: number, d: number) => [number, number];
+    readonly rawelementtemplate_add_class: (a: number, b: number, c: number, d: number, e: number) => void;
+    readonly rawelementtemplate_add_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number) => void;
+    readonly rawelementtemplate_create_element: (a: number, b: number, c: number, d: number, e: number) => void;
+    readonly rawelementtemplate_decode: (a: any) => [number, number, number];
+    readonly rawelementtemplate_end_element: (a: number, b: number, c: number) => void;
+    readonly rawelementtemplate_has_template: (a: number, b: number, c: number) => number;
+    readonly rawelementtemplate_new: () => number;
+    readonly rawelementtemplate_set_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+    readonly rawelementtemplate_set_builtin_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+    readonly rawelementtemplate_set_dataset: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+    readonly rawelementtemplate_set_id: (a: number, b: number, c: number, d: number, e: number) => void;
     readonly rawstyleinfo_append_import: (a: number, b: number, c: number) => void;
     readonly rawstyleinfo_new: () => number;
     readonly rawstyleinfo_push_rule: (a: number, b: number, c: number) => void;
@@ -249,6 +478,9 @@ export interface InitOutput {
     readonly set_inline_styles_in_str: (a: any, b: number, c: number, d: number, e: number, f: number) => number;
     readonly set_inline_styles_number_key: (a: any, b: number, c: number, d: number) => void;
     readonly stylesheetresource_new: (a: any, b: any) => [number, number, number];
+    readonly unittable_new: () => number;
+    readonly unittable_set_fixed: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
+    readonly unittable_set_variable: (a: number, b: number, c: number, d: number, e: number) => void;
     readonly selector_new: () => number;
     readonly __wbindgen_malloc: (a: number, b: number) => number;
     readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
@@ -1,24 +1,52 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
+export const __wbg_cssdiagnostic_free: (a: number, b: number) => void;
 export const __wbg_eventinfo_free: (a: number, b: number) => void;
+export const __wbg_get_cssdiagnostic_kind: (a: number) => number;
+export const __wbg_get_cssdiagnostic_offset: (a: number) => number;
+export const __wbg_get_cssdiagnostic_property: (a: number) => [number, number];
+export const __wbg_get_cssdiagnostic_reason: (a: number) => [number, number];
 export const __wbg_get_eventinfo_event_handler: (a: number) => any;
 export const __wbg_get_eventinfo_event_name: (a: number) => [number, number];
 export const __wbg_get_eventinfo_event_type: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_css_id: (a: number) => number;
+export const __wbg_get_stylesourcemapping_end: (a: number) => number;
+export const __wbg_get_stylesourcemapping_is_font_face: (a: number) => number;
+export const __wbg_get_stylesourcemapping_rule_path: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_selector: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_start: (a: number) => number;
 export const __wbg_mainthreadwasmcontext_free: (a: number, b: number) => void;
+export const __wbg_rawelementtemplate_free: (a: number, b: number) => void;
 export const __wbg_rawstyleinfo_free: (a: number, b: number) => void;
 export const __wbg_rule_free: (a: number, b: number) => void;
 export const __wbg_ruleprelude_free: (a: number, b: number) => void;
 export const __wbg_selector_free: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_kind: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_offset: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_property: (a: number, b: number, c: number) => void;
+export const __wbg_set_cssdiagnostic_reason: (a: number, b: number, c: number) => void;
 export const __wbg_set_eventinfo_event_handler: (a: number, b: any) => void;
 export const __wbg_set_eventinfo_event_name: (a: number, b: number, c: number) => void;
 export const __wbg_set_eventinfo_event_type: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_css_id: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_end: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_is_font_face: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_rule_path: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_selector: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_start: (a: number, b: number) => void;
 export const __wbg_stylesheetresource_free: (a: number, b: number) => void;
+export const __wbg_stylesourcemapping_free: (a: number, b: number) => void;
+export const __wbg_unittable_free: (a: number, b: number) => void;
 export const add_inline_style_raw_string_key: (a: any, b: number, c: number, d: number, e: number) => void;
-export const decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+export const decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number, number];
+export const diagnose_inline_style_key_value_vec: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const diagnose_inline_style_string: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const diagnose_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => [number, number, number, number];
 export const encode_legacy_json_generated_raw_style_info: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
 export const get_font_face_content: (a: any) => [number, number, number, number];
 export const get_style_content: (a: any) => [number, number, number, number];
+export const get_style_source_map: (a: any) => [number, number, number, number];
 export const mainthreadwasmcontext_add_closure_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => void;
 export const mainthreadwasmcontext_add_cross_thread_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => void;
 export const mainthreadwasmcontext_add_dataset: (a: number, b: number, c: any, d: any) => [number, number];
@@ -47,6 +75,17 @@ export const mainthreadwasmcontext_take_timing_flags: (a: number) => [number, nu
 export const mainthreadwasmcontext_update_component_css_id: (a: number, b: number, c: number) => [number, number];
 export const mainthreadwasmcontext_update_component_id: (a: number, b: number, c: number, d: number) => [number, number];
 export const mainthreadwasmcontext_update_css_og_style: (a: number, b: number, c: number, d: number) => [number, number];
+export const rawelementtemplate_add_class: (a: number, b: number, c: number, d: number, e: number) => void;
+export const rawelementtemplate_add_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number) => void;
+export const rawelementtemplate_create_element: (a: number, b: number, c: number, d: number, e: number) => void;
+export const rawelementtemplate_decode: (a: any) => [number, number, number];
+export const rawelementtemplate_end_element: (a: number, b: number, c: number) => void;
+export const rawelementtemplate_has_template: (a: number, b: number, c: number) => number;
+export const rawelementtemplate_new: () => number;
+export const rawelementtemplate_set_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_builtin_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_dataset: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_id: (a: number, b: number, c: number, d: number, e: number) => void;
 export const rawstyleinfo_append_import: (a: number, b: number, c: number) => void;
 export const rawstyleinfo_new: () => number;
 export const rawstyleinfo_push_rule: (a: number, b: number, c: number) => void;
@@ -61,6 +100,9 @@ export const set_inline_styles_in_key_value_vec: (a: any, b: number, c: number,
 export const set_inline_styles_in_str: (a: any, b: number, c: number, d: number, e: number, f: number) => number;
 export const set_inline_styles_number_key: (a: any, b: number, c: number, d: number) => void;
 export const stylesheetresource_new: (a: any, b: any) => [number, number, number];
+export const unittable_new: () => number;
+export const unittable_set_fixed: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
+export const unittable_set_variable: (a: number, b: number, c: number, d: number, e: number) => void;
 export const selector_new: () => number;
 export const __wbindgen_malloc: (a: number, b: number) => number;
 export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
@@ -1,15 +1,53 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
+export const __wbg_cssdiagnostic_free: (a: number, b: number) => void;
+export const __wbg_get_cssdiagnostic_kind: (a: number) => number;
+export const __wbg_get_cssdiagnostic_offset: (a: number) => number;
+export const __wbg_get_cssdiagnostic_property: (a: number) => [number, number];
+export const __wbg_get_cssdiagnostic_reason: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_css_id: (a: number) => number;
+export const __wbg_get_stylesourcemapping_end: (a: number) => number;
+export const __wbg_get_stylesourcemapping_is_font_face: (a: number) => number;
+export const __wbg_get_stylesourcemapping_rule_path: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_selector: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_start: (a: number) => number;
+export const __wbg_rawelementtemplate_free: (a: number, b: number) => void;
 export const __wbg_rawstyleinfo_free: (a: number, b: number) => void;
 export const __wbg_rule_free: (a: number, b: number) => void;
 export const __wbg_ruleprelude_free: (a: number, b: number) => void;
 export const __wbg_selector_free: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_kind: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_offset: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_property: (a: number, b: number, c: number) => void;
+export const __wbg_set_cssdiagnostic_reason: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_css_id: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_end: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_is_font_face: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_rule_path: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_selector: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_start: (a: number, b: number) => void;
 export const __wbg_styleinfodecoder_free: (a: number, b: number) => void;
-export const decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+export const __wbg_stylesourcemapping_free: (a: number, b: number) => void;
+export const __wbg_unittable_free: (a: number, b: number) => void;
+export const decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number, number];
+export const diagnose_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => [number, number, number, number];
 export const encode_legacy_json_generated_raw_style_info: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
 export const get_font_face_content: (a: any) => [number, number, number, number];
 export const get_style_content: (a: any) => [number, number, number, number];
+export const get_style_source_map: (a: any) => [number, number, number, number];
+export const rawelementtemplate_add_class: (a: number, b: number, c: number, d: number, e: number) => void;
+export const rawelementtemplate_add_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number) => void;
+export const rawelementtemplate_create_element: (a: number, b: number, c: number, d: number, e: number) => void;
+export const rawelementtemplate_decode: (a: any) => [number, number, number];
+export const rawelementtemplate_encode: (a: number) => [number, number, number];
+export const rawelementtemplate_end_element: (a: number, b: number, c: number) => void;
+export const rawelementtemplate_has_template: (a: number, b: number, c: number) => number;
+export const rawelementtemplate_new: () => number;
+export const rawelementtemplate_set_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_builtin_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_dataset: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_id: (a: number, b: number, c: number, d: number, e: number) => void;
 export const rawstyleinfo_append_import: (a: number, b: number, c: number) => void;
 export const rawstyleinfo_encode: (a: number) => [number, number, number];
 export const rawstyleinfo_new: () => number;
@@ -21,6 +59,9 @@ export const rule_set_prelude: (a: number, b: number) => void;
 export const ruleprelude_new: () => number;
 export const ruleprelude_push_selector: (a: number, b: number) => void;
 export const selector_push_one_selector_section: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const unittable_new: () => number;
+export const unittable_set_fixed: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
+export const unittable_set_variable: (a: number, b: number, c: number, d: number, e: number) => void;
 export const selector_new: () => number;
 export const __wbindgen_malloc: (a: number, b: number) => number;
 export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
@@ -1,28 +1,67 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
+export const __wbg_cssdiagnostic_free: (a: number, b: number) => void;
+export const __wbg_get_cssdiagnostic_kind: (a: number) => number;
+export const __wbg_get_cssdiagnostic_offset: (a: number) => number;
+export const __wbg_get_cssdiagnostic_property: (a: number) => [number, number];
+export const __wbg_get_cssdiagnostic_reason: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_css_id: (a: number) => number;
+export const __wbg_get_stylesourcemapping_end: (a: number) => number;
+export const __wbg_get_stylesourcemapping_is_font_face: (a: number) => number;
+export const __wbg_get_stylesourcemapping_rule_path: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_selector: (a: number) => [number, number];
+export const __wbg_get_stylesourcemapping_start: (a: number) => number;
 export const __wbg_mainthreadservercontext_free: (a: number, b: number) => void;
+export const __wbg_rawelementtemplate_free: (a: number, b: number) => void;
 export const __wbg_rawstyleinfo_free: (a: number, b: number) => void;
 export const __wbg_rule_free: (a: number, b: number) => void;
 export const __wbg_ruleprelude_free: (a: number, b: number) => void;
 export const __wbg_selector_free: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_kind: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_offset: (a: number, b: number) => void;
+export const __wbg_set_cssdiagnostic_property: (a: number, b: number, c: number) => void;
+export const __wbg_set_cssdiagnostic_reason: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_css_id: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_end: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_is_font_face: (a: number, b: number) => void;
+export const __wbg_set_stylesourcemapping_rule_path: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_selector: (a: number, b: number, c: number) => void;
+export const __wbg_set_stylesourcemapping_start: (a: number, b: number) => void;
 export const __wbg_stylesheetresource_free: (a: number, b: number) => void;
-export const decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+export const __wbg_stylesourcemapping_free: (a: number, b: number) => void;
+export const __wbg_unittable_free: (a: number, b: number) => void;
+export const decode_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number, number];
+export const diagnose_inline_style_key_value_vec: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const diagnose_inline_style_string: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const diagnose_style_info: (a: any, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => [number, number, number, number];
 export const encode_legacy_json_generated_raw_style_info: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
 export const get_font_face_content: (a: any) => [number, number, number, number];
 export const get_style_content: (a: any) => [number, number, number, number];
+export const get_style_source_map: (a: any) => [number, number, number, number];
 export const init_server_in_shadow_css: (a: number, b: number) => void;
 export const mainthreadservercontext_add_class: (a: number, b: number, c: number, d: number) => [number, number];
+export const mainthreadservercontext_add_cross_thread_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => void;
+export const mainthreadservercontext_add_dataset: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
 export const mainthreadservercontext_add_inline_style_raw_string_key: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
+export const mainthreadservercontext_add_run_worklet_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number) => void;
 export const mainthreadservercontext_append_child: (a: number, b: number, c: number) => void;
 export const mainthreadservercontext_create_element: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => number;
 export const mainthreadservercontext_generate_html: (a: number, b: number) => [number, number];
+export const mainthreadservercontext_generate_html_stream: (a: number, b: number, c: any, d: number) => [number, number];
+export const mainthreadservercontext_generate_hydration_data: (a: number, b: number) => [number, number];
 export const mainthreadservercontext_get_attribute: (a: number, b: number, c: number, d: number) => [number, number];
 export const mainthreadservercontext_get_attributes: (a: number, b: number) => [number, number, number];
+export const mainthreadservercontext_get_component_id: (a: number, b: number) => [number, number];
+export const mainthreadservercontext_get_data_by_key: (a: number, b: number, c: number, d: number) => [number, number];
+export const mainthreadservercontext_get_dataset: (a: number, b: number) => [number, number];
+export const mainthreadservercontext_get_event: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
 export const mainthreadservercontext_get_page_css: (a: number) => [number, number];
 export const mainthreadservercontext_get_parent: (a: number, b: number) => number;
+export const mainthreadservercontext_get_run_worklet_event: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
 export const mainthreadservercontext_get_tag: (a: number, b: number) => [number, number];
 export const mainthreadservercontext_insert_before: (a: number, b: number, c: number, d: number) => void;
+export const mainthreadservercontext_instantiate_element_template: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
 export const mainthreadservercontext_new: (a: number, b: number, c: number, d: number, e: number, f: number) => number;
 export const mainthreadservercontext_push_style_sheet: (a: number, b: number, c: number, d: number) => [number, number];
 export const mainthreadservercontext_remove_attribute: (a: number, b: number, c: number, d: number) => void;
@@ -31,10 +70,25 @@ export const mainthreadservercontext_replace_element: (a: number, b: number, c:
 export const mainthreadservercontext_replace_elements: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
 export const mainthreadservercontext_set_attribute: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
 export const mainthreadservercontext_set_css_id: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
+export const mainthreadservercontext_set_dataset: (a: number, b: number, c: number, d: number) => void;
 export const mainthreadservercontext_set_inline_styles_in_key_value_vec: (a: number, b: number, c: number, d: number) => void;
 export const mainthreadservercontext_set_inline_styles_in_str: (a: number, b: number, c: number, d: number) => number;
 export const mainthreadservercontext_set_inline_styles_number_key: (a: number, b: number, c: number, d: number, e: number) => void;
+export const mainthreadservercontext_set_remove_unused_css: (a: number, b: number) => void;
+export const mainthreadservercontext_set_unit_table: (a: number, b: number) => void;
+export const mainthreadservercontext_update_component_id: (a: number, b: number, c: number, d: number) => void;
 export const mainthreadservercontext_update_css_og_style: (a: number, b: number, c: number, d: number) => [number, number];
+export const rawelementtemplate_add_class: (a: number, b: number, c: number, d: number, e: number) => void;
+export const rawelementtemplate_add_event: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number) => void;
+export const rawelementtemplate_create_element: (a: number, b: number, c: number, d: number, e: number) => void;
+export const rawelementtemplate_decode: (a: any) => [number, number, number];
+export const rawelementtemplate_end_element: (a: number, b: number, c: number) => void;
+export const rawelementtemplate_has_template: (a: number, b: number, c: number) => number;
+export const rawelementtemplate_new: () => number;
+export const rawelementtemplate_set_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_builtin_attribute: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_dataset: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+export const rawelementtemplate_set_id: (a: number, b: number, c: number, d: number, e: number) => void;
 export const rawstyleinfo_append_import: (a: number, b: number, c: number) => void;
 export const rawstyleinfo_new: () => number;
 export const rawstyleinfo_push_rule: (a: number, b: number, c: number) => void;
@@ -46,6 +100,9 @@ export const ruleprelude_new: () => number;
 export const ruleprelude_push_selector: (a: number, b: number) => void;
 export const selector_push_one_selector_section: (a: number, b: number, c: number, d: number, e: number) => [number, number];
 export const stylesheetresource_new: (a: any, b: any) => [number, number, number];
+export const unittable_new: () => number;
+export const unittable_set_fixed: (a: number, b: number, c: number, d: number, e: number, f: number) => void;
+export const unittable_set_variable: (a: number, b: number, c: number, d: number, e: number) => void;
 export const selector_new: () => number;
 export const __wbindgen_malloc: (a: number, b: number) => number;
 export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
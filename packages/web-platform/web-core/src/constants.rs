@@ -11,6 +11,10 @@ pub const LYNX_ENTRY_NAME_ATTRIBUTE: &str = "l-e-name";
 
 #[cfg(any(feature = "client", feature = "server"))]
 pub const LYNX_UNIQUE_ID_ATTRIBUTE: &str = "l-uid";
+#[cfg(feature = "server")]
+pub const SSR_HYDRATION_SCRIPT_ID: &str = "lynx-ssr-hydration";
+#[cfg(feature = "server")]
+pub const LYNX_ELEMENT_TEMPLATE_MARKER_ATTRIBUTE: &str = "l-template";
 // #[cfg(feature = "client")]
 // pub const LYNX_TEMPLATE_MEMBER_ID_ATTRIBUTE: &str = "l-t-e-id";
 // #[cfg(feature = "client")]
@@ -23,13 +23,22 @@ pub use main_thread::{
   },
   element_data::LynxElementData,
 };
-pub use style_transformer::{Generator, StyleTransformer};
+pub use style_transformer::{
+  token_transformer::TransformerConfig, CssDiagnostic, CssDiagnosticKind, Generator,
+  StyleTransformer, UnitTable, UnitTransform,
+};
+pub use template::template_sections::element_template::{
+  element_template_error::ElementTemplateError, raw_element_template::RawElementTemplate,
+};
+pub use template::template_sections::section_header::SectionError;
 pub use template::template_sections::style_info::{
   css_property::{ParsedDeclaration, ValueToken},
-  decoded_style_data::DecodedStyleData,
+  decoded_style_data::{DecodedStyleData, StyleDecodeOptions},
   flattened_style_info::{FlattenedStyleInfo, FlattenedStyleSheet},
   raw_style_info::{RawStyleInfo, Rule, RulePrelude, Selector, StyleSheet},
   style_info_decoder::StyleInfoDecoder,
+  style_info_error::StyleInfoError,
+  style_source_map::StyleSourceMapping,
 };
 
 #[cfg(feature = "client")]
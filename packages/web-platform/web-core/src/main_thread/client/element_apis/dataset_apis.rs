@@ -44,7 +44,15 @@ impl MainThreadWasmContext {
           if new_value.is_undefined() || new_value.is_null() {
             let _ = dom.remove_attribute(&format!("data-{key_str}"));
           } else {
-            let value_str = new_value.as_string().unwrap_or_default();
+            // like `__AddDataset`, values other than strings are written as JSON
+            let value_str = new_value
+              .as_string()
+              .or_else(|| {
+                js_sys::JSON::stringify(&new_value)
+                  .ok()
+                  .and_then(|json| json.as_string())
+              })
+              .unwrap_or_default();
             let _ = dom.set_attribute(&format!("data-{key_str}"), &value_str);
           }
         }
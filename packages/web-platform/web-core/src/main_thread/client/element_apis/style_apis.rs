@@ -131,6 +131,7 @@ pub fn set_inline_styles_in_str(
       transform_vw,
       transform_vh,
       transform_rem,
+      ..Default::default()
     },
   );
   // we compare the transformed style string with the original one
@@ -156,6 +157,7 @@ pub fn set_inline_styles_in_key_value_vec(
       transform_vw,
       transform_vh,
       transform_rem,
+      ..Default::default()
     },
   );
   let _ = dom.set_attribute("style", &transformed_style_str);
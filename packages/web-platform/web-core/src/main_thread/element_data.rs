@@ -27,6 +27,10 @@ pub struct EventHandler {
   /// keeps a list too. Registration order is preserved, matching `EventTarget`.
   #[with(rkyv::with::Skip)]
   pub(crate) closure_identifier: FnvHashMap<String, Vec<wasm_bindgen::JsValue>>,
+  /// Worklet identifiers as JSON, since SSR has no JS value to hold on to.
+  #[cfg(feature = "server")]
+  #[with(rkyv::with::Skip)]
+  pub(crate) serialized_run_worklet_identifier: FnvHashMap<String, String>,
 }
 
 #[derive(Archive, Deserialize, Clone)]
@@ -59,6 +63,12 @@ pub struct LynxElementData {
   #[cfg(feature = "server")]
   #[with(rkyv::with::Skip)]
   pub(crate) parent_id: Option<usize>,
+
+  /// Dataset entries as `(key, JSON value)` in insertion order, the SSR
+  /// counterpart of `dataset`.
+  #[cfg(feature = "server")]
+  #[with(rkyv::with::Skip)]
+  pub(crate) serialized_dataset: Vec<(String, String)>,
 }
 
 impl LynxElementData {
@@ -84,11 +94,12 @@ impl LynxElementData {
       children: Vec::new(),
       #[cfg(feature = "server")]
       parent_id: None,
+      #[cfg(feature = "server")]
+      serialized_dataset: Vec::new(),
     }
   }
 }
 
-#[cfg(feature = "client")]
 impl LynxElementData {
   pub(crate) fn get_framework_cross_thread_event_handler(
     &self,
@@ -126,7 +137,10 @@ impl LynxElementData {
         .remove(&event_type);
     }
   }
+}
 
+#[cfg(feature = "client")]
+impl LynxElementData {
   pub(crate) fn get_framework_run_worklet_event_handler(
     &self,
     event_name: &str,
@@ -263,6 +277,57 @@ impl LynxElementData {
     self.children.push(child_id);
   }
 
+  pub(crate) fn get_serialized_run_worklet_event_handler(
+    &self,
+    event_name: &str,
+    event_type: &str,
+  ) -> Option<String> {
+    let event_handlers_map = self.event_handlers_map.as_ref()?;
+    let event_handler_store = event_handlers_map.get(event_name)?;
+    event_handler_store
+      .serialized_run_worklet_identifier
+      .get(event_type)
+      .cloned()
+  }
+
+  pub(crate) fn replace_serialized_run_worklet_event_handler(
+    &mut self,
+    event_name: String,
+    event_type: String,
+    worklet_json: Option<String>,
+  ) {
+    let event_handlers_map = self.event_handlers_map.get_or_insert_default();
+    let event_handler_store = event_handlers_map.entry(event_name).or_default();
+    if let Some(worklet_json) = worklet_json {
+      event_handler_store
+        .serialized_run_worklet_identifier
+        .insert(event_type, worklet_json);
+    } else {
+      event_handler_store
+        .serialized_run_worklet_identifier
+        .remove(&event_type);
+    }
+  }
+
+  pub(crate) fn get_serialized_data(&self, key: &str) -> Option<&str> {
+    self
+      .serialized_dataset
+      .iter()
+      .find(|(existing, _)| existing == key)
+      .map(|(_, value)| value.as_str())
+  }
+
+  pub(crate) fn set_serialized_data(&mut self, key: String, value_json: String) {
+    match self
+      .serialized_dataset
+      .iter_mut()
+      .find(|(existing, _)| *existing == key)
+    {
+      Some((_, value)) => *value = value_json,
+      None => self.serialized_dataset.push((key, value_json)),
+    }
+  }
+
   // pub(crate) fn clone_node(&self, parent_component_unique_id: usize, css_id: i32) -> Self {
   //   LynxElementData {
   //     parent_component_unique_id,
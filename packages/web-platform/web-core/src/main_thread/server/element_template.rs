@@ -0,0 +1,171 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Builds element trees from the element template section, so a compiled
+//! template is instantiated in one call instead of one call per element.
+
+use super::hydration::push_json_string;
+use super::main_thread_server_context::MainThreadServerContext;
+use crate::constants;
+use crate::template::template_sections::element_template::raw_element_template::{
+  ElementTemplateOperation, RawElementTemplate,
+};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl MainThreadServerContext {
+  /**
+   * Creates the elements of the template identified by `template_id` and returns the unique id
+   * of its root, which is marked as a template element like `__MarkTemplateElement` does.
+   * The root is not appended anywhere, insert it like an element from `create_element`.
+   */
+  pub fn instantiate_element_template(
+    &mut self,
+    templates: &RawElementTemplate,
+    template_id: &str,
+    parent_component_unique_id: Option<usize>,
+  ) -> Result<usize, JsError> {
+    let operations = templates.operations(template_id)?;
+    let mut open_element_ids: Vec<usize> = Vec::new();
+    let mut root_id = 0;
+    for operation in operations {
+      let element_id = open_element_ids.last().copied().unwrap_or_default();
+      match operation {
+        ElementTemplateOperation::CreateElement { tag_name } => {
+          let id = self.create_element(tag_name.clone(), parent_component_unique_id, None, None);
+          if !self.enable_css_selector {
+            self.set_attribute(
+              id,
+              constants::LYNX_UNIQUE_ID_ATTRIBUTE.to_string(),
+              id.to_string(),
+            );
+          }
+          if open_element_ids.is_empty() {
+            root_id = id;
+          } else {
+            self.append_child(element_id, id);
+          }
+          open_element_ids.push(id);
+        }
+        ElementTemplateOperation::EndElement => {
+          open_element_ids.pop();
+          if !self.enable_css_selector
+            && self
+              .get_attribute(element_id, "class".to_string())
+              .is_some()
+          {
+            self.update_css_og_style(element_id, None)?;
+          }
+        }
+        ElementTemplateOperation::SetId(id) => {
+          self.set_attribute(element_id, "id".to_string(), id.clone());
+        }
+        ElementTemplateOperation::AddClass(class_name) => {
+          self.add_class(element_id, class_name.clone())?;
+        }
+        ElementTemplateOperation::SetAttribute { key, value } => {
+          self.set_attribute(element_id, key.clone(), value.clone());
+        }
+        ElementTemplateOperation::SetBuiltinAttribute { key, value } => {
+          if let Some(Some(element)) = self.elements.get_mut(element_id) {
+            element.set_attribute(key.clone(), value.clone());
+          }
+        }
+        ElementTemplateOperation::SetDataset { key, value } => {
+          let mut value_json = String::with_capacity(value.len() + 2);
+          push_json_string(&mut value_json, value);
+          self.set_attribute(element_id, format!("data-{key}"), value.clone());
+          self.add_dataset(element_id, key.clone(), value_json);
+        }
+        ElementTemplateOperation::AddEvent {
+          event_type,
+          name,
+          value,
+        } => {
+          self.add_cross_thread_event(
+            element_id,
+            event_type.clone(),
+            name.clone(),
+            Some(value.clone()),
+          );
+        }
+      }
+    }
+    self.set_attribute(
+      root_id,
+      constants::LYNX_ELEMENT_TEMPLATE_MARKER_ATTRIBUTE.to_string(),
+      root_id.to_string(),
+    );
+    Ok(root_id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn card_template() -> RawElementTemplate {
+    let mut templates = RawElementTemplate::new();
+    let id = || "card".to_string();
+    templates.create_element(id(), "x-view".to_string());
+    templates.set_id(id(), "card".to_string());
+    templates.add_class(id(), "card".to_string());
+    templates.set_builtin_attribute(id(), "dirtyID".to_string(), "0".to_string());
+    templates.create_element(id(), "x-text".to_string());
+    templates.set_attribute(id(), "style".to_string(), "width:2rpx".to_string());
+    templates.set_dataset(id(), "index".to_string(), "1".to_string());
+    templates.add_event(
+      id(),
+      "bindEvent".to_string(),
+      "tap".to_string(),
+      "2:0:".to_string(),
+    );
+    templates.end_element(id());
+    templates.create_element(id(), "x-image".to_string());
+    templates.end_element(id());
+    templates.end_element(id());
+    templates
+  }
+
+  #[test]
+  fn instantiates_the_element_tree() {
+    let mut ctx = MainThreadServerContext::new("".to_string(), true, false, false, false);
+    let page_id = ctx.create_element("div".to_string(), None, None, None);
+    let templates = card_template();
+    let root_id = ctx
+      .instantiate_element_template(&templates, "card", None)
+      .unwrap();
+    ctx.append_child(page_id, root_id);
+    let text_id = root_id + 1;
+
+    assert_eq!(ctx.get_tag(root_id), Some("x-view".to_string()));
+    assert_eq!(ctx.get_parent(text_id), Some(root_id));
+    assert_eq!(ctx.get_parent(root_id + 2), Some(root_id));
+    assert_eq!(
+      ctx.get_attribute(root_id, "l-template".to_string()),
+      Some(root_id.to_string())
+    );
+    assert_eq!(
+      ctx.get_attribute(root_id, "dirtyID".to_string()),
+      Some("0".to_string())
+    );
+    assert_eq!(ctx.get_dataset(text_id), r#"{"index":"1"}"#);
+    assert_eq!(
+      ctx.get_event(text_id, "tap".to_string(), "bindEvent".to_string()),
+      Some("2:0:".to_string())
+    );
+    let html = ctx.generate_html(page_id);
+    assert!(html.contains("id=\"card\""));
+    assert!(html.contains("class=\"card\""));
+    assert!(html.contains("calc(2 * var(--rpx-unit))"));
+
+    // every instance gets its own elements
+    let second_root_id = ctx
+      .instantiate_element_template(&templates, "card", None)
+      .unwrap();
+    assert_eq!(second_root_id, root_id + 3);
+  }
+}
@@ -0,0 +1,235 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Chunked output for the SSR renderer.
+//!
+//! [`MainThreadServerContext::generate_html`] collects the whole document in
+//! one `String`. The streaming entry points below write the same bytes through
+//! a [`ChunkedSink`] instead, which hands out the `<lynx-view>` shell and
+//! styles first and then whole tags in chunks of roughly `chunk_size` bytes.
+
+use super::main_thread_server_context::MainThreadServerContext;
+use wasm_bindgen::prelude::*;
+
+/// Soft upper bound of one streamed chunk, in bytes.
+pub const DEFAULT_HTML_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Where the renderer writes its markup.
+pub(crate) trait HtmlSink {
+  fn push_str(&mut self, value: &str);
+
+  fn push(&mut self, c: char) {
+    self.push_str(c.encode_utf8(&mut [0; 4]));
+  }
+
+  /// Called between tags; a chunked sink emits once it holds a full chunk.
+  fn end_of_tag(&mut self) {}
+
+  /// Emits everything written so far.
+  fn flush_chunk(&mut self) {}
+
+  /// Whether the consumer went away, in which case rendering stops early.
+  fn is_closed(&self) -> bool {
+    false
+  }
+}
+
+impl HtmlSink for String {
+  fn push_str(&mut self, value: &str) {
+    String::push_str(self, value);
+  }
+
+  fn push(&mut self, c: char) {
+    String::push(self, c);
+  }
+}
+
+/// Buffers markup and hands it to `emit` in chunks. The first error returned
+/// by `emit` closes the sink and is reported by [`ChunkedSink::finish`].
+pub(crate) struct ChunkedSink<F, E>
+where
+  F: FnMut(&str) -> Result<(), E>,
+{
+  buffer: String,
+  chunk_size: usize,
+  emit: F,
+  error: Option<E>,
+}
+
+impl<F, E> ChunkedSink<F, E>
+where
+  F: FnMut(&str) -> Result<(), E>,
+{
+  pub(crate) fn new(chunk_size: usize, emit: F) -> Self {
+    let chunk_size = chunk_size.max(1);
+    Self {
+      buffer: String::with_capacity(chunk_size.min(DEFAULT_HTML_CHUNK_SIZE) * 2),
+      chunk_size,
+      emit,
+      error: None,
+    }
+  }
+
+  pub(crate) fn finish(mut self) -> Result<(), E> {
+    self.flush_chunk();
+    match self.error {
+      Some(error) => Err(error),
+      None => Ok(()),
+    }
+  }
+}
+
+impl<F, E> HtmlSink for ChunkedSink<F, E>
+where
+  F: FnMut(&str) -> Result<(), E>,
+{
+  fn push_str(&mut self, value: &str) {
+    if self.error.is_none() {
+      self.buffer.push_str(value);
+    }
+  }
+
+  fn end_of_tag(&mut self) {
+    if self.buffer.len() >= self.chunk_size {
+      self.flush_chunk();
+    }
+  }
+
+  fn flush_chunk(&mut self) {
+    if self.buffer.is_empty() || self.error.is_some() {
+      return;
+    }
+    if let Err(error) = (self.emit)(&self.buffer) {
+      self.error = Some(error);
+    }
+    self.buffer.clear();
+  }
+
+  fn is_closed(&self) -> bool {
+    self.error.is_some()
+  }
+}
+
+#[wasm_bindgen]
+impl MainThreadServerContext {
+  /**
+   * Streams the same document as `generate_html`.
+   *
+   *   * on_chunk: (chunk: string) => void, e.g. a `ReadableStream` controller's `enqueue`
+   *   * chunk_size: soft chunk size in bytes, 16 KiB by default
+   *
+   * The first chunk holds the `<lynx-view>` shell and styles. If `on_chunk`
+   * throws, rendering stops and the exception is rethrown.
+   */
+  pub fn generate_html_stream(
+    &self,
+    element_id: usize,
+    on_chunk: &js_sys::Function,
+    chunk_size: Option<usize>,
+  ) -> Result<(), JsValue> {
+    self.stream_html(
+      element_id,
+      chunk_size.unwrap_or(DEFAULT_HTML_CHUNK_SIZE),
+      |chunk| {
+        on_chunk
+          .call1(&JsValue::NULL, &JsValue::from_str(chunk))
+          .map(|_| ())
+      },
+    )
+  }
+}
+
+impl MainThreadServerContext {
+  /// Writes the document for `element_id` to `writer` in chunks of about
+  /// [`DEFAULT_HTML_CHUNK_SIZE`] bytes.
+  pub fn write_html<W: std::io::Write>(
+    &self,
+    element_id: usize,
+    writer: &mut W,
+  ) -> std::io::Result<()> {
+    self.stream_html(element_id, DEFAULT_HTML_CHUNK_SIZE, |chunk| {
+      writer.write_all(chunk.as_bytes())
+    })?;
+    writer.flush()
+  }
+
+  pub(crate) fn stream_html<E>(
+    &self,
+    element_id: usize,
+    chunk_size: usize,
+    emit: impl FnMut(&str) -> Result<(), E>,
+  ) -> Result<(), E> {
+    let mut sink = ChunkedSink::new(chunk_size, emit);
+    self.write_document(element_id, &mut sink);
+    sink.finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn context_with_list(items: usize) -> (MainThreadServerContext, usize) {
+    let mut ctx = MainThreadServerContext::new("".to_string(), true, false, false, false);
+    let page_id = ctx.create_element("div".to_string(), None, None, None);
+    for index in 0..items {
+      let item_id = ctx.create_element("x-view".to_string(), None, None, None);
+      ctx.set_attribute(item_id, "id".to_string(), format!("item-{index}"));
+      ctx.append_child(page_id, item_id);
+    }
+    (ctx, page_id)
+  }
+
+  #[test]
+  fn streams_the_same_document_in_chunks() {
+    let (ctx, page_id) = context_with_list(50);
+    let mut chunks = Vec::new();
+    ctx
+      .stream_html(page_id, 64, |chunk| {
+        chunks.push(chunk.to_string());
+        Ok::<_, ()>(())
+      })
+      .unwrap();
+
+    assert!(chunks.len() > 2);
+    assert_eq!(
+      chunks[0],
+      r#"<lynx-view ssr><template shadowrootmode="open"><style></style>"#
+    );
+    // Chunks are cut between tags only.
+    for chunk in &chunks {
+      assert!(chunk.starts_with('<') && chunk.ends_with('>'), "{chunk}");
+    }
+    assert_eq!(chunks.concat(), ctx.generate_html(page_id));
+  }
+
+  #[test]
+  fn writes_to_an_io_sink() {
+    let (ctx, page_id) = context_with_list(3);
+    let mut output = Vec::new();
+    ctx.write_html(page_id, &mut output).unwrap();
+    assert_eq!(
+      String::from_utf8(output).unwrap(),
+      ctx.generate_html(page_id)
+    );
+  }
+
+  #[test]
+  fn stops_after_the_first_failed_chunk() {
+    let (ctx, page_id) = context_with_list(50);
+    let mut calls = 0;
+    let result = ctx.stream_html(page_id, 64, |_| {
+      calls += 1;
+      if calls == 2 {
+        Err("closed")
+      } else {
+        Ok(())
+      }
+    });
+    assert_eq!(result, Err("closed"));
+    assert_eq!(calls, 2);
+  }
+}
@@ -0,0 +1,252 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The hydration payload written next to the SSR markup.
+//!
+//! It lists every rendered element that carries state the markup cannot
+//! express (a component id, dataset entries or event bindings), keyed by the
+//! element's unique id, which the markup exposes as `l-uid`:
+//!
+//! ```json
+//! {"version":1,"elements":[{"uid":1,"parentComponentUid":0,
+//!   "componentId":"0","dataset":{"index":1},
+//!   "events":[{"name":"tap","type":"bindevent","handler":"2:0:"}],
+//!   "worklets":[{"name":"tap","type":"catchevent","worklet":{"_wkltId":"a"}}]}]}
+//! ```
+
+use super::main_thread_server_context::MainThreadServerContext;
+use crate::main_thread::element_data::LynxElementData;
+
+pub(crate) const HYDRATION_PAYLOAD_VERSION: u32 = 1;
+
+impl MainThreadServerContext {
+  /// Unique ids of the elements under `root_id` that need hydration, in
+  /// document order.
+  pub(super) fn hydrated_element_ids(&self, root_id: usize) -> Vec<usize> {
+    let mut ids = Vec::new();
+    let mut stack = vec![root_id];
+    while let Some(element_id) = stack.pop() {
+      if let Some(Some(element)) = self.elements.get(element_id) {
+        if needs_hydration(element) {
+          ids.push(element_id);
+        }
+        stack.extend(element.children.iter().rev());
+      }
+    }
+    ids
+  }
+
+  pub(super) fn write_hydration_payload(&self, element_ids: &[usize], buffer: &mut String) {
+    buffer.push_str(r#"{"version":"#);
+    buffer.push_str(&HYDRATION_PAYLOAD_VERSION.to_string());
+    buffer.push_str(r#","elements":["#);
+    let elements = element_ids.iter().filter_map(|&element_id| {
+      self
+        .elements
+        .get(element_id)?
+        .as_ref()
+        .map(|element| (element_id, element))
+    });
+    for (index, (element_id, element)) in elements.enumerate() {
+      if index > 0 {
+        buffer.push(',');
+      }
+      write_element(element_id, element, buffer);
+    }
+    buffer.push_str("]}");
+  }
+}
+
+fn needs_hydration(element: &LynxElementData) -> bool {
+  element.component_id.is_some()
+    || !element.serialized_dataset.is_empty()
+    || element.event_handlers_map.as_ref().is_some_and(|map| {
+      map.values().any(|handler| {
+        !handler.framework_cross_thread_identifier.is_empty()
+          || !handler.serialized_run_worklet_identifier.is_empty()
+      })
+    })
+}
+
+fn write_element(element_id: usize, element: &LynxElementData, buffer: &mut String) {
+  buffer.push_str(r#"{"uid":"#);
+  buffer.push_str(&element_id.to_string());
+  buffer.push_str(r#","parentComponentUid":"#);
+  buffer.push_str(&element.parent_component_unique_id.to_string());
+  if let Some(component_id) = &element.component_id {
+    buffer.push_str(r#","componentId":"#);
+    push_json_string(buffer, component_id);
+  }
+  if !element.serialized_dataset.is_empty() {
+    buffer.push_str(r#","dataset":{"#);
+    for (index, (key, value)) in element.serialized_dataset.iter().enumerate() {
+      if index > 0 {
+        buffer.push(',');
+      }
+      push_json_string(buffer, key);
+      buffer.push(':');
+      buffer.push_str(value);
+    }
+    buffer.push('}');
+  }
+
+  let mut events = Vec::new();
+  let mut worklets = Vec::new();
+  if let Some(map) = &element.event_handlers_map {
+    for (name, handler) in map {
+      for (event_type, identifier) in &handler.framework_cross_thread_identifier {
+        events.push((name.as_str(), event_type.as_str(), identifier.as_str()));
+      }
+      for (event_type, worklet) in &handler.serialized_run_worklet_identifier {
+        worklets.push((name.as_str(), event_type.as_str(), worklet.as_str()));
+      }
+    }
+  }
+  // The handler maps are unordered; sort for byte-stable output.
+  events.sort_unstable();
+  worklets.sort_unstable();
+  if !events.is_empty() {
+    buffer.push_str(r#","events":["#);
+    for (index, (name, event_type, identifier)) in events.into_iter().enumerate() {
+      if index > 0 {
+        buffer.push(',');
+      }
+      write_binding_head(name, event_type, buffer);
+      buffer.push_str(r#","handler":"#);
+      push_json_string(buffer, identifier);
+      buffer.push('}');
+    }
+    buffer.push(']');
+  }
+  if !worklets.is_empty() {
+    buffer.push_str(r#","worklets":["#);
+    for (index, (name, event_type, worklet)) in worklets.into_iter().enumerate() {
+      if index > 0 {
+        buffer.push(',');
+      }
+      write_binding_head(name, event_type, buffer);
+      buffer.push_str(r#","worklet":"#);
+      buffer.push_str(worklet);
+      buffer.push('}');
+    }
+    buffer.push(']');
+  }
+  buffer.push('}');
+}
+
+fn write_binding_head(name: &str, event_type: &str, buffer: &mut String) {
+  buffer.push_str(r#"{"name":"#);
+  push_json_string(buffer, name);
+  buffer.push_str(r#","type":"#);
+  push_json_string(buffer, event_type);
+}
+
+/// Writes `value` as a JSON string literal. `<` is escaped as well so the
+/// payload can never close the `<script>` element that carries it.
+pub(super) fn push_json_string(buffer: &mut String, value: &str) {
+  buffer.push('"');
+  for c in value.chars() {
+    match c {
+      '"' => buffer.push_str("\\\""),
+      '\\' => buffer.push_str("\\\\"),
+      '\n' => buffer.push_str("\\n"),
+      '\r' => buffer.push_str("\\r"),
+      '\t' => buffer.push_str("\\t"),
+      '<' => buffer.push_str("\\u003c"),
+      c if (c as u32) < 0x20 => buffer.push_str(&format!("\\u{:04x}", c as u32)),
+      c => buffer.push(c),
+    }
+  }
+  buffer.push('"');
+}
+
+/// Escapes `<` in already serialized JSON, which is only legal inside
+/// strings, for the same reason as [`push_json_string`].
+pub(super) fn escape_script_json(json: &str) -> String {
+  json.replace('<', "\\u003c")
+}
+
+/// Decodes a JSON string literal, or returns `None` for any other JSON value.
+pub(super) fn decode_json_string(json: &str) -> Option<String> {
+  let inner = json.trim().strip_prefix('"')?.strip_suffix('"')?;
+  let mut decoded = String::with_capacity(inner.len());
+  let mut chars = inner.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      decoded.push(c);
+      continue;
+    }
+    match chars.next()? {
+      '"' => decoded.push('"'),
+      '\\' => decoded.push('\\'),
+      '/' => decoded.push('/'),
+      'b' => decoded.push('\u{8}'),
+      'f' => decoded.push('\u{c}'),
+      'n' => decoded.push('\n'),
+      'r' => decoded.push('\r'),
+      't' => decoded.push('\t'),
+      'u' => {
+        let high = read_hex4(&mut chars)?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+          if chars.next()? != '\\' || chars.next()? != 'u' {
+            return None;
+          }
+          let low = read_hex4(&mut chars)?;
+          if !(0xDC00..0xE000).contains(&low) {
+            return None;
+          }
+          0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+          high
+        };
+        decoded.push(char::from_u32(code)?);
+      }
+      _ => return None,
+    }
+  }
+  Some(decoded)
+}
+
+fn read_hex4(chars: &mut std::str::Chars<'_>) -> Option<u32> {
+  let mut value = 0;
+  for _ in 0..4 {
+    value = value * 16 + chars.next()?.to_digit(16)?;
+  }
+  Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn json_strings_round_trip() {
+    let mut buffer = String::new();
+    push_json_string(&mut buffer, "a\"b\\c\n</script>\u{1}");
+    assert_eq!(buffer, r#""a\"b\\c\n\u003c/script>\u0001""#);
+    assert_eq!(
+      decode_json_string(&buffer).as_deref(),
+      Some("a\"b\\c\n</script>\u{1}")
+    );
+    assert_eq!(decode_json_string(r#""😀 é""#).as_deref(), Some("😀 é"));
+    assert_eq!(
+      decode_json_string(r#""\ud83d\ude00""#).as_deref(),
+      Some("😀")
+    );
+    assert_eq!(decode_json_string(r#""\ud83d\ue000""#), None);
+    assert_eq!(decode_json_string(r#""\ud83d\u0041""#), None);
+    assert_eq!(decode_json_string("12"), None);
+    assert_eq!(decode_json_string(r#"{"a":"b"}"#), None);
+  }
+
+  #[test]
+  fn script_json_cannot_close_its_element() {
+    assert_eq!(
+      escape_script_json(r#"{"html":"</script>"}"#),
+      r#"{"html":"\u003c/script>"}"#
+    );
+  }
+}
@@ -4,13 +4,19 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use super::html_stream::HtmlSink;
+use super::hydration::{decode_json_string, escape_script_json};
 use super::style_manager_server::StyleManagerServer;
+use super::unused_css::{remove_unused_rules, UsedSelectors};
+use crate::constants;
 use crate::main_thread::element_data::LynxElementData;
+use crate::style_transformer::token_transformer::{TransformerConfig, UnitTable};
 use crate::style_transformer::{
   query_transform_rules, transform_inline_style_key_value_vec, transform_inline_style_string,
 };
 use crate::template::template_sections::style_info::css_property::CSSProperty;
 use crate::template::template_sections::style_info::StyleSheetResource;
+use fnv::FnvHashSet;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
@@ -28,13 +34,12 @@ pub fn init_server_in_shadow_css(css: &str) {
 
 #[wasm_bindgen]
 pub struct MainThreadServerContext {
-  elements: Vec<Option<LynxElementData>>,
+  pub(super) elements: Vec<Option<LynxElementData>>,
   style_manager: StyleManagerServer,
   view_attributes: String,
-  enable_css_selector: bool,
-  transform_vw: bool,
-  transform_vh: bool,
-  transform_rem: bool,
+  pub(super) enable_css_selector: bool,
+  transformer_config: TransformerConfig,
+  remove_unused_css: bool,
 }
 
 #[wasm_bindgen]
@@ -52,12 +57,33 @@ impl MainThreadServerContext {
       style_manager: StyleManagerServer::new(),
       view_attributes,
       enable_css_selector,
-      transform_vw,
-      transform_vh,
-      transform_rem,
+      transformer_config: TransformerConfig {
+        transform_vw,
+        transform_vh,
+        transform_rem,
+        ..Default::default()
+      },
+      remove_unused_css: false,
     }
   }
 
+  /**
+   * Rewrites units in inline styles with this table from now on.
+   * Style sheets are not affected, pass the same table to `decode_style_info` for them.
+   */
+  pub fn set_unit_table(&mut self, unit_table: &UnitTable) {
+    self.transformer_config.unit_table = unit_table.clone();
+  }
+
+  /**
+   * Drops the style rules that match no rendered element from the generated HTML.
+   * Only class names and ids are checked, see `remove_unused_rules`.
+   * The client should load the full style sheet before it changes classes or ids after hydration.
+   */
+  pub fn set_remove_unused_css(&mut self, remove_unused_css: bool) {
+    self.remove_unused_css = remove_unused_css;
+  }
+
   pub fn push_style_sheet(
     &mut self,
     resource: &StyleSheetResource,
@@ -276,14 +302,7 @@ impl MainThreadServerContext {
   pub fn set_attribute(&mut self, element_id: usize, key: String, value: String) {
     if let Some(Some(element)) = self.elements.get_mut(element_id) {
       if key == "style" {
-        let transformed = transform_inline_style_string(
-          &value,
-          &crate::style_transformer::token_transformer::TransformerConfig {
-            transform_vw: self.transform_vw,
-            transform_vh: self.transform_vh,
-            transform_rem: self.transform_rem,
-          },
-        );
+        let transformed = transform_inline_style_string(&value, &self.transformer_config);
         element.set_attribute(key, transformed);
       } else {
         element.set_attribute(key, value);
@@ -334,14 +353,7 @@ impl MainThreadServerContext {
   }
 
   pub fn set_inline_styles_in_str(&mut self, element_id: usize, styles: String) -> bool {
-    let transformed_style_str = transform_inline_style_string(
-      &styles,
-      &crate::style_transformer::token_transformer::TransformerConfig {
-        transform_vw: self.transform_vw,
-        transform_vh: self.transform_vh,
-        transform_rem: self.transform_rem,
-      },
-    );
+    let transformed_style_str = transform_inline_style_string(&styles, &self.transformer_config);
     if transformed_style_str == styles {
       return false;
     }
@@ -352,14 +364,8 @@ impl MainThreadServerContext {
   }
 
   pub fn set_inline_styles_in_key_value_vec(&mut self, element_id: usize, k_v_vec: Vec<String>) {
-    let transformed_style_str = transform_inline_style_key_value_vec(
-      k_v_vec,
-      &crate::style_transformer::token_transformer::TransformerConfig {
-        transform_vw: self.transform_vw,
-        transform_vh: self.transform_vh,
-        transform_rem: self.transform_rem,
-      },
-    );
+    let transformed_style_str =
+      transform_inline_style_key_value_vec(k_v_vec, &self.transformer_config);
     if let Some(Some(element)) = self.elements.get_mut(element_id) {
       element.set_attribute("style".to_string(), transformed_style_str);
     }
@@ -407,8 +413,159 @@ impl MainThreadServerContext {
     }
   }
 
+  /**
+   * keys_and_json_values: [key, JSON value, key, JSON value, ...]
+   * Replaces the dataset and its `data-*` attributes, like the client does.
+   */
+  pub fn set_dataset(&mut self, element_id: usize, keys_and_json_values: Vec<String>) {
+    if let Some(Some(element)) = self.elements.get_mut(element_id) {
+      for (key, _) in std::mem::take(&mut element.serialized_dataset) {
+        element.attributes.remove(&format!("data-{key}"));
+      }
+      let mut entries = keys_and_json_values.into_iter();
+      while let (Some(key), Some(value_json)) = (entries.next(), entries.next()) {
+        if value_json != "null" {
+          // Like `__AddDataset`, strings are written as is and other values as JSON.
+          element.set_attribute(
+            format!("data-{key}"),
+            decode_json_string(&value_json).unwrap_or_else(|| value_json.clone()),
+          );
+        }
+        element.set_serialized_data(key, value_json);
+      }
+    }
+  }
+
+  /// Adds one dataset entry. As on the client, the caller owns the matching
+  /// `data-*` attribute.
+  pub fn add_dataset(&mut self, element_id: usize, key: String, value_json: String) {
+    if let Some(Some(element)) = self.elements.get_mut(element_id) {
+      element.set_serialized_data(key, value_json);
+    }
+  }
+
+  /// The dataset as a JSON object.
+  pub fn get_dataset(&self, element_id: usize) -> String {
+    let mut buffer = String::from("{");
+    if let Some(Some(element)) = self.elements.get(element_id) {
+      for (index, (key, value)) in element.serialized_dataset.iter().enumerate() {
+        if index > 0 {
+          buffer.push(',');
+        }
+        super::hydration::push_json_string(&mut buffer, key);
+        buffer.push(':');
+        buffer.push_str(value);
+      }
+    }
+    buffer.push('}');
+    buffer
+  }
+
+  /// The JSON value stored under `key`, if any.
+  pub fn get_data_by_key(&self, element_id: usize, key: &str) -> Option<String> {
+    match self.elements.get(element_id) {
+      Some(Some(element)) => element.get_serialized_data(key).map(str::to_string),
+      _ => None,
+    }
+  }
+
+  pub fn add_cross_thread_event(
+    &mut self,
+    element_id: usize,
+    event_type: String,
+    event_name: String,
+    event_handler_identifier: Option<String>,
+  ) {
+    if let Some(Some(element)) = self.elements.get_mut(element_id) {
+      element.replace_framework_cross_thread_event_handler(
+        event_name.to_ascii_lowercase(),
+        event_type.to_ascii_lowercase(),
+        event_handler_identifier,
+      );
+    }
+  }
+
+  /**
+   * worklet_json: the worklet object serialized with JSON.stringify
+   */
+  pub fn add_run_worklet_event(
+    &mut self,
+    element_id: usize,
+    event_type: String,
+    event_name: String,
+    worklet_json: Option<String>,
+  ) {
+    if let Some(Some(element)) = self.elements.get_mut(element_id) {
+      element.replace_serialized_run_worklet_event_handler(
+        event_name.to_ascii_lowercase(),
+        event_type.to_ascii_lowercase(),
+        worklet_json,
+      );
+    }
+  }
+
+  pub fn get_event(
+    &self,
+    element_id: usize,
+    event_name: String,
+    event_type: String,
+  ) -> Option<String> {
+    let Some(Some(element)) = self.elements.get(element_id) else {
+      return None;
+    };
+    let event_name = event_name.to_ascii_lowercase();
+    let event_type = event_type.to_ascii_lowercase();
+    element.get_framework_cross_thread_event_handler(&event_name, &event_type)
+  }
+
+  /// The worklet bound to this event as JSON, if any.
+  pub fn get_run_worklet_event(
+    &self,
+    element_id: usize,
+    event_name: String,
+    event_type: String,
+  ) -> Option<String> {
+    let Some(Some(element)) = self.elements.get(element_id) else {
+      return None;
+    };
+    let event_name = event_name.to_ascii_lowercase();
+    let event_type = event_type.to_ascii_lowercase();
+    element.get_serialized_run_worklet_event_handler(&event_name, &event_type)
+  }
+
+  pub fn get_component_id(&self, element_id: usize) -> Option<String> {
+    match self.elements.get(element_id) {
+      Some(Some(element)) => element.component_id.clone(),
+      _ => None,
+    }
+  }
+
+  pub fn update_component_id(&mut self, element_id: usize, component_id: Option<String>) {
+    if let Some(Some(element)) = self.elements.get_mut(element_id) {
+      element.component_id = component_id;
+    }
+  }
+
+  /// The hydration payload for the subtree at `element_id`, the same JSON
+  /// that [`Self::generate_html`] embeds.
+  pub fn generate_hydration_data(&self, element_id: usize) -> String {
+    let mut buffer = String::new();
+    self.write_hydration_payload(&self.hydrated_element_ids(element_id), &mut buffer);
+    buffer
+  }
+
   pub fn generate_html(&self, element_id: usize) -> String {
     let mut buffer = String::with_capacity(4096);
+    self.write_document(element_id, &mut buffer);
+    buffer
+  }
+}
+
+impl MainThreadServerContext {
+  /// Writes the `<lynx-view>` document for the subtree at `element_id`. The
+  /// shell and styles are flushed as a chunk of their own so a streaming sink
+  /// can send them before any element is rendered.
+  pub(super) fn write_document(&self, element_id: usize, buffer: &mut impl HtmlSink) {
     buffer.push_str("<lynx-view ssr");
     if !self.view_attributes.is_empty() {
       buffer.push(' ');
@@ -418,25 +575,69 @@ impl MainThreadServerContext {
     SERVER_IN_SHADOW_CSS.with(|css| {
       buffer.push_str(&css.borrow());
     });
-    buffer.push_str(&self.style_manager.get_css_string());
+    let page_css = self.style_manager.get_css_string();
+    if self.remove_unused_css {
+      buffer.push_str(&remove_unused_rules(
+        &page_css,
+        &self.used_selectors(element_id),
+      ));
+    } else {
+      buffer.push_str(&page_css);
+    }
     buffer.push_str("</style>");
-    self.render_element(element_id, &mut buffer);
+    buffer.flush_chunk();
+    let hydrated_ids = self.hydrated_element_ids(element_id);
+    self.render_element(element_id, &hydrated_ids, buffer);
+    if !hydrated_ids.is_empty() {
+      buffer.push_str(r#"<script type="application/json" id=""#);
+      buffer.push_str(constants::SSR_HYDRATION_SCRIPT_ID);
+      buffer.push_str(r#"">"#);
+      let mut payload = String::new();
+      self.write_hydration_payload(&hydrated_ids, &mut payload);
+      buffer.push_str(&escape_script_json(&payload));
+      buffer.push_str("</script>");
+    }
     buffer.push_str("</template></lynx-view>");
-    buffer
+    buffer.flush_chunk();
   }
-}
 
-impl MainThreadServerContext {
-  fn render_element(&self, root_id: usize, buffer: &mut String) {
+  fn used_selectors(&self, root_id: usize) -> UsedSelectors {
+    let mut used = UsedSelectors::default();
+    let mut stack = vec![root_id];
+    while let Some(element_id) = stack.pop() {
+      let Some(Some(element)) = self.elements.get(element_id) else {
+        continue;
+      };
+      if let Some(class_attr) = element.attributes.get("class") {
+        used
+          .class_names
+          .extend(class_attr.split_whitespace().map(|s| s.to_string()));
+      }
+      if let Some(id) = element.attributes.get("id") {
+        used.ids.insert(id.clone());
+      }
+      stack.extend(element.children.iter().copied());
+    }
+    used
+  }
+
+  /// Elements listed in `hydrated_ids` always carry `l-uid`, so the client can
+  /// find them from the hydration payload.
+  fn render_element(&self, root_id: usize, hydrated_ids: &[usize], buffer: &mut impl HtmlSink) {
     enum Action {
       Open(usize),
       Close(usize),
     }
 
+    let hydrated_ids: FnvHashSet<usize> = hydrated_ids.iter().copied().collect();
     let mut stack = Vec::with_capacity(64);
     stack.push(Action::Open(root_id));
 
     while let Some(action) = stack.pop() {
+      if buffer.is_closed() {
+        return;
+      }
+      buffer.end_of_tag();
       match action {
         Action::Open(element_id) => {
           if let Some(Some(element)) = self.elements.get(element_id) {
@@ -468,6 +669,18 @@ impl MainThreadServerContext {
               buffer.push('"');
             }
 
+            if !element
+              .attributes
+              .contains_key(constants::LYNX_UNIQUE_ID_ATTRIBUTE)
+              && hydrated_ids.contains(&element_id)
+            {
+              buffer.push(' ');
+              buffer.push_str(constants::LYNX_UNIQUE_ID_ATTRIBUTE);
+              buffer.push_str("=\"");
+              buffer.push_str(&element_id.to_string());
+              buffer.push('"');
+            }
+
             buffer.push('>');
 
             let template_str: Option<Cow<'static, str>> = match element.tag_name.as_str() {
@@ -578,6 +791,39 @@ mod tests {
     assert!(!html.contains("background-color:"));
   }
 
+  #[test]
+  fn test_unit_table_for_inline_styles() {
+    let mut ctx = MainThreadServerContext::new("".to_string(), true, false, false, false);
+    let mut unit_table = UnitTable::new();
+    unit_table.set_fixed("rpx".to_string(), 0.5, "px".to_string());
+    ctx.set_unit_table(&unit_table);
+    let div_id = ctx.create_element("div".to_string(), None, None, None);
+    ctx.set_inline_styles_in_str(div_id, "width:10rpx;height:20rpx".to_string());
+
+    let html = ctx.generate_html(div_id);
+    assert!(html.contains("width:5px;height:10px;"));
+  }
+
+  #[test]
+  fn test_remove_unused_css() {
+    let mut ctx = MainThreadServerContext::new("".to_string(), true, false, false, false);
+    ctx.style_manager.global_style_buffer =
+      ".a{width:1px}.b{width:2px}#c{height:1px}#d{height:2px}".to_string();
+    let page_id = ctx.create_element("div".to_string(), None, None, None);
+    let child_id = ctx.create_element("div".to_string(), None, None, None);
+    ctx.append_child(page_id, child_id);
+    ctx.set_attribute(page_id, "class".to_string(), "a".to_string());
+    ctx.set_attribute(child_id, "id".to_string(), "c".to_string());
+
+    assert!(ctx
+      .generate_html(page_id)
+      .contains(".a{width:1px}.b{width:2px}#c{height:1px}#d{height:2px}"));
+    ctx.set_remove_unused_css(true);
+    assert!(ctx
+      .generate_html(page_id)
+      .contains("<style>.a{width:1px}#c{height:1px}</style>"));
+  }
+
   #[test]
   fn test_component_css_id() {
     let mut ctx = MainThreadServerContext::new("".to_string(), true, false, false, false);
@@ -598,4 +844,108 @@ mod tests {
     // The child should have `l-css-id="42"`
     assert!(html.contains("l-css-id=\"42\""), "Generated HTML: {}", html);
   }
+
+  #[test]
+  fn test_dataset_apis() {
+    let mut ctx = MainThreadServerContext::new("".to_string(), true, false, false, false);
+    let view_id = ctx.create_element("x-view".to_string(), None, None, None);
+    ctx.set_dataset(
+      view_id,
+      vec![
+        "name".to_string(),
+        r#""a \"b\"""#.to_string(),
+        "count".to_string(),
+        "3".to_string(),
+        "flags".to_string(),
+        r#"{"a":true}"#.to_string(),
+      ],
+    );
+    assert_eq!(
+      ctx
+        .get_attribute(view_id, "data-name".to_string())
+        .as_deref(),
+      Some(r#"a "b""#)
+    );
+    assert_eq!(
+      ctx
+        .get_attribute(view_id, "data-count".to_string())
+        .as_deref(),
+      Some("3")
+    );
+    assert_eq!(
+      ctx
+        .get_attribute(view_id, "data-flags".to_string())
+        .as_deref(),
+      Some(r#"{"a":true}"#)
+    );
+
+    ctx.add_dataset(view_id, "count".to_string(), "4".to_string());
+    assert_eq!(ctx.get_data_by_key(view_id, "count").as_deref(), Some("4"));
+    assert_eq!(
+      ctx.get_dataset(view_id),
+      r#"{"name":"a \"b\"","count":4,"flags":{"a":true}}"#
+    );
+
+    ctx.set_dataset(view_id, vec!["other".to_string(), "null".to_string()]);
+    assert_eq!(ctx.get_attribute(view_id, "data-name".to_string()), None);
+    assert_eq!(ctx.get_attribute(view_id, "data-other".to_string()), None);
+    assert_eq!(ctx.get_dataset(view_id), r#"{"other":null}"#);
+  }
+
+  #[test]
+  fn test_hydration_payload() {
+    let mut ctx = MainThreadServerContext::new("".to_string(), true, false, false, false);
+    let page_id = ctx.create_element("div".to_string(), None, None, Some("0".to_string()));
+    let plain_id = ctx.create_element("x-view".to_string(), Some(page_id), None, None);
+    let button_id = ctx.create_element("x-view".to_string(), Some(page_id), None, None);
+    ctx.append_child(page_id, plain_id);
+    ctx.append_child(page_id, button_id);
+    ctx.add_dataset(button_id, "index".to_string(), "1".to_string());
+    ctx.add_cross_thread_event(
+      button_id,
+      "bindEvent".to_string(),
+      "Tap".to_string(),
+      Some("2:0:".to_string()),
+    );
+    ctx.add_run_worklet_event(
+      button_id,
+      "catchEvent".to_string(),
+      "tap".to_string(),
+      Some(r#"{"_wkltId":"a</script>"}"#.to_string()),
+    );
+    assert_eq!(
+      ctx.get_event(button_id, "tap".to_string(), "bindevent".to_string()),
+      Some("2:0:".to_string())
+    );
+
+    let payload = ctx.generate_hydration_data(page_id);
+    assert_eq!(
+      payload,
+      concat!(
+        r#"{"version":1,"elements":["#,
+        r#"{"uid":0,"parentComponentUid":0,"componentId":"0"},"#,
+        r#"{"uid":2,"parentComponentUid":0,"dataset":{"index":1},"#,
+        r#""events":[{"name":"tap","type":"bindevent","handler":"2:0:"}],"#,
+        r#""worklets":[{"name":"tap","type":"catchevent","worklet":{"_wkltId":"a</script>"}}]}"#,
+        "]}"
+      )
+    );
+
+    let html = ctx.generate_html(page_id);
+    assert!(html.starts_with(
+      r#"<lynx-view ssr><template shadowrootmode="open"><style></style><div l-uid="0">"#
+    ));
+    assert!(html.contains(r#"<x-view></x-view><x-view l-uid="2"></x-view></div>"#));
+    assert!(html.contains(r#"<script type="application/json" id="lynx-ssr-hydration">"#));
+    assert!(html.contains(r#""worklet":{"_wkltId":"a\u003c/script>"}"#));
+    assert!(html.ends_with("</script></template></lynx-view>"));
+
+    ctx.add_cross_thread_event(button_id, "bindevent".to_string(), "tap".to_string(), None);
+    ctx.add_run_worklet_event(button_id, "catchevent".to_string(), "tap".to_string(), None);
+    ctx.set_dataset(button_id, vec![]);
+    assert_eq!(
+      ctx.generate_hydration_data(button_id),
+      r#"{"version":1,"elements":[]}"#
+    );
+  }
 }
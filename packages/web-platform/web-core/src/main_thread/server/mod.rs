@@ -1,3 +1,7 @@
+pub(crate) mod element_template;
+pub(crate) mod html_stream;
+pub(crate) mod hydration;
 pub(crate) mod main_thread_server_context;
 
 pub(crate) mod style_manager_server;
+pub(crate) mod unused_css;
@@ -10,7 +10,7 @@ use fnv::FnvHashMap;
 
 pub struct StyleManagerServer {
   css_query_map_by_entry_name: FnvHashMap<String, StyleSheetResource>,
-  global_style_buffer: String,
+  pub(super) global_style_buffer: String,
   unique_id_to_style_declarations_map: FnvHashMap<usize, String>,
 }
 
@@ -0,0 +1,172 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use crate::template::template_sections::style_info::css_tree::{
+  is_at_rule, parse_css, split_selector_list, write_css, AtRuleBody, CssNode,
+};
+use fnv::FnvHashSet;
+
+/// The class names and ids carried by the rendered elements.
+#[derive(Default)]
+pub(crate) struct UsedSelectors {
+  pub(crate) class_names: FnvHashSet<String>,
+  pub(crate) ids: FnvHashSet<String>,
+}
+
+/**
+ * Removes the style rules that cannot match any rendered element.
+ * A selector is unused if it requires a class name or an id no element carries.
+ * Class names and ids nested in pseudo classes like `:not(.a)` are not checked.
+ * @keyframes, @font-face and @layer are always kept, while @media and similar blocks
+ * are removed once none of their rules is left.
+ */
+pub(crate) fn remove_unused_rules(css: &str, used: &UsedSelectors) -> String {
+  let mut nodes = parse_css(css);
+  retain_used_rules(&mut nodes, used);
+  let mut buffer = String::with_capacity(css.len());
+  write_css(&nodes, &mut buffer);
+  buffer
+}
+
+fn retain_used_rules(nodes: &mut Vec<CssNode>, used: &UsedSelectors) {
+  nodes.retain_mut(|node| match node {
+    CssNode::Style { selectors, .. } => {
+      let used_selectors: Vec<&str> = split_selector_list(selectors)
+        .into_iter()
+        .filter(|selector| is_selector_used(selector, used))
+        .collect();
+      if used_selectors.is_empty() {
+        return false;
+      }
+      *selectors = used_selectors.join(",");
+      true
+    }
+    CssNode::AtRule {
+      prelude,
+      body: AtRuleBody::Rules(rules),
+    } if !is_at_rule(prelude, "keyframes") => {
+      retain_used_rules(rules, used);
+      // an empty @layer block still declares the layer order
+      !rules.is_empty() || is_at_rule(prelude, "layer")
+    }
+    CssNode::AtRule { .. } => true,
+  });
+}
+
+fn is_selector_used(selector: &str, used: &UsedSelectors) -> bool {
+  let bytes = selector.as_bytes();
+  let mut depth = 0usize;
+  let mut index = 0;
+  while index < bytes.len() {
+    match bytes[index] {
+      b'\\' => index += 1,
+      b'(' | b'[' => depth += 1,
+      b')' | b']' => depth = depth.saturating_sub(1),
+      b'"' | b'\'' => {
+        let quote = bytes[index];
+        index += 1;
+        while index < bytes.len() && bytes[index] != quote {
+          index += if bytes[index] == b'\\' { 2 } else { 1 };
+        }
+      }
+      prefix @ (b'.' | b'#') if depth == 0 => {
+        let (name, end) = read_identifier(selector, index + 1);
+        let names = if prefix == b'.' {
+          &used.class_names
+        } else {
+          &used.ids
+        };
+        if !names.contains(&name) {
+          return false;
+        }
+        index = end;
+        continue;
+      }
+      _ => {}
+    }
+    index += 1;
+  }
+  true
+}
+
+// reads an identifier starting at `start`, resolving escapes like `\:` or `\31 `
+fn read_identifier(selector: &str, start: usize) -> (String, usize) {
+  let mut name = String::new();
+  let mut chars = selector[start..].char_indices().peekable();
+  while let Some((offset, c)) = chars.next() {
+    if c == '\\' {
+      let mut hex = String::new();
+      while hex.len() < 6 {
+        match chars.peek() {
+          Some((_, digit)) if digit.is_ascii_hexdigit() => {
+            hex.push(*digit);
+            chars.next();
+          }
+          _ => break,
+        }
+      }
+      if hex.is_empty() {
+        if let Some((_, escaped)) = chars.next() {
+          name.push(escaped);
+        }
+      } else {
+        if chars.peek().is_some_and(|(_, c)| *c == ' ') {
+          chars.next();
+        }
+        name.push(
+          u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .unwrap_or(char::REPLACEMENT_CHARACTER),
+        );
+      }
+    } else if c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii() {
+      name.push(c);
+    } else {
+      return (name, start + offset);
+    }
+  }
+  (name, selector.len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn used(class_names: &[&str], ids: &[&str]) -> UsedSelectors {
+    UsedSelectors {
+      class_names: class_names.iter().map(|name| name.to_string()).collect(),
+      ids: ids.iter().map(|id| id.to_string()).collect(),
+    }
+  }
+
+  #[test]
+  fn removes_rules_for_missing_class_names_and_ids() {
+    let used = used(&["a", "b", "w-1/2"], &["main"]);
+    assert_eq!(
+      remove_unused_rules(
+        ".a .b{width:1px}.a,.c{height:1px}.c{color:red}#main{top:0}#side{top:0}\
+         .w-1\\/2{width:50%}x-view:not(.c){left:0}[part=\"page\"]{right:0}",
+        &used
+      ),
+      ".a .b{width:1px}.a{height:1px}#main{top:0}.w-1\\/2{width:50%}\
+       x-view:not(.c){left:0}[part=\"page\"]{right:0}"
+    );
+  }
+
+  #[test]
+  fn keeps_at_rules_but_drops_empty_conditional_blocks() {
+    let used = used(&["a"], &[]);
+    assert_eq!(
+      remove_unused_rules(
+        "@media (x){.a{width:1px}.b{width:2px}}@supports (y){.b{width:1px}}\
+         @keyframes k{from{opacity:0}}@font-face{font-family:f}@layer l{}",
+        &used
+      ),
+      "@media (x){.a{width:1px}}@keyframes k{from{opacity:0}}@font-face{font-family:f}@layer l{}"
+    );
+  }
+}
@@ -0,0 +1,67 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+use crate::css_tokenizer::token_types::*;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CssDiagnosticKind {
+  /// The declaration is malformed and was dropped.
+  BadDeclaration = 0,
+  /// The property is not a known CSS property; the declaration is kept as written.
+  UnknownProperty = 1,
+  /// The value closes a block or function it never opened, or leaves one open.
+  UnbalancedBlock = 2,
+  /// `!important` does not end the value, or appears where the browser ignores it.
+  IgnoredImportant = 3,
+}
+
+/**
+ * One problem found while transforming a declaration.
+ * For style text, `offset` is the byte offset of the declaration in the source.
+ * Key/value inline styles are measured as if they were written as `key:value;` pairs.
+ * For the style info section, which has no source text, `offset` points into the decoded
+ * style content where the declaration is (or would have been) written.
+ */
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CssDiagnostic {
+  pub offset: usize,
+  #[wasm_bindgen(getter_with_clone)]
+  pub property: String,
+  pub kind: CssDiagnosticKind,
+  #[wasm_bindgen(getter_with_clone)]
+  pub reason: String,
+}
+
+impl CssDiagnostic {
+  pub(crate) fn new(
+    offset: usize,
+    property: impl Into<String>,
+    kind: CssDiagnosticKind,
+    reason: impl Into<String>,
+  ) -> Self {
+    CssDiagnostic {
+      offset,
+      property: property.into(),
+      kind,
+      reason: reason.into(),
+    }
+  }
+}
+
+/// How a token changes the nesting depth of functions and simple blocks.
+#[inline(always)]
+pub(crate) fn block_depth_delta(token_type: u8) -> i32 {
+  match token_type {
+    FUNCTION_TOKEN
+    | LEFT_PARENTHESES_TOKEN
+    | LEFT_SQUARE_BRACKET_TOKEN
+    | LEFT_CURLY_BRACKET_TOKEN => 1,
+    RIGHT_PARENTHESES_TOKEN | RIGHT_SQUARE_BRACKET_TOKEN | RIGHT_CURLY_BRACKET_TOKEN => -1,
+    _ => 0,
+  }
+}
@@ -3,14 +3,18 @@
  * Licensed under the Apache License Version 2.0 that can be found in the
  * LICENSE file in the root directory of this source tree.
  */
+use super::diagnostics::CssDiagnostic;
 use super::transformer::StyleTransformer;
 use crate::style_transformer::token_transformer::TransformerConfig;
 use crate::style_transformer::transformer::Generator;
+#[cfg(any(feature = "client", feature = "server"))]
+use wasm_bindgen::prelude::*;
 
 #[cfg(any(feature = "client", feature = "server"))]
 use crate::utils::hyphenate_style_name::hyphenate_style_name;
 struct InlineStyleGenerator {
   string_buffer: String,
+  diagnostics: Option<Vec<CssDiagnostic>>,
 }
 impl Generator for InlineStyleGenerator {
   fn push_transform_kids_style(&mut self, _: String) {
@@ -19,22 +23,44 @@ impl Generator for InlineStyleGenerator {
   fn push_transformed_style(&mut self, value: String) {
     self.string_buffer.push_str(&value);
   }
+  fn collects_diagnostics(&self) -> bool {
+    self.diagnostics.is_some()
+  }
+  fn push_diagnostic(&mut self, diagnostic: CssDiagnostic) {
+    if let Some(diagnostics) = self.diagnostics.as_mut() {
+      diagnostics.push(diagnostic);
+    }
+  }
 }
 
 pub(crate) fn transform_inline_style_string(source: &str, config: &TransformerConfig) -> String {
+  run_inline_style_string(source, config, None).string_buffer
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) fn transform_inline_style_string_with_diagnostics(
+  source: &str,
+  config: &TransformerConfig,
+) -> (String, Vec<CssDiagnostic>) {
+  let generator = run_inline_style_string(source, config, Some(Vec::new()));
+  (
+    generator.string_buffer,
+    generator.diagnostics.unwrap_or_default(),
+  )
+}
+
+fn run_inline_style_string(
+  source: &str,
+  config: &TransformerConfig,
+  diagnostics: Option<Vec<CssDiagnostic>>,
+) -> InlineStyleGenerator {
   let mut generator = InlineStyleGenerator {
     string_buffer: String::with_capacity(source.len() + 16),
+    diagnostics,
   };
-  let transformer = &mut StyleTransformer::new(
-    &mut generator,
-    TransformerConfig {
-      transform_vw: config.transform_vw,
-      transform_vh: config.transform_vh,
-      transform_rem: config.transform_rem,
-    },
-  );
+  let transformer = &mut StyleTransformer::new(&mut generator, config.clone());
   transformer.parse(source);
-  generator.string_buffer
+  generator
 }
 
 #[cfg(any(feature = "client", feature = "server"))]
@@ -42,17 +68,32 @@ pub(crate) fn transform_inline_style_key_value_vec(
   source: Vec<String>,
   config: &TransformerConfig,
 ) -> String {
+  run_inline_style_key_value_vec(source, config, None).string_buffer
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) fn transform_inline_style_key_value_vec_with_diagnostics(
+  source: Vec<String>,
+  config: &TransformerConfig,
+) -> (String, Vec<CssDiagnostic>) {
+  let generator = run_inline_style_key_value_vec(source, config, Some(Vec::new()));
+  (
+    generator.string_buffer,
+    generator.diagnostics.unwrap_or_default(),
+  )
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+fn run_inline_style_key_value_vec(
+  source: Vec<String>,
+  config: &TransformerConfig,
+  diagnostics: Option<Vec<CssDiagnostic>>,
+) -> InlineStyleGenerator {
   let mut generator = InlineStyleGenerator {
     string_buffer: String::new(),
+    diagnostics,
   };
-  let transformer = &mut StyleTransformer::new(
-    &mut generator,
-    TransformerConfig {
-      transform_vw: config.transform_vw,
-      transform_vh: config.transform_vh,
-      transform_rem: config.transform_rem,
-    },
-  );
+  let transformer = &mut StyleTransformer::new(&mut generator, config.clone());
 
   // the even value of source should be processed by hyphenate_style_name
   // iterate 2 values at a time
@@ -70,7 +111,54 @@ pub(crate) fn transform_inline_style_key_value_vec(
     }
   }
 
-  generator.string_buffer
+  generator
+}
+
+/**
+ * Reports the declarations of an inline style string that would be dropped or
+ * passed through untransformed.
+ */
+#[cfg(any(feature = "client", feature = "server"))]
+#[wasm_bindgen]
+pub fn diagnose_inline_style_string(
+  styles: String,
+  transform_vw: bool,
+  transform_vh: bool,
+  transform_rem: bool,
+) -> Vec<CssDiagnostic> {
+  transform_inline_style_string_with_diagnostics(
+    &styles,
+    &TransformerConfig {
+      transform_vw,
+      transform_vh,
+      transform_rem,
+      ..Default::default()
+    },
+  )
+  .1
+}
+
+/**
+ * Like `diagnose_inline_style_string`, for styles given as a flat `[key, value, ...]` list.
+ */
+#[cfg(any(feature = "client", feature = "server"))]
+#[wasm_bindgen]
+pub fn diagnose_inline_style_key_value_vec(
+  k_v_vec: Vec<String>,
+  transform_vw: bool,
+  transform_vh: bool,
+  transform_rem: bool,
+) -> Vec<CssDiagnostic> {
+  transform_inline_style_key_value_vec_with_diagnostics(
+    k_v_vec,
+    &TransformerConfig {
+      transform_vw,
+      transform_vh,
+      transform_rem,
+      ..Default::default()
+    },
+  )
+  .1
 }
 
 #[cfg(test)]
@@ -301,4 +389,40 @@ mod tests {
     );
     assert_eq!(result, "height:calc(100 * var(--rpx-unit));");
   }
+
+  #[test]
+  fn diagnostics_for_inline_style_string() {
+    let (result, diagnostics) = transform_inline_style_string_with_diagnostics(
+      "height:1px;colr:red;width:",
+      &TransformerConfig::default(),
+    );
+    assert_eq!(result, "height:1px;colr:red;");
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.offset, diagnostic.property.as_str()))
+        .collect::<Vec<_>>(),
+      vec![(11, "colr"), (20, "width")]
+    );
+  }
+
+  #[test]
+  fn diagnostics_for_key_value_vec_count_the_joined_pairs() {
+    let (result, diagnostics) = transform_inline_style_key_value_vec_with_diagnostics(
+      vec![
+        "height".to_string(),
+        "1px".to_string(),
+        "widht".to_string(),
+        "1px".to_string(),
+      ],
+      &TransformerConfig::default(),
+    );
+    assert_eq!(result, "height:1px;widht:1px;");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].offset, "height:1px;".len());
+    assert_eq!(
+      diagnostics[0].kind,
+      crate::style_transformer::CssDiagnosticKind::UnknownProperty
+    );
+  }
 }
@@ -12,16 +12,20 @@
 /// - `transformer`: Defines `StyleTransformer` and `Generator` trait for processing styles.
 /// - `rules`: Defines transformation rules for CSS properties.
 /// - `inline_style`: Handles transformation of inline styles.
+/// - `diagnostics`: Reports declarations the transformer drops or cannot fully understand.
+mod diagnostics;
 #[cfg(any(feature = "client", feature = "server"))]
 mod inline_style;
 mod rules;
 pub mod token_transformer;
 mod transformer;
+pub use diagnostics::{CssDiagnostic, CssDiagnosticKind};
 #[cfg(any(feature = "client", feature = "server"))]
 pub(crate) use inline_style::transform_inline_style_key_value_vec;
 #[cfg(any(feature = "client", feature = "server"))]
 pub(crate) use inline_style::transform_inline_style_string;
 #[cfg(any(feature = "client", feature = "server"))]
 pub(crate) use rules::query_transform_rules;
+pub use token_transformer::{UnitTable, UnitTransform};
 pub use transformer::Generator;
 pub use transformer::StyleTransformer;
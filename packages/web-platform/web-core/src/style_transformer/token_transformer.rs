@@ -6,18 +6,124 @@
 
 use crate::css_tokenizer::token_types::*;
 use std::borrow::Cow;
+use wasm_bindgen::prelude::*;
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct TransformerConfig {
   pub transform_vw: bool,
   pub transform_vh: bool,
   pub transform_rem: bool,
+  pub unit_table: UnitTable,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnitTransform {
+  /// `2x` becomes `calc(2 * var(--name))`, resolved by the page at runtime.
+  Variable(String),
+  /// `2x` becomes the value multiplied by `factor`, written in `unit`.
+  Fixed { factor: f64, unit: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct UnitRule {
+  unit: String,
+  transform: UnitTransform,
+}
+
+/**
+ * Custom dimension units, consulted before the built-in `rpx`, `ppx`, `rem`, `vw` and `vh` rewrites.
+ * A unit listed here replaces the built-in rewrite of the same unit, so mapping `rpx` to a fixed
+ * factor lets a fixed-width device render without the `--rpx-unit` variable.
+ */
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnitTable {
+  rules: Vec<UnitRule>,
+}
+
+#[wasm_bindgen]
+impl UnitTable {
+  #[wasm_bindgen(constructor)]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /**
+   * `2<unit>` becomes `calc(2 * var(<variable>))`.
+   */
+  pub fn set_variable(&mut self, unit: String, variable: String) {
+    self.set(unit, UnitTransform::Variable(variable));
+  }
+
+  /**
+   * `2<unit>` becomes `<2 * factor><target_unit>`, like `1px` for `2rpx` with a factor of 0.5.
+   * `calc(2 * var(--<unit>-unit))` in a value is folded the same way.
+   */
+  pub fn set_fixed(&mut self, unit: String, factor: f64, target_unit: String) {
+    self.set(
+      unit,
+      UnitTransform::Fixed {
+        factor,
+        unit: target_unit,
+      },
+    );
+  }
+}
+
+impl UnitTable {
+  pub fn set(&mut self, unit: String, transform: UnitTransform) {
+    if let Some(rule) = self
+      .rules
+      .iter_mut()
+      .find(|rule| rule.unit.eq_ignore_ascii_case(&unit))
+    {
+      rule.transform = transform;
+    } else {
+      self.rules.push(UnitRule { unit, transform });
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rules.is_empty()
+  }
+
+  // splits a dimension token like `2.5rpx` into its number and the matching rule
+  fn find<'a, 'b>(&'a self, token_value: &'b str) -> Option<(&'b str, &'a UnitTransform)> {
+    self.rules.iter().find_map(|rule| {
+      let number_len = token_value.len().checked_sub(rule.unit.len())?;
+      if number_len == 0
+        || !token_value.is_char_boundary(number_len)
+        || !token_value[number_len..].eq_ignore_ascii_case(&rule.unit)
+        // `px` must not match the end of `rpx`
+        || !token_value.as_bytes()[number_len - 1].is_ascii_digit()
+          && token_value.as_bytes()[number_len - 1] != b'.'
+      {
+        return None;
+      }
+      Some((&token_value[..number_len], &rule.transform))
+    })
+  }
+
+  fn fixed_factor(&self, variable: &str) -> Option<(f64, &str)> {
+    self.rules.iter().find_map(|rule| match &rule.transform {
+      UnitTransform::Fixed { factor, unit }
+        if variable
+          .strip_prefix("--")
+          .and_then(|name| name.strip_suffix("-unit"))
+          .is_some_and(|name| name.eq_ignore_ascii_case(&rule.unit)) =>
+      {
+        Some((*factor, unit.as_str()))
+      }
+      _ => None,
+    })
+  }
 }
 
 /**
  * Transform one token according to specific rules.
  * Rule list:
- * 1. If the token is a DIMENSION_TOKEN with "rpx" unit, convert it to a calc(value * var(--rpx-unit));
+ * 1. If the token is a DIMENSION_TOKEN with a unit in the unit table, apply the table entry;
+ * 2. If the token is a DIMENSION_TOKEN with "rpx" unit, convert it to a calc(value * var(--rpx-unit));
  */
 pub(crate) fn transform_one_token<'a>(
   token_type: u8,
@@ -26,6 +132,18 @@ pub(crate) fn transform_one_token<'a>(
 ) -> (u8, Cow<'a, str>) {
   match token_type {
     DIMENSION_TOKEN => {
+      if let Some((value, transform)) = config.unit_table.find(token_value) {
+        let transformed = match transform {
+          UnitTransform::Variable(variable) => Some(format!("calc({value} * var({variable}))")),
+          UnitTransform::Fixed { factor, unit } => value
+            .parse::<f64>()
+            .ok()
+            .map(|value| format!("{}{unit}", format_number(value * factor))),
+        };
+        if let Some(transformed) = transformed {
+          return (token_type, Cow::Owned(transformed));
+        }
+      }
       let len = token_value.len();
       if len > 3 {
         let suffix = &token_value[len - 3..];
@@ -71,6 +189,84 @@ pub(crate) fn transform_one_token<'a>(
   }
 }
 
+/**
+ * Folds `calc(<number> * var(--<unit>-unit))` into a plain dimension
+ * when the unit table maps `<unit>` to a fixed factor.
+ * Returns `None` if nothing was folded.
+ */
+pub(crate) fn fold_unit_variables(value: &str, config: &TransformerConfig) -> Option<String> {
+  if config.unit_table.is_empty() {
+    return None;
+  }
+  let mut folded = String::new();
+  let mut rest = value;
+  let mut is_folded = false;
+  while let Some(calc_start) = find_ignore_ascii_case(rest, "calc(") {
+    let after_calc = &rest[calc_start + "calc(".len()..];
+    match fold_one_calc(after_calc, &config.unit_table) {
+      Some((replacement, consumed)) => {
+        folded.push_str(&rest[..calc_start]);
+        folded.push_str(&replacement);
+        rest = &after_calc[consumed..];
+        is_folded = true;
+      }
+      None => {
+        folded.push_str(&rest[..calc_start + "calc(".len()]);
+        rest = after_calc;
+      }
+    }
+  }
+  if !is_folded {
+    return None;
+  }
+  folded.push_str(rest);
+  Some(folded)
+}
+
+// parses `<number> * var(--<unit>-unit))`, returning the folded value and the consumed length
+fn fold_one_calc(source: &str, unit_table: &UnitTable) -> Option<(String, usize)> {
+  let trimmed = source.trim_start();
+  let number_len = trimmed
+    .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+    .unwrap_or(trimmed.len());
+  let number: f64 = trimmed[..number_len].parse().ok()?;
+  let rest = trimmed[number_len..]
+    .trim_start()
+    .strip_prefix('*')?
+    .trim_start();
+  if !rest
+    .get(.."var(".len())
+    .is_some_and(|name| name.eq_ignore_ascii_case("var("))
+  {
+    return None;
+  }
+  let rest = rest["var(".len()..].trim_start();
+  let variable_len = rest.find(|c: char| c == ')' || c.is_ascii_whitespace())?;
+  let (factor, unit) = unit_table.fixed_factor(&rest[..variable_len])?;
+  let rest = rest[variable_len..].trim_start().strip_prefix(')')?;
+  let rest = rest.trim_start().strip_prefix(')')?;
+  Some((
+    format!("{}{unit}", format_number(number * factor)),
+    source.len() - rest.len(),
+  ))
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+  haystack
+    .as_bytes()
+    .windows(needle.len())
+    .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+// the shortest representation that parses back to `value`, without an exponent; -0 prints as 0
+fn format_number(value: f64) -> String {
+  if value == 0.0 {
+    "0".to_string()
+  } else {
+    value.to_string()
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -186,4 +382,91 @@ mod tests {
     let (_, tv) = transform_one_token(IDENT_TOKEN, "red", &TransformerConfig::default());
     assert_eq!(tv, "red");
   }
+  fn fixed_rpx_config() -> TransformerConfig {
+    let mut unit_table = UnitTable::new();
+    unit_table.set_fixed("rpx".to_string(), 0.5, "px".to_string());
+    TransformerConfig {
+      unit_table,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_transform_custom_variable_unit() {
+    let mut unit_table = UnitTable::new();
+    unit_table.set_variable("dp".to_string(), "--dp-unit".to_string());
+    let config = TransformerConfig {
+      unit_table,
+      ..Default::default()
+    };
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "3DP", &config);
+    assert_eq!(tv, "calc(3 * var(--dp-unit))");
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "3px", &config);
+    assert_eq!(tv, "3px");
+  }
+
+  #[test]
+  fn test_transform_fixed_unit_overrides_builtin() {
+    let config = fixed_rpx_config();
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "3rpx", &config);
+    assert_eq!(tv, "1.5px");
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "-.2rpx", &config);
+    assert_eq!(tv, "-0.1px");
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "2ppx", &config);
+    assert_eq!(tv, "calc(2 * var(--ppx-unit))");
+  }
+
+  #[test]
+  fn test_fixed_unit_keeps_full_precision() {
+    let mut unit_table = UnitTable::new();
+    unit_table.set_fixed("rpx".to_string(), 0.123456789, "px".to_string());
+    let config = TransformerConfig {
+      unit_table,
+      ..Default::default()
+    };
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "1rpx", &config);
+    assert_eq!(tv, "0.123456789px");
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "-0rpx", &config);
+    assert_eq!(tv, "0px");
+    assert_eq!(format_number(1e-7), "0.0000001");
+    assert_eq!(format_number(0.1 + 0.2).parse::<f64>(), Ok(0.1 + 0.2));
+  }
+
+  #[test]
+  fn test_custom_unit_does_not_match_longer_units() {
+    let mut unit_table = UnitTable::new();
+    unit_table.set_fixed("px".to_string(), 2.0, "px".to_string());
+    let config = TransformerConfig {
+      unit_table,
+      ..Default::default()
+    };
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "1rpx", &config);
+    assert_eq!(tv, "calc(1 * var(--rpx-unit))");
+    let (_, tv) = transform_one_token(DIMENSION_TOKEN, "1px", &config);
+    assert_eq!(tv, "2px");
+  }
+
+  #[test]
+  fn test_fold_unit_variables() {
+    let config = fixed_rpx_config();
+    assert_eq!(
+      fold_unit_variables(
+        "calc(2 * var(--rpx-unit)) CALC( 0.1*var( --RPX-unit ) )",
+        &config
+      )
+      .as_deref(),
+      Some("1px 0.05px")
+    );
+    assert_eq!(
+      fold_unit_variables("calc(2 * var(--ppx-unit)) calc(100% - 2px)", &config),
+      None
+    );
+    assert_eq!(
+      fold_unit_variables(
+        "calc(2 * var(--rpx-unit) + 1px)",
+        &TransformerConfig::default()
+      ),
+      None
+    );
+  }
 }
@@ -3,14 +3,15 @@
  * Licensed under the Apache License Version 2.0 that can be found in the
  * LICENSE file in the root directory of this source tree.
  */
-use super::token_transformer::{transform_one_token, TransformerConfig};
+use super::diagnostics::{block_depth_delta, CssDiagnostic, CssDiagnosticKind};
+use super::token_transformer::{fold_unit_variables, transform_one_token, TransformerConfig};
 #[cfg(any(feature = "client", feature = "server", test))]
-use crate::css_tokenizer::tokenize;
+use crate::css_tokenizer::{char_code_definitions::get_start_offset, tokenize};
 use crate::css_tokenizer::{
   char_code_definitions::is_white_space, token_types::*, tokenize::Parser,
 };
 use crate::template::template_sections::style_info::css_property::{
-  CSSProperty, ParsedDeclaration,
+  CSSProperty, CSSPropertyEnum, ParsedDeclaration,
 };
 
 use super::rules::query_transform_rules;
@@ -24,15 +25,33 @@ pub struct StyleTransformer<'a, T: Generator> {
   current_value: String,
   is_important: bool,
   prev_token_type: u8,
+
+  // the following fields are only maintained when the generator collects diagnostics
+  collect_diagnostics: bool,
+  offset: usize,
+  declaration_offset: usize,
+  block_depth: i32,
+  is_block_unbalanced: bool,
+  value_len_at_important: usize,
 }
 
 pub trait Generator {
   fn push_transformed_style(&mut self, value: String);
   fn push_transform_kids_style(&mut self, value: String);
+  /**
+   * Opt in to diagnostics. The transformer asks once, when it is created,
+   * and skips all checks for generators that keep the default.
+   */
+  fn collects_diagnostics(&self) -> bool {
+    false
+  }
+  fn push_diagnostic(&mut self, _diagnostic: CssDiagnostic) {}
 }
 
 impl<'a, T: Generator> Parser for StyleTransformer<'a, T> {
   fn on_token(&mut self, token_type: u8, token_value: &str) {
+    let token_offset = self.offset;
+    self.offset += token_value.len();
     let (token_type, token_value) = transform_one_token(token_type, token_value, &self.config);
     //https://drafts.csswg.org/css-syntax-3/#consume-declaration
     // on_token(type, start, offset);
@@ -52,6 +71,11 @@ impl<'a, T: Generator> Parser for StyleTransformer<'a, T> {
       self.current_property_id = Some(token_value.into());
       self.prev_token_type = token_type;
       self.status = 1;
+      if self.collect_diagnostics {
+        self.declaration_offset = token_offset;
+        self.block_depth = 0;
+        self.is_block_unbalanced = false;
+      }
     }
     // 2. Discard whitespace from input.
     else if self.status == 1 && token_type == WHITESPACE_TOKEN {
@@ -79,6 +103,9 @@ impl<'a, T: Generator> Parser for StyleTransformer<'a, T> {
         */
         self.current_value.push_str(&token_value);
         self.status = 3; // now find a semicolon
+        if self.collect_diagnostics {
+          self.track_block_depth(token_type);
+        }
       }
     } else if self.status == 3 && token_type == SEMICOLON_TOKEN {
       /*
@@ -90,11 +117,32 @@ impl<'a, T: Generator> Parser for StyleTransformer<'a, T> {
       {
         self.current_value.pop();
       }
-      assert!(
-        self.current_property_id.is_some(),
-        "property name should be set before semicolon"
-      );
-      let property_id = self.current_property_id.take().unwrap();
+      let Some(property_id) = self.current_property_id.take() else {
+        self.report(
+          CssDiagnosticKind::BadDeclaration,
+          String::new(),
+          "the declaration has no property name",
+        );
+        self.reset_declaration();
+        self.prev_token_type = token_type;
+        return;
+      };
+      if self.collect_diagnostics {
+        if self.is_block_unbalanced || self.block_depth != 0 {
+          self.report(
+            CssDiagnosticKind::UnbalancedBlock,
+            property_id.to_string(),
+            format!("unbalanced parentheses in `{}`", self.current_value),
+          );
+        }
+        if self.is_important && self.current_value.len() > self.value_len_at_important {
+          self.report(
+            CssDiagnosticKind::IgnoredImportant,
+            property_id.to_string(),
+            "`!important` is not at the end of the value; the tokens after it are kept in the value",
+          );
+        }
+      }
       // create a string with buf size 8 chars
       let property_value = std::mem::replace(&mut self.current_value, String::with_capacity(8));
       self.status = 0; // reset
@@ -109,7 +157,20 @@ impl<'a, T: Generator> Parser for StyleTransformer<'a, T> {
       // height: 1px /important;
       // we accept such limited cases for performance consideration
       self.is_important = true;
-      self.current_value.pop(); // remove the '!' char
+      let delim = self.current_value.pop(); // remove the '!' char
+      if self.collect_diagnostics {
+        self.value_len_at_important = self.current_value.len();
+        if delim != Some('!') {
+          self.report(
+            CssDiagnosticKind::IgnoredImportant,
+            self.current_property_name(),
+            format!(
+              "`{}important` is read as `!important`",
+              delim.unwrap_or_default()
+            ),
+          );
+        }
+      }
     } else if self.status == 3
       && token_type != LEFT_CURLY_BRACKET_TOKEN
       && token_type != LEFT_PARENTHESES_TOKEN
@@ -117,12 +178,38 @@ impl<'a, T: Generator> Parser for StyleTransformer<'a, T> {
       && token_type != SEMICOLON_TOKEN
     {
       self.current_value.push_str(&token_value);
+      if self.collect_diagnostics {
+        self.track_block_depth(token_type);
+      }
     } else if self.status != 0 {
       // we have a bad declaration
-      self.status = 0; // reset
-      self.current_property_id = None;
-      self.current_value = String::with_capacity(8);
-      self.is_important = false;
+      if self.collect_diagnostics {
+        let reason = match (self.status, token_type) {
+          (1, _) => format!("expected `:` after the property name, found `{token_value}`"),
+          (2, SEMICOLON_TOKEN) => "the declaration has no value".to_string(),
+          _ => format!("`{token_value}` blocks are not supported in declaration values"),
+        };
+        self.report(
+          CssDiagnosticKind::BadDeclaration,
+          self.current_property_name(),
+          reason,
+        );
+      }
+      self.reset_declaration();
+    } else if self.collect_diagnostics
+      && !matches!(
+        token_type,
+        WHITESPACE_TOKEN | SEMICOLON_TOKEN | COMMENT_TOKEN | CDO_TOKEN | CDC_TOKEN
+      )
+      && matches!(self.prev_token_type, WHITESPACE_TOKEN | SEMICOLON_TOKEN)
+    {
+      // only the first token of a run of garbage is reported
+      self.declaration_offset = token_offset;
+      self.report(
+        CssDiagnosticKind::BadDeclaration,
+        String::new(),
+        format!("expected a property name, found `{token_value}`"),
+      );
     }
     self.prev_token_type = token_type;
   }
@@ -130,18 +217,25 @@ impl<'a, T: Generator> Parser for StyleTransformer<'a, T> {
 impl<'a, T: Generator> StyleTransformer<'a, T> {
   pub fn new(generator: &'a mut T, config: TransformerConfig) -> Self {
     StyleTransformer {
-      generator,
       config,
       status: 0,
       current_property_id: None,
       current_value: String::with_capacity(8),
       is_important: false,
       prev_token_type: WHITESPACE_TOKEN, // start with whitespace
+      collect_diagnostics: generator.collects_diagnostics(),
+      generator,
+      offset: 0,
+      declaration_offset: 0,
+      block_depth: 0,
+      is_block_unbalanced: false,
+      value_len_at_important: 0,
     }
   }
 
   #[cfg(any(feature = "client", feature = "server", test))]
   pub fn parse(&mut self, source: &str) {
+    self.offset = get_start_offset(source);
     tokenize::tokenize(source, self);
     if self.prev_token_type != SEMICOLON_TOKEN {
       self.on_token(SEMICOLON_TOKEN, ";");
@@ -151,9 +245,28 @@ impl<'a, T: Generator> StyleTransformer<'a, T> {
   pub(crate) fn on_declaration_parsed(
     &mut self,
     property_id: CSSProperty,
-    property_value: String,
+    mut property_value: String,
     is_important: bool,
   ) {
+    if let Some(folded) = fold_unit_variables(&property_value, &self.config) {
+      property_value = folded;
+    }
+    if self.collect_diagnostics && property_id.id == CSSPropertyEnum::Unknown {
+      let property_name = property_id.to_string();
+      if property_name.is_empty() {
+        self.report(
+          CssDiagnosticKind::BadDeclaration,
+          property_name,
+          "the declaration has no property name",
+        );
+      } else if !property_name.starts_with("--") {
+        self.report(
+          CssDiagnosticKind::UnknownProperty,
+          property_name,
+          "unknown property; the declaration is passed through untransformed",
+        );
+      }
+    }
     let empty: bool = {
       let (current_declarations, kids_declarations) =
         query_transform_rules(&property_id, &property_value);
@@ -193,6 +306,9 @@ impl<'a, T: Generator> StyleTransformer<'a, T> {
   }
 
   pub fn on_half_parsed_declaration(&mut self, parsed_declaration: ParsedDeclaration) {
+    if self.collect_diagnostics {
+      self.check_value_tokens(&parsed_declaration);
+    }
     self.on_declaration_parsed(
       parsed_declaration.property_id,
       parsed_declaration
@@ -208,6 +324,83 @@ impl<'a, T: Generator> StyleTransformer<'a, T> {
   }
 }
 
+impl<'a, T: Generator> StyleTransformer<'a, T> {
+  fn report(
+    &mut self,
+    kind: CssDiagnosticKind,
+    property: impl Into<String>,
+    reason: impl Into<String>,
+  ) {
+    if self.collect_diagnostics {
+      self.generator.push_diagnostic(CssDiagnostic::new(
+        self.declaration_offset,
+        property,
+        kind,
+        reason,
+      ));
+    }
+  }
+
+  fn current_property_name(&self) -> String {
+    self
+      .current_property_id
+      .as_ref()
+      .map(|property_id| property_id.to_string())
+      .unwrap_or_default()
+  }
+
+  fn reset_declaration(&mut self) {
+    self.status = 0;
+    self.current_property_id = None;
+    self.current_value = String::with_capacity(8);
+    self.is_important = false;
+  }
+
+  fn track_block_depth(&mut self, token_type: u8) {
+    self.block_depth += block_depth_delta(token_type);
+    if self.block_depth < 0 {
+      self.is_block_unbalanced = true;
+    }
+  }
+
+  // declarations from the style info section were tokenized when the template was encoded,
+  // so the checks done while parsing style text run over their value tokens instead
+  fn check_value_tokens(&mut self, parsed_declaration: &ParsedDeclaration) {
+    let property_name = parsed_declaration.property_id.to_string();
+    let tokens = &parsed_declaration.value_token_list;
+    let mut block_depth = 0;
+    let mut is_block_unbalanced = false;
+    for token in tokens.iter() {
+      block_depth += block_depth_delta(token.token_type);
+      is_block_unbalanced |= block_depth < 0;
+    }
+    if is_block_unbalanced || block_depth != 0 {
+      let value: String = tokens.iter().map(|token| token.value.as_str()).collect();
+      self.report(
+        CssDiagnosticKind::UnbalancedBlock,
+        property_name.clone(),
+        format!("unbalanced parentheses in `{value}`"),
+      );
+    }
+    let last_significant_token = tokens
+      .iter()
+      .rposition(|token| !matches!(token.token_type, WHITESPACE_TOKEN | COMMENT_TOKEN));
+    for (index, pair) in tokens.windows(2).enumerate() {
+      if pair[0].token_type == DELIM_TOKEN
+        && pair[0].value == "!"
+        && pair[1].value.eq_ignore_ascii_case("important")
+        && Some(index + 1) != last_significant_token
+      {
+        self.report(
+          CssDiagnosticKind::IgnoredImportant,
+          property_name.clone(),
+          "`!important` is not at the end of the value",
+        );
+      }
+    }
+  }
+}
+
 #[inline(always)]
 fn generate_one_declaration(
   property_string: &str,
@@ -222,7 +415,8 @@ fn generate_one_declaration(
 
 #[cfg(test)]
 mod tests {
-  use crate::style_transformer::token_transformer::TransformerConfig;
+  use crate::style_transformer::diagnostics::{CssDiagnostic, CssDiagnosticKind};
+  use crate::style_transformer::token_transformer::{TransformerConfig, UnitTable};
 
   use super::Generator;
 
@@ -331,6 +525,23 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_fixed_unit_table() {
+    let mut unit_table = UnitTable::new();
+    unit_table.set_fixed("rpx".to_string(), 0.5, "px".to_string());
+    let (transformer, _) = parse_css_with_config(
+      "width: 2rpx; height: calc(4 * var(--rpx-unit)); margin: calc(1px + 2rpx);",
+      TransformerConfig {
+        unit_table,
+        ..Default::default()
+      },
+    );
+    assert_eq!(
+      transformer.declarations,
+      vec!["width:1px;", "height:2px;", "margin:calc(1px + 1px);"]
+    );
+  }
+
   #[test]
   fn test_vw_transformation_disabled() {
     let (transformer, _) = parse_css("width: 100vw;");
@@ -595,6 +806,140 @@ mod tests {
     assert_eq!(transformer.declarations[0], "background-color:red;");
   }
 
+  struct DiagnosticsCollector {
+    declarations: Vec<String>,
+    diagnostics: Vec<CssDiagnostic>,
+  }
+
+  impl Generator for DiagnosticsCollector {
+    fn push_transform_kids_style(&mut self, value: String) {
+      self.declarations.push(value);
+    }
+
+    fn push_transformed_style(&mut self, value: String) {
+      self.declarations.push(value);
+    }
+
+    fn collects_diagnostics(&self) -> bool {
+      true
+    }
+
+    fn push_diagnostic(&mut self, diagnostic: CssDiagnostic) {
+      self.diagnostics.push(diagnostic);
+    }
+  }
+
+  fn parse_css_with_diagnostics(css: &str) -> DiagnosticsCollector {
+    let mut collector = DiagnosticsCollector {
+      declarations: Vec::new(),
+      diagnostics: Vec::new(),
+    };
+    super::StyleTransformer::new(&mut collector, Default::default()).parse(css);
+    collector
+  }
+
+  #[test]
+  fn test_diagnostics_for_bad_declarations() {
+    let collector = parse_css_with_diagnostics("width: 1px; color red; height:; top: (1px);");
+    assert_eq!(collector.declarations, vec!["width:1px;"]);
+    assert_eq!(
+      collector.diagnostics,
+      vec![
+        CssDiagnostic::new(
+          12,
+          "color",
+          CssDiagnosticKind::BadDeclaration,
+          "expected `:` after the property name, found `red`",
+        ),
+        CssDiagnostic::new(
+          23,
+          "height",
+          CssDiagnosticKind::BadDeclaration,
+          "the declaration has no value",
+        ),
+        CssDiagnostic::new(
+          32,
+          "top",
+          CssDiagnosticKind::BadDeclaration,
+          "`(` blocks are not supported in declaration values",
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_diagnostics_for_garbage_before_a_property() {
+    let collector = parse_css_with_diagnostics("\u{FEFF}123: red; width: 1px;");
+    assert_eq!(collector.declarations, vec!["width:1px;"]);
+    assert_eq!(collector.diagnostics.len(), 2);
+    assert_eq!(
+      collector.diagnostics[0],
+      CssDiagnostic::new(
+        3,
+        "",
+        CssDiagnosticKind::BadDeclaration,
+        "expected a property name, found `123`",
+      )
+    );
+    assert_eq!(collector.diagnostics[1].property, "red");
+  }
+
+  #[test]
+  fn test_diagnostics_for_unknown_properties() {
+    let collector = parse_css_with_diagnostics("--theme: red; colr: blue;");
+    assert_eq!(collector.declarations, vec!["--theme:red;", "colr:blue;"]);
+    assert_eq!(
+      collector.diagnostics,
+      vec![CssDiagnostic::new(
+        14,
+        "colr",
+        CssDiagnosticKind::UnknownProperty,
+        "unknown property; the declaration is passed through untransformed",
+      )]
+    );
+  }
+
+  #[test]
+  fn test_diagnostics_for_unbalanced_values() {
+    let collector = parse_css_with_diagnostics("width: calc(1px + 2px; height: 1px);");
+    assert_eq!(collector.diagnostics.len(), 2);
+    assert!(collector
+      .diagnostics
+      .iter()
+      .all(|diagnostic| diagnostic.kind == CssDiagnosticKind::UnbalancedBlock));
+    assert_eq!(collector.diagnostics[0].property, "width");
+    assert_eq!(collector.diagnostics[1].offset, 23);
+  }
+
+  #[test]
+  fn test_diagnostics_for_misplaced_important() {
+    let collector = parse_css_with_diagnostics(
+      "width: 1px !important 2px; height: 1px /important; top: 1px !important;",
+    );
+    assert_eq!(
+      collector
+        .diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.kind, diagnostic.property.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (CssDiagnosticKind::IgnoredImportant, "width"),
+        (CssDiagnosticKind::IgnoredImportant, "height"),
+      ]
+    );
+    assert_eq!(
+      collector.diagnostics[1].reason,
+      "`/important` is read as `!important`"
+    );
+  }
+
+  #[test]
+  fn test_diagnostics_are_opt_in() {
+    // the same input reports nothing through a generator that keeps the defaults
+    let (transformer, _) = parse_css("colr: blue; width: calc(1px;");
+    assert_eq!(transformer.declarations.len(), 2);
+  }
+
   #[test]
   fn test_bom_handling() {
     // Test with Byte Order Mark
@@ -0,0 +1,43 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use crate::template::template_sections::section_header::SectionError;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ElementTemplateError {
+  /// No template with this id is in the section.
+  UnknownTemplate(String),
+  /// The operations of this template do not open and close exactly one root element.
+  MalformedTemplate(String),
+  /// The buffer does not hold a valid section, or the section could not be encoded.
+  Section(SectionError),
+}
+
+impl fmt::Display for ElementTemplateError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ElementTemplateError::UnknownTemplate(template_id) => {
+        write!(f, "Unknown element template: {template_id}")
+      }
+      ElementTemplateError::MalformedTemplate(template_id) => write!(
+        f,
+        "Element template {template_id} does not build exactly one element tree"
+      ),
+      ElementTemplateError::Section(error) => {
+        write!(f, "Invalid element template section: {error}")
+      }
+    }
+  }
+}
+
+impl std::error::Error for ElementTemplateError {}
+
+impl From<SectionError> for ElementTemplateError {
+  fn from(error: SectionError) -> Self {
+    ElementTemplateError::Section(error)
+  }
+}
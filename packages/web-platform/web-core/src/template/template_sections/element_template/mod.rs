@@ -0,0 +1,8 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+pub(crate) mod element_template_error;
+pub(crate) mod raw_element_template;
@@ -0,0 +1,288 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use super::element_template_error::ElementTemplateError;
+#[cfg(feature = "encode")]
+use crate::template::template_sections::section_header::encode_section;
+use crate::template::template_sections::section_header::{decode_section, SectionFormat};
+use fnv::FnvHashMap;
+#[cfg(feature = "encode")]
+use rkyv::Serialize;
+use rkyv::{Archive, Deserialize};
+use wasm_bindgen::prelude::*;
+
+pub(super) const ELEMENT_TEMPLATE_SECTION: SectionFormat = SectionFormat {
+  magic: *b"LETM",
+  version: 1,
+};
+
+/**
+ * The element templates of a bundle, keyed by template id.
+ * Each template is a list of operations building its element tree depth first:
+ * `CreateElement` opens an element as a child of the open one, `EndElement` closes it,
+ * and every other operation applies to the innermost open element.
+ */
+#[derive(Clone, Default, Archive, Deserialize)]
+#[archive(check_bytes)]
+#[cfg_attr(feature = "encode", derive(Serialize))]
+#[wasm_bindgen]
+pub struct RawElementTemplate {
+  pub(crate) template_id_to_operations: FnvHashMap<String, Vec<ElementTemplateOperation>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Archive, Deserialize)]
+#[archive(check_bytes)]
+#[cfg_attr(feature = "encode", derive(Serialize))]
+pub(crate) enum ElementTemplateOperation {
+  /// `tag_name` is the tag written to the document, e.g. `x-view` for `view`.
+  CreateElement {
+    tag_name: String,
+  },
+  SetId(String),
+  AddClass(String),
+  /// Attributes are processed like `__SetAttribute`, inline styles are transformed.
+  SetAttribute {
+    key: String,
+    value: String,
+  },
+  /// Builtin attributes are written to the element as they are.
+  SetBuiltinAttribute {
+    key: String,
+    value: String,
+  },
+  SetDataset {
+    key: String,
+    value: String,
+  },
+  AddEvent {
+    event_type: String,
+    name: String,
+    value: String,
+  },
+  EndElement,
+}
+
+#[wasm_bindgen]
+impl RawElementTemplate {
+  #[wasm_bindgen(constructor)]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /**
+   * Opens an element in the template identified by `template_id`.
+   * If the template does not exist, it is created and the element becomes its root.
+   * @param template_id - The ID of the element template.
+   * @param tag_name - The tag written to the document, e.g. "x-view".
+   */
+  pub fn create_element(&mut self, template_id: String, tag_name: String) {
+    self.push_operation(
+      template_id,
+      ElementTemplateOperation::CreateElement { tag_name },
+    );
+  }
+
+  /**
+   * Closes the innermost open element of the template.
+   * @param template_id - The ID of the element template.
+   */
+  pub fn end_element(&mut self, template_id: String) {
+    self.push_operation(template_id, ElementTemplateOperation::EndElement);
+  }
+
+  pub fn set_id(&mut self, template_id: String, id: String) {
+    self.push_operation(template_id, ElementTemplateOperation::SetId(id));
+  }
+
+  pub fn add_class(&mut self, template_id: String, class_name: String) {
+    self.push_operation(template_id, ElementTemplateOperation::AddClass(class_name));
+  }
+
+  pub fn set_attribute(&mut self, template_id: String, key: String, value: String) {
+    self.push_operation(
+      template_id,
+      ElementTemplateOperation::SetAttribute { key, value },
+    );
+  }
+
+  pub fn set_builtin_attribute(&mut self, template_id: String, key: String, value: String) {
+    self.push_operation(
+      template_id,
+      ElementTemplateOperation::SetBuiltinAttribute { key, value },
+    );
+  }
+
+  /**
+   * Sets a dataset entry of the open element.
+   * @param value - The string value, the dataset of a template only holds strings.
+   */
+  pub fn set_dataset(&mut self, template_id: String, key: String, value: String) {
+    self.push_operation(
+      template_id,
+      ElementTemplateOperation::SetDataset { key, value },
+    );
+  }
+
+  /**
+   * Binds a cross thread event handler to the open element.
+   * @param event_type - The event type, e.g. "bindEvent" or "catchEvent".
+   * @param name - The event name, e.g. "tap".
+   * @param value - The handler identifier.
+   */
+  pub fn add_event(
+    &mut self,
+    template_id: String,
+    event_type: String,
+    name: String,
+    value: String,
+  ) {
+    self.push_operation(
+      template_id,
+      ElementTemplateOperation::AddEvent {
+        event_type,
+        name,
+        value,
+      },
+    );
+  }
+
+  /**
+   * Encodes the element templates into a Uint8Array using rkyv serialization, behind a section header.
+   * Fails if a template does not build exactly one element tree.
+   */
+  #[cfg(feature = "encode")]
+  pub fn encode(&self) -> Result<js_sys::Uint8Array, JsError> {
+    let serialized = self.encode_to_bytes()?;
+    Ok(js_sys::Uint8Array::from(serialized.as_slice()))
+  }
+
+  /**
+   * Decodes an element template section, failing on truncated or malformed buffers.
+   */
+  pub fn decode(buffer: js_sys::Uint8Array) -> Result<RawElementTemplate, JsError> {
+    Ok(RawElementTemplate::try_from(buffer.to_vec().as_slice())?)
+  }
+
+  pub fn has_template(&self, template_id: &str) -> bool {
+    self.template_id_to_operations.contains_key(template_id)
+  }
+}
+
+impl RawElementTemplate {
+  fn push_operation(&mut self, template_id: String, operation: ElementTemplateOperation) {
+    self
+      .template_id_to_operations
+      .entry(template_id)
+      .or_default()
+      .push(operation);
+  }
+
+  #[cfg(feature = "encode")]
+  pub(crate) fn encode_to_bytes(&self) -> Result<Vec<u8>, ElementTemplateError> {
+    for template_id in self.template_id_to_operations.keys() {
+      self.operations(template_id)?;
+    }
+    Ok(encode_section(&ELEMENT_TEMPLATE_SECTION, self)?)
+  }
+
+  /**
+   * The operations of a template, checked to open and close exactly one root element.
+   */
+  #[cfg(any(feature = "encode", feature = "server", test))]
+  pub(crate) fn operations(
+    &self,
+    template_id: &str,
+  ) -> Result<&[ElementTemplateOperation], ElementTemplateError> {
+    let operations = self
+      .template_id_to_operations
+      .get(template_id)
+      .ok_or_else(|| ElementTemplateError::UnknownTemplate(template_id.to_string()))?;
+    let mut depth = 0usize;
+    for (index, operation) in operations.iter().enumerate() {
+      // everything but the root needs an open element, a second root would have no parent
+      let is_valid = depth > 0
+        || (index == 0 && matches!(operation, ElementTemplateOperation::CreateElement { .. }));
+      if !is_valid {
+        return Err(ElementTemplateError::MalformedTemplate(
+          template_id.to_string(),
+        ));
+      }
+      match operation {
+        ElementTemplateOperation::CreateElement { .. } => depth += 1,
+        ElementTemplateOperation::EndElement => depth -= 1,
+        _ => {}
+      }
+    }
+    if depth != 0 || operations.is_empty() {
+      return Err(ElementTemplateError::MalformedTemplate(
+        template_id.to_string(),
+      ));
+    }
+    Ok(operations)
+  }
+}
+
+impl TryFrom<&[u8]> for RawElementTemplate {
+  type Error = ElementTemplateError;
+  fn try_from(buffer: &[u8]) -> Result<RawElementTemplate, Self::Error> {
+    Ok(decode_section(&ELEMENT_TEMPLATE_SECTION, buffer)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn operations_must_build_one_element_tree() {
+    let mut templates = RawElementTemplate::new();
+    templates.create_element("card".to_string(), "x-view".to_string());
+    templates.create_element("card".to_string(), "x-text".to_string());
+    templates.end_element("card".to_string());
+    templates.end_element("card".to_string());
+    assert_eq!(templates.operations("card").map(<[_]>::len), Ok(4));
+
+    templates.create_element("card".to_string(), "x-view".to_string());
+    templates.end_element("card".to_string());
+    assert_eq!(
+      templates.operations("card"),
+      Err(ElementTemplateError::MalformedTemplate("card".to_string()))
+    );
+
+    templates.create_element("open".to_string(), "x-view".to_string());
+    assert_eq!(
+      templates.operations("open"),
+      Err(ElementTemplateError::MalformedTemplate("open".to_string()))
+    );
+    templates.add_class("detached".to_string(), "a".to_string());
+    assert_eq!(
+      templates.operations("detached"),
+      Err(ElementTemplateError::MalformedTemplate(
+        "detached".to_string()
+      ))
+    );
+    assert_eq!(
+      templates.operations("missing"),
+      Err(ElementTemplateError::UnknownTemplate("missing".to_string()))
+    );
+  }
+
+  #[cfg(feature = "encode")]
+  #[test]
+  fn roundtrips_through_the_section_header() {
+    let mut templates = RawElementTemplate::new();
+    templates.create_element("card".to_string(), "x-view".to_string());
+    templates.set_dataset("card".to_string(), "index".to_string(), "1".to_string());
+    templates.end_element("card".to_string());
+    let bytes = templates.encode_to_bytes().unwrap();
+    let decoded = RawElementTemplate::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(
+      decoded.operations("card").unwrap(),
+      templates.operations("card").unwrap()
+    );
+    assert!(RawElementTemplate::try_from(&bytes[..bytes.len() - 1]).is_err());
+  }
+}
@@ -4,4 +4,6 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+pub(crate) mod element_template;
+pub(crate) mod section_header;
 pub(crate) mod style_info;
@@ -0,0 +1,192 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The header written in front of every rkyv-encoded template section.
+//!
+//! Layout, little endian: 4 bytes of magic naming the section, the `u32` format
+//! version and the `u32` length of the rkyv payload that follows. Buffers are
+//! validated with bytecheck before they are read, since templates are loaded
+//! from the network and a corrupt buffer must not reach the archived data.
+
+use rkyv::de::deserializers::SharedDeserializeMap;
+use rkyv::ser::serializers::AllocSerializer;
+use rkyv::validation::validators::DefaultValidator;
+use rkyv::{Archive, CheckBytes, Deserialize, Serialize};
+use std::fmt;
+
+const HEADER_SIZE: usize = 12;
+
+pub(crate) struct SectionFormat {
+  pub(crate) magic: [u8; 4],
+  /// Bumped whenever the archived layout of the section changes.
+  pub(crate) version: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SectionError {
+  /// The buffer does not start with the magic of the expected section.
+  UnknownMagic,
+  /// The section was written by an encoder with a different format version.
+  UnsupportedVersion { found: u32, supported: u32 },
+  /// The buffer is shorter or longer than the header says.
+  LengthMismatch { expected: usize, found: usize },
+  /// The payload failed validation.
+  Corrupt(String),
+  /// rkyv failed to serialize the section.
+  Encode(String),
+}
+
+impl fmt::Display for SectionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SectionError::UnknownMagic => f.write_str("the buffer is not a section of the expected kind"),
+      SectionError::UnsupportedVersion { found, supported } => write!(
+        f,
+        "the section has format version {found}, but only version {supported} is supported"
+      ),
+      SectionError::LengthMismatch { expected, found } => write!(
+        f,
+        "the section payload should be {expected} bytes, but {found} bytes were given"
+      ),
+      SectionError::Corrupt(reason) => write!(f, "the section is corrupt: {reason}"),
+      SectionError::Encode(reason) => write!(f, "the section could not be encoded: {reason}"),
+    }
+  }
+}
+
+impl std::error::Error for SectionError {}
+
+pub(crate) fn encode_section<T>(format: &SectionFormat, value: &T) -> Result<Vec<u8>, SectionError>
+where
+  T: Serialize<AllocSerializer<1024>>,
+{
+  let payload =
+    rkyv::to_bytes::<_, 1024>(value).map_err(|e| SectionError::Encode(format!("{e:?}")))?;
+  let mut buffer = Vec::with_capacity(HEADER_SIZE + payload.len());
+  buffer.extend_from_slice(&format.magic);
+  buffer.extend_from_slice(&format.version.to_le_bytes());
+  buffer.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+  buffer.extend_from_slice(&payload);
+  Ok(buffer)
+}
+
+pub(crate) fn decode_section<T>(format: &SectionFormat, bytes: &[u8]) -> Result<T, SectionError>
+where
+  T: Archive,
+  for<'a> T::Archived: CheckBytes<DefaultValidator<'a>> + Deserialize<T, SharedDeserializeMap>,
+{
+  let Some((header, payload)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+    return Err(if bytes.starts_with(&format.magic) {
+      SectionError::LengthMismatch {
+        expected: HEADER_SIZE,
+        found: bytes.len(),
+      }
+    } else {
+      SectionError::UnknownMagic
+    });
+  };
+  if header[..4] != format.magic {
+    return Err(SectionError::UnknownMagic);
+  }
+  let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+  if version != format.version {
+    return Err(SectionError::UnsupportedVersion {
+      found: version,
+      supported: format.version,
+    });
+  }
+  let length = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
+  if length != payload.len() {
+    return Err(SectionError::LengthMismatch {
+      expected: length,
+      found: payload.len(),
+    });
+  }
+  // rkyv needs the archived data aligned, which a borrowed slice does not promise
+  let mut aligned = rkyv::AlignedVec::with_capacity(payload.len());
+  aligned.extend_from_slice(payload);
+  rkyv::from_bytes::<T>(&aligned).map_err(|e| SectionError::Corrupt(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TEST_SECTION: SectionFormat = SectionFormat {
+    magic: *b"TEST",
+    version: 2,
+  };
+
+  #[derive(Debug, PartialEq, Archive, Deserialize, Serialize)]
+  #[archive(check_bytes)]
+  struct Payload {
+    names: Vec<String>,
+  }
+
+  fn encoded() -> Vec<u8> {
+    encode_section(
+      &TEST_SECTION,
+      &Payload {
+        names: vec!["a".to_string(), "b".to_string()],
+      },
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn roundtrips_through_the_header() {
+    assert_eq!(
+      decode_section::<Payload>(&TEST_SECTION, &encoded()),
+      Ok(Payload {
+        names: vec!["a".to_string(), "b".to_string()],
+      })
+    );
+  }
+
+  #[test]
+  fn rejects_foreign_and_incompatible_buffers() {
+    let other_section = SectionFormat {
+      magic: *b"MISC",
+      version: 2,
+    };
+    assert_eq!(
+      decode_section::<Payload>(&other_section, &encoded()),
+      Err(SectionError::UnknownMagic)
+    );
+    let newer_section = SectionFormat {
+      magic: *b"TEST",
+      version: 3,
+    };
+    assert_eq!(
+      decode_section::<Payload>(&newer_section, &encoded()),
+      Err(SectionError::UnsupportedVersion {
+        found: 2,
+        supported: 3
+      })
+    );
+    assert_eq!(
+      decode_section::<Payload>(&TEST_SECTION, b"TE"),
+      Err(SectionError::UnknownMagic)
+    );
+  }
+
+  #[test]
+  fn rejects_truncated_and_corrupt_payloads() {
+    let bytes = encoded();
+    assert!(matches!(
+      decode_section::<Payload>(&TEST_SECTION, &bytes[..bytes.len() - 1]),
+      Err(SectionError::LengthMismatch { .. })
+    ));
+    let mut corrupt = bytes.clone();
+    let payload_end = corrupt.len();
+    // the root object sits at the end of the payload, point its vector far outside the buffer
+    corrupt[payload_end - 8..payload_end - 4].copy_from_slice(&i32::MAX.to_le_bytes());
+    assert!(matches!(
+      decode_section::<Payload>(&TEST_SECTION, &corrupt),
+      Err(SectionError::Corrupt(_))
+    ));
+  }
+}
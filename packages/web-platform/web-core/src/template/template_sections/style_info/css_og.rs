@@ -0,0 +1,268 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Class rules extracted for CSS OG (`enableCSSSelector: false`).
+//!
+//! Without a selector engine, an element gets the declarations of every rule
+//! whose class names it carries. Those are merged by cascade rather than in
+//! class attribute order: more class names win over fewer, later rules win over
+//! earlier ones (imported style sheets come before their importers), and
+//! `!important` declarations win over normal ones.
+
+use fnv::FnvHashMap;
+use rkyv::{Archive, Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub(crate) struct CssOgDeclaration {
+  pub(crate) property: String,
+  pub(crate) value: String,
+  pub(crate) is_important: bool,
+}
+
+impl CssOgDeclaration {
+  /**
+   * Splits a declaration generated by the style transformer, `name:value;` or `name:value !important;`.
+   */
+  pub(crate) fn from_generated(declaration_str: &str) -> Option<Self> {
+    let declaration_str = declaration_str.strip_suffix(';').unwrap_or(declaration_str);
+    let (property, value) = declaration_str.split_once(':')?;
+    // declarations from the style info section keep `!important` as part of their value tokens
+    let value = value.trim_end();
+    let important_start = value.len().saturating_sub("!important".len());
+    let (value, is_important) = if value.is_char_boundary(important_start)
+      && value[important_start..].eq_ignore_ascii_case("!important")
+    {
+      (value[..important_start].trim_end(), true)
+    } else {
+      (value, false)
+    };
+    Some(CssOgDeclaration {
+      property: property.to_string(),
+      value: value.to_string(),
+      is_important,
+    })
+  }
+
+  #[cfg(any(feature = "client", feature = "server", test))]
+  fn generate_to_string_buf(&self, string_buf: &mut String) {
+    string_buf.push_str(&self.property);
+    string_buf.push(':');
+    string_buf.push_str(&self.value);
+    string_buf.push_str(if self.is_important {
+      " !important;"
+    } else {
+      ";"
+    });
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub(crate) struct CssOgRule {
+  // a compound selector, like .a.b, matches elements carrying all of these class names
+  pub(crate) class_names: Vec<String>,
+  // the cascade order of the style sheet that declared the rule
+  pub(crate) style_sheet_order: u32,
+  // the index of the rule in that style sheet
+  pub(crate) rule_order: u32,
+  pub(crate) declarations: Vec<CssOgDeclaration>,
+}
+
+impl CssOgRule {
+  // compares specificity first, then source order
+  #[cfg(any(feature = "client", feature = "server", test))]
+  fn cascade_key(&self) -> (usize, u32, u32) {
+    (
+      self.class_names.len(),
+      self.style_sheet_order,
+      self.rule_order,
+    )
+  }
+}
+
+#[derive(Clone, Default, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub(crate) struct CssOgStyleSheet {
+  pub(crate) rules: Vec<CssOgRule>,
+  class_name_to_rule_indices: FnvHashMap<String, Vec<u32>>,
+}
+
+impl CssOgStyleSheet {
+  pub(crate) fn push_rule(&mut self, rule: CssOgRule) {
+    let rule_index = self.rules.len() as u32;
+    for class_name in rule.class_names.iter() {
+      let rule_indices = self
+        .class_name_to_rule_indices
+        .entry(class_name.clone())
+        .or_default();
+      if rule_indices.last() != Some(&rule_index) {
+        rule_indices.push(rule_index);
+      }
+    }
+    self.rules.push(rule);
+  }
+
+  /**
+   * Resolves the declarations of all rules matching an element with these class names.
+   * Every property keeps only the declaration that wins the cascade, and the winners are
+   * written in cascade order so shorthands and longhands still override each other correctly.
+   */
+  #[cfg(any(feature = "client", feature = "server", test))]
+  pub(crate) fn resolve(&self, class_names: &[String]) -> String {
+    let class_name_set: fnv::FnvHashSet<&str> = class_names.iter().map(String::as_str).collect();
+    let mut matched_rules: Vec<&CssOgRule> = Vec::new();
+    let mut visited_rule_indices: fnv::FnvHashSet<u32> = fnv::FnvHashSet::default();
+    for class_name in class_name_set.iter() {
+      let Some(rule_indices) = self.class_name_to_rule_indices.get(*class_name) else {
+        continue;
+      };
+      for rule_index in rule_indices.iter() {
+        if !visited_rule_indices.insert(*rule_index) {
+          continue;
+        }
+        let rule = &self.rules[*rule_index as usize];
+        if rule
+          .class_names
+          .iter()
+          .all(|name| class_name_set.contains(name.as_str()))
+        {
+          matched_rules.push(rule);
+        }
+      }
+    }
+    matched_rules.sort_by_key(|rule| rule.cascade_key());
+
+    let declarations: Vec<&CssOgDeclaration> = matched_rules
+      .iter()
+      .flat_map(|rule| rule.declarations.iter())
+      .collect();
+    let important_properties: fnv::FnvHashSet<&str> = declarations
+      .iter()
+      .filter(|declaration| declaration.is_important)
+      .map(|declaration| declaration.property.as_str())
+      .collect();
+    // walk backwards so the last declaration of each property is the one kept
+    let mut seen_properties: fnv::FnvHashSet<&str> = fnv::FnvHashSet::default();
+    let mut winners: Vec<&CssOgDeclaration> = Vec::with_capacity(declarations.len());
+    for declaration in declarations.iter().rev() {
+      let property = declaration.property.as_str();
+      if important_properties.contains(property) && !declaration.is_important {
+        continue;
+      }
+      if seen_properties.insert(property) {
+        winners.push(declaration);
+      }
+    }
+
+    let mut result = String::new();
+    for declaration in winners.iter().rev() {
+      declaration.generate_to_string_buf(&mut result);
+    }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rule(
+    class_names: &[&str],
+    style_sheet_order: u32,
+    rule_order: u32,
+    declarations: &[&str],
+  ) -> CssOgRule {
+    CssOgRule {
+      class_names: class_names.iter().map(|name| name.to_string()).collect(),
+      style_sheet_order,
+      rule_order,
+      declarations: declarations
+        .iter()
+        .map(|declaration| CssOgDeclaration::from_generated(declaration).unwrap())
+        .collect(),
+    }
+  }
+
+  fn class_names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+  }
+
+  #[test]
+  fn later_rules_win_regardless_of_class_attribute_order() {
+    let mut style_sheet = CssOgStyleSheet::default();
+    style_sheet.push_rule(rule(&["a"], 0, 0, &["color:red;", "width:1px;"]));
+    style_sheet.push_rule(rule(&["b"], 0, 1, &["color:blue;"]));
+    assert_eq!(
+      style_sheet.resolve(&class_names(&["b", "a"])),
+      "width:1px;color:blue;"
+    );
+  }
+
+  #[test]
+  fn imported_style_sheets_come_first() {
+    let mut style_sheet = CssOgStyleSheet::default();
+    style_sheet.push_rule(rule(&["a"], 1, 0, &["color:red;"]));
+    style_sheet.push_rule(rule(&["a"], 0, 5, &["color:blue;"]));
+    assert_eq!(style_sheet.resolve(&class_names(&["a"])), "color:red;");
+  }
+
+  #[test]
+  fn important_declarations_win_over_later_ones() {
+    let mut style_sheet = CssOgStyleSheet::default();
+    style_sheet.push_rule(rule(&["a"], 0, 0, &["color:red !important;"]));
+    style_sheet.push_rule(rule(&["b"], 0, 1, &["color:blue;", "height:2px;"]));
+    assert_eq!(
+      style_sheet.resolve(&class_names(&["a", "b"])),
+      "color:red !important;height:2px;"
+    );
+  }
+
+  #[test]
+  fn compound_class_selectors_need_every_class_and_are_more_specific() {
+    let mut style_sheet = CssOgStyleSheet::default();
+    style_sheet.push_rule(rule(&["a", "b"], 0, 0, &["color:green;"]));
+    style_sheet.push_rule(rule(&["a"], 0, 1, &["color:red;"]));
+    assert_eq!(style_sheet.resolve(&class_names(&["a"])), "color:red;");
+    assert_eq!(
+      style_sheet.resolve(&class_names(&["b", "a"])),
+      "color:green;"
+    );
+    assert_eq!(style_sheet.resolve(&class_names(&["c"])), "");
+  }
+
+  #[test]
+  fn parses_generated_declarations() {
+    assert_eq!(
+      CssOgDeclaration::from_generated("color:red!IMPORTANT;"),
+      Some(CssOgDeclaration {
+        property: "color".to_string(),
+        value: "red".to_string(),
+        is_important: true,
+      })
+    );
+    assert_eq!(
+      CssOgDeclaration::from_generated("background:url(a:b);"),
+      Some(CssOgDeclaration {
+        property: "background".to_string(),
+        value: "url(a:b)".to_string(),
+        is_important: false,
+      })
+    );
+  }
+
+  #[test]
+  fn shorthands_keep_their_cascade_position() {
+    let mut style_sheet = CssOgStyleSheet::default();
+    style_sheet.push_rule(rule(&["a"], 0, 0, &["margin:0;"]));
+    style_sheet.push_rule(rule(&["b"], 0, 1, &["margin-top:5px;"]));
+    style_sheet.push_rule(rule(&["c"], 0, 2, &["margin:1px;"]));
+    assert_eq!(
+      style_sheet.resolve(&class_names(&["a", "b", "c"])),
+      "margin-top:5px;margin:1px;"
+    );
+  }
+}
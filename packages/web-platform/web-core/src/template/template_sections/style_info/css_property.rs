@@ -10,7 +10,7 @@ use lazy_static::lazy_static;
 use rkyv::Serialize;
 use rkyv::{Archive, Deserialize};
 
-use crate::css_tokenizer::tokenize;
+use crate::css_tokenizer::{token_types, tokenize};
 
 const STYLE_PROPERTY_MAP: &[&str] = &[
   "",
@@ -243,6 +243,7 @@ lazy_static! {
 
 #[repr(u32)]
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 pub enum CSSPropertyEnum {
   Unknown = 0,
@@ -474,6 +475,7 @@ impl CSSPropertyEnum {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 pub struct CSSProperty {
   pub id: CSSPropertyEnum,
@@ -570,6 +572,7 @@ impl std::fmt::Display for CSSProperty {
 }
 
 #[derive(Clone, PartialEq, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 pub struct ValueToken {
   pub token_type: u8,
@@ -577,6 +580,7 @@ pub struct ValueToken {
 }
 
 #[derive(Clone, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 pub struct ParsedDeclaration {
   pub property_id: CSSProperty,
@@ -595,6 +599,15 @@ impl ParsedDeclaration {
     tokenize::tokenize(&property_value, &mut self_entity);
     self_entity
   }
+  // the encoder keeps `!important` in the value tokens, so look there as well
+  pub(crate) fn has_important(&self) -> bool {
+    self.is_important
+      || self.value_token_list.windows(2).any(|pair| {
+        pair[0].token_type == token_types::DELIM_TOKEN
+          && pair[0].value == "!"
+          && pair[1].value.eq_ignore_ascii_case("important")
+      })
+  }
 }
 
 impl tokenize::Parser for ParsedDeclaration {
@@ -0,0 +1,487 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! A small model of the CSS text written by `StyleInfoDecoder`.
+//!
+//! Passes that work on whole rules, like minification or dropping rules no
+//! element uses, parse the decoded style content into this tree instead of
+//! decoding the style info section again.
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CssDeclaration {
+  pub(crate) property: String,
+  // `!important` stays part of the value, like `red !important`
+  pub(crate) value: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum AtRuleBody {
+  // like @layer base,components;
+  Statement,
+  // like @font-face{...}
+  Declarations(Vec<CssDeclaration>),
+  // like @media (...){...} or @keyframes name{...}
+  Rules(Vec<CssNode>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum CssNode {
+  Style {
+    selectors: String,
+    declarations: Vec<CssDeclaration>,
+  },
+  AtRule {
+    // the at-keyword and its prelude, like `@media (min-width:100px)`
+    prelude: String,
+    body: AtRuleBody,
+  },
+}
+
+impl CssNode {
+  fn is_empty(&self) -> bool {
+    match self {
+      CssNode::Style { declarations, .. } => declarations.is_empty(),
+      CssNode::AtRule {
+        body: AtRuleBody::Declarations(declarations),
+        ..
+      } => declarations.is_empty(),
+      // an empty @layer block still declares the layer order
+      CssNode::AtRule {
+        prelude,
+        body: AtRuleBody::Rules(rules),
+      } => rules.is_empty() && !is_at_rule(prelude, "layer"),
+      CssNode::AtRule {
+        body: AtRuleBody::Statement,
+        ..
+      } => false,
+    }
+  }
+}
+
+pub(crate) fn is_at_rule(prelude: &str, name: &str) -> bool {
+  prelude
+    .strip_prefix('@')
+    .and_then(|rest| rest.get(..name.len()))
+    .is_some_and(|keyword| keyword.eq_ignore_ascii_case(name))
+    && prelude[1 + name.len()..]
+      .bytes()
+      .next()
+      .is_none_or(|next| !(next.is_ascii_alphanumeric() || next == b'-'))
+}
+
+// at-rules whose block holds declarations rather than rules
+const DECLARATION_AT_RULES: [&str; 5] = [
+  "font-face",
+  "page",
+  "property",
+  "counter-style",
+  "font-palette-values",
+];
+
+pub(crate) fn parse_css(css: &str) -> Vec<CssNode> {
+  let mut scanner = Scanner {
+    source: css,
+    position: 0,
+  };
+  scanner.parse_rules()
+}
+
+pub(crate) fn write_css(nodes: &[CssNode], buffer: &mut String) {
+  for node in nodes.iter() {
+    match node {
+      CssNode::Style {
+        selectors,
+        declarations,
+      } => {
+        buffer.push_str(selectors);
+        write_declarations(declarations, buffer);
+      }
+      CssNode::AtRule { prelude, body } => {
+        buffer.push_str(prelude);
+        match body {
+          AtRuleBody::Statement => buffer.push(';'),
+          AtRuleBody::Declarations(declarations) => write_declarations(declarations, buffer),
+          AtRuleBody::Rules(rules) => {
+            buffer.push('{');
+            write_css(rules, buffer);
+            buffer.push('}');
+          }
+        }
+      }
+    }
+  }
+}
+
+fn write_declarations(declarations: &[CssDeclaration], buffer: &mut String) {
+  buffer.push('{');
+  for (index, declaration) in declarations.iter().enumerate() {
+    if index > 0 {
+      buffer.push(';');
+    }
+    buffer.push_str(&declaration.property);
+    buffer.push(':');
+    buffer.push_str(&declaration.value);
+  }
+  buffer.push('}');
+}
+
+/**
+ * Minifies decoded style content:
+ * 1. comments are removed and whitespace runs collapse to one space;
+ * 2. a declaration repeated later in the same block with the same value is removed; declarations
+ *    with different values are all kept, because an earlier one is the fallback when the engine
+ *    rejects a later one, like `background:red` before `background:linear-gradient(...)`;
+ * 3. empty rules are removed;
+ * 4. adjacent rules with identical declarations are merged into one rule with both selector lists.
+ */
+pub(crate) fn minify_css(css: &str) -> String {
+  let mut nodes = parse_css(css);
+  minify_nodes(&mut nodes);
+  let mut buffer = String::with_capacity(css.len());
+  write_css(&nodes, &mut buffer);
+  buffer
+}
+
+fn minify_nodes(nodes: &mut Vec<CssNode>) {
+  for node in nodes.iter_mut() {
+    match node {
+      CssNode::Style { declarations, .. }
+      | CssNode::AtRule {
+        body: AtRuleBody::Declarations(declarations),
+        ..
+      } => remove_repeated_declarations(declarations),
+      CssNode::AtRule {
+        body: AtRuleBody::Rules(rules),
+        ..
+      } => minify_nodes(rules),
+      CssNode::AtRule {
+        body: AtRuleBody::Statement,
+        ..
+      } => {}
+    }
+  }
+  nodes.retain(|node| !node.is_empty());
+
+  let mut merged: Vec<CssNode> = Vec::with_capacity(nodes.len());
+  for node in nodes.drain(..) {
+    if let (
+      Some(CssNode::Style {
+        selectors: previous_selectors,
+        declarations: previous_declarations,
+      }),
+      CssNode::Style {
+        selectors,
+        declarations,
+      },
+    ) = (merged.last_mut(), &node)
+    {
+      // the CSS OG decoder writes blocks without a selector, which must not be
+      // joined into a selector list
+      if previous_declarations == declarations
+        && !previous_selectors.trim().is_empty()
+        && !selectors.trim().is_empty()
+      {
+        previous_selectors.push(',');
+        previous_selectors.push_str(selectors);
+        continue;
+      }
+    }
+    merged.push(node);
+  }
+  *nodes = merged;
+}
+
+fn remove_repeated_declarations(declarations: &mut Vec<CssDeclaration>) {
+  let repeated: Vec<bool> = (0..declarations.len())
+    .map(|index| declarations[index + 1..].contains(&declarations[index]))
+    .collect();
+  let mut repeated = repeated.into_iter();
+  declarations.retain(|_| !repeated.next().unwrap_or(false));
+}
+
+/**
+ * Splits a selector list at the commas that are not nested in brackets or strings.
+ */
+#[cfg(any(feature = "server", test))]
+pub(crate) fn split_selector_list(selectors: &str) -> Vec<&str> {
+  let mut result = Vec::new();
+  let mut start = 0;
+  let mut scanner = Scanner {
+    source: selectors,
+    position: 0,
+  };
+  while scanner.position < selectors.len() {
+    let (_, stop) = scanner.read_until(b",");
+    let end = if stop.is_some() {
+      scanner.position - 1
+    } else {
+      scanner.position
+    };
+    result.push(&selectors[start..end]);
+    start = scanner.position;
+  }
+  result
+}
+
+struct Scanner<'a> {
+  source: &'a str,
+  position: usize,
+}
+
+impl<'a> Scanner<'a> {
+  // reads up to the first of `stops` outside strings, comments and brackets;
+  // the stop is consumed and returned. A `}` always stops, so broken values cannot eat the rest of the sheet
+  fn read_until(&mut self, stops: &[u8]) -> (&'a str, Option<u8>) {
+    let bytes = self.source.as_bytes();
+    let start = self.position;
+    let mut depth = 0usize;
+    while self.position < bytes.len() {
+      let byte = bytes[self.position];
+      match byte {
+        b'\\' => {
+          self.position += 2;
+          continue;
+        }
+        b'"' | b'\'' => {
+          self.skip_string(byte);
+          continue;
+        }
+        b'/' if bytes.get(self.position + 1) == Some(&b'*') => {
+          self.skip_comment();
+          continue;
+        }
+        b'(' | b'[' => depth += 1,
+        b')' | b']' => depth = depth.saturating_sub(1),
+        _ => {}
+      }
+      if (depth == 0 && stops.contains(&byte)) || (byte == b'}' && stops.contains(&b'}')) {
+        self.position += 1;
+        return (&self.source[start..self.position - 1], Some(byte));
+      }
+      self.position += 1;
+    }
+    self.position = bytes.len();
+    (&self.source[start..], None)
+  }
+
+  fn skip_string(&mut self, quote: u8) {
+    let bytes = self.source.as_bytes();
+    self.position += 1;
+    while self.position < bytes.len() {
+      match bytes[self.position] {
+        b'\\' => self.position += 2,
+        byte if byte == quote => {
+          self.position += 1;
+          return;
+        }
+        _ => self.position += 1,
+      }
+    }
+    self.position = bytes.len();
+  }
+
+  fn skip_comment(&mut self) {
+    self.position = match self.source[self.position + 2..].find("*/") {
+      Some(end) => self.position + 2 + end + 2,
+      None => self.source.len(),
+    };
+  }
+
+  fn parse_rules(&mut self) -> Vec<CssNode> {
+    let mut nodes = Vec::new();
+    loop {
+      let (prelude, stop) = self.read_until(b"{;}");
+      let prelude = clean(prelude);
+      match stop {
+        Some(b'{') => {
+          if !prelude.starts_with('@') {
+            nodes.push(CssNode::Style {
+              selectors: prelude,
+              declarations: self.parse_declarations(),
+            });
+          } else if DECLARATION_AT_RULES
+            .iter()
+            .any(|name| is_at_rule(&prelude, name))
+          {
+            let declarations = self.parse_declarations();
+            nodes.push(CssNode::AtRule {
+              prelude,
+              body: AtRuleBody::Declarations(declarations),
+            });
+          } else {
+            let rules = self.parse_rules();
+            nodes.push(CssNode::AtRule {
+              prelude,
+              body: AtRuleBody::Rules(rules),
+            });
+          }
+        }
+        // declarations outside of a block are dropped, like browsers do
+        Some(b';') => {
+          if prelude.starts_with('@') {
+            nodes.push(CssNode::AtRule {
+              prelude,
+              body: AtRuleBody::Statement,
+            });
+          }
+        }
+        _ => {
+          if prelude.starts_with('@') {
+            nodes.push(CssNode::AtRule {
+              prelude,
+              body: AtRuleBody::Statement,
+            });
+          }
+          return nodes;
+        }
+      }
+    }
+  }
+
+  fn parse_declarations(&mut self) -> Vec<CssDeclaration> {
+    let mut declarations = Vec::new();
+    loop {
+      let (declaration, stop) = self.read_until(b";}");
+      if let Some((property, value)) = declaration.split_once(':') {
+        let property = clean(property);
+        if !property.is_empty() {
+          declarations.push(CssDeclaration {
+            property,
+            value: clean(value),
+          });
+        }
+      }
+      if stop != Some(b';') {
+        return declarations;
+      }
+    }
+  }
+}
+
+// removes comments and collapses whitespace outside strings
+fn clean(text: &str) -> String {
+  let bytes = text.as_bytes();
+  let mut result = String::with_capacity(text.len());
+  let mut scanner = Scanner {
+    source: text,
+    position: 0,
+  };
+  let mut pending_space = false;
+  while scanner.position < bytes.len() {
+    let start = scanner.position;
+    let byte = bytes[start];
+    if byte.is_ascii_whitespace() {
+      pending_space = true;
+      scanner.position += 1;
+      continue;
+    }
+    if byte == b'/' && bytes.get(start + 1) == Some(&b'*') {
+      scanner.skip_comment();
+      pending_space = true;
+      continue;
+    }
+    match byte {
+      b'"' | b'\'' => scanner.skip_string(byte),
+      b'\\' => {
+        scanner.position = start + 1 + text[start + 1..].chars().next().map_or(0, char::len_utf8)
+      }
+      _ => {
+        scanner.position += 1;
+        while scanner.position < bytes.len()
+          && !bytes[scanner.position].is_ascii_whitespace()
+          && !matches!(bytes[scanner.position], b'"' | b'\'' | b'\\' | b'/')
+        {
+          scanner.position += 1;
+        }
+      }
+    }
+    if pending_space && !result.is_empty() {
+      result.push(' ');
+    }
+    pending_space = false;
+    result.push_str(&text[start..scanner.position.min(bytes.len())]);
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn roundtrip(css: &str) -> String {
+    let mut buffer = String::new();
+    write_css(&parse_css(css), &mut buffer);
+    buffer
+  }
+
+  #[test]
+  fn parses_rules_at_rules_and_strings() {
+    assert_eq!(
+      roundtrip(
+        "@layer a, b;\n.a > .b { content: \"};{\" ; background: url(data:image/png;base64,AA) }\
+         @media (min-width: 1px) { .c { width: 1px; } }\
+         @font-face { font-family: f; }\
+         @keyframes k { from { opacity: 0; } to { opacity: 1; } }"
+      ),
+      "@layer a, b;.a > .b{content:\"};{\";background:url(data:image/png;base64,AA)}\
+       @media (min-width: 1px){.c{width:1px}}@font-face{font-family:f}\
+       @keyframes k{from{opacity:0}to{opacity:1}}"
+    );
+  }
+
+  #[test]
+  fn minify_collapses_whitespace_and_comments() {
+    assert_eq!(
+      minify_css(".a { border : 1px   /* c */ solid\n red ; margin: 'a  b' }"),
+      ".a{border:1px solid red;margin:'a  b'}"
+    );
+  }
+
+  #[test]
+  fn minify_removes_repeated_declarations_only() {
+    assert_eq!(
+      minify_css(".a{width:1px;height:1px;width:2px;width:1px;height:1px}"),
+      ".a{width:2px;width:1px;height:1px}"
+    );
+    // earlier values are fallbacks for engines that reject the later ones
+    assert_eq!(
+      minify_css(
+        ".a{background:red;background:linear-gradient(red,blue);display:-webkit-box;display:flex}"
+      ),
+      ".a{background:red;background:linear-gradient(red,blue);display:-webkit-box;display:flex}"
+    );
+    assert_eq!(
+      minify_css(".a{color:red !important;color:blue}"),
+      ".a{color:red !important;color:blue}"
+    );
+  }
+
+  #[test]
+  fn minify_merges_adjacent_identical_blocks_and_drops_empty_rules() {
+    assert_eq!(
+      minify_css(
+        ".a{width:1px}.b{width:1px}.c{}.d{width:2px}.e{width:1px}@media (x){.f{}}@layer g{}"
+      ),
+      ".a,.b{width:1px}.d{width:2px}.e{width:1px}@layer g{}"
+    );
+  }
+
+  #[test]
+  fn minify_does_not_merge_blocks_without_selectors() {
+    assert_eq!(
+      minify_css("{width:1px}#x{width:1px}.a{width:1px}{width:1px}"),
+      "{width:1px}#x,.a{width:1px}{width:1px}"
+    );
+  }
+
+  #[test]
+  fn splits_selector_lists_outside_brackets() {
+    assert_eq!(
+      split_selector_list(".a:is(.b,.c),[x=\",\"],.d"),
+      vec![".a:is(.b,.c)", "[x=\",\"]", ".d"]
+    );
+  }
+}
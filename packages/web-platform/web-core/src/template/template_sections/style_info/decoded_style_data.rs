@@ -6,17 +6,30 @@
 
 use super::raw_style_info::RawStyleInfo;
 use super::style_info_decoder::StyleInfoDecoder;
+use super::style_info_error::StyleInfoError;
+use super::style_source_map::StyleSourceMapping;
+use crate::style_transformer::token_transformer::{TransformerConfig, UnitTable};
+use crate::style_transformer::CssDiagnostic;
+use crate::template::template_sections::section_header::{
+  decode_section, encode_section, SectionFormat,
+};
 use rkyv::{Archive, Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+pub(super) const DECODED_STYLE_DATA_SECTION: SectionFormat = SectionFormat {
+  magic: *b"LSTD",
+  version: 1,
+};
+
 #[derive(Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct DecodedStyleData {
   pub(super) style_content: Option<String>,
   // the font face should be placed at the head of the css content, therefore we use a separate buffer
   pub(super) font_face_content: Option<String>,
   // if we are processing font_face, the declaration should be pushed to font_face_content for generating
-  pub(super) css_og_css_id_to_class_selector_name_to_declarations_map:
-    Option<super::CssOgCssIdToClassSelectorNameToDeclarationsMap>,
+  pub(super) css_og_css_id_to_style_sheet_map: Option<super::CssOgCssIdToStyleSheetMap>,
+  pub(super) source_map: Option<Vec<StyleSourceMapping>>,
 }
 
 impl From<StyleInfoDecoder> for DecodedStyleData {
@@ -24,23 +37,110 @@ impl From<StyleInfoDecoder> for DecodedStyleData {
     DecodedStyleData {
       style_content: Some(decoder.style_content),
       font_face_content: Some(decoder.font_face_content),
-      css_og_css_id_to_class_selector_name_to_declarations_map: decoder
-        .css_og_css_id_to_class_selector_name_to_declarations_map,
+      css_og_css_id_to_style_sheet_map: decoder.css_og_css_id_to_style_sheet_map,
+      source_map: decoder.source_map,
+    }
+  }
+}
+
+/**
+ * How the style info section is turned into style content.
+ * By default CSS selectors are enabled and units are transformed by the built-in rules.
+ */
+#[derive(Clone)]
+pub struct StyleDecodeOptions {
+  pub entry_name: Option<String>,
+  pub enable_css_selector: bool,
+  pub transformer_config: TransformerConfig,
+  /// Collapses whitespace, removes repeated declarations and merges adjacent rules
+  /// with identical declarations.
+  pub minify: bool,
+  /// Links every rule back to its css id and source rule. It cannot be combined with
+  /// `minify`, which moves the rules.
+  pub source_map: bool,
+}
+
+impl Default for StyleDecodeOptions {
+  fn default() -> Self {
+    StyleDecodeOptions {
+      entry_name: None,
+      enable_css_selector: true,
+      transformer_config: TransformerConfig::default(),
+      minify: false,
+      source_map: false,
+    }
+  }
+}
+
+impl DecodedStyleData {
+  pub fn decode(
+    raw_style_info: RawStyleInfo,
+    options: StyleDecodeOptions,
+  ) -> Result<DecodedStyleData, StyleInfoError> {
+    if options.minify && options.source_map {
+      return Err(StyleInfoError::IncompatibleOptions(
+        "source maps cannot be generated for minified style content",
+      ));
+    }
+    let decoder = if options.source_map {
+      StyleInfoDecoder::new_with_source_map(
+        raw_style_info,
+        options.entry_name,
+        options.enable_css_selector,
+        options.transformer_config,
+      )?
+    } else {
+      StyleInfoDecoder::new(
+        raw_style_info,
+        options.entry_name,
+        options.enable_css_selector,
+        options.transformer_config,
+      )?
+    };
+    let mut decode_data: DecodedStyleData = decoder.into();
+    if options.minify {
+      decode_data.minify();
+    }
+    Ok(decode_data)
+  }
+
+  pub(super) fn encode_to_bytes(&self) -> Result<Vec<u8>, StyleInfoError> {
+    Ok(encode_section(&DECODED_STYLE_DATA_SECTION, self)?)
+  }
+
+  fn minify(&mut self) {
+    for content in [&mut self.style_content, &mut self.font_face_content]
+      .into_iter()
+      .flatten()
+    {
+      *content = super::css_tree::minify_css(content);
     }
   }
 }
 
+impl TryFrom<&[u8]> for DecodedStyleData {
+  type Error = StyleInfoError;
+  fn try_from(buffer: &[u8]) -> Result<DecodedStyleData, Self::Error> {
+    Ok(decode_section(&DECODED_STYLE_DATA_SECTION, buffer)?)
+  }
+}
+
 impl TryFrom<js_sys::Uint8Array> for DecodedStyleData {
   type Error = wasm_bindgen::JsError;
   fn try_from(buffer: js_sys::Uint8Array) -> Result<DecodedStyleData, Self::Error> {
-    let buf = buffer.to_vec();
-    let data = unsafe { rkyv::from_bytes_unchecked::<DecodedStyleData>(&buf) }.map_err(|e| {
-      wasm_bindgen::JsError::new(&format!("Failed to decode DecodedStyleData: {e:?}"))
-    })?;
-    Ok(data)
+    Ok(DecodedStyleData::try_from(buffer.to_vec().as_slice())?)
   }
 }
 
+/**
+ * Decodes the style info section into style content.
+ * Units in `unit_table` are rewritten with the table instead of the built-in rules;
+ * the table is consumed by the call.
+ * With `minify`, whitespace is collapsed, repeated declarations are removed and
+ * adjacent rules with identical declarations are merged.
+ * With `source_map`, the result also links every rule back to its css id and source rule,
+ * see `get_style_source_map`. It cannot be combined with `minify`, which moves the rules.
+ */
 #[wasm_bindgen]
 pub fn decode_style_info(
   buffer: js_sys::Uint8Array,
@@ -49,28 +149,63 @@ pub fn decode_style_info(
   transform_vw: bool,
   transform_vh: bool,
   transform_rem: bool,
+  unit_table: Option<UnitTable>,
+  minify: Option<bool>,
+  source_map: Option<bool>,
 ) -> Result<js_sys::Uint8Array, wasm_bindgen::JsError> {
-  let buf = buffer.to_vec();
-  let data = unsafe { rkyv::from_bytes_unchecked::<RawStyleInfo>(&buf) }
-    .map_err(|e| wasm_bindgen::JsError::new(&format!("Failed to decode RawStyleInfo: {e:?}")))?;
+  let data = RawStyleInfo::try_from(buffer.to_vec().as_slice())?;
 
-  let decode_data: DecodedStyleData = StyleInfoDecoder::new(
+  let decode_data = DecodedStyleData::decode(
     data,
-    entry_name,
-    config_enable_css_selector,
-    transform_vw,
-    transform_vh,
-    transform_rem,
-  )?
-  .into();
+    StyleDecodeOptions {
+      entry_name,
+      enable_css_selector: config_enable_css_selector,
+      transformer_config: TransformerConfig {
+        transform_vw,
+        transform_vh,
+        transform_rem,
+        unit_table: unit_table.unwrap_or_default(),
+      },
+      minify: minify.unwrap_or(false),
+      source_map: source_map.unwrap_or(false),
+    },
+  )?;
 
-  let serialized = rkyv::to_bytes::<_, 1024>(&decode_data).map_err(|e| {
-    wasm_bindgen::JsError::new(&format!("Failed to encode DecodedStyleData: {e:?}"))
-  })?;
+  let serialized = decode_data.encode_to_bytes()?;
 
   Ok(js_sys::Uint8Array::from(serialized.as_slice()))
 }
 
+/**
+ * Decodes the style info section like `decode_style_info`, but returns the diagnostics
+ * for declarations that were dropped or could not be fully understood instead of the styles.
+ */
+#[wasm_bindgen]
+pub fn diagnose_style_info(
+  buffer: js_sys::Uint8Array,
+  entry_name: Option<String>,
+  config_enable_css_selector: bool,
+  transform_vw: bool,
+  transform_vh: bool,
+  transform_rem: bool,
+  unit_table: Option<UnitTable>,
+) -> Result<Vec<CssDiagnostic>, wasm_bindgen::JsError> {
+  let data = RawStyleInfo::try_from(buffer.to_vec().as_slice())?;
+
+  let decoder = StyleInfoDecoder::new_with_diagnostics(
+    data,
+    entry_name,
+    config_enable_css_selector,
+    TransformerConfig {
+      transform_vw,
+      transform_vh,
+      transform_rem,
+      unit_table: unit_table.unwrap_or_default(),
+    },
+  )?;
+  Ok(decoder.diagnostics.unwrap_or_default())
+}
+
 #[wasm_bindgen]
 pub fn encode_legacy_json_generated_raw_style_info(
   raw_style_info: RawStyleInfo,
@@ -84,14 +219,15 @@ pub fn encode_legacy_json_generated_raw_style_info(
     raw_style_info,
     entry_name,
     config_enable_css_selector,
-    transform_vw,
-    transform_vh,
-    transform_rem,
+    TransformerConfig {
+      transform_vw,
+      transform_vh,
+      transform_rem,
+      ..Default::default()
+    },
   )?
   .into();
-  let serialized = rkyv::to_bytes::<_, 1024>(&decode_data).map_err(|e| {
-    wasm_bindgen::JsError::new(&format!("Failed to encode DecodedStyleData: {e:?}"))
-  })?;
+  let serialized = decode_data.encode_to_bytes()?;
   Ok(js_sys::Uint8Array::from(serialized.as_slice()))
 }
 
@@ -103,6 +239,19 @@ pub fn get_style_content(buffer: js_sys::Uint8Array) -> Result<String, wasm_bind
   Ok(decode_data.style_content.unwrap_or_default())
 }
 
+/**
+ * Returns the source map of style data decoded with `source_map` enabled, or an empty list.
+ */
+#[wasm_bindgen]
+pub fn get_style_source_map(
+  buffer: js_sys::Uint8Array,
+) -> Result<Vec<StyleSourceMapping>, wasm_bindgen::JsError> {
+  let decode_data = DecodedStyleData::try_from(buffer).map_err(|e| {
+    wasm_bindgen::JsError::new(&format!("Failed to decode from Uint8Array: {e:?}",))
+  })?;
+  Ok(decode_data.source_map.unwrap_or_default())
+}
+
 #[wasm_bindgen]
 pub fn get_font_face_content(buffer: js_sys::Uint8Array) -> Result<String, wasm_bindgen::JsError> {
   let decode_data = DecodedStyleData::try_from(buffer).map_err(|e| {
@@ -110,3 +259,104 @@ pub fn get_font_face_content(buffer: js_sys::Uint8Array) -> Result<String, wasm_
   })?;
   Ok(decode_data.font_face_content.unwrap_or_default())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::template::template_sections::section_header::SectionError;
+  use crate::template::template_sections::style_info::raw_style_info::StyleSheet;
+  use crate::template::template_sections::style_info::{Rule, RulePrelude, Selector};
+  use fnv::FnvHashMap;
+
+  fn class_rule(class_name: &str, declarations: &[(&str, &str)]) -> Rule {
+    let mut selector = Selector::new();
+    selector
+      .push_one_selector_section("ClassSelector".to_string(), class_name.to_string())
+      .unwrap();
+    let mut prelude = RulePrelude::new();
+    prelude.push_selector(selector);
+    let mut rule = Rule::new("StyleRule".to_string()).unwrap();
+    rule.set_prelude(prelude);
+    for (name, value) in declarations {
+      rule.push_declaration(name.to_string(), value.to_string());
+    }
+    rule
+  }
+
+  #[test]
+  fn decoded_style_data_is_read_back_through_its_section_header() {
+    let mut raw_style_info = RawStyleInfo::new();
+    raw_style_info.push_rule(0, class_rule("a", &[("width", "1px")]));
+    let decoded = DecodedStyleData::decode(raw_style_info, StyleDecodeOptions::default()).unwrap();
+    let bytes = decoded.encode_to_bytes().unwrap();
+    let read_back = DecodedStyleData::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(read_back.style_content, decoded.style_content);
+    // a style info section is not decoded style data, even though both are rkyv archives
+    assert!(matches!(
+      RawStyleInfo::try_from(bytes.as_slice()),
+      Err(StyleInfoError::Section(SectionError::UnknownMagic))
+    ));
+    assert!(matches!(
+      DecodedStyleData::try_from(&bytes[..bytes.len() - 3]),
+      Err(StyleInfoError::Section(SectionError::LengthMismatch { .. }))
+    ));
+  }
+
+  #[test]
+  fn minify_merges_rules_and_removes_repeated_declarations() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![(
+        0,
+        StyleSheet {
+          imports: vec![],
+          rules: vec![
+            class_rule("a", &[("width", "1px"), ("height", "2px")]),
+            class_rule(
+              "b",
+              &[("width", "1px"), ("height", "2px"), ("height", "2px")],
+            ),
+          ],
+        },
+      )]),
+      style_content_str_size_hint: 0,
+    };
+    let mut decoded: DecodedStyleData =
+      StyleInfoDecoder::new(raw_style_info, None, true, TransformerConfig::default())
+        .unwrap()
+        .into();
+    decoded.minify();
+    assert_eq!(
+      decoded.style_content.unwrap(),
+      ".a:not([l-e-name]),.b:not([l-e-name]){width:1px;height:2px}"
+    );
+  }
+
+  #[test]
+  fn minify_keeps_css_og_rules_apart_from_selector_rules() {
+    let mut id_selector = Selector::new();
+    id_selector
+      .push_one_selector_section("IdSelector".to_string(), "x".to_string())
+      .unwrap();
+    let mut prelude = RulePrelude::new();
+    prelude.push_selector(id_selector);
+    let mut id_rule = Rule::new("StyleRule".to_string()).unwrap();
+    id_rule.set_prelude(prelude);
+    id_rule.push_declaration("width".to_string(), "1px".to_string());
+
+    let mut raw_style_info = RawStyleInfo::new();
+    raw_style_info.push_rule(0, class_rule("a", &[("width", "1px")]));
+    raw_style_info.push_rule(0, id_rule);
+    let decoded = DecodedStyleData::decode(
+      raw_style_info,
+      StyleDecodeOptions {
+        enable_css_selector: false,
+        minify: true,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    let style_content = decoded.style_content.unwrap();
+    assert!(!style_content.starts_with(','), "{style_content}");
+    assert!(style_content.contains("#x:not([l-e-name]){width:1px}"));
+  }
+}
@@ -9,6 +9,8 @@ use fnv::{FnvHashMap, FnvHashSet};
 #[derive(Default)]
 pub struct FlattenedStyleSheet {
   pub(crate) imported_by: Vec<i32>,
+  // the position of the style sheet in the cascade, imported style sheets come first
+  pub(crate) cascade_order: usize,
   pub(crate) rules: Vec<Rule>,
 }
 
@@ -45,6 +47,9 @@ impl From<RawStyleInfo> for FlattenedStyleInfo {
         sorted_css_ids.push(*css_id);
       }
     }
+    // keep the roots in a stable order, they are also the starting points of the cascade order
+    sorted_css_ids.sort_unstable();
+    let root_css_ids = sorted_css_ids.clone();
 
     let mut index = 0;
     // Process the queue in place
@@ -76,13 +81,27 @@ impl From<RawStyleInfo> for FlattenedStyleInfo {
       }
     }
 
-    // Step 3. generate flattened style info
+    // Step 3. rank the style sheets in cascade order
+    // an imported style sheet precedes the one importing it, like a depth first post-order walk of @import
+    let mut cascade_order_map: FnvHashMap<i32, usize> = FnvHashMap::default();
+    let mut next_cascade_order = 0;
+    for css_id in root_css_ids.iter() {
+      rank_in_cascade_order(
+        *css_id,
+        &style_info,
+        &mut cascade_order_map,
+        &mut next_cascade_order,
+      );
+    }
+
+    // Step 4. generate flattened style info
     for css_id in sorted_css_ids.iter() {
       if let Some(style_sheet) = style_info.css_id_to_style_sheet.remove(css_id) {
         let imported_by_set = imported_by_map.get(css_id).unwrap();
         let imported_by: Vec<i32> = imported_by_set.iter().cloned().collect();
         let flattened_style_sheet = FlattenedStyleSheet {
           imported_by,
+          cascade_order: cascade_order_map.get(css_id).copied().unwrap_or_default(),
           rules: style_sheet.rules,
         };
         flattened_style_info
@@ -96,6 +115,31 @@ impl From<RawStyleInfo> for FlattenedStyleInfo {
   }
 }
 
+fn rank_in_cascade_order(
+  css_id: i32,
+  style_info: &RawStyleInfo,
+  cascade_order_map: &mut FnvHashMap<i32, usize>,
+  next_cascade_order: &mut usize,
+) {
+  if cascade_order_map.contains_key(&css_id) {
+    return;
+  }
+  // reserve the entry first, so that a style sheet is ranked only once
+  cascade_order_map.insert(css_id, 0);
+  if let Some(style_sheet) = style_info.css_id_to_style_sheet.get(&css_id) {
+    for imported_css_id in style_sheet.imports.iter() {
+      rank_in_cascade_order(
+        *imported_css_id,
+        style_info,
+        cascade_order_map,
+        next_cascade_order,
+      );
+    }
+  }
+  cascade_order_map.insert(css_id, *next_cascade_order);
+  *next_cascade_order += 1;
+}
+
 #[cfg(test)]
 mod tests {
   use super::super::raw_style_info::StyleSheet;
@@ -238,6 +282,23 @@ mod tests {
       .collect();
     let expected_imported_by_4: FnvHashSet<i32> = [1, 2, 3, 4].iter().cloned().collect();
     assert_eq!(imported_by_4, expected_imported_by_4);
+
+    let cascade_order = |css_id: i32| {
+      flattened_info
+        .css_id_to_style_sheet
+        .get(&css_id)
+        .unwrap()
+        .cascade_order
+    };
+    assert_eq!(
+      [
+        cascade_order(4),
+        cascade_order(2),
+        cascade_order(3),
+        cascade_order(1)
+      ],
+      [0, 1, 2, 3]
+    );
   }
 
   #[test]
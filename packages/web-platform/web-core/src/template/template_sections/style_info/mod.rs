@@ -4,16 +4,20 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+pub(crate) mod css_og;
 pub(crate) mod css_property;
+pub(crate) mod css_tree;
 pub(crate) mod decoded_style_data;
 pub(crate) mod flattened_style_info;
+#[cfg(feature = "native")]
+mod native;
 pub(crate) mod raw_style_info;
 pub(crate) mod style_info_decoder;
+pub(crate) mod style_info_error;
+pub(crate) mod style_source_map;
 use fnv::FnvHashMap;
 use raw_style_info::RawStyleInfo;
-type CssOgClassSelectorNameToDeclarationsMap = FnvHashMap<String, String>;
-type CssOgCssIdToClassSelectorNameToDeclarationsMap =
-  FnvHashMap<i32, CssOgClassSelectorNameToDeclarationsMap>;
+type CssOgCssIdToStyleSheetMap = FnvHashMap<i32, css_og::CssOgStyleSheet>;
 
 #[cfg(any(feature = "client", feature = "server"))]
 pub(crate) mod style_sheet_resource;
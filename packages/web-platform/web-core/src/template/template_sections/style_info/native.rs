@@ -0,0 +1,207 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Plain Rust entry points for the style info section, so build tools can encode and
+//! inspect it without a JS host.
+
+use super::css_property::ParsedDeclaration;
+use super::decoded_style_data::DecodedStyleData;
+use super::raw_style_info::{
+  OneSimpleSelector, OneSimpleSelectorType, RawStyleInfo, Rule, RuleType, Selector,
+};
+use super::style_info_error::StyleInfoError;
+use super::style_source_map::StyleSourceMapping;
+
+impl RawStyleInfo {
+  /**
+   * Adds an import to the stylesheet identified by `css_id`, see `append_import`.
+   */
+  pub fn with_import(mut self, css_id: i32, import_css_id: i32) -> Self {
+    self.append_import(css_id, import_css_id);
+    self
+  }
+
+  /**
+   * Adds a rule to the stylesheet identified by `css_id`, see `push_rule`.
+   */
+  pub fn with_rule(mut self, css_id: i32, rule: Rule) -> Self {
+    self.push_rule(css_id, rule);
+    self
+  }
+
+  /**
+   * Encodes the style info section, the same bytes `encode` returns to JS.
+   */
+  pub fn to_bytes(&mut self) -> Result<Vec<u8>, StyleInfoError> {
+    self.encode_to_bytes()
+  }
+
+  /**
+   * Decodes a style info section, failing on truncated or malformed buffers.
+   */
+  pub fn from_bytes(bytes: &[u8]) -> Result<RawStyleInfo, StyleInfoError> {
+    RawStyleInfo::try_from(bytes)
+  }
+}
+
+impl Rule {
+  /**
+   * Creates a rule from the same type names as the wasm constructor, like "StyleRule".
+   */
+  pub fn from_type_name(rule_type: &str) -> Result<Rule, StyleInfoError> {
+    Ok(Rule::with_rule_type(RuleType::from_name(rule_type)?))
+  }
+
+  /**
+   * Adds a selector to the prelude. For keyframes and at-rules, the selector holds the prelude text.
+   */
+  pub fn with_selector(mut self, selector: Selector) -> Self {
+    self.prelude.selector_list.push(selector);
+    self
+  }
+
+  pub fn with_declaration(mut self, property_name: &str, value: &str) -> Self {
+    self
+      .declaration_block
+      .declarations
+      .push(ParsedDeclaration::new(
+        property_name.to_string(),
+        value.to_string(),
+      ));
+    self
+  }
+
+  pub fn with_nested_rule(mut self, rule: Rule) -> Self {
+    self.nested_rules.push(rule);
+    self
+  }
+}
+
+impl Selector {
+  /**
+   * Adds a selector section named like in `push_one_selector_section`, e.g. "ClassSelector".
+   */
+  pub fn with_section(
+    mut self,
+    selector_type: &str,
+    value: impl Into<String>,
+  ) -> Result<Self, StyleInfoError> {
+    self.simple_selectors.push(OneSimpleSelector {
+      selector_type: OneSimpleSelectorType::from_name(selector_type)?,
+      value: value.into(),
+    });
+    Ok(self)
+  }
+}
+
+impl DecodedStyleData {
+  /**
+   * Reads decoded style data, as returned by `decode_style_info`, failing on
+   * truncated or malformed buffers.
+   */
+  pub fn from_bytes(bytes: &[u8]) -> Result<DecodedStyleData, StyleInfoError> {
+    DecodedStyleData::try_from(bytes)
+  }
+
+  pub fn to_bytes(&self) -> Result<Vec<u8>, StyleInfoError> {
+    self.encode_to_bytes()
+  }
+
+  pub fn style_content(&self) -> &str {
+    self.style_content.as_deref().unwrap_or_default()
+  }
+
+  pub fn font_face_content(&self) -> &str {
+    self.font_face_content.as_deref().unwrap_or_default()
+  }
+
+  /**
+   * The source map, if the style data was decoded with `source_map` enabled.
+   */
+  pub fn source_map(&self) -> &[StyleSourceMapping] {
+    self.source_map.as_deref().unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::template::template_sections::section_header::SectionError;
+  use crate::template::template_sections::style_info::decoded_style_data::StyleDecodeOptions;
+
+  fn sample_style_info() -> RawStyleInfo {
+    let keyframes = Rule::from_type_name("KeyframesRule")
+      .unwrap()
+      .with_selector(Selector::new().with_section("UnknownText", "fade").unwrap())
+      .with_nested_rule(
+        Rule::from_type_name("StyleRule")
+          .unwrap()
+          .with_selector(Selector::new().with_section("UnknownText", "to").unwrap())
+          .with_declaration("opacity", "1"),
+      );
+    RawStyleInfo::new()
+      .with_import(1, 2)
+      .with_rule(
+        1,
+        Rule::from_type_name("StyleRule")
+          .unwrap()
+          .with_selector(Selector::new().with_section("ClassSelector", "a").unwrap())
+          .with_declaration("width", "1px"),
+      )
+      .with_rule(1, keyframes)
+      .with_rule(
+        2,
+        Rule::from_type_name("StyleRule")
+          .unwrap()
+          .with_selector(Selector::new().with_section("IdSelector", "b").unwrap())
+          .with_declaration("height", "2px"),
+      )
+  }
+
+  #[test]
+  fn encodes_and_decodes_without_a_js_host() {
+    let bytes = sample_style_info().to_bytes().unwrap();
+    let raw_style_info = RawStyleInfo::from_bytes(&bytes).unwrap();
+    let decoded = DecodedStyleData::decode(
+      raw_style_info,
+      StyleDecodeOptions {
+        source_map: true,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    let decoded = DecodedStyleData::from_bytes(&decoded.to_bytes().unwrap()).unwrap();
+    assert_eq!(
+      decoded.style_content(),
+      "#b:where([l-css-id=\"1\"]):not([l-e-name]),#b:where([l-css-id=\"2\"]):not([l-e-name]){height:2px;}\
+       .a:where([l-css-id=\"1\"]):not([l-e-name]){width:1px;}\
+       @keyframes fade{to{opacity:1;}}"
+    );
+    assert_eq!(decoded.font_face_content(), "");
+    assert_eq!(decoded.source_map().len(), 3);
+  }
+
+  #[test]
+  fn rejects_unknown_names_and_corrupt_buffers() {
+    assert_eq!(
+      Rule::from_type_name("PageRule").err(),
+      Some(StyleInfoError::UnknownRuleType("PageRule".to_string()))
+    );
+    assert!(matches!(
+      Selector::new().with_section("NestingSelector", "&"),
+      Err(StyleInfoError::UnknownSelectorSectionType(_))
+    ));
+    let bytes = sample_style_info().to_bytes().unwrap();
+    assert!(matches!(
+      RawStyleInfo::from_bytes(&bytes[..bytes.len() / 2]),
+      Err(StyleInfoError::Section(SectionError::LengthMismatch { .. }))
+    ));
+    assert_eq!(
+      DecodedStyleData::from_bytes(&bytes).err(),
+      Some(StyleInfoError::Section(SectionError::UnknownMagic))
+    );
+  }
+}
@@ -5,16 +5,28 @@
  */
 
 use super::css_property::ParsedDeclaration;
+use super::style_info_error::StyleInfoError;
+#[cfg(feature = "encode")]
+use crate::template::template_sections::section_header::encode_section;
+use crate::template::template_sections::section_header::{decode_section, SectionFormat};
 
 #[cfg(feature = "encode")]
 use super::style_info_decoder::StyleInfoDecoder;
+#[cfg(feature = "encode")]
+use crate::style_transformer::token_transformer::TransformerConfig;
 use fnv::FnvHashMap;
 #[cfg(feature = "encode")]
 use rkyv::Serialize;
 use rkyv::{Archive, Deserialize};
 use wasm_bindgen::prelude::*;
 
+pub(super) const RAW_STYLE_INFO_SECTION: SectionFormat = SectionFormat {
+  magic: *b"LSTI",
+  version: 1,
+};
+
 #[derive(Clone, Default, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 #[wasm_bindgen]
 pub struct RawStyleInfo {
@@ -23,6 +35,7 @@ pub struct RawStyleInfo {
 }
 
 #[derive(Clone, Default, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 pub struct StyleSheet {
   pub(super) imports: Vec<i32>,
@@ -30,30 +43,41 @@ pub struct StyleSheet {
 }
 
 #[derive(Clone, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 #[archive(bound(
   serialize = "__S: rkyv::ser::Serializer + rkyv::ser::ScratchSpace",
   deserialize = "__D: rkyv::de::SharedDeserializeRegistry"
 ))]
+#[archive_attr(check_bytes(
+  bound = "__C: rkyv::validation::ArchiveContext, <__C as rkyv::Fallible>::Error: rkyv::bytecheck::Error"
+))]
 #[wasm_bindgen]
 pub struct Rule {
   pub(super) rule_type: RuleType,
   pub(super) prelude: RulePrelude,
   pub(super) declaration_block: DeclarationBlock,
   #[omit_bounds]
+  #[archive_attr(omit_bounds)]
   pub(super) nested_rules: Vec<Rule>,
 }
 
 #[derive(Clone, PartialEq, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 #[repr(i32)]
 pub(super) enum RuleType {
   Declaration = 1,
   FontFace = 2,
   KeyFrames = 3,
+  Media = 4,
+  Supports = 5,
+  Layer = 6,
+  Container = 7,
 }
 
 #[derive(Clone, Default, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 #[wasm_bindgen]
 /**
@@ -61,6 +85,7 @@ pub(super) enum RuleType {
  * Depending on the RuleType
  * If it is SelectorList, then selectors is a list of Selector
  * If it is KeyFramesPrelude, then selectors has only one selector which is Prelude text, its simple_selectors is empty
+ * If the parent is Media, Supports, Layer or Container, then selectors holds the at-rule prelude text the same way; an anonymous Layer has no selector
  * If the parent is FontFace, then selectors is empty
  */
 pub struct RulePrelude {
@@ -68,6 +93,7 @@ pub struct RulePrelude {
 }
 
 #[derive(Clone, Default, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 #[wasm_bindgen]
 pub struct Selector {
@@ -75,6 +101,7 @@ pub struct Selector {
 }
 
 #[derive(Clone, PartialEq, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 pub(crate) struct OneSimpleSelector {
   pub(crate) selector_type: OneSimpleSelectorType,
@@ -82,6 +109,7 @@ pub(crate) struct OneSimpleSelector {
 }
 
 #[derive(Clone, PartialEq, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 #[repr(i32)]
 /**
@@ -100,6 +128,7 @@ pub(crate) enum OneSimpleSelectorType {
 }
 
 #[derive(Clone, Archive, Deserialize)]
+#[archive(check_bytes)]
 #[cfg_attr(feature = "encode", derive(Serialize))]
 pub(crate) struct DeclarationBlock {
   pub(crate) declarations: Vec<ParsedDeclaration>,
@@ -136,37 +165,52 @@ impl RawStyleInfo {
   }
 
   /**
-   * Encodes the RawStyleInfo into a Uint8Array using rkyv serialization.
+   * Encodes the RawStyleInfo into a Uint8Array using rkyv serialization, behind a section header.
    * @returns A Uint8Array containing the serialized RawStyleInfo.
    */
   #[cfg(feature = "encode")]
   pub fn encode(&mut self) -> Result<js_sys::Uint8Array, JsError> {
-    let decoded_style_info = StyleInfoDecoder::new(self.clone(), None, true, false, false, false)?;
-    self.style_content_str_size_hint = decoded_style_info.style_content.len();
-    let serialized = rkyv::to_bytes::<_, 1024>(self)
-      .map_err(|e| JsError::new(&format!("Failed to encode RawStyleInfo: {e:?}")))?;
+    let serialized = self.encode_to_bytes()?;
     Ok(js_sys::Uint8Array::from(serialized.as_slice()))
   }
 }
 
-#[wasm_bindgen]
+impl RawStyleInfo {
+  #[cfg(feature = "encode")]
+  pub(super) fn encode_to_bytes(&mut self) -> Result<Vec<u8>, StyleInfoError> {
+    let decoded_style_info =
+      StyleInfoDecoder::new(self.clone(), None, true, TransformerConfig::default())?;
+    self.style_content_str_size_hint = decoded_style_info.style_content.len();
+    Ok(encode_section(&RAW_STYLE_INFO_SECTION, self)?)
+  }
+}
+
+impl TryFrom<&[u8]> for RawStyleInfo {
+  type Error = StyleInfoError;
+  fn try_from(buffer: &[u8]) -> Result<RawStyleInfo, Self::Error> {
+    Ok(decode_section(&RAW_STYLE_INFO_SECTION, buffer)?)
+  }
+}
+
+impl RuleType {
+  pub(super) fn from_name(rule_type: &str) -> Result<RuleType, StyleInfoError> {
+    match rule_type {
+      "StyleRule" => Ok(RuleType::Declaration),
+      "FontFaceRule" => Ok(RuleType::FontFace),
+      "KeyframesRule" => Ok(RuleType::KeyFrames),
+      "MediaRule" => Ok(RuleType::Media),
+      "SupportsRule" => Ok(RuleType::Supports),
+      "LayerRule" => Ok(RuleType::Layer),
+      "ContainerRule" => Ok(RuleType::Container),
+      _ => Err(StyleInfoError::UnknownRuleType(rule_type.to_string())),
+    }
+  }
+}
+
 impl Rule {
-  /**
-   * Creates a new Rule with the specified type.
-   * @param rule_type - The type of the rule (e.g., "StyleRule", "FontFaceRule", "KeyframesRule").
-   */
-  #[wasm_bindgen(constructor)]
-  pub fn new(rule_type: String) -> Result<Rule, JsError> {
-    let rule_type_enum = match rule_type.as_str() {
-      "StyleRule" => RuleType::Declaration,
-      "FontFaceRule" => RuleType::FontFace,
-      "KeyframesRule" => RuleType::KeyFrames,
-      _ => {
-        return Err(JsError::new(&format!("Unknown rule type: {rule_type}")));
-      }
-    };
-    Ok(Rule {
-      rule_type: rule_type_enum,
+  pub(super) fn with_rule_type(rule_type: RuleType) -> Rule {
+    Rule {
+      rule_type,
       prelude: RulePrelude {
         selector_list: vec![],
       },
@@ -174,7 +218,39 @@ impl Rule {
         declarations: vec![],
       },
       nested_rules: vec![],
-    })
+    }
+  }
+}
+
+impl OneSimpleSelectorType {
+  pub(super) fn from_name(selector_type: &str) -> Result<OneSimpleSelectorType, StyleInfoError> {
+    match selector_type {
+      "ClassSelector" => Ok(OneSimpleSelectorType::ClassSelector),
+      "IdSelector" => Ok(OneSimpleSelectorType::IdSelector),
+      "AttributeSelector" => Ok(OneSimpleSelectorType::AttributeSelector),
+      "TypeSelector" => Ok(OneSimpleSelectorType::TypeSelector),
+      "Combinator" => Ok(OneSimpleSelectorType::Combinator),
+      "PseudoClassSelector" => Ok(OneSimpleSelectorType::PseudoClassSelector),
+      "PseudoElementSelector" => Ok(OneSimpleSelectorType::PseudoElementSelector),
+      "UniversalSelector" => Ok(OneSimpleSelectorType::UniversalSelector),
+      "UnknownText" => Ok(OneSimpleSelectorType::UnknownText),
+      _ => Err(StyleInfoError::UnknownSelectorSectionType(
+        selector_type.to_string(),
+      )),
+    }
+  }
+}
+
+#[wasm_bindgen]
+impl Rule {
+  /**
+   * Creates a new Rule with the specified type.
+   * @param rule_type - The type of the rule (e.g., "StyleRule", "FontFaceRule", "KeyframesRule", "MediaRule", "SupportsRule", "LayerRule", "ContainerRule").
+   * Media, supports, layer and container rules carry their style rules as nested rules.
+   */
+  #[wasm_bindgen(constructor)]
+  pub fn new(rule_type: String) -> Result<Rule, JsError> {
+    Ok(Rule::with_rule_type(RuleType::from_name(&rule_type)?))
   }
 
   /**
@@ -244,24 +320,8 @@ impl Selector {
     selector_type: String,
     value: String,
   ) -> Result<(), JsError> {
-    let selector_selector_type = match selector_type.as_str() {
-      "ClassSelector" => OneSimpleSelectorType::ClassSelector,
-      "IdSelector" => OneSimpleSelectorType::IdSelector,
-      "AttributeSelector" => OneSimpleSelectorType::AttributeSelector,
-      "TypeSelector" => OneSimpleSelectorType::TypeSelector,
-      "Combinator" => OneSimpleSelectorType::Combinator,
-      "PseudoClassSelector" => OneSimpleSelectorType::PseudoClassSelector,
-      "PseudoElementSelector" => OneSimpleSelectorType::PseudoElementSelector,
-      "UniversalSelector" => OneSimpleSelectorType::UniversalSelector,
-      "UnknownText" => OneSimpleSelectorType::UnknownText,
-      _ => {
-        return Err(JsError::new(&format!(
-          "Unknown selector section type: {selector_type}"
-        )))
-      }
-    };
     let selector_section = OneSimpleSelector {
-      selector_type: selector_selector_type,
+      selector_type: OneSimpleSelectorType::from_name(&selector_type)?,
       value,
     };
     self.simple_selectors.push(selector_section);
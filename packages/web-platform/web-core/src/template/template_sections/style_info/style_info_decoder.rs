@@ -4,11 +4,15 @@
  * LICENSE file in the root directory of this source tree.
 */
 
-use super::flattened_style_info::FlattenedStyleInfo;
+use super::css_og::{CssOgDeclaration, CssOgRule};
+use super::flattened_style_info::{FlattenedStyleInfo, FlattenedStyleSheet};
 use super::raw_style_info::RuleType;
-use crate::style_transformer::{Generator, StyleTransformer};
+use super::style_info_error::StyleInfoError;
+use super::style_source_map::{source_selector_text, StyleSourceMapping};
+use crate::style_transformer::token_transformer::TransformerConfig;
+use crate::style_transformer::{CssDiagnostic, CssDiagnosticKind, Generator, StyleTransformer};
 use crate::template::template_sections::style_info::raw_style_info::{
-  DeclarationBlock, OneSimpleSelector, OneSimpleSelectorType,
+  DeclarationBlock, OneSimpleSelector, OneSimpleSelectorType, Rule, RulePrelude,
 };
 use crate::template::template_sections::style_info::RawStyleInfo;
 use fnv::FnvHashMap;
@@ -21,17 +25,26 @@ pub struct StyleInfoDecoder {
   // the font face should be placed at the head of the css content, therefore we use a separate buffer
   pub(super) font_face_content: String,
   // if we are processing font_face, the declaration should be pushed to font_face_content for generating
-  pub(super) css_og_css_id_to_class_selector_name_to_declarations_map:
-    Option<super::CssOgCssIdToClassSelectorNameToDeclarationsMap>,
+  pub(super) css_og_css_id_to_style_sheet_map: Option<super::CssOgCssIdToStyleSheetMap>,
   is_processing_font_face: bool,
   temp_child_rules_buffer: String,
   config_enable_css_selector: bool,
-  transform_vw: bool,
-  transform_vh: bool,
-  transform_rem: bool,
+  transformer_config: TransformerConfig,
   entry_name: Option<String>,
   css_og_current_processing_css_ids: Option<Vec<i32>>,
-  css_og_current_processing_class_selector_names: Option<Vec<String>>,
+  // the class names of every class-only selector of the current rule, like [["a"], ["a", "b"]] for .a, .a.b
+  css_og_current_processing_compound_class_names: Option<Vec<Vec<String>>>,
+  css_og_current_declarations: Vec<CssOgDeclaration>,
+  css_og_current_style_sheet_order: u32,
+  css_og_next_rule_order: u32,
+  // how many @media, @supports, @layer or @container rules enclose the rule being decoded
+  at_rule_depth: usize,
+  // only collected when created by new_with_diagnostics
+  pub(super) diagnostics: Option<Vec<CssDiagnostic>>,
+  // only collected when created by new_with_source_map
+  pub(super) source_map: Option<Vec<StyleSourceMapping>>,
+  source_map_css_id: i32,
+  source_map_rule_path: Vec<u32>,
 }
 
 impl StyleInfoDecoder {
@@ -39,246 +52,481 @@ impl StyleInfoDecoder {
     raw_style_info: RawStyleInfo,
     entry_name: Option<String>,
     config_enable_css_selector: bool,
-    transform_vw: bool,
-    transform_vh: bool,
-    transform_rem: bool,
-  ) -> Result<Self, wasm_bindgen::JsError> {
+    transformer_config: TransformerConfig,
+  ) -> Result<Self, StyleInfoError> {
+    Self::create(
+      raw_style_info,
+      entry_name,
+      config_enable_css_selector,
+      transformer_config,
+      false,
+      false,
+    )
+  }
+
+  /**
+   * Decodes like `new`, and also records a diagnostic for every declaration
+   * that is dropped or cannot be fully understood.
+   */
+  pub fn new_with_diagnostics(
+    raw_style_info: RawStyleInfo,
+    entry_name: Option<String>,
+    config_enable_css_selector: bool,
+    transformer_config: TransformerConfig,
+  ) -> Result<Self, StyleInfoError> {
+    Self::create(
+      raw_style_info,
+      entry_name,
+      config_enable_css_selector,
+      transformer_config,
+      true,
+      false,
+    )
+  }
+
+  /**
+   * Decodes like `new`, and also records where every rule is written in the style content,
+   * together with the css id, rule index and selector it was decoded from.
+   */
+  pub fn new_with_source_map(
+    raw_style_info: RawStyleInfo,
+    entry_name: Option<String>,
+    config_enable_css_selector: bool,
+    transformer_config: TransformerConfig,
+  ) -> Result<Self, StyleInfoError> {
+    Self::create(
+      raw_style_info,
+      entry_name,
+      config_enable_css_selector,
+      transformer_config,
+      false,
+      true,
+    )
+  }
+
+  fn create(
+    raw_style_info: RawStyleInfo,
+    entry_name: Option<String>,
+    config_enable_css_selector: bool,
+    transformer_config: TransformerConfig,
+    collect_diagnostics: bool,
+    collect_source_map: bool,
+  ) -> Result<Self, StyleInfoError> {
     let flattened_style_info: FlattenedStyleInfo = raw_style_info.into();
     let mut decoded_style_info = StyleInfoDecoder {
       style_content: String::with_capacity(flattened_style_info.style_content_str_size_hint + 64),
       font_face_content: String::with_capacity(256),
       temp_child_rules_buffer: String::new(),
-      css_og_css_id_to_class_selector_name_to_declarations_map: if !config_enable_css_selector {
+      css_og_css_id_to_style_sheet_map: if !config_enable_css_selector {
         Some(FnvHashMap::default())
       } else {
         None
       },
       entry_name,
       config_enable_css_selector,
-      transform_vw,
-      transform_vh,
-      transform_rem,
+      transformer_config,
       is_processing_font_face: false,
       css_og_current_processing_css_ids: None,
-      css_og_current_processing_class_selector_names: None,
+      css_og_current_processing_compound_class_names: None,
+      css_og_current_declarations: Vec::new(),
+      css_og_current_style_sheet_order: 0,
+      css_og_next_rule_order: 0,
+      at_rule_depth: 0,
+      diagnostics: collect_diagnostics.then(Vec::new),
+      source_map: collect_source_map.then(Vec::new),
+      source_map_css_id: 0,
+      source_map_rule_path: Vec::new(),
     };
     decoded_style_info.decode(flattened_style_info)?;
     Ok(decoded_style_info)
   }
 
-  fn decode(
+  fn decode(&mut self, flattened_style_info: FlattenedStyleInfo) -> Result<(), StyleInfoError> {
+    // emit the style sheets in cascade order, so that imported rules come before the importing ones
+    let mut style_sheets: Vec<(i32, FlattenedStyleSheet)> = flattened_style_info
+      .css_id_to_style_sheet
+      .into_iter()
+      .collect();
+    style_sheets.sort_by_key(|(_, style_sheet)| style_sheet.cascade_order);
+    for (css_id, style_sheet) in style_sheets.into_iter() {
+      self.css_og_current_style_sheet_order = style_sheet.cascade_order as u32;
+      self.css_og_next_rule_order = 0;
+      self.source_map_css_id = css_id;
+      for (rule_index, style_rule) in style_sheet.rules.into_iter().enumerate() {
+        self.source_map_rule_path.push(rule_index as u32);
+        self.decode_one_rule(style_rule, &style_sheet.imported_by)?;
+        self.source_map_rule_path.pop();
+      }
+    }
+    Ok(())
+  }
+
+  fn decode_one_rule(
+    &mut self,
+    style_rule: Rule,
+    imported_by: &[i32],
+  ) -> Result<(), StyleInfoError> {
+    let Some(selector) = self
+      .source_map
+      .is_some()
+      .then(|| source_selector_text(&style_rule))
+      .flatten()
+    else {
+      return self.generate_one_rule(style_rule, imported_by);
+    };
+    let is_font_face = style_rule.rule_type == RuleType::FontFace;
+    let content_len = |decoder: &Self| {
+      if is_font_face {
+        decoder.font_face_content.len()
+      } else {
+        decoder.style_content.len()
+      }
+    };
+    let start = content_len(self);
+    self.generate_one_rule(style_rule, imported_by)?;
+    let end = content_len(self);
+    // class selectors extracted for CSS OG write nothing
+    if end > start {
+      let mapping = StyleSourceMapping {
+        start,
+        end,
+        is_font_face,
+        css_id: self.source_map_css_id,
+        rule_path: self.source_map_rule_path.clone(),
+        selector,
+      };
+      if let Some(source_map) = self.source_map.as_mut() {
+        source_map.push(mapping);
+      }
+    }
+    Ok(())
+  }
+
+  fn generate_one_rule(
     &mut self,
-    flattened_style_info: FlattenedStyleInfo,
-  ) -> Result<(), wasm_bindgen::JsError> {
-    for (_, style_sheet) in flattened_style_info.css_id_to_style_sheet.into_iter() {
-      for mut style_rule in style_sheet.rules.into_iter() {
-        match style_rule.rule_type {
-          RuleType::Declaration => {
-            if !self.config_enable_css_selector {
-              self.css_og_current_processing_css_ids = Some(style_sheet.imported_by.clone());
-              self.css_og_current_processing_class_selector_names = Some(Vec::new());
+    mut style_rule: Rule,
+    imported_by: &[i32],
+  ) -> Result<(), StyleInfoError> {
+    match style_rule.rule_type {
+      RuleType::Declaration => {
+        if !self.config_enable_css_selector {
+          // class selectors inside an at-rule only apply under its condition or layer,
+          // so they are kept as ordinary rules instead of being merged into the CSS OG map
+          let extract_class_selectors = self.at_rule_depth == 0;
+          self.css_og_current_processing_css_ids =
+            extract_class_selectors.then(|| imported_by.to_vec());
+          self.css_og_current_processing_compound_class_names =
+            extract_class_selectors.then(Vec::new);
+        }
+        let mut new_selectors_to_add = Vec::new(); // selectors will be added for removeCSSScope false
+                                                   // handle selectors
+        for (selector_index, selector) in style_rule.prelude.selector_list.iter_mut().enumerate() {
+          /*
+           1. for :root selector section, we should transform it to [part="page"] and move it to the start of the current compound selector
+           2. for ::placeholder selector section, we should transform it to ::part(placeholder)::placeholder
+           3. for type selector section, we should transform it to [part="type"]
+           4 if enableCSSSelector is false:
+             4.1 if the current selector consists of class selectors only (like .a or .a.b), we extract the class names and record a rule with the declarations in css_og_css_id_to_style_sheet_map
+                 the declarations should be transformed by calling transform_one_declaration function.
+                 the current selector should be skipped in following phases.
+                 this is not applied to rules nested in @media, @supports, @layer or @container
+           5 if the self.entryName is Some, we should add a [{constants::LYNX_CSS_ENTRY_NAME_ATTRIBUTE}="{entry_name}"] to the last compound selector just before the first pseudo class or pseudo element
+               otherwise, we should add a :not({constants::LYNX_CSS_ENTRY_NAME_ATTRIBUTE}) just before the first pseudo class or pseudo element in the current compound selector
+           6 if imported_by_css_id != 0, we should add a :where([{constants::LYNX_CSS_ID_ATTRIBUTE}="{imported_by_css_id}"]) to the last compound selector just before the first pseudo class or pseudo element
+          */
+          // process rule 4
+          if !self.config_enable_css_selector
+            && self.at_rule_depth == 0
+            && !selector.simple_selectors.is_empty()
+            && selector.simple_selectors.iter().all(|simple_selector| {
+              simple_selector.selector_type == OneSimpleSelectorType::ClassSelector
+            })
+          {
+            if let Some(compound_class_names) =
+              self.css_og_current_processing_compound_class_names.as_mut()
+            {
+              compound_class_names.push(
+                selector
+                  .simple_selectors
+                  .iter()
+                  .map(|simple_selector| simple_selector.value.clone())
+                  .collect(),
+              );
             }
-            let mut new_selectors_to_add = Vec::new(); // selectors will be added for removeCSSScope false
-                                                       // handle selectors
-            for (selector_index, selector) in
-              style_rule.prelude.selector_list.iter_mut().enumerate()
+            continue;
+          }
+          let mut the_index_of_last_compound_selector = 0;
+          let mut simple_selector_index = 0;
+          while simple_selector_index < selector.simple_selectors.len() {
+            let simple_selector = &mut selector.simple_selectors[simple_selector_index];
+            if simple_selector.selector_type == OneSimpleSelectorType::PseudoClassSelector
+              && simple_selector.value == "root"
             {
-              /*
-               1. for :root selector section, we should transform it to [part="page"] and move it to the start of the current compound selector
-               2. for ::placeholder selector section, we should transform it to ::part(placeholder)::placeholder
-               3. for type selector section, we should transform it to [part="type"]
-               4 if enableCSSSelector is false:
-                 4.1 if the current selector has only one class selector, we extract the class selector name and use it to map to the declarations in css_og_css_id_to_class_selector_name_to_declarations_map
-                     the declarations should be transformed by calling transform_one_declaration function.
-                     the current selector should be skipped in following phases.
-               5 if the self.entryName is Some, we should add a [{constants::LYNX_CSS_ENTRY_NAME_ATTRIBUTE}="{entry_name}"] to the last compound selector just before the first pseudo class or pseudo element
-                   otherwise, we should add a :not({constants::LYNX_CSS_ENTRY_NAME_ATTRIBUTE}) just before the first pseudo class or pseudo element in the current compound selector
-               6 if imported_by_css_id != 0, we should add a :where([{constants::LYNX_CSS_ID_ATTRIBUTE}="{imported_by_css_id}"]) to the last compound selector just before the first pseudo class or pseudo element
-              */
-              // process rule 4
-              if !self.config_enable_css_selector
-                && selector.simple_selectors.len() == 1
-                && selector.simple_selectors[0].selector_type
-                  == OneSimpleSelectorType::ClassSelector
-              {
-                if let Some(names) = self.css_og_current_processing_class_selector_names.as_mut() {
-                  names.push(selector.simple_selectors[0].value.clone());
-                }
-                continue;
-              }
-              let mut the_index_of_last_compound_selector = 0;
-              let mut simple_selector_index = 0;
-              while simple_selector_index < selector.simple_selectors.len() {
-                let simple_selector = &mut selector.simple_selectors[simple_selector_index];
-                if simple_selector.selector_type == OneSimpleSelectorType::PseudoClassSelector
-                  && simple_selector.value == "root"
-                {
-                  // transform :root to [part="page"]
-                  simple_selector.selector_type = OneSimpleSelectorType::AttributeSelector;
-                  simple_selector.value = "part=\"page\"".to_string();
-                  // find the position to insert
-                  let mut compound_selector_start_index = simple_selector_index;
-                  while compound_selector_start_index > 0 {
-                    let prev_simple_selector =
-                      &selector.simple_selectors[compound_selector_start_index - 1];
-                    if prev_simple_selector.selector_type == OneSimpleSelectorType::Combinator {
-                      break;
-                    }
-                    compound_selector_start_index -= 1;
-                  }
-                  // move the current simple selector to the compound selector start index
-                  let root_simple_selector =
-                    selector.simple_selectors.remove(simple_selector_index);
-                  selector
-                    .simple_selectors
-                    .insert(compound_selector_start_index, root_simple_selector);
-                } else if simple_selector.selector_type
-                  == OneSimpleSelectorType::PseudoElementSelector
-                  && simple_selector.value == "placeholder"
-                {
-                  // transform ::placeholder to ::part(input)::placeholder
-                  selector.simple_selectors.insert(
-                    simple_selector_index,
-                    OneSimpleSelector {
-                      selector_type: OneSimpleSelectorType::PseudoElementSelector,
-                      value: "part(input)".to_string(),
-                    },
-                  );
-                  simple_selector_index += 1; // skip the newly inserted simple selector
-                } else if simple_selector.selector_type == OneSimpleSelectorType::TypeSelector {
-                  // transform type selector
-                  let simple_selector = &mut selector.simple_selectors[simple_selector_index];
-                  if let Some(mapped_tag) =
-                    crate::constants::LYNX_TAG_TO_HTML_TAG_MAP.get(simple_selector.value.as_str())
-                  {
-                    simple_selector.value = mapped_tag.to_string();
-                  }
-                }
-                if matches!(
-                  selector.simple_selectors[simple_selector_index].selector_type,
-                  OneSimpleSelectorType::ClassSelector
-                    | OneSimpleSelectorType::IdSelector
-                    | OneSimpleSelectorType::AttributeSelector
-                    | OneSimpleSelectorType::TypeSelector
-                    | OneSimpleSelectorType::UniversalSelector
-                    | OneSimpleSelectorType::PseudoClassSelector
-                ) {
-                  the_index_of_last_compound_selector = simple_selector_index + 1;
+              // transform :root to [part="page"]
+              simple_selector.selector_type = OneSimpleSelectorType::AttributeSelector;
+              simple_selector.value = "part=\"page\"".to_string();
+              // find the position to insert
+              let mut compound_selector_start_index = simple_selector_index;
+              while compound_selector_start_index > 0 {
+                let prev_simple_selector =
+                  &selector.simple_selectors[compound_selector_start_index - 1];
+                if prev_simple_selector.selector_type == OneSimpleSelectorType::Combinator {
+                  break;
                 }
-                simple_selector_index += 1;
+                compound_selector_start_index -= 1;
               }
-              // rule 5
-              if let Some(entry_name) = &self.entry_name {
-                selector.simple_selectors.insert(
-                  the_index_of_last_compound_selector,
-                  OneSimpleSelector {
-                    selector_type: OneSimpleSelectorType::AttributeSelector,
-                    value: format!(
-                      "{}=\"{}\"",
-                      crate::constants::LYNX_ENTRY_NAME_ATTRIBUTE,
-                      entry_name
-                    ),
-                  },
-                );
-              } else {
-                selector.simple_selectors.insert(
-                  the_index_of_last_compound_selector,
-                  OneSimpleSelector {
-                    selector_type: OneSimpleSelectorType::PseudoClassSelector,
-                    value: format!("not([{}])", crate::constants::LYNX_ENTRY_NAME_ATTRIBUTE),
-                  },
-                );
-              }
-
-              // for rule 6, we should copy selectors and add the css id attribute selector
-              for (imported_by_index, imported_by_css_id) in
-                style_sheet.imported_by.iter().enumerate()
+              // move the current simple selector to the compound selector start index
+              let root_simple_selector = selector.simple_selectors.remove(simple_selector_index);
+              selector
+                .simple_selectors
+                .insert(compound_selector_start_index, root_simple_selector);
+            } else if simple_selector.selector_type == OneSimpleSelectorType::PseudoElementSelector
+              && simple_selector.value == "placeholder"
+            {
+              // transform ::placeholder to ::part(input)::placeholder
+              selector.simple_selectors.insert(
+                simple_selector_index,
+                OneSimpleSelector {
+                  selector_type: OneSimpleSelectorType::PseudoElementSelector,
+                  value: "part(input)".to_string(),
+                },
+              );
+              simple_selector_index += 1; // skip the newly inserted simple selector
+            } else if simple_selector.selector_type == OneSimpleSelectorType::TypeSelector {
+              // transform type selector
+              let simple_selector = &mut selector.simple_selectors[simple_selector_index];
+              if let Some(mapped_tag) =
+                crate::constants::LYNX_TAG_TO_HTML_TAG_MAP.get(simple_selector.value.as_str())
               {
-                // add a comma separator if not the last selector
-                if selector_index != 0 || imported_by_index != 0 {
-                  self.style_content.push(',');
-                }
-
-                if *imported_by_css_id == 0 {
-                  selector.generate_to_string_buf(&mut self.style_content);
-                } else {
-                  let mut new_selector = selector.clone();
-                  // for the first imported_by_css_id, we can reuse the current selector
-                  new_selector.simple_selectors.insert(
-                    the_index_of_last_compound_selector,
-                    OneSimpleSelector {
-                      selector_type: OneSimpleSelectorType::PseudoClassSelector,
-                      value: format!(
-                        "where([{}=\"{}\"])",
-                        crate::constants::CSS_ID_ATTRIBUTE,
-                        imported_by_css_id
-                      ),
-                    },
-                  );
-                  new_selector.generate_to_string_buf(&mut self.style_content);
-                  new_selectors_to_add.push(new_selector);
-                };
+                simple_selector.value = mapped_tag.to_string();
               }
             }
-            style_rule
-              .prelude
-              .selector_list
-              .extend(new_selectors_to_add);
-            self.temp_child_rules_buffer.clear();
-            self.generate_one_declaration_block(style_rule.declaration_block);
-            if !self.temp_child_rules_buffer.is_empty() {
-              // regenerate the child rules by adding > * for all selectors
-              for (selector_index, selector) in style_rule.prelude.selector_list.iter().enumerate()
-              {
-                selector.generate_to_string_buf(&mut self.style_content);
-                self.style_content.push_str(" > *");
-                // add a comma separator if not the last selector
-                if selector_index < style_rule.prelude.selector_list.len() - 1 {
-                  self.style_content.push(',');
-                }
-              }
-              self.style_content.push('{');
-              self.style_content.push_str(&self.temp_child_rules_buffer);
-              self.style_content.push('}');
+            if matches!(
+              selector.simple_selectors[simple_selector_index].selector_type,
+              OneSimpleSelectorType::ClassSelector
+                | OneSimpleSelectorType::IdSelector
+                | OneSimpleSelectorType::AttributeSelector
+                | OneSimpleSelectorType::TypeSelector
+                | OneSimpleSelectorType::UniversalSelector
+                | OneSimpleSelectorType::PseudoClassSelector
+            ) {
+              the_index_of_last_compound_selector = simple_selector_index + 1;
             }
+            simple_selector_index += 1;
           }
-          RuleType::FontFace => {
-            self.font_face_content.push_str("@font-face");
-            self.is_processing_font_face = true;
-            self.generate_one_declaration_block(style_rule.declaration_block);
-            self.is_processing_font_face = false;
+          // rule 5
+          if let Some(entry_name) = &self.entry_name {
+            selector.simple_selectors.insert(
+              the_index_of_last_compound_selector,
+              OneSimpleSelector {
+                selector_type: OneSimpleSelectorType::AttributeSelector,
+                value: format!(
+                  "{}=\"{}\"",
+                  crate::constants::LYNX_ENTRY_NAME_ATTRIBUTE,
+                  entry_name
+                ),
+              },
+            );
+          } else {
+            selector.simple_selectors.insert(
+              the_index_of_last_compound_selector,
+              OneSimpleSelector {
+                selector_type: OneSimpleSelectorType::PseudoClassSelector,
+                value: format!("not([{}])", crate::constants::LYNX_ENTRY_NAME_ATTRIBUTE),
+              },
+            );
           }
-          RuleType::KeyFrames => {
-            self.style_content.push_str("@keyframes ");
-            if style_rule.prelude.selector_list.len() != 1 {
-              return Err(wasm_bindgen::JsError::new(
-                "KeyFrames rule must have exactly one selector",
-              ));
-            }
-            let keyframes_name = &style_rule.prelude.selector_list[0];
-            keyframes_name.generate_to_string_buf(&mut self.style_content);
-            self.style_content.push('{');
-            for nested_rule in style_rule.nested_rules.into_iter() {
-              for (selector_index, selector) in nested_rule.prelude.selector_list.iter().enumerate()
-              {
-                // add a comma separator if not the last selector
-                if selector_index > 0 {
-                  self.style_content.push(',');
-                }
-                selector.generate_to_string_buf(&mut self.style_content);
-              }
-              if nested_rule.rule_type == RuleType::Declaration {
-                self.generate_one_declaration_block(nested_rule.declaration_block);
-              }
+
+          // for rule 6, we should copy selectors and add the css id attribute selector
+          for (imported_by_index, imported_by_css_id) in imported_by.iter().enumerate() {
+            // add a comma separator if not the last selector
+            if selector_index != 0 || imported_by_index != 0 {
+              self.style_content.push(',');
             }
 
-            self.style_content.push('}');
+            if *imported_by_css_id == 0 {
+              selector.generate_to_string_buf(&mut self.style_content);
+            } else {
+              let mut new_selector = selector.clone();
+              // for the first imported_by_css_id, we can reuse the current selector
+              new_selector.simple_selectors.insert(
+                the_index_of_last_compound_selector,
+                OneSimpleSelector {
+                  selector_type: OneSimpleSelectorType::PseudoClassSelector,
+                  value: format!(
+                    "where([{}=\"{}\"])",
+                    crate::constants::CSS_ID_ATTRIBUTE,
+                    imported_by_css_id
+                  ),
+                },
+              );
+              new_selector.generate_to_string_buf(&mut self.style_content);
+              new_selectors_to_add.push(new_selector);
+            };
+          }
+        }
+        style_rule
+          .prelude
+          .selector_list
+          .extend(new_selectors_to_add);
+        self.temp_child_rules_buffer.clear();
+        self.generate_one_declaration_block(style_rule.declaration_block);
+        if !self.temp_child_rules_buffer.is_empty() {
+          // regenerate the child rules by adding > * for all selectors
+          for (selector_index, selector) in style_rule.prelude.selector_list.iter().enumerate() {
+            selector.generate_to_string_buf(&mut self.style_content);
+            self.style_content.push_str(" > *");
+            // add a comma separator if not the last selector
+            if selector_index < style_rule.prelude.selector_list.len() - 1 {
+              self.style_content.push(',');
+            }
+          }
+          self.style_content.push('{');
+          self.style_content.push_str(&self.temp_child_rules_buffer);
+          self.style_content.push('}');
+        }
+        self.flush_css_og_rule();
+      }
+      RuleType::FontFace => {
+        self.font_face_content.push_str("@font-face");
+        self.is_processing_font_face = true;
+        self.generate_one_declaration_block(style_rule.declaration_block);
+        self.is_processing_font_face = false;
+      }
+      RuleType::KeyFrames => {
+        self.style_content.push_str("@keyframes ");
+        if style_rule.prelude.selector_list.len() != 1 {
+          return Err(StyleInfoError::InvalidRule(
+            "KeyFrames rule must have exactly one selector",
+          ));
+        }
+        let keyframes_name = &style_rule.prelude.selector_list[0];
+        keyframes_name.generate_to_string_buf(&mut self.style_content);
+        self.style_content.push('{');
+        for nested_rule in style_rule.nested_rules.into_iter() {
+          for (selector_index, selector) in nested_rule.prelude.selector_list.iter().enumerate() {
+            // add a comma separator if not the last selector
+            if selector_index > 0 {
+              self.style_content.push(',');
+            }
+            selector.generate_to_string_buf(&mut self.style_content);
           }
+          if nested_rule.rule_type == RuleType::Declaration {
+            if self.diagnostics.is_some() {
+              self.check_keyframe_declarations(&nested_rule.declaration_block);
+            }
+            self.generate_one_declaration_block(nested_rule.declaration_block);
+          }
+        }
+
+        self.style_content.push('}');
+      }
+      RuleType::Media | RuleType::Supports | RuleType::Container => {
+        self.style_content.push_str(match style_rule.rule_type {
+          RuleType::Media => "@media ",
+          RuleType::Supports => "@supports ",
+          _ => "@container ",
+        });
+        self.generate_at_rule_prelude(&style_rule.prelude);
+        self.decode_nested_rules(style_rule.nested_rules, imported_by)?;
+      }
+      RuleType::Layer => {
+        self.style_content.push_str("@layer");
+        if !style_rule.prelude.selector_list.is_empty() {
+          self.style_content.push(' ');
+          self.generate_at_rule_prelude(&style_rule.prelude);
+        }
+        if style_rule.nested_rules.is_empty() {
+          // the statement form only declares the layer order, e.g. @layer base,components;
+          self.style_content.push(';');
+        } else {
+          self.decode_nested_rules(style_rule.nested_rules, imported_by)?;
         }
       }
     }
     Ok(())
   }
+
+  // records the declarations collected for the current rule once for every css id and class-only selector
+  fn flush_css_og_rule(&mut self) {
+    let declarations = std::mem::take(&mut self.css_og_current_declarations);
+    let (Some(map), Some(css_ids), Some(compound_class_names)) = (
+      self.css_og_css_id_to_style_sheet_map.as_mut(),
+      self.css_og_current_processing_css_ids.take(),
+      self.css_og_current_processing_compound_class_names.take(),
+    ) else {
+      return;
+    };
+    if compound_class_names.is_empty() || declarations.is_empty() {
+      return;
+    }
+    let rule_order = self.css_og_next_rule_order;
+    self.css_og_next_rule_order += 1;
+    for css_id in css_ids.into_iter() {
+      let style_sheet = map.entry(css_id).or_default();
+      for class_names in compound_class_names.iter() {
+        style_sheet.push_rule(CssOgRule {
+          class_names: class_names.clone(),
+          style_sheet_order: self.css_og_current_style_sheet_order,
+          rule_order,
+          declarations: declarations.clone(),
+        });
+      }
+    }
+  }
+
+  fn collect_css_og_declaration(&mut self, declaration_str: &str) {
+    if self
+      .css_og_current_processing_compound_class_names
+      .is_some()
+    {
+      if let Some(declaration) = CssOgDeclaration::from_generated(declaration_str) {
+        self.css_og_current_declarations.push(declaration);
+      }
+    }
+  }
+
+  fn check_keyframe_declarations(&mut self, declaration_block: &DeclarationBlock) {
+    for declaration in declaration_block.declarations.iter() {
+      if declaration.has_important() {
+        self.push_diagnostic(CssDiagnostic::new(
+          0,
+          declaration.property_id.to_string(),
+          CssDiagnosticKind::IgnoredImportant,
+          "declarations marked `!important` are ignored inside @keyframes",
+        ));
+      }
+    }
+  }
+
+  fn generate_at_rule_prelude(&mut self, prelude: &RulePrelude) {
+    for (selector_index, selector) in prelude.selector_list.iter().enumerate() {
+      if selector_index > 0 {
+        self.style_content.push(',');
+      }
+      selector.generate_to_string_buf(&mut self.style_content);
+    }
+  }
+
+  // nested rules go through the same transforms as top level rules,
+  // so the :root, type selector, entry name and css id rewrites apply inside the block as well
+  fn decode_nested_rules(
+    &mut self,
+    nested_rules: Vec<Rule>,
+    imported_by: &[i32],
+  ) -> Result<(), StyleInfoError> {
+    self.style_content.push('{');
+    self.at_rule_depth += 1;
+    for (rule_index, nested_rule) in nested_rules.into_iter().enumerate() {
+      self.source_map_rule_path.push(rule_index as u32);
+      self.decode_one_rule(nested_rule, imported_by)?;
+      self.source_map_rule_path.pop();
+    }
+    self.at_rule_depth -= 1;
+    self.style_content.push('}');
+    Ok(())
+  }
+
   fn generate_one_declaration_block(&mut self, declaration_block: DeclarationBlock) {
     (if self.is_processing_font_face {
       &mut self.font_face_content
@@ -286,14 +534,8 @@ impl StyleInfoDecoder {
       &mut self.style_content
     })
     .push('{');
-    let mut transformer = StyleTransformer::new(
-      self,
-      crate::style_transformer::token_transformer::TransformerConfig {
-        transform_vw: self.transform_vw,
-        transform_vh: self.transform_vh,
-        transform_rem: self.transform_rem,
-      },
-    );
+    let transformer_config = self.transformer_config.clone();
+    let mut transformer = StyleTransformer::new(self, transformer_config);
 
     for decl in declaration_block.declarations.into_iter() {
       transformer.on_half_parsed_declaration(decl);
@@ -310,45 +552,11 @@ impl StyleInfoDecoder {
 impl Generator for StyleInfoDecoder {
   fn push_transform_kids_style(&mut self, declaration_str: String) {
     self.temp_child_rules_buffer.push_str(&declaration_str);
-    if !self.config_enable_css_selector {
-      if let (Some(map), Some(css_ids), Some(names)) = (
-        self
-          .css_og_css_id_to_class_selector_name_to_declarations_map
-          .as_mut(),
-        self.css_og_current_processing_css_ids.as_ref(),
-        self.css_og_current_processing_class_selector_names.as_ref(),
-      ) {
-        for css_id in css_ids.iter() {
-          let class_selector_map = map.entry(*css_id).or_default();
-          for class_selector_name in names.iter() {
-            let string_buf = class_selector_map
-              .entry(class_selector_name.clone())
-              .or_default();
-            string_buf.push_str(&declaration_str);
-          }
-        }
-      }
-    }
+    self.collect_css_og_declaration(&declaration_str);
   }
   fn push_transformed_style(&mut self, declaration_str: String) {
-    if !self.config_enable_css_selector && !self.is_processing_font_face {
-      if let (Some(map), Some(css_ids), Some(names)) = (
-        self
-          .css_og_css_id_to_class_selector_name_to_declarations_map
-          .as_mut(),
-        self.css_og_current_processing_css_ids.as_ref(),
-        self.css_og_current_processing_class_selector_names.as_ref(),
-      ) {
-        for css_id in css_ids.iter() {
-          let class_selector_map = map.entry(*css_id).or_default();
-          for class_selector_name in names.iter() {
-            let string_buf = class_selector_map
-              .entry(class_selector_name.clone())
-              .or_default();
-            string_buf.push_str(&declaration_str);
-          }
-        }
-      }
+    if !self.is_processing_font_face {
+      self.collect_css_og_declaration(&declaration_str);
     }
     (if self.is_processing_font_face {
       &mut self.font_face_content
@@ -357,6 +565,22 @@ impl Generator for StyleInfoDecoder {
     })
     .push_str(&declaration_str);
   }
+
+  fn collects_diagnostics(&self) -> bool {
+    self.diagnostics.is_some()
+  }
+
+  fn push_diagnostic(&mut self, mut diagnostic: CssDiagnostic) {
+    // the style info section has no source text, so point into the generated content instead
+    diagnostic.offset = if self.is_processing_font_face {
+      self.font_face_content.len()
+    } else {
+      self.style_content.len()
+    };
+    if let Some(diagnostics) = self.diagnostics.as_mut() {
+      diagnostics.push(diagnostic);
+    }
+  }
 }
 
 #[cfg(test)]
@@ -371,6 +595,8 @@ mod test {
   };
 
   use super::StyleInfoDecoder;
+  use crate::style_transformer::token_transformer::{TransformerConfig, UnitTable};
+  use crate::style_transformer::CssDiagnosticKind;
 
   use super::super::{
     DeclarationBlock, OneSimpleSelector, OneSimpleSelectorType, RawStyleInfo, RuleType,
@@ -385,9 +611,7 @@ mod test {
       raw_style_info,
       entry_name,
       config_enable_css_selector,
-      false,
-      false,
-      false,
+      TransformerConfig::default(),
     )
     .unwrap()
   }
@@ -899,6 +1123,421 @@ mod test {
     let expected = ":not([l-e-name])::part(input)::placeholder{--lynx-text-bg-color:initial;-webkit-background-clip:initial;background-clip:initial;color:red;}";
     assert_eq!(result.style_content, expected);
   }
+
+  fn at_rule(rule_type: RuleType, prelude: &str, nested_rules: Vec<Rule>) -> Rule {
+    Rule {
+      rule_type,
+      prelude: RulePrelude {
+        selector_list: if prelude.is_empty() {
+          vec![]
+        } else {
+          vec![Selector {
+            simple_selectors: vec![OneSimpleSelector {
+              selector_type: OneSimpleSelectorType::UnknownText,
+              value: prelude.to_string(),
+            }],
+          }]
+        },
+      },
+      declaration_block: DeclarationBlock {
+        declarations: vec![],
+      },
+      nested_rules,
+    }
+  }
+
+  fn width_rule(selector_type: OneSimpleSelectorType, value: &str, width: &str) -> Rule {
+    Rule {
+      rule_type: RuleType::Declaration,
+      prelude: RulePrelude {
+        selector_list: vec![Selector {
+          simple_selectors: vec![OneSimpleSelector {
+            selector_type,
+            value: value.to_string(),
+          }],
+        }],
+      },
+      declaration_block: DeclarationBlock {
+        declarations: vec![ParsedDeclaration {
+          property_id: CSSPropertyEnum::Width.into(),
+          is_important: false,
+          value_token_list: vec![ValueToken {
+            token_type: crate::css_tokenizer::token_types::DIMENSION_TOKEN,
+            value: width.to_string(),
+          }],
+        }],
+      },
+      nested_rules: vec![],
+    }
+  }
+
+  #[test]
+  fn test_media_rule_keeps_scoping_rewrites() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![(
+        1,
+        StyleSheet {
+          imports: vec![],
+          rules: vec![at_rule(
+            RuleType::Media,
+            "(max-width:600px)",
+            vec![
+              width_rule(OneSimpleSelectorType::PseudoClassSelector, "root", "100rpx"),
+              width_rule(OneSimpleSelectorType::TypeSelector, "view", "10px"),
+            ],
+          )],
+        },
+      )]),
+      style_content_str_size_hint: 0,
+    };
+    let result = generate_string_buf(raw_style_info, true, None);
+    let expected = "@media (max-width:600px){[part=\"page\"]:where([l-css-id=\"1\"]):not([l-e-name]){width:calc(100 * var(--rpx-unit));}x-view:where([l-css-id=\"1\"]):not([l-e-name]){width:10px;}}";
+    assert_eq!(result.style_content, expected);
+  }
+
+  #[test]
+  fn test_source_map_links_rules_to_css_id_and_selector() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![
+        (
+          1,
+          StyleSheet {
+            imports: vec![2],
+            rules: vec![
+              width_rule(OneSimpleSelectorType::TypeSelector, "view", "1px"),
+              at_rule(
+                RuleType::Media,
+                "(max-width:600px)",
+                vec![width_rule(OneSimpleSelectorType::IdSelector, "a", "2px")],
+              ),
+            ],
+          },
+        ),
+        (
+          2,
+          StyleSheet {
+            imports: vec![],
+            rules: vec![width_rule(OneSimpleSelectorType::ClassSelector, "b", "3px")],
+          },
+        ),
+      ]),
+      style_content_str_size_hint: 0,
+    };
+    let result = StyleInfoDecoder::new_with_source_map(
+      raw_style_info,
+      None,
+      true,
+      TransformerConfig::default(),
+    )
+    .unwrap();
+    let mappings: Vec<(i32, Vec<u32>, &str, &str)> = result
+      .source_map
+      .as_ref()
+      .unwrap()
+      .iter()
+      .map(|mapping| {
+        (
+          mapping.css_id,
+          mapping.rule_path.clone(),
+          mapping.selector.as_str(),
+          &result.style_content[mapping.start..mapping.end],
+        )
+      })
+      .collect();
+    assert_eq!(
+      mappings,
+      vec![
+        (
+          2,
+          vec![0],
+          ".b",
+          ".b:where([l-css-id=\"1\"]):not([l-e-name]),.b:where([l-css-id=\"2\"]):not([l-e-name]){width:3px;}"
+        ),
+        (
+          1,
+          vec![0],
+          "view",
+          "x-view:where([l-css-id=\"1\"]):not([l-e-name]){width:1px;}"
+        ),
+        (
+          1,
+          vec![1, 0],
+          "#a",
+          "#a:where([l-css-id=\"1\"]):not([l-e-name]){width:2px;}"
+        ),
+      ]
+    );
+    assert!(generate_string_buf(RawStyleInfo::new(), true, None)
+      .source_map
+      .is_none());
+  }
+
+  #[test]
+  fn test_nested_supports_and_container_rules_with_entry_name() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![(
+        0,
+        StyleSheet {
+          imports: vec![],
+          rules: vec![at_rule(
+            RuleType::Supports,
+            "(display:grid)",
+            vec![at_rule(
+              RuleType::Container,
+              "card (min-width:400px)",
+              vec![width_rule(
+                OneSimpleSelectorType::ClassSelector,
+                "title",
+                "100px",
+              )],
+            )],
+          )],
+        },
+      )]),
+      style_content_str_size_hint: 0,
+    };
+    let result = generate_string_buf(raw_style_info, true, Some("lazy-bundle".to_string()));
+    let expected = "@supports (display:grid){@container card (min-width:400px){.title[l-e-name=\"lazy-bundle\"]{width:100px;}}}";
+    assert_eq!(result.style_content, expected);
+  }
+
+  #[test]
+  fn test_layer_statement_and_block() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![(
+        0,
+        StyleSheet {
+          imports: vec![],
+          rules: vec![
+            at_rule(RuleType::Layer, "base,components", vec![]),
+            at_rule(
+              RuleType::Layer,
+              "base",
+              vec![width_rule(
+                OneSimpleSelectorType::ClassSelector,
+                "card",
+                "100px",
+              )],
+            ),
+            at_rule(
+              RuleType::Layer,
+              "",
+              vec![width_rule(
+                OneSimpleSelectorType::ClassSelector,
+                "card",
+                "50px",
+              )],
+            ),
+          ],
+        },
+      )]),
+      style_content_str_size_hint: 0,
+    };
+    let result = generate_string_buf(raw_style_info, true, None);
+    let expected = "@layer base,components;@layer base{.card:not([l-e-name]){width:100px;}}@layer{.card:not([l-e-name]){width:50px;}}";
+    assert_eq!(result.style_content, expected);
+  }
+
+  #[test]
+  fn test_css_og_class_in_media_rule_is_not_extracted() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![(
+        0,
+        StyleSheet {
+          imports: vec![],
+          rules: vec![
+            width_rule(OneSimpleSelectorType::ClassSelector, "card", "100px"),
+            at_rule(
+              RuleType::Media,
+              "(min-width:400px)",
+              vec![width_rule(
+                OneSimpleSelectorType::ClassSelector,
+                "card",
+                "50px",
+              )],
+            ),
+          ],
+        },
+      )]),
+      style_content_str_size_hint: 0,
+    };
+    let result = generate_string_buf(raw_style_info, false, None);
+    assert_eq!(
+      result.style_content,
+      "{width:100px;}@media (min-width:400px){.card:not([l-e-name]){width:50px;}}"
+    );
+    let css_og_map = result.css_og_css_id_to_style_sheet_map.unwrap();
+    assert_eq!(
+      css_og_map[&0].resolve(&["card".to_string()]),
+      "width:100px;"
+    );
+  }
+
+  #[test]
+  fn test_diagnostics_point_into_decoded_content() {
+    let mut important_keyframe = width_rule(OneSimpleSelectorType::UnknownText, "to", "1px");
+    important_keyframe.declaration_block.declarations[0]
+      .value_token_list
+      .extend([
+        ValueToken {
+          token_type: crate::css_tokenizer::token_types::DELIM_TOKEN,
+          value: "!".to_string(),
+        },
+        ValueToken {
+          token_type: crate::css_tokenizer::token_types::IDENT_TOKEN,
+          value: "important".to_string(),
+        },
+      ]);
+    let mut unknown_property = width_rule(OneSimpleSelectorType::ClassSelector, "card", "1px");
+    unknown_property.declaration_block.declarations[0].property_id = "colr".into();
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![(
+        0,
+        StyleSheet {
+          imports: vec![],
+          rules: vec![
+            unknown_property,
+            at_rule(RuleType::KeyFrames, "grow", vec![important_keyframe]),
+          ],
+        },
+      )]),
+      style_content_str_size_hint: 0,
+    };
+    let decoder = StyleInfoDecoder::new_with_diagnostics(
+      raw_style_info,
+      None,
+      true,
+      TransformerConfig::default(),
+    )
+    .unwrap();
+    let diagnostics = decoder.diagnostics.unwrap();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.kind, diagnostic.property.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (CssDiagnosticKind::UnknownProperty, "colr"),
+        (CssDiagnosticKind::IgnoredImportant, "width"),
+      ]
+    );
+    assert_eq!(diagnostics[0].offset, ".card:not([l-e-name]){".len());
+    assert_eq!(
+      &decoder.style_content[diagnostics[1].offset..],
+      "{width:1px!important;}}"
+    );
+    assert!(generate_string_buf(RawStyleInfo::new(), true, None)
+      .diagnostics
+      .is_none());
+  }
+
+  fn css_og_rule(class_names: &[&str], declarations: &[(&str, &str)]) -> Rule {
+    let mut selector = Selector::new();
+    for class_name in class_names {
+      selector
+        .push_one_selector_section("ClassSelector".to_string(), class_name.to_string())
+        .unwrap();
+    }
+    let mut prelude = RulePrelude::new();
+    prelude.push_selector(selector);
+    let mut rule = Rule::new("StyleRule".to_string()).unwrap();
+    rule.set_prelude(prelude);
+    for (name, value) in declarations {
+      rule.push_declaration(name.to_string(), value.to_string());
+    }
+    rule
+  }
+
+  #[test]
+  fn test_css_og_cascade_across_imports() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![
+        (
+          1,
+          StyleSheet {
+            imports: vec![2],
+            rules: vec![
+              css_og_rule(&["a"], &[("opacity", "0.3")]),
+              css_og_rule(&["c"], &[("width", "2px")]),
+            ],
+          },
+        ),
+        (
+          2,
+          StyleSheet {
+            imports: vec![],
+            rules: vec![
+              css_og_rule(&["a", "b"], &[("opacity", "0.5")]),
+              css_og_rule(&["a"], &[("opacity", "0.1"), ("height", "1px")]),
+              css_og_rule(&["c"], &[("width", "1px !important")]),
+            ],
+          },
+        ),
+      ]),
+      style_content_str_size_hint: 0,
+    };
+    let result = generate_string_buf(raw_style_info, false, None);
+    let css_og_map = result.css_og_css_id_to_style_sheet_map.unwrap();
+    let resolve = |css_id: i32, class_names: &[&str]| {
+      css_og_map[&css_id].resolve(
+        &class_names
+          .iter()
+          .map(|class_name| class_name.to_string())
+          .collect::<Vec<_>>(),
+      )
+    };
+    // the importing style sheet wins over the imported one
+    assert_eq!(resolve(1, &["a"]), "height:1px;opacity:0.3;");
+    assert_eq!(resolve(2, &["a"]), "opacity:0.1;height:1px;");
+    // .a.b is more specific than .a
+    assert_eq!(resolve(1, &["b", "a"]), "height:1px;opacity:0.5;");
+    assert_eq!(resolve(1, &["b"]), "");
+    // !important wins over the later declaration
+    assert_eq!(resolve(1, &["c"]), "width:1px !important;");
+  }
+
+  #[test]
+  fn test_unit_table_applies_at_decode_time() {
+    let raw_style_info = RawStyleInfo {
+      css_id_to_style_sheet: FnvHashMap::from_iter(vec![(
+        0,
+        StyleSheet {
+          imports: vec![],
+          rules: vec![css_og_rule(
+            &["a"],
+            &[("width", "30rpx"), ("height", "calc(10 * var(--rpx-unit))")],
+          )],
+        },
+      )]),
+      style_content_str_size_hint: 0,
+    };
+    let mut unit_table = UnitTable::new();
+    unit_table.set_fixed("rpx".to_string(), 0.5, "px".to_string());
+    let result = StyleInfoDecoder::new(
+      raw_style_info,
+      None,
+      true,
+      TransformerConfig {
+        unit_table,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    assert!(result.style_content.contains("{width:15px;height:5px;}"));
+    assert!(!result.style_content.contains("var("));
+  }
+
+  #[test]
+  fn test_conditional_rule_types_from_js_names() {
+    for (name, rule_type) in [
+      ("MediaRule", RuleType::Media),
+      ("SupportsRule", RuleType::Supports),
+      ("LayerRule", RuleType::Layer),
+      ("ContainerRule", RuleType::Container),
+    ] {
+      assert!(Rule::new(name.to_string()).unwrap().rule_type == rule_type);
+    }
+  }
 }
 
 #[cfg(test)]
@@ -989,10 +1628,10 @@ mod tests_roundtrip {
       // Need to decode bytes back to RawStyleInfo first
       let mut buf = vec![0u8; bytes.length() as usize];
       bytes.copy_to(&mut buf);
-      let decoded_raw = unsafe { rkyv::from_bytes_unchecked::<RawStyleInfo>(&buf) }
-        .expect("RawStyleInfo decode should succeed");
+      let decoded_raw =
+        RawStyleInfo::try_from(buf.as_slice()).expect("RawStyleInfo decode should succeed");
 
-      let decoder = StyleInfoDecoder::new(decoded_raw, None, true, false, false, false)
+      let decoder = StyleInfoDecoder::new(decoded_raw, None, true, TransformerConfig::default())
         .expect("StyleInfoDecoder should succeed");
       let decoded_string = decoder.style_content;
 
@@ -1040,10 +1679,10 @@ mod tests_roundtrip {
 
       let mut buf = vec![0u8; bytes.length() as usize];
       bytes.copy_to(&mut buf);
-      let decoded_raw = unsafe { rkyv::from_bytes_unchecked::<RawStyleInfo>(&buf) }
-        .expect("RawStyleInfo decode should succeed");
+      let decoded_raw =
+        RawStyleInfo::try_from(buf.as_slice()).expect("RawStyleInfo decode should succeed");
 
-      let decoder = StyleInfoDecoder::new(decoded_raw, None, true, false, false, false)
+      let decoder = StyleInfoDecoder::new(decoded_raw, None, true, TransformerConfig::default())
         .expect("StyleInfoDecoder should succeed");
       let decoded_string = decoder.style_content;
 
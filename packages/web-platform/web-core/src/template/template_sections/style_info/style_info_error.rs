@@ -0,0 +1,49 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use crate::template::template_sections::section_header::SectionError;
+use std::fmt;
+
+/**
+ * An error building, encoding or decoding the style info section.
+ * It converts into a `JsError` for the wasm bindings, so it is also usable without a JS host.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StyleInfoError {
+  /// The rule type name passed to the rule builder is not known.
+  UnknownRuleType(String),
+  /// The selector section type name passed to the selector builder is not known.
+  UnknownSelectorSectionType(String),
+  /// The rule cannot be written as CSS.
+  InvalidRule(&'static str),
+  /// The requested decode options cannot be combined.
+  IncompatibleOptions(&'static str),
+  /// The buffer does not hold a valid section, or the section could not be encoded.
+  Section(SectionError),
+}
+
+impl fmt::Display for StyleInfoError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StyleInfoError::UnknownRuleType(rule_type) => write!(f, "Unknown rule type: {rule_type}"),
+      StyleInfoError::UnknownSelectorSectionType(selector_type) => {
+        write!(f, "Unknown selector section type: {selector_type}")
+      }
+      StyleInfoError::InvalidRule(reason) | StyleInfoError::IncompatibleOptions(reason) => {
+        f.write_str(reason)
+      }
+      StyleInfoError::Section(error) => write!(f, "Invalid style info section: {error}"),
+    }
+  }
+}
+
+impl std::error::Error for StyleInfoError {}
+
+impl From<SectionError> for StyleInfoError {
+  fn from(error: SectionError) -> Self {
+    StyleInfoError::Section(error)
+  }
+}
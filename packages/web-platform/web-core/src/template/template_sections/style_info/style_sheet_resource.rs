@@ -21,8 +21,7 @@ pub struct StyleSheetResource {
   #[cfg(feature = "server")]
   pub(crate) font_face_content_str: Option<String>,
 
-  pub(crate) css_og_css_id_to_class_selector_name_to_declarations_map:
-    Option<Rc<super::CssOgCssIdToClassSelectorNameToDeclarationsMap>>,
+  pub(crate) css_og_css_id_to_style_sheet_map: Option<Rc<super::CssOgCssIdToStyleSheetMap>>,
 }
 
 #[wasm_bindgen]
@@ -74,8 +73,8 @@ impl StyleSheetResource {
       style_content_str,
       #[cfg(feature = "server")]
       font_face_content_str,
-      css_og_css_id_to_class_selector_name_to_declarations_map: decoded_style_data
-        .css_og_css_id_to_class_selector_name_to_declarations_map
+      css_og_css_id_to_style_sheet_map: decoded_style_data
+        .css_og_css_id_to_style_sheet_map
         .map(Rc::new),
     })
   }
@@ -87,16 +86,11 @@ impl StyleSheetResource {
     css_id: i32,
     class_name: Vec<String>,
   ) -> String {
-    let mut result = String::new();
-    if let Some(map) = &self.css_og_css_id_to_class_selector_name_to_declarations_map {
-      if let Some(class_selector_map) = map.get(&css_id) {
-        for class_name in class_name.iter() {
-          if let Some(declarations) = class_selector_map.get(class_name) {
-            result.push_str(declarations);
-          }
-        }
-      }
-    }
-    result
+    self
+      .css_og_css_id_to_style_sheet_map
+      .as_ref()
+      .and_then(|map| map.get(&css_id))
+      .map(|style_sheet| style_sheet.resolve(&class_name))
+      .unwrap_or_default()
   }
 }
@@ -0,0 +1,51 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use super::raw_style_info::{Rule, RuleType};
+use rkyv::{Archive, Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/**
+ * Links one rule of the decoded style content back to the style sheet it came from.
+ * `start` and `end` are byte offsets into the style content, or into the font face
+ * content if `is_font_face` is set.
+ */
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct StyleSourceMapping {
+  pub start: usize,
+  pub end: usize,
+  pub is_font_face: bool,
+  pub css_id: i32,
+  /// The index of the rule in its style sheet, followed by the index in every
+  /// enclosing @media, @supports, @layer or @container block.
+  #[wasm_bindgen(getter_with_clone)]
+  pub rule_path: Vec<u32>,
+  /// The selector list as encoded, before scoping, or the at-rule prelude like `@keyframes name`.
+  #[wasm_bindgen(getter_with_clone)]
+  pub selector: String,
+}
+
+/**
+ * The selector list or prelude of a rule, in the form the encoder stored it.
+ * Conditional at-rules return `None`, their nested rules are mapped on their own.
+ */
+pub(super) fn source_selector_text(rule: &Rule) -> Option<String> {
+  let mut text = match rule.rule_type {
+    RuleType::Declaration => String::new(),
+    RuleType::FontFace => return Some("@font-face".to_string()),
+    RuleType::KeyFrames => "@keyframes ".to_string(),
+    RuleType::Media | RuleType::Supports | RuleType::Layer | RuleType::Container => return None,
+  };
+  for (selector_index, selector) in rule.prelude.selector_list.iter().enumerate() {
+    if selector_index > 0 {
+      text.push(',');
+    }
+    selector.generate_to_string_buf(&mut text);
+  }
+  Some(text)
+}
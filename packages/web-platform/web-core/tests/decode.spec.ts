@@ -9,6 +9,9 @@ import * as wasm from '../ts/server/wasm.js';
 
 rstest.mock('../ts/server/wasm.js', () => ({
   decode_style_info: rstest.fn((buffer: Uint8Array) => buffer),
+  RawElementTemplate: {
+    decode: rstest.fn((buffer: Uint8Array) => ({ buffer })),
+  },
 }));
 
 function encodeString(str: string): Uint8Array {
@@ -196,4 +199,18 @@ describe('decodeTemplate', () => {
     const result = decodeTemplate(buffer);
     expect(result.customSections).toEqual(customObj);
   });
+
+  it('should decode element templates section', () => {
+    const content = new Uint8Array([4, 5, 6]);
+
+    const buffer = createTemplate({
+      sections: [
+        { label: TemplateSectionLabel.ElementTemplates, content },
+      ],
+    });
+
+    const result = decodeTemplate(buffer);
+    expect(wasm.RawElementTemplate.decode).toHaveBeenCalled();
+    expect(result.elementTemplates).toEqual({ buffer: content });
+  });
 });
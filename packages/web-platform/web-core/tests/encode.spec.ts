@@ -12,6 +12,7 @@ import {
   get_style_content,
   get_font_face_content,
   decode_style_info,
+  RawElementTemplate,
 } from '../binary/encode/encode.js';
 import { encode, TasmJSONInfo } from '../ts/encode/webEncoder.js';
 import { TemplateSectionLabel } from '../ts/constants.js';
@@ -171,6 +172,38 @@ describe('encodeCSS', () => {
     expect(decodedString).toContain('opacity:var(--my-var, 1);');
   });
 
+  test('should encode media, supports and layer rules with their nested rules', () => {
+    const css = `
+      @layer base, components;
+      @media (max-width: 600px) {
+        @supports (display: grid) {
+          .card {
+            width: 100px;
+          }
+        }
+      }
+      @layer base {
+        .card {
+          height: 10px;
+        }
+      }
+    `;
+    const cssMap = {
+      '0': CSS.parse(css).root,
+    };
+    const buffer = encodeCSS(cssMap);
+    const decodedString = get_style_content(
+      decode_style_info(buffer, undefined, true),
+    );
+    expect(decodedString).toContain('@layer base');
+    expect(decodedString).toMatch(
+      /@media [^{]+\{@supports [^{]+\{\.card:not\(\[l-e-name\]\)\{width:100px;\}\}\}/,
+    );
+    expect(decodedString).toContain(
+      '{.card:not([l-e-name]){height:10px;}}',
+    );
+  });
+
   test('should handle complex selectors', () => {
     const css = `
       div > .foo + #bar[attr="val"]::before:hover {
@@ -534,4 +567,42 @@ describe('webEncoder', () => {
       offset += length;
     }
   });
+
+  test('should write elementTemplates section if present', () => {
+    const tasmJSON: TasmJSONInfo = {
+      styleInfo: {},
+      manifest: {},
+      cardType: 'card',
+      appType: 'card',
+      pageConfig: {},
+      lepusCode: {},
+      customSections: {},
+      elementTemplates: {
+        card: {
+          type: 'view',
+          idSelector: 'card',
+          children: [{ type: 'text', class: ['title'] }],
+        },
+      },
+    };
+    const buffer = encode(tasmJSON);
+    const view = new DataView(buffer.buffer);
+    let offset = 8 + 4; // Magic + Version
+    let elementTemplates: Uint8Array | undefined;
+
+    while (offset < buffer.byteLength) {
+      const label = view.getUint32(offset, true);
+      offset += 4;
+      const length = view.getUint32(offset, true);
+      offset += 4;
+      if (label === TemplateSectionLabel.ElementTemplates) {
+        elementTemplates = buffer.subarray(offset, offset + length);
+      }
+      offset += length;
+    }
+    expect(elementTemplates).toBeDefined();
+    expect(RawElementTemplate.decode(elementTemplates!).has_template('card'))
+      .toBe(true);
+  });
 });
+
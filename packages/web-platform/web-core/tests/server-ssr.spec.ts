@@ -11,7 +11,10 @@ rstest.mock('../css/in_shadow.css?inline', () => ({
 }));
 
 import { createElementAPI, type SSRBinding } from '../ts/server/index.js';
-import { MainThreadServerContext } from '../ts/server/wasm.js';
+import {
+  MainThreadServerContext,
+  RawElementTemplate,
+} from '../ts/server/wasm.js';
 
 describe('Server SSR', () => {
   it('should generate html correctly', () => {
@@ -62,6 +65,67 @@ describe('Server SSR', () => {
     expect(html).toContain('</div>');
   });
 
+  it('should stream the flushed html in chunks', () => {
+    const chunks: string[] = [];
+    const binding: SSRBinding = {
+      ssrResult: '',
+      onChunk: (chunk) => chunks.push(chunk),
+    };
+    const { globalThisAPIs: api } = createElementAPI(
+      binding,
+      undefined,
+      '',
+      {
+        enableCSSSelector: true,
+        defaultOverflowVisible: false,
+        defaultDisplayLinear: true,
+      },
+    );
+    const page = api.__CreatePage('0', 0);
+    const view = api.__CreateElement('view', 0);
+    api.__SetAttribute(view, 'id', 'main');
+    api.__AppendElement(page, view);
+
+    api.__FlushElementTree();
+
+    expect(binding.ssrResult).toBe('');
+    expect(chunks.length).toBeGreaterThan(1);
+    expect(chunks[0]).toContain('/* INJECTED_SHADOW_CSS */');
+    expect(chunks[0]!.endsWith('</style>')).toBe(true);
+    const html = chunks.join('');
+    expect(html).toContain('id="main"');
+    expect(html.endsWith('</template></lynx-view>')).toBe(true);
+  });
+
+  it('should stream the document only once when flushed twice', () => {
+    const chunks: string[] = [];
+    const binding: SSRBinding = {
+      ssrResult: '',
+      onChunk: (chunk) => chunks.push(chunk),
+    };
+    const { globalThisAPIs: api } = createElementAPI(
+      binding,
+      undefined,
+      '',
+      {
+        enableCSSSelector: true,
+        defaultOverflowVisible: false,
+        defaultDisplayLinear: true,
+      },
+    );
+    const page = api.__CreatePage('0', 0);
+    api.__AppendElement(page, api.__CreateElement('view', 0));
+
+    api.__FlushElementTree();
+    const streamedChunks = chunks.length;
+    api.__FlushElementTree();
+
+    expect(chunks.length).toBe(streamedChunks);
+    const html = chunks.join('');
+    expect(html.match(/INJECTED_SHADOW_CSS/g)).toHaveLength(1);
+    expect(html.match(/<\/lynx-view>/g)).toHaveLength(1);
+  });
+
   it('should handle attributes and styles', () => {
     const binding: any = {};
     const config = { enableCSSSelector: true };
@@ -195,4 +259,43 @@ describe('Server SSR', () => {
 
     expect(html).not.toContain('l-css-id');
   });
+
+  it('should instantiate element templates', () => {
+    const binding: SSRBinding = {
+      ssrResult: '',
+    };
+    const config = {
+      enableCSSSelector: true,
+      defaultOverflowVisible: false,
+      defaultDisplayLinear: true,
+      transformVW: false,
+      transformVH: false,
+      transformREM: false,
+    };
+    const templates = new RawElementTemplate();
+    templates.create_element('card', 'x-view');
+    templates.set_id('card', 'card');
+    templates.create_element('card', 'x-text');
+    templates.add_class('card', 'title');
+    templates.end_element('card');
+    templates.end_element('card');
+    const { globalThisAPIs: api } = createElementAPI(
+      binding,
+      undefined,
+      '',
+      config,
+      templates,
+    );
+
+    const page = api.__CreatePage('0', 0);
+    const card = api.__ElementFromBinary!('card', 0);
+    api.__AppendElement(page, card);
+    api.__FlushElementTree();
+
+    const html = binding.ssrResult;
+    expect(html).toContain('<x-view');
+    expect(html).toContain('id="card"');
+    expect(html).toContain('<x-text');
+    expect(html).toContain('class="title"');
+  });
 });
@@ -12,7 +12,7 @@
  * structures - they walk the produced bytes exactly as
  * `decodeWorker/decode.worker.ts` does, and hand the `StyleInfo` section to the
  * same `decode_style_info` the browser calls. If the encoder produced something
- * the decoder could not read, `from_bytes_unchecked` on the other side would not
+ * the decoder could not read, the validated decode on the other side would not
  * return this CSS.
  */
 
@@ -271,6 +271,10 @@ export class TemplateManager {
         instance.onBTSScriptsLoaded(url);
         break;
       }
+      case TemplateSectionLabel.ElementTemplates: {
+        // Element templates are only instantiated during SSR for now
+        break;
+      }
       default:
         throw new Error(`Unknown section label: ${label}`);
     }
@@ -0,0 +1,54 @@
+/*
+ * Copyright 2025 The Lynx Authors. All rights reserved.
+ * Licensed under the Apache License Version 2.0 that can be found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+// @ts-ignore
+import { RawElementTemplate } from '../../binary/encode/encode.js';
+import { LYNX_TAG_TO_HTML_TAG_MAP } from '../constants.js';
+import type { ElementTemplateData } from '../types/index.js';
+
+function encodeElement(
+  templates: RawElementTemplate,
+  templateId: string,
+  element: ElementTemplateData,
+) {
+  templates.create_element(
+    templateId,
+    LYNX_TAG_TO_HTML_TAG_MAP[element.type] ?? element.type,
+  );
+  if (element.idSelector) {
+    templates.set_id(templateId, element.idSelector);
+  }
+  for (const className of element.class ?? []) {
+    templates.add_class(templateId, className);
+  }
+  for (const [key, value] of Object.entries(element.attributes ?? {})) {
+    templates.set_attribute(templateId, key, value);
+  }
+  for (
+    const [key, value] of Object.entries(element.builtinAttributes ?? {})
+  ) {
+    templates.set_builtin_attribute(templateId, key, value);
+  }
+  for (const [key, value] of Object.entries(element.dataset ?? {})) {
+    templates.set_dataset(templateId, key, value);
+  }
+  for (const { type, name, value } of element.events ?? []) {
+    templates.add_event(templateId, type, name, value);
+  }
+  for (const child of element.children ?? []) {
+    encodeElement(templates, templateId, child);
+  }
+  templates.end_element(templateId);
+}
+
+export function encodeElementTemplates(
+  elementTemplates: Record<string, ElementTemplateData>,
+): Uint8Array {
+  const templates = new RawElementTemplate();
+  for (const [templateId, root] of Object.entries(elementTemplates)) {
+    encodeElement(templates, templateId, root);
+  }
+  return templates.encode();
+}
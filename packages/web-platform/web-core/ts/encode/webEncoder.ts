@@ -6,6 +6,7 @@
 import type * as CSS from '@lynx-js/css-serializer';
 import type { ElementTemplateData } from '../types/index.js';
 import { encodeCSS } from './encodeCSS.js';
+import { encodeElementTemplates } from './encodeElementTemplates.js';
 import {
   MagicHeader0,
   MagicHeader1,
@@ -82,12 +83,17 @@ export function encode(tasmJSON: TasmJSONInfo): Uint8Array {
     pageConfig,
     lepusCode,
     customSections,
+    elementTemplates,
   } = tasmJSON;
   const encodedStyleInfo = encodeCSS(styleInfo);
   const encodedManifest = encodeStringMap(manifest);
   const encodedLepusCode = encodeStringMap(lepusCode);
 
   const encodedCustomSections = encodeAsJSON(customSections);
+  const encodedElementTemplates =
+    elementTemplates && Object.keys(elementTemplates).length > 0
+      ? encodeElementTemplates(elementTemplates)
+      : undefined;
 
   const configMap: Record<string, string> = {};
   configMap['cardType'] = cardType;
@@ -106,6 +112,9 @@ export function encode(tasmJSON: TasmJSONInfo): Uint8Array {
     + 4 + 4 + encodedLepusCode.length // Lepus Code
     + 4 + 4 + encodedCustomSections.length // Custom Sections
     + 4 + 4 + encodedManifest.length // Manifest
+    + (encodedElementTemplates
+      ? 4 + 4 + encodedElementTemplates.length
+      : 0) // Element Templates
   ;
 
   // generate final buffer in order
@@ -150,6 +159,15 @@ export function encode(tasmJSON: TasmJSONInfo): Uint8Array {
   offset += 4;
   buffer.set(encodedStyleInfo, offset);
   offset += encodedStyleInfo.length;
+  // Element Templates
+  if (encodedElementTemplates) {
+    dataView.setUint32(offset, TemplateSectionLabel.ElementTemplates, true); // section label
+    offset += 4;
+    dataView.setUint32(offset, encodedElementTemplates.length, true); // section length
+    offset += 4;
+    buffer.set(encodedElementTemplates, offset);
+    offset += encodedElementTemplates.length;
+  }
   // Manifest
   dataView.setUint32(offset, TemplateSectionLabel.Manifest, true); // section label
   offset += 4;
@@ -4,7 +4,7 @@ import {
   MagicHeader1,
 } from '../constants.js';
 
-import { decode_style_info } from './wasm.js';
+import { decode_style_info, RawElementTemplate } from './wasm.js';
 import { decodeBinaryMap } from '../common/decodeUtils.js';
 
 export interface DecodedTemplate {
@@ -12,6 +12,7 @@ export interface DecodedTemplate {
   styleInfo?: Uint8Array;
   lepusCode: Record<string, Uint8Array>;
   customSections?: Record<string, any>;
+  elementTemplates?: RawElementTemplate;
 }
 
 export function decodeTemplate(
@@ -53,6 +54,7 @@ export function decodeTemplate(
   let styleInfo: Uint8Array | undefined;
   let lepusCode: Record<string, Uint8Array> = {};
   let customSections: Record<string, any> | undefined;
+  let elementTemplates: RawElementTemplate | undefined;
 
   while (offset < buffer.length) {
     if (buffer.length < offset + 4) {
@@ -104,9 +106,12 @@ export function decodeTemplate(
         customSections = JSON.parse(decoder.decode(content));
         break;
       }
-      case TemplateSectionLabel.Manifest:
       case TemplateSectionLabel.ElementTemplates: {
-        // Ignore these sections for now
+        elementTemplates = RawElementTemplate.decode(content);
+        break;
+      }
+      case TemplateSectionLabel.Manifest: {
+        // Ignore this section for now
         break;
       }
       default:
@@ -120,5 +125,6 @@ export function decodeTemplate(
     styleInfo,
     lepusCode,
     customSections,
+    elementTemplates,
   };
 }
@@ -11,12 +11,79 @@ export function executeTemplate(
   templateBuffer: Buffer,
   initData: Cloneable,
   globalProps: Cloneable,
-  _initI18nResources: InitI18nResources,
+  initI18nResources: InitI18nResources,
   transformVW: boolean,
   transformVH: boolean,
   viewAttributes?: string,
   transformREM?: boolean,
 ): string | undefined {
+  const binding: SSRBinding = { ssrResult: '' };
+  const rendered = renderTemplate(
+    binding,
+    templateBuffer,
+    initData,
+    globalProps,
+    initI18nResources,
+    transformVW,
+    transformVH,
+    viewAttributes,
+    transformREM,
+  );
+  return rendered ? binding.ssrResult : undefined;
+}
+
+/**
+ * Same as `executeTemplate`, but the markup is streamed: the `<lynx-view>`
+ * shell and styles are enqueued first, then the element tree in chunks.
+ * Returns `undefined` when the template does not render a page.
+ */
+export function executeTemplateStream(
+  templateBuffer: Buffer,
+  initData: Cloneable,
+  globalProps: Cloneable,
+  initI18nResources: InitI18nResources,
+  transformVW: boolean,
+  transformVH: boolean,
+  viewAttributes?: string,
+  transformREM?: boolean,
+): ReadableStream<string> | undefined {
+  let rendered = false;
+  // `start` runs while the stream is constructed, so every chunk is handed to
+  // the consumer's queue as soon as the sink flushes it, without a copy.
+  const stream = new ReadableStream<string>({
+    start(controller) {
+      const binding: SSRBinding = {
+        ssrResult: '',
+        onChunk: (chunk) => controller.enqueue(chunk),
+      };
+      rendered = renderTemplate(
+        binding,
+        templateBuffer,
+        initData,
+        globalProps,
+        initI18nResources,
+        transformVW,
+        transformVH,
+        viewAttributes,
+        transformREM,
+      );
+      controller.close();
+    },
+  });
+  return rendered ? stream : undefined;
+}
+
+function renderTemplate(
+  binding: SSRBinding,
+  templateBuffer: Buffer,
+  initData: Cloneable,
+  globalProps: Cloneable,
+  _initI18nResources: InitI18nResources,
+  transformVW: boolean,
+  transformVH: boolean,
+  viewAttributes?: string,
+  transformREM?: boolean,
+): boolean {
   const result = decodeTemplate(
     templateBuffer,
     transformVW,
@@ -25,7 +92,6 @@ export function executeTemplate(
   );
   const config = result.config;
 
-  const binding: SSRBinding = { ssrResult: '' };
   const { globalThisAPIs: elementAPIs } = createElementAPI(
     binding,
     result.styleInfo,
@@ -38,6 +104,7 @@ export function executeTemplate(
       transformVH: transformVH,
       transformREM: !!transformREM,
     },
+    result.elementTemplates,
   );
 
   const sandbox: Record<string, any> = {
@@ -98,9 +165,9 @@ export function executeTemplate(
         : initData;
       renderPageFunction(processedData);
       elementAPIs.__FlushElementTree();
-      return binding.ssrResult;
+      return true;
     }
   }
 
-  return undefined;
+  return false;
 }
@@ -4,7 +4,11 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-import { MainThreadServerContext, StyleSheetResource } from '../wasm.js';
+import {
+  MainThreadServerContext,
+  RawElementTemplate,
+  StyleSheetResource,
+} from '../wasm.js';
 
 import {
   LYNX_TAG_TO_HTML_TAG_MAP,
@@ -16,6 +20,8 @@ import {
 } from '../../constants.js';
 import type {
   AddClassPAPI,
+  AddDatasetPAPI,
+  AddEventPAPI,
   AddInlineStylePAPI,
   AppendElementPAPI,
   CreateComponentPAPI,
@@ -30,35 +36,36 @@ import type {
   CreateViewPAPI,
   CreateWrapperElementPAPI,
   DecoratedHTMLElement,
+  ElementFromBinaryPAPI,
   ElementPAPIs,
   GetAttributeNamesPAPI,
   GetAttributesPAPI,
   GetClassesPAPI,
+  GetComponentIdPAPI,
+  GetDataByKeyPAPI,
+  GetDatasetPAPI,
+  GetEventPAPI,
   GetIDPAPI,
   GetTagPAPI,
   SetAttributePAPI,
   SetAttributePAPIUpdateListInfo,
   SetCSSIdPAPI,
   SetClassesPAPI,
+  SetDatasetPAPI,
   SetIDPAPI,
   SetInlineStylesPAPI,
+  UpdateComponentIDPAPI,
   UpdateListInfoAttributeValue,
 } from '../../types/index.js';
 import {
   __AddConfig,
-  __AddDataset,
-  __AddEvent,
   __AddEventListener,
   __ElementIsEqual,
   __FirstElement,
   __GetChildren,
-  __GetComponentID,
   __GetConfig,
-  __GetDataByKey,
-  __GetDataset,
   __GetElementConfig,
   __GetElementUniqueID,
-  __GetEvent,
   __GetEvents,
   __GetPageElement,
   __GetParent,
@@ -73,10 +80,8 @@ import {
   __ReplaceElement,
   __ReplaceElements,
   __SetConfig,
-  __SetDataset,
   __SetEvents,
   __SwapElement,
-  __UpdateComponentID,
   __UpdateComponentInfo,
   __UpdateListCallbacks,
   getUniqueId,
@@ -92,8 +97,24 @@ import type {
 
 export type SSRBinding = {
   ssrResult: string;
+  /**
+   * When set, the flushed page is streamed through this callback instead of
+   * being collected into `ssrResult`. A streamed document cannot be taken
+   * back, so only the first flush is streamed and later ones are ignored.
+   */
+  onChunk?: (chunk: string) => void;
 };
 
+// Dataset values and worklets cross into wasm as JSON so they can be written
+// verbatim into the hydration payload.
+function toJSON(value: unknown): string {
+  return JSON.stringify(value) ?? 'null';
+}
+
+function fromJSON(json: string | undefined): any {
+  return json === undefined ? undefined : JSON.parse(json);
+}
+
 export function createElementAPI(
   mtsBinding: SSRBinding,
   styleInfo: Uint8Array | undefined,
@@ -106,6 +127,7 @@ export function createElementAPI(
     transformVH: boolean;
     transformREM: boolean;
   },
+  elementTemplates?: RawElementTemplate,
 ): { globalThisAPIs: ElementPAPIs; wasmContext: MainThreadServerContext } {
   const wasmContext = new MainThreadServerContext(
     viewAttributes,
@@ -120,6 +142,7 @@ export function createElementAPI(
   }
 
   let pageElementId: number | undefined;
+  let streamed = false;
 
   function getAttribute(
     element: ServerElement,
@@ -159,6 +182,95 @@ export function createElementAPI(
     wasmContext.add_class(el[uniqueIdSymbol], className);
   };
 
+  const __AddEvent: AddEventPAPI = (
+    element,
+    eventType,
+    eventName,
+    frameworkCrossThreadIdentifier,
+  ) => {
+    const uniqueId = getUniqueId(element);
+    if (typeof frameworkCrossThreadIdentifier === 'string') {
+      wasmContext.add_cross_thread_event(
+        uniqueId,
+        eventType,
+        eventName,
+        frameworkCrossThreadIdentifier,
+      );
+    } else if (frameworkCrossThreadIdentifier == null) {
+      wasmContext.add_cross_thread_event(
+        uniqueId,
+        eventType,
+        eventName,
+        undefined,
+      );
+      wasmContext.add_run_worklet_event(
+        uniqueId,
+        eventType,
+        eventName,
+        undefined,
+      );
+    } else if (typeof frameworkCrossThreadIdentifier === 'object') {
+      wasmContext.add_run_worklet_event(
+        uniqueId,
+        eventType,
+        eventName,
+        toJSON(frameworkCrossThreadIdentifier),
+      );
+    }
+  };
+
+  const __GetEvent: GetEventPAPI = (element, eventName, eventType) => {
+    const uniqueId = getUniqueId(element);
+    const handler = wasmContext.get_event(uniqueId, eventName, eventType);
+    if (handler !== undefined) {
+      return handler;
+    }
+    return fromJSON(
+      wasmContext.get_run_worklet_event(uniqueId, eventName, eventType),
+    );
+  };
+
+  const __SetDataset: SetDatasetPAPI = (element, dataset) => {
+    const entries: string[] = [];
+    for (const [key, value] of Object.entries(dataset)) {
+      entries.push(key, toJSON(value));
+    }
+    wasmContext.set_dataset(getUniqueId(element), entries);
+  };
+
+  const __AddDataset: AddDatasetPAPI = (element, key, value) => {
+    const uniqueId = getUniqueId(element);
+    if (value) {
+      wasmContext.set_attribute(
+        uniqueId,
+        `data-${key}`,
+        typeof value === 'object' ? JSON.stringify(value) : value.toString(),
+      );
+    } else {
+      wasmContext.remove_attribute(uniqueId, `data-${key}`);
+    }
+    wasmContext.add_dataset(uniqueId, key, toJSON(value));
+  };
+
+  const __GetDataset: GetDatasetPAPI = (element) => {
+    return Object.assign(
+      Object.create(null),
+      JSON.parse(wasmContext.get_dataset(getUniqueId(element))),
+    );
+  };
+
+  const __GetDataByKey: GetDataByKeyPAPI = (element, key) => {
+    return fromJSON(wasmContext.get_data_by_key(getUniqueId(element), key));
+  };
+
+  const __GetComponentID: GetComponentIdPAPI = (element) => {
+    return wasmContext.get_component_id(getUniqueId(element));
+  };
+
+  const __UpdateComponentID: UpdateComponentIDPAPI = (element, componentID) => {
+    wasmContext.update_component_id(getUniqueId(element), componentID);
+  };
+
   return {
     globalThisAPIs: {
       // Pure/Throwing Methods
@@ -336,6 +448,17 @@ export function createElementAPI(
         );
         return { [uniqueIdSymbol]: id } as unknown as DecoratedHTMLElement;
       }) as CreateScrollViewPAPI,
+      __ElementFromBinary: ((
+        templateId: string,
+        parentComponentUniqueId: number,
+      ) => {
+        const id = wasmContext.instantiate_element_template(
+          elementTemplates ?? new RawElementTemplate(),
+          templateId,
+          parentComponentUniqueId,
+        );
+        return { [uniqueIdSymbol]: id } as unknown as DecoratedHTMLElement;
+      }) as ElementFromBinaryPAPI,
       __CreateElement: ((tagName: string, parentComponentUniqueId: number) => {
         const htmlTag = LYNX_TAG_TO_HTML_TAG_MAP[tagName] ?? tagName;
         const id = wasmContext.create_element(htmlTag, parentComponentUniqueId);
@@ -505,7 +628,17 @@ export function createElementAPI(
 
       __FlushElementTree: (() => {
         if (pageElementId !== undefined) {
-          mtsBinding.ssrResult = wasmContext.generate_html(pageElementId);
+          if (mtsBinding.onChunk) {
+            if (!streamed) {
+              streamed = true;
+              wasmContext.generate_html_stream(
+                pageElementId,
+                mtsBinding.onChunk,
+              );
+            }
+          } else {
+            mtsBinding.ssrResult = wasmContext.generate_html(pageElementId);
+          }
         }
       }),
 
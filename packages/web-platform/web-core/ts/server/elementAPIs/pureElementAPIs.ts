@@ -7,19 +7,12 @@
 import { uniqueIdSymbol } from '../../constants.js';
 import type {
   AddConfigPAPI,
-  AddDatasetPAPI,
   AddEventListenerPAPI,
-  AddEventPAPI,
-  Cloneable,
   ElementIsEqualPAPI,
   FirstElementPAPI,
   GetChildrenPAPI,
-  GetComponentIdPAPI,
-  GetDataByKeyPAPI,
-  GetDatasetPAPI,
   GetElementConfigPAPI,
   GetElementUniqueIDPAPI,
-  GetEventPAPI,
   GetEventsPAPI,
   GetPageElementPAPI,
   GetParentPAPI,
@@ -34,10 +27,8 @@ import type {
   ReplaceElementPAPI,
   ReplaceElementsPAPI,
   SetConfigPAPI,
-  SetDatasetPAPI,
   SetEventsPAPI,
   SwapElementPAPI,
-  UpdateComponentIDPAPI,
   UpdateComponentInfoPAPI,
   UpdateListCallbacksPAPI,
 } from '../../types/index.js';
@@ -74,20 +65,12 @@ export const __GetChildren: GetChildrenPAPI = (_element: HTMLElement) => {
   throw new Error('__GetChildren is not implemented in SSR');
 };
 
-export const __AddEvent: AddEventPAPI = () => {
-  // Silent return for SSR compatibility
-};
-
-// SSR only produces the initial markup and no interaction happens server-side,
-// so element event listeners are silently dropped, as `__AddEvent` is.
+// Listeners bind main-thread closures, which cannot be carried in the hydration
+// payload, and no interaction happens server-side, so they are dropped.
 export const __AddEventListener: AddEventListenerPAPI = () => {};
 
 export const __RemoveEventListener: RemoveEventListenerPAPI = () => {};
 
-export const __GetEvent: GetEventPAPI = () => {
-  throw new Error('__GetEvent is not implemented in SSR');
-};
-
 export const __GetEvents: GetEventsPAPI = () => {
   throw new Error('__GetEvents is not implemented in SSR');
 };
@@ -113,36 +96,6 @@ export const __GetElementConfig: GetElementConfigPAPI = () => {
   throw new Error('__GetElementConfig is not implemented in SSR');
 };
 
-export const __GetComponentID: GetComponentIdPAPI = () => {
-  throw new Error('__GetComponentID is not implemented in SSR');
-};
-
-export const __GetDataset: GetDatasetPAPI = (_element: HTMLElement) => {
-  throw new Error('__GetDataset is not implemented in SSR');
-};
-
-export const __SetDataset: SetDatasetPAPI = (
-  _element: HTMLElement,
-  _dataset: Record<string, Cloneable>,
-) => {
-  throw new Error('__SetDataset is not implemented in SSR');
-};
-
-export const __AddDataset: AddDatasetPAPI = (
-  _element: HTMLElement,
-  _key: string,
-  _value: Cloneable,
-) => {
-  // No-op in SSR
-};
-
-export const __GetDataByKey: GetDataByKeyPAPI = (
-  _element: HTMLElement,
-  _key: string,
-) => {
-  throw new Error('__GetDataByKey is not implemented in SSR');
-};
-
 export const __FirstElement: FirstElementPAPI = (_element: HTMLElement) => {
   throw new Error('__FirstElement is not implemented in SSR');
 };
@@ -184,10 +137,6 @@ export const __UpdateComponentInfo: UpdateComponentInfoPAPI = () => {
   throw new Error('__UpdateComponentInfo is not implemented in SSR');
 };
 
-export const __UpdateComponentID: UpdateComponentIDPAPI = () => {
-  throw new Error('__UpdateComponentID is not implemented in SSR');
-};
-
 export const __MarkTemplateElement: MarkTemplateElementPAPI = () => {
   throw new Error('__MarkTemplateElement is not implemented in SSR');
 };
@@ -354,6 +354,11 @@ export type MarkTemplateElementPAPI = (
   element: HTMLElement,
 ) => void;
 
+export type ElementFromBinaryPAPI = (
+  templateId: string,
+  parentComponentUniqueId: number,
+) => HTMLElement;
+
 export type MarkPartElementPAPI = (
   element: HTMLElement,
   partId: string,
@@ -412,6 +417,8 @@ export interface ElementPAPIs {
 
   __MarkPartElement: MarkPartElementPAPI;
   __MarkTemplateElement: MarkTemplateElementPAPI;
+  // __ElementFromBinary currently only provided by SSR, which reads the element templates section
+  __ElementFromBinary?: ElementFromBinaryPAPI;
   __AddEvent: AddEventPAPI;
   __AddEventListener: AddEventListenerPAPI;
   __RemoveEventListener: RemoveEventListenerPAPI;